@@ -0,0 +1,192 @@
+//! Decompress the body of a request.
+use std::io::Result as IoResult;
+#[cfg(any(feature = "gzip", feature = "deflate"))]
+use std::io::Write;
+#[cfg(any(feature = "brotli", feature = "zstd"))]
+use std::io::{Error as IoError, ErrorKind};
+
+#[cfg(feature = "brotli")]
+use brotli::{BrotliDecompressStream, BrotliResult, BrotliState, HeapAlloc, HuffmanCode};
+use bytes::Bytes;
+#[cfg(feature = "gzip")]
+use flate2::write::GzDecoder;
+#[cfg(feature = "deflate")]
+use flate2::write::ZlibDecoder;
+#[cfg(feature = "zstd")]
+use zstd::stream::raw::{Decoder as ZstdDecoder, InBuffer, Operation, OutBuffer};
+
+#[cfg(any(feature = "gzip", feature = "deflate"))]
+use super::encoder::Writer;
+use super::CompressionAlgo;
+
+/// Size of the output buffer of the decoders, so the data decoded by one call is bounded.
+#[cfg(any(feature = "brotli", feature = "zstd"))]
+const OUTPUT_BUFFER_SIZE: usize = 32 * 1024;
+
+#[cfg(feature = "brotli")]
+pub(super) struct BrotliDecoder {
+    state: BrotliState<HeapAlloc<u8>, HeapAlloc<u32>, HeapAlloc<HuffmanCode>>,
+    total_out: usize,
+    done: bool,
+}
+
+#[cfg(feature = "zstd")]
+pub(super) struct ZstdFrames {
+    decoder: ZstdDecoder<'static>,
+    // The last hint returned by zstd, it is `0` if a frame is complete and fully flushed.
+    hint: usize,
+}
+
+pub(super) enum Decoder {
+    #[cfg(feature = "brotli")]
+    Brotli(Box<BrotliDecoder>),
+    #[cfg(feature = "deflate")]
+    Deflate(ZlibDecoder<Writer>),
+    #[cfg(feature = "gzip")]
+    Gzip(GzDecoder<Writer>),
+    #[cfg(feature = "zstd")]
+    Zstd(Box<ZstdFrames>),
+}
+
+impl Decoder {
+    pub(super) fn new(algo: CompressionAlgo) -> Self {
+        match algo {
+            #[cfg(feature = "brotli")]
+            CompressionAlgo::Brotli => Self::Brotli(Box::new(BrotliDecoder {
+                state: BrotliState::new(
+                    HeapAlloc::new(0),
+                    HeapAlloc::new(0),
+                    HeapAlloc::new(HuffmanCode::default()),
+                ),
+                total_out: 0,
+                done: false,
+            })),
+            #[cfg(feature = "deflate")]
+            CompressionAlgo::Deflate => Self::Deflate(ZlibDecoder::new(Writer::new())),
+            #[cfg(feature = "gzip")]
+            CompressionAlgo::Gzip => Self::Gzip(GzDecoder::new(Writer::new())),
+            #[cfg(feature = "zstd")]
+            CompressionAlgo::Zstd => Self::Zstd(Box::new(ZstdFrames {
+                decoder: ZstdDecoder::new().expect("`ZstdDecoder::new` returned an error"),
+                hint: 1,
+            })),
+        }
+    }
+
+    /// Decode a part of `input`, returns the count of consumed bytes and the decoded data.
+    ///
+    /// The decoded data is bounded by the buffer size of the decoder, so callers should call it again with the rest
+    /// of `input`. Nothing is consumed and decoded if the compressed stream is ended.
+    #[allow(unused_variables)]
+    pub(super) fn decode(&mut self, input: &[u8]) -> IoResult<(usize, Bytes)> {
+        match *self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(ref mut decoder) => decoder.decode(input),
+            #[cfg(feature = "deflate")]
+            Self::Deflate(ref mut decoder) => {
+                let consumed = decoder.write(input)?;
+                decoder.flush()?;
+                Ok((consumed, decoder.get_mut().take()))
+            }
+            #[cfg(feature = "gzip")]
+            Self::Gzip(ref mut decoder) => {
+                let consumed = decoder.write(input)?;
+                decoder.flush()?;
+                Ok((consumed, decoder.get_mut().take()))
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd(ref mut decoder) => decoder.decode(input),
+        }
+    }
+
+    /// Decode the rest data after all input is written, it should be called until `None` is returned.
+    ///
+    /// An error is returned if the compressed data is truncated.
+    pub(super) fn finish(&mut self) -> IoResult<Option<Bytes>> {
+        match *self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(ref mut decoder) => {
+                if decoder.done {
+                    return Ok(None);
+                }
+                let (_, chunk) = decoder.decode(&[])?;
+                if !chunk.is_empty() {
+                    Ok(Some(chunk))
+                } else if decoder.done {
+                    Ok(None)
+                } else {
+                    Err(IoError::new(ErrorKind::UnexpectedEof, "brotli stream is incomplete"))
+                }
+            }
+            #[cfg(feature = "deflate")]
+            Self::Deflate(ref mut decoder) => {
+                decoder.try_finish()?;
+                Ok(Some(decoder.get_mut().take()).filter(|chunk| !chunk.is_empty()))
+            }
+            #[cfg(feature = "gzip")]
+            Self::Gzip(ref mut decoder) => {
+                decoder.try_finish()?;
+                Ok(Some(decoder.get_mut().take()).filter(|chunk| !chunk.is_empty()))
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd(ref mut decoder) => {
+                if decoder.hint == 0 {
+                    return Ok(None);
+                }
+                let (_, chunk) = decoder.decode(&[])?;
+                if !chunk.is_empty() {
+                    Ok(Some(chunk))
+                } else if decoder.hint == 0 {
+                    Ok(None)
+                } else {
+                    Err(IoError::new(ErrorKind::UnexpectedEof, "zstd frame is incomplete"))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "brotli")]
+impl BrotliDecoder {
+    fn decode(&mut self, input: &[u8]) -> IoResult<(usize, Bytes)> {
+        if self.done {
+            return Ok((0, Bytes::new()));
+        }
+        let mut output = vec![0; OUTPUT_BUFFER_SIZE];
+        let mut available_in = input.len();
+        let mut input_offset = 0;
+        let mut available_out = output.len();
+        let mut output_offset = 0;
+        let result = BrotliDecompressStream(
+            &mut available_in,
+            &mut input_offset,
+            input,
+            &mut available_out,
+            &mut output_offset,
+            &mut output,
+            &mut self.total_out,
+            &mut self.state,
+        );
+        match result {
+            BrotliResult::ResultFailure => return Err(IoError::new(ErrorKind::InvalidData, "invalid brotli data")),
+            BrotliResult::ResultSuccess => self.done = true,
+            BrotliResult::NeedsMoreInput | BrotliResult::NeedsMoreOutput => {}
+        }
+        output.truncate(output_offset);
+        Ok((input_offset, output.into()))
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl ZstdFrames {
+    fn decode(&mut self, input: &[u8]) -> IoResult<(usize, Bytes)> {
+        let mut output = vec![0; OUTPUT_BUFFER_SIZE];
+        let mut src = InBuffer::around(input);
+        let mut dst = OutBuffer::around(&mut output[..]);
+        // A new frame is started by zstd automatically after a frame is complete.
+        self.hint = self.decoder.run(&mut src, &mut dst)?;
+        let written = dst.pos();
+        output.truncate(written);
+        Ok((src.pos(), output.into()))
+    }
+}
@@ -0,0 +1,379 @@
+//! Decompress the body of a request.
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use bytes::{Buf, Bytes};
+use salvo_core::http::body::{Body, Frame, ReqBody};
+use salvo_core::http::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH};
+use salvo_core::http::StatusError;
+use salvo_core::{async_trait, BoxedError, Depot, FlowCtrl, Handler, Request, Response};
+
+use super::decoder::Decoder;
+use super::CompressionAlgo;
+
+/// Request decompression middleware, it decompresses request body which is encoded as described by the
+/// `Content-Encoding` header, so handlers always see a plain body.
+///
+/// `br`, `deflate`, `gzip` and `zstd` are supported when the corresponding features are enabled, multiple
+/// encodings such as `gzip, br` are decoded in reverse order. The body is decoded lazily while it is read, and
+/// the `Content-Encoding` and `Content-Length` headers are removed. Limits of request body size, such as
+/// [`Request::payload_with_max_size`], apply to the decompressed data, so they also protect from decompression
+/// bombs.
+///
+/// If an encoding is not supported, `415 Unsupported Media Type` is responded with an `Accept-Encoding` header
+/// listing supported encodings, as defined in [RFC 7694](https://www.rfc-editor.org/rfc/rfc7694). When
+/// [`strict`](Self::strict) is disabled, such request is passed to handlers untouched instead.
+///
+/// # Example
+///
+/// ```
+/// use salvo_compression::RequestDecompress;
+/// use salvo_core::prelude::*;
+///
+/// #[handler]
+/// async fn upload(req: &mut Request) -> String {
+///     req.parse_body::<String>().await.unwrap_or_default()
+/// }
+///
+/// let router = Router::new().hoop(RequestDecompress::new()).post(upload);
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RequestDecompress {
+    /// Respond `415 Unsupported Media Type` for unsupported encodings, default is `true`.
+    pub strict: bool,
+}
+
+impl Default for RequestDecompress {
+    #[inline]
+    fn default() -> Self {
+        Self { strict: true }
+    }
+}
+
+impl RequestDecompress {
+    /// Create a new `RequestDecompress`.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets whether to respond `415 Unsupported Media Type` for unsupported encodings, if it is `false`, such
+    /// request is passed to handlers with the body and headers untouched.
+    #[inline]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+}
+
+/// Parses `Content-Encoding` header, returns algorithms in order of decoding, or the unsupported encoding.
+fn parse_content_encoding(value: &str) -> Result<Vec<CompressionAlgo>, String> {
+    let mut algos = Vec::new();
+    for encoding in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let encoding = encoding.to_ascii_lowercase();
+        if encoding == "identity" {
+            continue;
+        }
+        match encoding.parse::<CompressionAlgo>() {
+            Ok(algo) => algos.push(algo),
+            Err(_) => return Err(encoding),
+        }
+    }
+    algos.reverse();
+    Ok(algos)
+}
+
+fn supported_encodings() -> HeaderValue {
+    let encodings = [
+        #[cfg(feature = "brotli")]
+        "br",
+        #[cfg(feature = "gzip")]
+        "gzip",
+        #[cfg(feature = "zstd")]
+        "zstd",
+        #[cfg(feature = "deflate")]
+        "deflate",
+        "identity",
+    ];
+    HeaderValue::from_str(&encodings.join(", ")).expect("invalid header value")
+}
+
+#[async_trait]
+impl Handler for RequestDecompress {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let Some(value) = req.headers().get(CONTENT_ENCODING) else {
+            return;
+        };
+        let algos = match value
+            .to_str()
+            .map_err(|_| String::from_utf8_lossy(value.as_bytes()).into_owned())
+        {
+            Ok(value) => parse_content_encoding(value),
+            Err(value) => Err(value),
+        };
+        match algos {
+            Ok(algos) => {
+                req.headers_mut().remove(CONTENT_ENCODING);
+                if !algos.is_empty() {
+                    req.headers_mut().remove(CONTENT_LENGTH);
+                    let body = req.take_body();
+                    req.replace_body(ReqBody::Boxed {
+                        inner: Box::pin(DecodeBody::new(body, algos)),
+                        fusewire: None,
+                    });
+                }
+            }
+            Err(encoding) => {
+                if self.strict {
+                    tracing::debug!(encoding, "unsupported request content encoding");
+                    res.headers_mut().insert(ACCEPT_ENCODING, supported_encodings());
+                    res.render(
+                        StatusError::unsupported_media_type()
+                            .brief(format!("Content encoding `{encoding}` is not supported.")),
+                    );
+                    ctrl.skip_rest();
+                    return;
+                }
+            }
+        }
+        ctrl.call_next(req, depot, res).await;
+    }
+}
+
+/// Body which decodes the inner body lazily, one bounded chunk is decoded in each poll, so a small compressed frame
+/// can not be expanded into memory at once.
+struct DecodeBody {
+    inner: ReqBody,
+    stages: Vec<Stage>,
+    eof: bool,
+}
+
+/// A decoder and its input which is not consumed yet.
+struct Stage {
+    decoder: Decoder,
+    input: Bytes,
+    // Whether all input of this stage is received.
+    input_ended: bool,
+    finished: bool,
+}
+
+impl DecodeBody {
+    fn new(inner: ReqBody, algos: Vec<CompressionAlgo>) -> Self {
+        Self {
+            inner,
+            stages: algos
+                .into_iter()
+                .map(|algo| Stage {
+                    decoder: Decoder::new(algo),
+                    input: Bytes::new(),
+                    input_ended: false,
+                    finished: false,
+                })
+                .collect(),
+            eof: false,
+        }
+    }
+
+    /// Runs one step of the last stage which has work to do, returns decoded data of the last stage, or `None` if
+    /// all stages need more input from the inner body.
+    fn step(&mut self) -> std::io::Result<Option<Bytes>> {
+        let last = self.stages.len() - 1;
+        // Later stages are drained first, so the input of a stage is always consumed before it gets more.
+        while let Some(index) = self
+            .stages
+            .iter()
+            .rposition(|stage| !stage.input.is_empty() || (stage.input_ended && !stage.finished))
+        {
+            let stage = &mut self.stages[index];
+            let chunk = if stage.input.is_empty() {
+                match stage.decoder.finish()? {
+                    Some(chunk) => chunk,
+                    None => {
+                        stage.finished = true;
+                        if index == last {
+                            self.eof = true;
+                        } else {
+                            self.stages[index + 1].input_ended = true;
+                        }
+                        continue;
+                    }
+                }
+            } else {
+                let (consumed, chunk) = stage.decoder.decode(&stage.input)?;
+                if consumed == 0 && chunk.is_empty() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "unexpected data after the end of compressed stream",
+                    ));
+                }
+                stage.input.advance(consumed);
+                chunk
+            };
+            if index == last {
+                if !chunk.is_empty() {
+                    return Ok(Some(chunk));
+                }
+            } else {
+                self.stages[index + 1].input = chunk;
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Body for DecodeBody {
+    type Data = Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, BoxedError>>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(chunk) = this.step()? {
+                return Poll::Ready(Some(Ok(Frame::data(chunk))));
+            }
+            if this.eof {
+                return Poll::Ready(None);
+            }
+            match ready!(Pin::new(&mut this.inner).poll_frame(cx)) {
+                Some(Ok(frame)) => match frame.into_data() {
+                    Ok(data) => this.stages[0].input = data,
+                    Err(frame) => return Poll::Ready(Some(Ok(frame))),
+                },
+                Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                None => this.stages[0].input_ended = true,
+            }
+        }
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.eof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn echo(req: &mut Request) -> Result<String, StatusError> {
+        assert!(req.headers().get(CONTENT_ENCODING).is_none());
+        let body = req
+            .payload()
+            .await
+            .map_err(|_| StatusError::bad_request().brief("decode body failed"))?;
+        Ok(String::from_utf8_lossy(body).into_owned())
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    async fn upload(service: &Service, encoding: &str, body: Vec<u8>) -> Response {
+        TestClient::post("http://127.0.0.1:5801/")
+            .add_header(CONTENT_ENCODING, encoding, true)
+            .bytes(body)
+            .send(service)
+            .await
+    }
+
+    #[test]
+    fn test_parse_content_encoding() {
+        assert_eq!(
+            parse_content_encoding("gzip, identity, BR").unwrap(),
+            vec![CompressionAlgo::Brotli, CompressionAlgo::Gzip]
+        );
+        assert!(parse_content_encoding("identity").unwrap().is_empty());
+        assert_eq!(parse_content_encoding("gzip, compress"), Err("compress".into()));
+    }
+
+    #[tokio::test]
+    async fn test_request_decompress() {
+        let content = "hello world ".repeat(1000);
+        let service = Service::new(Router::with_hoop(RequestDecompress::new()).post(echo));
+
+        let mut res = upload(&service, "gzip", gzip(content.as_bytes())).await;
+        assert_eq!(res.take_string().await.unwrap(), content);
+
+        let mut deflate = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::fast());
+        deflate.write_all(content.as_bytes()).unwrap();
+        let mut res = upload(&service, "deflate", deflate.finish().unwrap()).await;
+        assert_eq!(res.take_string().await.unwrap(), content);
+
+        let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        brotli.write_all(&gzip(content.as_bytes())).unwrap();
+        let mut res = upload(&service, "gzip, br", brotli.into_inner()).await;
+        assert_eq!(res.take_string().await.unwrap(), content);
+
+        let zstd = zstd::encode_all(content.as_bytes(), 1).unwrap();
+        let mut res = upload(&service, "zstd", zstd).await;
+        assert_eq!(res.take_string().await.unwrap(), content);
+
+        let mut res = upload(&service, "identity", content.clone().into_bytes()).await;
+        assert_eq!(res.take_string().await.unwrap(), content);
+
+        // Truncated data can not be decoded.
+        let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        brotli.write_all(content.as_bytes()).unwrap();
+        for (encoding, mut data) in [
+            ("gzip", gzip(content.as_bytes())),
+            ("zstd", zstd::encode_all(content.as_bytes(), 1).unwrap()),
+            ("br", brotli.into_inner()),
+        ] {
+            data.truncate(data.len() / 2);
+            let res = upload(&service, encoding, data).await;
+            assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST), "{encoding}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_body_bounded() {
+        use futures_util::future::poll_fn;
+
+        let content = vec![0; 8 * 1024 * 1024];
+        let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        brotli.write_all(&zstd::encode_all(&content[..], 19).unwrap()).unwrap();
+        for (encoding, data) in [("zstd, br", brotli.into_inner()), ("gzip", gzip(&content))] {
+            let algos = parse_content_encoding(encoding).unwrap();
+            let mut body = DecodeBody::new(ReqBody::Once(data.into()), algos);
+            let mut total = 0;
+            while let Some(frame) = poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await {
+                let chunk = frame.unwrap().into_data().unwrap();
+                assert!(chunk.len() <= 64 * 1024, "{encoding}: {}", chunk.len());
+                assert!(chunk.iter().all(|b| *b == 0));
+                total += chunk.len();
+            }
+            assert_eq!(total, content.len(), "{encoding}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_decompress_unsupported() {
+        #[handler]
+        async fn raw(req: &mut Request) -> String {
+            let encoding = req.header::<String>(CONTENT_ENCODING).unwrap_or_default();
+            let body = req.payload().await.unwrap();
+            format!("{encoding}:{}", String::from_utf8_lossy(body))
+        }
+
+        let service = Service::new(Router::with_hoop(RequestDecompress::new()).post(raw));
+        let res = upload(&service, "compress", b"hello".to_vec()).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+        assert_eq!(
+            res.headers().get(ACCEPT_ENCODING).unwrap(),
+            "br, gzip, zstd, deflate, identity"
+        );
+
+        let service = Service::new(Router::with_hoop(RequestDecompress::new().strict(false)).post(raw));
+        let mut res = upload(&service, "compress", b"hello".to_vec()).await;
+        assert_eq!(res.take_string().await.unwrap(), "compress:hello");
+    }
+}
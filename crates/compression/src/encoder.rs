@@ -19,14 +19,14 @@ pub(super) struct Writer {
 
 impl Writer {
     #[allow(dead_code)]
-    fn new() -> Writer {
+    pub(super) fn new() -> Writer {
         Writer {
             buf: BytesMut::with_capacity(8192),
         }
     }
 
     #[allow(dead_code)]
-    fn take(&mut self) -> Bytes {
+    pub(super) fn take(&mut self) -> Bytes {
         self.buf.split().freeze()
     }
 }
@@ -85,8 +85,8 @@ impl CompressionLevel {
     fn into_zstd(self) -> ZstdEncoder<'static, Writer> {
         let quality = match self {
             Self::Fastest => 1,
-            Self::Minsize => 21,
-            Self::Precise(quality) => quality.min(21) as i32,
+            Self::Minsize => 22,
+            Self::Precise(quality) => quality.clamp(1, 22) as i32,
             Self::Default => 1,
         };
         ZstdEncoder::new(Writer::new(), quality).expect("`ZstdEncoder::new` returned an error")
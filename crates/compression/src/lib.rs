@@ -14,11 +14,17 @@ use salvo_core::http::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, C
 use salvo_core::http::{self, mime, Mime, StatusCode};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Request, Response};
 
+mod decoder;
+mod decompress;
 mod encoder;
+mod router;
 mod stream;
 use encoder::Encoder;
 use stream::EncodeStream;
 
+pub use decompress::RequestDecompress;
+pub use router::AcceptEncodingRouter;
+
 /// Level of compression data should be compressed with.
 #[non_exhaustive]
 #[derive(Clone, Copy, Default, Debug, Eq, PartialEq)]
@@ -119,7 +125,12 @@ impl From<CompressionAlgo> for HeaderValue {
     }
 }
 
-/// Compression
+/// Compression middleware, it compresses response body with algorithm negotiated by `Accept-Encoding` header.
+///
+/// If the client accepts multiple algorithms with the same quality, the order of `algos` is used, by default
+/// the order is `br`, `gzip`, `zstd` and `deflate`. `zstd` is not supported by all clients yet, so it is used as
+/// a secondary encoding after `br` and `gzip`. Use [`AcceptEncodingRouter`] to dispatch to different handlers,
+/// for example compressions with different settings, by the best accepted encoding.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
 pub struct Compression {
@@ -137,14 +148,14 @@ impl Default for Compression {
     fn default() -> Self {
         #[allow(unused_mut)]
         let mut algos = IndexMap::new();
-        #[cfg(feature = "zstd")]
-        algos.insert(CompressionAlgo::Zstd, CompressionLevel::Default);
+        #[cfg(feature = "brotli")]
+        algos.insert(CompressionAlgo::Brotli, CompressionLevel::Default);
         #[cfg(feature = "gzip")]
         algos.insert(CompressionAlgo::Gzip, CompressionLevel::Default);
+        #[cfg(feature = "zstd")]
+        algos.insert(CompressionAlgo::Zstd, CompressionLevel::Default);
         #[cfg(feature = "deflate")]
         algos.insert(CompressionAlgo::Deflate, CompressionLevel::Default);
-        #[cfg(feature = "brotli")]
-        algos.insert(CompressionAlgo::Brotli, CompressionLevel::Default);
         Self {
             algos,
             content_types: vec![
@@ -193,6 +204,11 @@ impl Compression {
         self
     }
     /// Enable zstd compression.
+    ///
+    /// Use `CompressionLevel::Precise` to set zstd level, it is clamped to `1..=22`.
+    ///
+    /// Zstd is not universally supported by clients yet, enable it after `br` and `gzip`, so it is used only when
+    /// clients prefer it by quality value or don't accept the others.
     #[cfg(feature = "zstd")]
     #[cfg_attr(docsrs, doc(cfg(feature = "zstd")))]
     #[inline]
@@ -289,17 +305,9 @@ impl Compression {
                 return None;
             }
         }
-        let header = req.headers().get(ACCEPT_ENCODING).and_then(|v| v.to_str().ok())?;
-
-        let accept_algos = http::parse_accept_encoding(header)
+        let accept_algos = accepted_algos(req)
             .into_iter()
-            .filter_map(|(algo, level)| {
-                if let Ok(algo) = algo.parse::<CompressionAlgo>() {
-                    Some((algo, level))
-                } else {
-                    None
-                }
-            })
+            .filter(|(algo, _)| self.algos.contains_key(algo))
             .collect::<Vec<_>>();
         if self.force_priority {
             let accept_algos = accept_algos.into_iter().map(|(algo, _)| algo).collect::<Vec<_>>();
@@ -308,13 +316,36 @@ impl Compression {
                 .find(|(algo, _level)| accept_algos.contains(algo))
                 .map(|(algo, level)| (*algo, *level))
         } else {
-            accept_algos
-                .into_iter()
-                .find_map(|(algo, _)| self.algos.get(&algo).map(|level| (algo, *level)))
+            let algo = best_algo(&accept_algos, self.algos.keys().copied())?;
+            self.algos.get(&algo).map(|level| (algo, *level))
         }
     }
 }
 
+/// Algorithms accepted by the request's `Accept-Encoding` header with their qualities.
+///
+/// `q=0` means the encoding is not acceptable, so such algorithms are excluded.
+fn accepted_algos(req: &Request) -> Vec<(CompressionAlgo, u8)> {
+    let Some(header) = req.headers().get(ACCEPT_ENCODING).and_then(|v| v.to_str().ok()) else {
+        return vec![];
+    };
+    http::parse_accept_encoding(header)
+        .into_iter()
+        .filter(|(_, q)| *q > 0)
+        .filter_map(|(algo, q)| algo.parse::<CompressionAlgo>().ok().map(|algo| (algo, q)))
+        .collect()
+}
+
+/// Highest quality wins, if qualities are equal, the order of `candidates` is used, so `br` is preferred over
+/// `gzip` by default.
+fn best_algo(
+    accept_algos: &[(CompressionAlgo, u8)],
+    mut candidates: impl Iterator<Item = CompressionAlgo>,
+) -> Option<CompressionAlgo> {
+    let max_q = accept_algos.iter().map(|(_, q)| *q).max()?;
+    candidates.find(|algo| accept_algos.contains(&(*algo, max_q)))
+}
+
 #[async_trait]
 impl Handler for Compression {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
@@ -437,6 +468,65 @@ mod tests {
         assert_eq!(content, "hello");
     }
 
+    #[tokio::test]
+    async fn test_prefer_brotli() {
+        let comp_handler = Compression::new().min_length(1);
+        let router = Router::with_hoop(comp_handler).push(Router::with_path("hello").get(hello));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "gzip, deflate, br", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "br");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "gzip, br;q=0.8", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "br;q=0", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_zstd() {
+        let comp_handler = Compression::new().min_length(1);
+        let router = Router::with_hoop(comp_handler).push(Router::with_path("hello").get(hello));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "zstd", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "zstd");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "zstd, gzip", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+
+        let comp_handler = Compression::new()
+            .disable_all()
+            .enable_zstd(CompressionLevel::Precise(19))
+            .enable_gzip(CompressionLevel::Default)
+            .min_length(1);
+        let router = Router::with_hoop(comp_handler).push(Router::with_path("hello").get(hello));
+        let mut res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "gzip, zstd", true)
+            .send(router)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "zstd");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+    }
+
     #[tokio::test]
     async fn test_deflate() {
         let comp_handler = Compression::new().min_length(1);
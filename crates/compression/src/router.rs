@@ -0,0 +1,151 @@
+//! Dispatch requests by the best accepted encoding.
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Request, Response};
+
+use super::{accepted_algos, best_algo, CompressionAlgo};
+
+/// `AcceptEncodingRouter` dispatches requests to handlers by the best encoding accepted in `Accept-Encoding` header.
+///
+/// The encoding with the highest quality value wins, if qualities are equal, the order in which handlers are added
+/// is used. When no added encoding is acceptable, the fallback handler is called, or the rest of the chain is
+/// executed directly if there is no fallback.
+///
+/// Since zstd is not universally supported by clients yet, it is usually added after brotli or gzip, so it is only
+/// used when clients prefer it or don't accept the others:
+///
+/// ```
+/// use salvo_compression::{AcceptEncodingRouter, Compression, CompressionAlgo, CompressionLevel};
+/// use salvo_core::prelude::*;
+///
+/// #[handler]
+/// async fn hello() -> &'static str {
+///     "hello"
+/// }
+///
+/// let encoding = AcceptEncodingRouter::new()
+///     .encoding(
+///         CompressionAlgo::Brotli,
+///         Compression::new().disable_all().enable_brotli(CompressionLevel::Default),
+///     )
+///     .encoding(
+///         CompressionAlgo::Gzip,
+///         Compression::new().disable_all().enable_gzip(CompressionLevel::Default),
+///     )
+///     .encoding(
+///         CompressionAlgo::Zstd,
+///         Compression::new().disable_all().enable_zstd(CompressionLevel::Precise(19)),
+///     );
+/// let router = Router::new().hoop(encoding).get(hello);
+/// ```
+#[derive(Default)]
+#[non_exhaustive]
+pub struct AcceptEncodingRouter {
+    /// Handlers for encodings, in order of preference.
+    pub handlers: IndexMap<CompressionAlgo, Arc<dyn Handler>>,
+    /// Handler which is called when no encoding is acceptable.
+    pub fallback: Option<Arc<dyn Handler>>,
+}
+
+impl AcceptEncodingRouter {
+    /// Create a new `AcceptEncodingRouter`.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a handler for `algo`, it is used when `algo` is the best accepted encoding.
+    #[inline]
+    pub fn encoding<H: Handler>(mut self, algo: CompressionAlgo, handler: H) -> Self {
+        self.handlers.insert(algo, Arc::new(handler));
+        self
+    }
+
+    /// Sets the handler which is called when no encoding is acceptable.
+    #[inline]
+    pub fn fallback<H: Handler>(mut self, handler: H) -> Self {
+        self.fallback = Some(Arc::new(handler));
+        self
+    }
+
+    fn negotiate(&self, req: &Request) -> Option<&Arc<dyn Handler>> {
+        let accept_algos = accepted_algos(req);
+        let algo = best_algo(&accept_algos, self.handlers.keys().copied())?;
+        self.handlers.get(&algo)
+    }
+}
+
+#[async_trait]
+impl Handler for AcceptEncodingRouter {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        match self.negotiate(req).or(self.fallback.as_ref()) {
+            Some(handler) => handler.handle(req, depot, res, ctrl).await,
+            None => {
+                ctrl.call_next(req, depot, res).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+    use crate::{Compression, CompressionLevel};
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    #[handler]
+    async fn identity(res: &mut Response) {
+        res.headers_mut().insert("x-fallback", "1".parse().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_accept_encoding_router() {
+        let encoding = AcceptEncodingRouter::new()
+            .encoding(
+                CompressionAlgo::Gzip,
+                Compression::new()
+                    .disable_all()
+                    .enable_gzip(CompressionLevel::Default)
+                    .min_length(1),
+            )
+            .encoding(
+                CompressionAlgo::Zstd,
+                Compression::new()
+                    .disable_all()
+                    .enable_zstd(CompressionLevel::Minsize)
+                    .min_length(1),
+            )
+            .fallback(identity);
+        let service = Service::new(Router::with_hoop(encoding).push(Router::with_path("hello").get(hello)));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "zstd, gzip", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "zstd, gzip;q=0.5", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "zstd");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "br, gzip;q=0", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.headers().get("x-fallback").unwrap(), "1");
+    }
+}
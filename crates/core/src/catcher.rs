@@ -43,7 +43,9 @@ use once_cell::sync::Lazy;
 use serde::Serialize;
 
 use crate::handler::{Handler, WhenHoop};
+use crate::http::errors::problem_detail;
 use crate::http::{guess_accept_mime, header, Request, ResBody, Response, StatusCode, StatusError};
+use crate::writing::escape_html;
 use crate::{Depot, FlowCtrl};
 
 static SUPPORTED_FORMATS: Lazy<Vec<mime::Name>> = Lazy::new(|| vec![mime::JSON, mime::HTML, mime::XML, mime::PLAIN]);
@@ -131,11 +133,15 @@ where
 #[derive(Default)]
 pub struct DefaultGoal {
     footer: Option<Cow<'static, str>>,
+    problem_details: bool,
 }
 impl DefaultGoal {
     /// Create new `DefaultGoal`.
     pub fn new() -> Self {
-        DefaultGoal { footer: None }
+        DefaultGoal {
+            footer: None,
+            problem_details: false,
+        }
     }
     /// Create new `DefaultGoal` with custom footer.
     #[inline]
@@ -151,18 +157,55 @@ impl DefaultGoal {
         self.footer = Some(footer.into());
         self
     }
+
+    /// Sets whether to send errors as [`ProblemDetail`](crate::http::errors::ProblemDetail) when the request
+    /// prefers JSON or XML to HTML, default is `false`.
+    ///
+    /// Errors are sent as `application/problem+json` or `application/problem+xml`, converted by
+    /// [`StatusError::into_problem`].
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use salvo_core::catcher::{Catcher, DefaultGoal};
+    /// use salvo_core::prelude::*;
+    ///
+    /// let goal: Arc<dyn Handler> = Arc::new(DefaultGoal::new().problem_details(true));
+    /// let service = Service::new(Router::new()).catcher(Catcher::new(goal));
+    /// ```
+    pub fn problem_details(mut self, enabled: bool) -> Self {
+        self.problem_details = enabled;
+        self
+    }
 }
 #[async_trait]
 impl Handler for DefaultGoal {
     async fn handle(&self, req: &mut Request, _depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
         let status = res.status_code.unwrap_or(StatusCode::NOT_FOUND);
         if (status.is_server_error() || status.is_client_error()) && (res.body.is_none() || res.body.is_error()) {
-            write_error_default(req, res, self.footer.as_deref());
+            if self.problem_details && problem_detail::preferred_format(req).is_some() {
+                let err = match res.take_body() {
+                    ResBody::Error(err) => err,
+                    _ => StatusError::from_code(status).unwrap_or_else(StatusError::internal_server_error),
+                };
+                err.into_problem().render_negotiated(req, res);
+            } else {
+                write_error_default(req, res, self.footer.as_deref());
+            }
         }
     }
 }
 
-fn status_error_html(code: StatusCode, name: &str, brief: &str, cause: Option<&str>, footer: Option<&str>) -> String {
+fn status_error_html(err: &StatusError, cause: Option<&str>, footer: Option<&str>) -> String {
+    let mut detail = String::new();
+    let extra = err.extra.as_deref();
+    if let Some(error_code) = extra.and_then(|extra| extra.code.as_deref()) {
+        detail.push_str(&format!("<p><code>{}</code></p>", escape_html(error_code)));
+    }
+    if let Some(data) = extra.and_then(|extra| extra.data.as_ref()) {
+        let data = serde_json::to_string_pretty(data).unwrap_or_default();
+        detail.push_str(&format!("<pre>{}</pre>", escape_html(&data)));
+    }
     format!(
         r#"<!DOCTYPE html>
 <html>
@@ -195,19 +238,20 @@ fn status_error_html(code: StatusCode, name: &str, brief: &str, cause: Option<&s
     </style>
 </head>
 <body>
-    <div><h1>{0}: {1}</h1><h3>{2}</h3><pre>{3}</pre><hr><footer>{4}</footer></div>
+    <div><h1>{0}: {1}</h1><h3>{2}</h3>{3}<pre>{4}</pre><hr><footer>{5}</footer></div>
 </body>
 </html>"#,
-        code.as_u16(),
-        name,
-        brief,
+        err.code.as_u16(),
+        err.name,
+        escape_html(err.message()),
+        detail,
         cause.unwrap_or(EMPTY_CAUSE_MSG),
         footer.unwrap_or(SALVO_LINK)
     )
 }
 
 #[inline]
-fn status_error_json(code: StatusCode, name: &str, brief: &str, cause: Option<&str>) -> String {
+fn status_error_json(err: &StatusError, cause: Option<&str>) -> String {
     #[derive(Serialize)]
     struct Data<'a> {
         error: Error<'a>,
@@ -217,13 +261,21 @@ fn status_error_json(code: StatusCode, name: &str, brief: &str, cause: Option<&s
         code: u16,
         name: &'a str,
         brief: &'a str,
+        message: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error_code: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        data: Option<&'a serde_json::Value>,
         cause: &'a str,
     }
     let data = Data {
         error: Error {
-            code: code.as_u16(),
-            name,
-            brief,
+            code: err.code.as_u16(),
+            name: &err.name,
+            brief: &err.brief,
+            message: err.message(),
+            error_code: err.extra.as_ref().and_then(|extra| extra.code.as_deref()),
+            data: err.extra.as_ref().and_then(|extra| extra.data.as_ref()),
             cause: cause.unwrap_or(EMPTY_CAUSE_MSG),
         },
     };
@@ -273,9 +325,9 @@ pub fn status_error_bytes(err: &StatusError, prefer_format: &Mime, footer: Optio
     let cause: Option<String> = None;
     let content = match format.subtype().as_ref() {
         "plain" => status_error_plain(err.code, &err.name, &err.brief, cause.as_deref()),
-        "json" => status_error_json(err.code, &err.name, &err.brief, cause.as_deref()),
+        "json" => status_error_json(err, cause.as_deref()),
         "xml" => status_error_xml(err.code, &err.name, &err.brief, cause.as_deref()),
-        _ => status_error_html(err.code, &err.name, &err.brief, cause.as_deref(), footer),
+        _ => status_error_html(err, cause.as_deref(), footer),
     };
     (format, Bytes::from(content))
 }
@@ -365,4 +417,83 @@ mod tests {
 
         assert_eq!(access(&service, "notfound").await, "Custom 404 Error Page");
     }
+
+    #[tokio::test]
+    async fn test_problem_details() {
+        #[handler]
+        async fn ship() -> Result<(), StatusError> {
+            Err(StatusError::conflict().detail("order already shipped"))
+        }
+        let router = Router::with_path("ship").post(ship);
+        let goal: Arc<dyn Handler> = Arc::new(DefaultGoal::new().problem_details(true));
+        let service = Service::new(router).catcher(Catcher::new(goal));
+
+        let mut res = TestClient::post("http://127.0.0.1:5800/ship")
+            .add_header("accept", "text/html;q=0.8, application/json", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::CONFLICT));
+        assert_eq!(res.content_type().unwrap().to_string(), "application/problem+json");
+        assert_eq!(
+            res.take_json::<serde_json::Value>().await.unwrap(),
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Conflict",
+                "status": 409,
+                "detail": "order already shipped",
+            })
+        );
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/missing")
+            .add_header("accept", "application/problem+xml", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        assert_eq!(res.content_type().unwrap().to_string(), "application/problem+xml");
+        assert!(res
+            .take_string()
+            .await
+            .unwrap()
+            .contains("<title>Not Found</title><status>404</status>"));
+
+        let res = TestClient::post("http://127.0.0.1:5800/ship")
+            .add_header("accept", "text/html, application/json", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.content_type().unwrap().subtype(), mime::HTML);
+    }
+
+    #[tokio::test]
+    async fn test_status_error_with_data() {
+        #[handler]
+        async fn register() -> Result<(), StatusError> {
+            Err(StatusError::unprocessable_entity()
+                .detail("email is invalid")
+                .error_code("invalid_email")
+                .data(serde_json::json!({"field": "email", "value": "<jobs>"})))
+        }
+        let service = Service::new(Router::with_path("register").post(register));
+
+        let mut res = TestClient::post("http://127.0.0.1:5800/register")
+            .add_header("accept", "application/json", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        let error = res.take_json::<serde_json::Value>().await.unwrap()["error"].take();
+        assert_eq!(error["code"], 422);
+        assert_eq!(error["message"], "email is invalid");
+        assert_eq!(error["error_code"], "invalid_email");
+        assert_eq!(error["data"], serde_json::json!({"field": "email", "value": "<jobs>"}));
+
+        let mut res = TestClient::post("http://127.0.0.1:5800/register")
+            .add_header("accept", "text/html", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        assert_eq!(res.content_type().unwrap().subtype(), mime::HTML);
+        let html = res.take_string().await.unwrap();
+        assert!(html.contains("<h1>422: Unprocessable Entity</h1><h3>email is invalid</h3>"));
+        assert!(html.contains("<p><code>invalid_email</code></p>"));
+        assert!(html.contains("&quot;value&quot;: &quot;&lt;jobs&gt;&quot;"));
+    }
 }
@@ -367,6 +367,7 @@ where
             local_addr,
             remote_addr,
             http_version,
+            extensions,
             ..
         } = self.inner.accept(fuse_factory).await?;
         let fusewire = conn.fusewire();
@@ -376,6 +377,7 @@ where
             remote_addr,
             http_version,
             http_scheme: Scheme::HTTPS,
+            extensions,
         })
     }
 }
@@ -0,0 +1,190 @@
+//! Informational (`1xx`) responses on HTTP/1.1 connections.
+//!
+//! hyper does not provide a way for servers to send informational responses other than `100 Continue`, so they are
+//! written to the connection directly, before hyper starts to write the final response.
+use std::future::poll_fn;
+use std::io::{ErrorKind, IoSlice, Result as IoResult};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+
+use http::{HeaderMap, Request, StatusCode};
+use hyper::service::Service;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+struct Shared<I> {
+    io: I,
+    // Whether hyper has started to write the response of current request.
+    responding: bool,
+    // Whether bytes written by hyper are not flushed yet.
+    unflushed: bool,
+}
+
+impl<I> Shared<I> {
+    fn wrote(&mut self) {
+        self.responding = true;
+        self.unflushed = true;
+    }
+}
+
+/// Connection which can be written by [`InterimSender`] between the writes of hyper.
+pub(crate) struct InterimIo<I> {
+    shared: Arc<Mutex<Shared<I>>>,
+}
+
+impl<I> InterimIo<I>
+where
+    I: AsyncWrite + Unpin + Send + 'static,
+{
+    pub(crate) fn new(io: I) -> (Self, InterimSender) {
+        let shared = Arc::new(Mutex::new(Shared {
+            io,
+            responding: false,
+            unflushed: false,
+        }));
+        let sender = InterimSender(shared.clone());
+        (Self { shared }, sender)
+    }
+}
+
+fn lock<I>(shared: &Mutex<Shared<I>>) -> MutexGuard<'_, Shared<I>> {
+    // The connection is still usable if a writer panicked.
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl<I> AsyncRead for InterimIo<I>
+where
+    I: AsyncRead + Unpin,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<IoResult<()>> {
+        Pin::new(&mut lock(&self.shared).io).poll_read(cx, buf)
+    }
+}
+
+impl<I> AsyncWrite for InterimIo<I>
+where
+    I: AsyncWrite + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        let mut shared = lock(&self.shared);
+        let result = Pin::new(&mut shared.io).poll_write(cx, buf);
+        if matches!(result, Poll::Ready(Ok(n)) if n > 0) {
+            shared.wrote();
+        }
+        result
+    }
+
+    fn poll_write_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &[IoSlice<'_>]) -> Poll<IoResult<usize>> {
+        let mut shared = lock(&self.shared);
+        let result = Pin::new(&mut shared.io).poll_write_vectored(cx, bufs);
+        if matches!(result, Poll::Ready(Ok(n)) if n > 0) {
+            shared.wrote();
+        }
+        result
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        lock(&self.shared).io.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        let mut shared = lock(&self.shared);
+        let result = Pin::new(&mut shared.io).poll_flush(cx);
+        if matches!(result, Poll::Ready(Ok(()))) {
+            shared.unflushed = false;
+        }
+        result
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Pin::new(&mut lock(&self.shared).io).poll_shutdown(cx)
+    }
+}
+
+trait InterimWrite: Send + Sync {
+    fn begin(&self);
+    fn is_responding(&self) -> bool;
+    fn poll_write_interim(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>>;
+    fn poll_flush_interim(&self, cx: &mut Context<'_>) -> Poll<IoResult<()>>;
+}
+
+impl<I> InterimWrite for Mutex<Shared<I>>
+where
+    I: AsyncWrite + Unpin + Send,
+{
+    fn begin(&self) {
+        lock(self).responding = false;
+    }
+    fn is_responding(&self) -> bool {
+        let shared = lock(self);
+        shared.responding || shared.unflushed
+    }
+    fn poll_write_interim(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        Pin::new(&mut lock(self).io).poll_write(cx, buf)
+    }
+    fn poll_flush_interim(&self, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        Pin::new(&mut lock(self).io).poll_flush(cx)
+    }
+}
+
+/// Sender of informational responses, it is inserted into extensions of HTTP/1.1 requests.
+#[derive(Clone)]
+pub(crate) struct InterimSender(Arc<dyn InterimWrite>);
+
+impl InterimSender {
+    /// Writes an informational response to the connection, `status` must be checked by caller.
+    ///
+    /// Returns `false` if it is skipped because hyper has started to write the final response.
+    pub(crate) async fn send(&self, status: StatusCode, headers: &HeaderMap) -> IoResult<bool> {
+        if self.0.is_responding() {
+            return Ok(false);
+        }
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\n",
+            status.as_str(),
+            status.canonical_reason().unwrap_or_default()
+        )
+        .into_bytes();
+        for (name, value) in headers {
+            head.extend_from_slice(name.as_str().as_bytes());
+            head.extend_from_slice(b": ");
+            head.extend_from_slice(value.as_bytes());
+            head.extend_from_slice(b"\r\n");
+        }
+        head.extend_from_slice(b"\r\n");
+        let mut written = 0;
+        while written < head.len() {
+            let n = poll_fn(|cx| self.0.poll_write_interim(cx, &head[written..])).await?;
+            if n == 0 {
+                return Err(ErrorKind::WriteZero.into());
+            }
+            written += n;
+        }
+        poll_fn(|cx| self.0.poll_flush_interim(cx)).await?;
+        Ok(true)
+    }
+}
+
+/// Service which inserts [`InterimSender`] into extensions of HTTP/1.1 requests, informational responses are not
+/// allowed on HTTP/1.0.
+pub(crate) struct InterimService<S> {
+    pub(crate) inner: S,
+    pub(crate) sender: InterimSender,
+}
+
+impl<S, B> Service<Request<B>> for InterimService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn call(&self, mut req: Request<B>) -> Self::Future {
+        if req.version() == http::Version::HTTP_11 {
+            self.sender.0.begin();
+            req.extensions_mut().insert(self.sender.clone());
+        }
+        self.inner.call(req)
+    }
+}
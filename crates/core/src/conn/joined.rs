@@ -68,6 +68,24 @@ where
 }
 
 /// `JoinedListener` is a listener that can join two listeners.
+///
+/// Connections are accepted from both listeners concurrently, and each [`Accepted`] keeps the scheme and
+/// version of the listener it comes from. Use [`Listener::join`] repeatedly to combine more listeners, for example
+/// h1/h2 over TLS, h3 over QUIC and a plaintext listener on another port:
+///
+/// ```no_run
+/// use salvo_core::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let acceptor = TcpListener::new("0.0.0.0:5800")
+///         .join(TcpListener::new("0.0.0.0:5801"))
+///         .join(TcpListener::new("0.0.0.0:5802"))
+///         .bind()
+///         .await;
+///     Server::new(acceptor).serve(Router::new()).await;
+/// }
+/// ```
 #[pin_project]
 pub struct JoinedListener<A, B> {
     #[pin]
@@ -129,7 +147,10 @@ where
         }
     }
     fn fusewire(&self) -> Option<ArcFusewire> {
-        None
+        match self {
+            JoinedStream::A(a) => a.fusewire(),
+            JoinedStream::B(b) => b.fusewire(),
+        }
     }
 }
 
@@ -187,4 +208,34 @@ mod tests {
         let second = conn.read_i32().await.unwrap();
         assert_eq!(first + second, 150);
     }
+
+    #[tokio::test]
+    async fn test_joined_listener_serve() {
+        use crate::prelude::*;
+
+        #[handler]
+        async fn hello(req: &mut Request) -> String {
+            format!("hello {}", req.scheme())
+        }
+
+        let addr1 = std::net::SocketAddr::from(([127, 0, 0, 1], 6980));
+        let addr2 = std::net::SocketAddr::from(([127, 0, 0, 1], 6981));
+        let acceptor = TcpListener::new(addr1).join(TcpListener::new(addr2)).bind().await;
+        let server = Server::new(acceptor);
+        let handle = server.handle();
+        tokio::spawn(server.serve(Router::new().get(hello)));
+
+        for addr in [addr1, addr2] {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            assert!(response.starts_with("HTTP/1.1 200"));
+            assert!(response.ends_with("hello http"));
+        }
+        handle.stop_forcible();
+    }
 }
@@ -6,13 +6,17 @@
 use std::fmt::{self, Display, Formatter};
 use std::future::Future;
 use std::io::Result as IoResult;
+use std::sync::{Arc, OnceLock};
 
 use http::uri::Scheme;
+use http::Extensions;
 use tokio::io::{AsyncRead, AsyncWrite};
 
 use crate::fuse::ArcFuseFactory;
 use crate::http::{HttpConnection, Version};
 
+#[cfg(feature = "http1")]
+pub(crate) mod interim;
 mod proto;
 pub use proto::HttpBuilder;
 mod stream;
@@ -45,6 +49,7 @@ cfg_feature! {
 cfg_feature! {
     #![feature = "http2"]
     pub use hyper::server::conn::http2;
+    pub use proto::Http2Config;
 }
 cfg_feature! {
     #![feature = "quinn"]
@@ -61,6 +66,9 @@ pub use addr::SocketAddr;
 pub mod tcp;
 pub use tcp::TcpListener;
 
+pub mod proxy_protocol;
+pub use proxy_protocol::ProxyProtocol;
+
 mod joined;
 pub use joined::JoinedListener;
 
@@ -98,6 +106,10 @@ where
     pub http_scheme: Scheme,
     /// Http version.
     pub http_version: Version,
+    /// Connection scoped data, such as tls peer certificate or the source address from proxy protocol.
+    ///
+    /// It is shared by all requests of the connection through [`ConnectionInfo`].
+    pub extensions: Extensions,
 }
 
 impl<C> Accepted<C>
@@ -116,6 +128,7 @@ where
             remote_addr,
             http_version,
             http_scheme,
+            extensions,
         } = self;
         Accepted {
             conn: wrap_fn(conn),
@@ -123,6 +136,48 @@ where
             remote_addr,
             http_version,
             http_scheme,
+            extensions,
+        }
+    }
+}
+
+/// Information of an accepted connection, it is shared by all requests of the connection.
+///
+/// It is inserted into the extensions of every request as `Arc<ConnectionInfo>`, and can be got by
+/// [`Request::conn_info`](crate::http::Request::conn_info).
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ConnectionInfo {
+    /// Local addr.
+    pub local_addr: SocketAddr,
+    /// Remote addr.
+    pub remote_addr: SocketAddr,
+    /// Http scheme.
+    pub http_scheme: Scheme,
+    /// Connection scoped data set by acceptors.
+    pub extensions: Extensions,
+}
+impl ConnectionInfo {
+    /// Create new `ConnectionInfo`.
+    #[inline]
+    pub fn new(local_addr: SocketAddr, remote_addr: SocketAddr, http_scheme: Scheme, extensions: Extensions) -> Self {
+        Self {
+            local_addr,
+            remote_addr,
+            http_scheme,
+            extensions,
+        }
+    }
+}
+
+/// Negotiated ALPN protocol in connection extensions, it is set by tls acceptors after the handshake is done.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AlpnProtocol(pub(crate) Arc<OnceLock<String>>);
+impl AlpnProtocol {
+    #[cfg_attr(not(any(feature = "openssl", feature = "rustls")), allow(dead_code))]
+    pub(crate) fn set(&self, protocol: Option<&[u8]>) {
+        if let Some(protocol) = protocol {
+            let _ = self.0.set(String::from_utf8_lossy(protocol).into_owned());
         }
     }
 }
@@ -157,6 +157,7 @@ where
             local_addr,
             remote_addr,
             http_version,
+            extensions,
             ..
         } = self.inner.accept(fuse_factory.clone()).await?;
         let fusewire = conn.fusewire();
@@ -172,6 +173,7 @@ where
             remote_addr,
             http_version,
             http_scheme: Scheme::HTTPS,
+            extensions,
         })
     }
 }
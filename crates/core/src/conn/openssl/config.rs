@@ -1,13 +1,13 @@
 //! openssl module
 use std::fmt::{self, Formatter};
-use std::fs::File;
-use std::future::{Ready, ready};
-use std::io::{Error as IoError, Read, Result as IoResult};
+use std::future::{ready, Ready};
+use std::io::{Error as IoError, Result as IoResult};
 use std::path::Path;
 
 use futures_util::stream::{once, Once, Stream};
 use openssl::pkey::PKey;
-use openssl::ssl::{SslAcceptor, SslMethod, SslRef};
+use openssl::ssl::{SslAcceptor, SslMethod, SslRef, SslVerifyMode};
+use openssl::x509::store::X509StoreBuilder;
 use openssl::x509::X509;
 use tokio::io::ErrorKind;
 
@@ -20,6 +20,8 @@ pub use openssl::ssl::SslAcceptorBuilder;
 pub struct Keycert {
     key: Vec<u8>,
     cert: Vec<u8>,
+    key_in_memory: bool,
+    cert_in_memory: bool,
 }
 
 impl Default for Keycert {
@@ -35,35 +37,46 @@ impl Keycert {
         Self {
             key: vec![],
             cert: vec![],
+            key_in_memory: false,
+            cert_in_memory: false,
         }
     }
     /// Sets the Tls private key via File Path, returns [`IoError`] if the file cannot be open.
+    ///
+    /// The file is not read if the private key is already set via PEM bytes by [`Keycert::with_key`], so secrets
+    /// injected in memory take precedence over files.
     #[inline]
     pub fn key_from_path(mut self, path: impl AsRef<Path>) -> IoResult<Self> {
-        let mut file = File::open(path.as_ref())?;
-        file.read_to_end(&mut self.key)?;
+        if !self.key_in_memory {
+            self.key = std::fs::read(path)?;
+        }
         Ok(self)
     }
 
-    /// Sets the Tls private key via bytes slice.
+    /// Sets the Tls private key via PEM bytes, it takes precedence over the file set by `key_from_path`.
     #[inline]
     pub fn with_key(mut self, key: impl Into<Vec<u8>>) -> Self {
         self.key = key.into();
+        self.key_in_memory = true;
         self
     }
 
     /// Specify the file path for the TLS certificate to use.
+    ///
+    /// The file is not read if the certificate is already set via PEM bytes by [`Keycert::with_cert`].
     #[inline]
     pub fn cert_from_path(mut self, path: impl AsRef<Path>) -> IoResult<Self> {
-        let mut file = File::open(path.as_ref())?;
-        file.read_to_end(&mut self.cert)?;
+        if !self.cert_in_memory {
+            self.cert = std::fs::read(path)?;
+        }
         Ok(self)
     }
 
-    /// Sets the Tls certificate via bytes slice
+    /// Sets the Tls certificate via PEM bytes, it takes precedence over the file set by `cert_from_path`.
     #[inline]
     pub fn with_cert(mut self, cert: impl Into<Vec<u8>>) -> Self {
         self.cert = cert.into();
+        self.cert_in_memory = true;
         self
     }
 
@@ -93,6 +106,8 @@ type BuilderModifier = Box<dyn FnMut(&mut SslAcceptorBuilder) + Send + 'static>;
 #[non_exhaustive]
 pub struct OpensslConfig {
     keycert: Keycert,
+    client_ca: Option<Vec<u8>>,
+    client_auth_required: bool,
     /// Builder modifier.
     pub builder_modifier: Option<BuilderModifier>,
 }
@@ -110,10 +125,26 @@ impl OpensslConfig {
     pub fn new(keycert: Keycert) -> Self {
         OpensslConfig {
             keycert,
+            client_ca: None,
+            client_auth_required: false,
             builder_modifier: None,
         }
     }
 
+    /// Verifies client certificates with the CA certificates in `ca_pem`.
+    ///
+    /// If `required` is `true`, handshake fails when the client does not present a valid certificate. Otherwise
+    /// client certificate is optional, and handlers can decide what to do by [`Request::client_cert`]. A certificate
+    /// which can not be verified always fails the handshake.
+    ///
+    /// [`Request::client_cert`]: crate::http::Request::client_cert
+    #[inline]
+    pub fn with_client_auth(mut self, ca_pem: impl Into<Vec<u8>>, required: bool) -> Self {
+        self.client_ca = Some(ca_pem.into());
+        self.client_auth_required = required;
+        self
+    }
+
     /// Set builder modifier.
     pub fn builder_modifier<F>(mut self, modifier: F) -> Self
     where
@@ -145,6 +176,21 @@ impl OpensslConfig {
         builder.set_alpn_select_callback(move |_: &mut SslRef, list: &[u8]| {
             openssl::ssl::select_next_proto(PROTOS, list).ok_or(openssl::ssl::AlpnError::NOACK)
         });
+        if let Some(ca_pem) = &self.client_ca {
+            let mut store = X509StoreBuilder::new()?;
+            for cert in X509::stack_from_pem(ca_pem)? {
+                builder.add_client_ca(&cert)?;
+                store.add_cert(cert)?;
+            }
+            builder.set_verify_cert_store(store.build())?;
+            // Session resumption fails without session id context when client is verified.
+            builder.set_session_id_context(b"salvo")?;
+            let mut mode = SslVerifyMode::PEER;
+            if self.client_auth_required {
+                mode |= SslVerifyMode::FAIL_IF_NO_PEER_CERT;
+            }
+            builder.set_verify(mode);
+        }
         if let Some(modifier) = &mut self.builder_modifier {
             modifier(&mut builder);
         }
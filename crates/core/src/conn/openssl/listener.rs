@@ -8,16 +8,105 @@ use std::task::{Context, Poll};
 use futures_util::stream::{BoxStream, Stream, StreamExt};
 use futures_util::task::noop_waker_ref;
 use http::uri::Scheme;
-use openssl::ssl::{Ssl, SslAcceptor};
+use openssl::ssl::{Error as SslError, ErrorCode, Ssl, SslAcceptor, SslRef};
+use openssl::x509::X509VerifyResult;
+use thiserror::Error;
 use tokio::io::ErrorKind;
 use tokio_openssl::SslStream;
 
-use super::SslAcceptorBuilder;
+use super::{ClientCerts, SslAcceptorBuilder};
 
-use crate::conn::{Accepted, Acceptor, HandshakeStream, Holding, IntoConfigStream, Listener};
+use crate::conn::{Accepted, Acceptor, AlpnProtocol, HandshakeStream, Holding, IntoConfigStream, Listener};
 use crate::fuse::ArcFuseFactory;
 use crate::http::{HttpConnection, Version};
 
+/// Tls handshake error, it is the inner error of the [`IoError`] returned when reading from or writing to the
+/// connection, and can be got by [`IoError::get_ref`] and `downcast_ref::<TlsError>()`.
+///
+/// Every variant holds the error message reported by openssl.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum TlsError {
+    /// A certificate is expired or not yet valid.
+    #[error("certificate expired: {0}")]
+    CertExpired(String),
+    /// The server name sent by client is not recognized.
+    #[error("unknown server name: {0}")]
+    UnknownSni(String),
+    /// Client and server can not agree on protocol version, cipher or application protocol, or the client does not
+    /// speak tls at all.
+    #[error("protocol mismatch: {0}")]
+    ProtocolMismatch(String),
+    /// The client certificate is missing or can not be verified.
+    #[error("client certificate rejected: {0}")]
+    ClientCertRejected(String),
+    /// Other handshake error.
+    #[error("tls handshake failed: {0}")]
+    Other(String),
+}
+
+impl TlsError {
+    fn from_ssl(ssl: &SslRef, err: SslError) -> IoError {
+        if err.code() == ErrorCode::SYSCALL {
+            return err.into_io_error().unwrap_or_else(|_| {
+                IoError::new(ErrorKind::UnexpectedEof, "openssl: connection closed during handshake.")
+            });
+        }
+        let message = err.to_string();
+        let reasons = err
+            .ssl_error()
+            .map(|stack| {
+                stack
+                    .errors()
+                    .iter()
+                    .filter_map(|e| e.reason())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        let contains = |patterns: &[&str]| patterns.iter().any(|p| reasons.contains(p));
+        let verify_result = ssl.verify_result();
+        let tls_err = if contains(&["certificate expired", "certificate is not yet valid"])
+            || verify_result.as_raw() == X509_V_ERR_CERT_NOT_YET_VALID
+            || verify_result.as_raw() == X509_V_ERR_CERT_HAS_EXPIRED
+        {
+            Self::CertExpired(message)
+        } else if contains(&["unrecognized name", "callback failed"]) {
+            Self::UnknownSni(message)
+        } else if verify_result != X509VerifyResult::OK
+            || contains(&[
+                "peer did not return a certificate",
+                "certificate verify failed",
+                "certificate required",
+            ])
+        {
+            let detail = if verify_result == X509VerifyResult::OK {
+                message
+            } else {
+                format!("{message} ({})", verify_result.error_string())
+            };
+            Self::ClientCertRejected(detail)
+        } else if contains(&[
+            "unsupported protocol",
+            "wrong version number",
+            "version too low",
+            "no shared cipher",
+            "no protocols available",
+            "no application protocol",
+            "http request",
+            "unknown protocol",
+        ]) {
+            Self::ProtocolMismatch(message)
+        } else {
+            Self::Other(message)
+        };
+        IoError::new(ErrorKind::Other, tls_err)
+    }
+}
+
+const X509_V_ERR_CERT_NOT_YET_VALID: i32 = 9;
+const X509_V_ERR_CERT_HAS_EXPIRED: i32 = 10;
+
 /// OpensslListener
 pub struct OpensslListener<S, C, T, E> {
     config_stream: S,
@@ -156,18 +245,30 @@ where
             local_addr,
             remote_addr,
             http_version,
+            mut extensions,
             ..
         } = self.inner.accept(fuse_factory).await?;
         let fusewire = conn.fusewire();
+        let peer_addr = remote_addr.clone();
+        let client_certs = ClientCerts::default();
+        extensions.insert(client_certs.clone());
+        let alpn_protocol = AlpnProtocol::default();
+        extensions.insert(alpn_protocol.clone());
         let conn = async move {
             let ssl =
                 Ssl::new(tls_acceptor.context()).map_err(|err| IoError::new(ErrorKind::Other, err.to_string()))?;
             let mut tls_stream =
                 SslStream::new(ssl, conn).map_err(|err| IoError::new(ErrorKind::Other, err.to_string()))?;
-            std::pin::Pin::new(&mut tls_stream)
-                .accept()
-                .await
-                .map_err(|err| IoError::new(ErrorKind::Other, err.to_string()))?;
+            // Handshake runs when the connection is served, the error only closes this connection.
+            if let Err(err) = std::pin::Pin::new(&mut tls_stream).accept().await {
+                let err = TlsError::from_ssl(tls_stream.ssl(), err);
+                tracing::warn!(remote_addr = %peer_addr, error = %err, "openssl: tls handshake failed.");
+                return Err(err);
+            }
+            alpn_protocol.set(tls_stream.ssl().selected_alpn_protocol());
+            if let Some(chain) = tls_stream.ssl().verified_chain() {
+                let _ = client_certs.0.set(chain.iter().map(|cert| cert.to_owned()).collect());
+            }
             Ok(tls_stream)
         };
 
@@ -177,6 +278,7 @@ where
             remote_addr,
             http_version,
             http_scheme: Scheme::HTTPS,
+            extensions,
         })
     }
 }
@@ -1,21 +1,38 @@
 //! OpensslListener and utils.
+use std::sync::{Arc, OnceLock};
+
+use openssl::x509::X509;
+
 mod config;
 pub use config::{Keycert, OpensslConfig, SslAcceptorBuilder};
 
 mod listener;
-pub use listener::{OpensslAcceptor, OpensslListener};
+pub use listener::{OpensslAcceptor, OpensslListener, TlsError};
+
+/// Verified client certificate chain in connection extensions, it is set after the tls handshake is done.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ClientCerts(pub(crate) Arc<OnceLock<Vec<X509>>>);
 
 #[cfg(test)]
 mod tests {
     use std::pin::Pin;
 
-    use openssl::ssl::{SslConnector, SslMethod};
+    use openssl::asn1::Asn1Time;
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::{PKey, Private};
+    use openssl::rsa::Rsa;
+    use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+    use openssl::x509::extension::BasicConstraints;
+    use openssl::x509::{X509Builder, X509NameBuilder, X509};
     use tokio::io::{AsyncReadExt, AsyncWriteExt};
     use tokio::net::TcpStream;
     use tokio_openssl::SslStream;
+    use tracing_test::traced_test;
 
     use super::*;
-    use crate::conn::{Accepted, Acceptor, Listener, TcpListener};
+    use crate::conn::{Accepted, Acceptor, ConnectionInfo, Listener, TcpListener};
+    use crate::prelude::*;
 
     #[tokio::test]
     async fn test_openssl_listener() {
@@ -52,4 +69,209 @@ mod tests {
         let Accepted { mut conn, .. } = acceptor.accept(None).await.unwrap();
         assert_eq!(conn.read_i32().await.unwrap(), 518);
     }
+
+    fn issue(name: &str, issuer: Option<(&PKey<Private>, &X509)>) -> (PKey<Private>, X509) {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let mut subject = X509NameBuilder::new().unwrap();
+        subject.append_entry_by_text("CN", name).unwrap();
+        let subject = subject.build();
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&subject).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder.set_not_before(&Asn1Time::days_from_now(0).unwrap()).unwrap();
+        builder.set_not_after(&Asn1Time::days_from_now(1).unwrap()).unwrap();
+        match issuer {
+            Some((issuer_key, issuer_cert)) => {
+                builder.set_issuer_name(issuer_cert.subject_name()).unwrap();
+                builder.sign(issuer_key, MessageDigest::sha256()).unwrap();
+            }
+            None => {
+                builder.set_issuer_name(&subject).unwrap();
+                builder
+                    .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
+                    .unwrap();
+                builder.sign(&key, MessageDigest::sha256()).unwrap();
+            }
+        }
+        (key, builder.build())
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_openssl_untrusted_client_cert() {
+        let mut acceptor = TcpListener::new("127.0.0.1:0")
+            .openssl(
+                OpensslConfig::new(
+                    Keycert::new()
+                        .key_from_path("certs/key.pem")
+                        .unwrap()
+                        .cert_from_path("certs/cert.pem")
+                        .unwrap(),
+                )
+                .builder_modifier(|builder| {
+                    builder.set_ca_file("certs/chain.pem").unwrap();
+                    builder.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+                }),
+            )
+            .bind()
+            .await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+
+        tokio::spawn(async move {
+            let (key, cert) = issue("untrusted client", None);
+            let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+            connector.set_ca_file("certs/chain.pem").unwrap();
+            connector.set_certificate(&cert).unwrap();
+            connector.set_private_key(&key).unwrap();
+            let connector = connector.build();
+
+            let ssl = connector.configure().unwrap().into_ssl("testserver.com").unwrap();
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let mut tls_stream = SslStream::new(ssl, stream).unwrap();
+            let _ = Pin::new(&mut tls_stream).connect().await;
+            let _ = tls_stream.write_i32(518).await;
+
+            // The acceptor keeps working after a failed handshake.
+            let ssl = connector.configure().unwrap().into_ssl("testserver.com").unwrap();
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let mut tls_stream = SslStream::new(ssl, stream).unwrap();
+            let _ = Pin::new(&mut tls_stream).connect().await;
+        });
+
+        let Accepted { mut conn, .. } = acceptor.accept(None).await.unwrap();
+        let err = conn.read_i32().await.unwrap_err();
+        let tls_err = err.get_ref().and_then(|e| e.downcast_ref::<TlsError>()).unwrap();
+        assert!(matches!(tls_err, TlsError::ClientCertRejected(_)), "{tls_err}");
+        assert!(logs_contain("openssl: tls handshake failed."));
+        assert!(logs_contain("client certificate rejected"));
+        assert!(logs_contain("remote_addr=socket://127.0.0.1:"));
+
+        let Accepted { mut conn, .. } = acceptor.accept(None).await.unwrap();
+        assert!(conn.read_i32().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_openssl_client_cert() {
+        #[crate::handler]
+        async fn hello(req: &mut Request) -> String {
+            match req.client_cert() {
+                Some(cert) => {
+                    let cn = cert.subject_name().entries_by_nid(Nid::COMMONNAME).next().unwrap();
+                    format!("{} {}", cn.data().to_string().unwrap(), req.client_cert_chain().len())
+                }
+                None => "anonymous".into(),
+            }
+        }
+
+        let (ca_key, ca_cert) = issue("client ca", None);
+        let (client_key, client_cert) = issue("salvo client", Some((&ca_key, &ca_cert)));
+        let acceptor = TcpListener::new("127.0.0.1:0")
+            .openssl(
+                OpensslConfig::new(
+                    Keycert::new()
+                        .key_from_path("certs/key.pem")
+                        .unwrap()
+                        .cert_from_path("certs/cert.pem")
+                        .unwrap(),
+                )
+                .with_client_auth(ca_cert.to_pem().unwrap(), false),
+            )
+            .bind()
+            .await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(Server::new(acceptor).serve(Router::new().get(hello)));
+
+        async fn get(addr: std::net::SocketAddr, keycert: Option<(&PKey<Private>, &X509)>) -> String {
+            let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+            connector.set_ca_file("certs/chain.pem").unwrap();
+            if let Some((key, cert)) = keycert {
+                connector.set_certificate(cert).unwrap();
+                connector.set_private_key(key).unwrap();
+            }
+            let ssl = connector
+                .build()
+                .configure()
+                .unwrap()
+                .into_ssl("testserver.com")
+                .unwrap();
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let mut tls_stream = SslStream::new(ssl, stream).unwrap();
+            Pin::new(&mut tls_stream).connect().await.unwrap();
+            tls_stream
+                .write_all(b"GET / HTTP/1.1\r\nHost: testserver.com\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            let mut response = String::new();
+            tls_stream.read_to_string(&mut response).await.unwrap();
+            response
+        }
+
+        let response = get(addr, Some((&client_key, &client_cert))).await;
+        assert!(response.ends_with("salvo client 2"), "{response}");
+        let response = get(addr, None).await;
+        assert!(response.ends_with("anonymous"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn test_openssl_alpn_protocol() {
+        let mut acceptor = TcpListener::new("127.0.0.1:0")
+            .openssl(OpensslConfig::new(
+                Keycert::new()
+                    .key_from_path("certs/key.pem")
+                    .unwrap()
+                    .cert_from_path("certs/cert.pem")
+                    .unwrap(),
+            ))
+            .bind()
+            .await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+
+        tokio::spawn(async move {
+            let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+            connector.set_ca_file("certs/chain.pem").unwrap();
+            connector.set_alpn_protos(b"\x02h2").unwrap();
+            let ssl = connector
+                .build()
+                .configure()
+                .unwrap()
+                .into_ssl("testserver.com")
+                .unwrap();
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let mut tls_stream = SslStream::new(ssl, stream).unwrap();
+            Pin::new(&mut tls_stream).connect().await.unwrap();
+            tls_stream.write_i32(518).await.unwrap();
+        });
+
+        let Accepted {
+            mut conn,
+            local_addr,
+            remote_addr,
+            http_scheme,
+            extensions,
+            ..
+        } = acceptor.accept(None).await.unwrap();
+        assert_eq!(conn.read_i32().await.unwrap(), 518);
+        let mut req = Request::new();
+        req.extensions_mut().insert(Arc::new(ConnectionInfo::new(
+            local_addr,
+            remote_addr,
+            http_scheme,
+            extensions,
+        )));
+        assert_eq!(req.alpn_protocol(), Some("h2"));
+    }
+
+    #[test]
+    fn test_keycert_in_memory() {
+        let keycert = Keycert::new()
+            .with_key(include_bytes!("../../../certs/key.pem").as_slice())
+            .with_cert(include_bytes!("../../../certs/cert.pem").as_slice())
+            // In-memory PEM takes precedence, so the missing files are not read.
+            .key_from_path("certs/missing-key.pem")
+            .unwrap()
+            .cert_from_path("certs/missing-cert.pem")
+            .unwrap();
+        assert!(OpensslConfig::new(keycert).create_acceptor_builder().is_ok());
+    }
 }
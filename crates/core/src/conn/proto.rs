@@ -5,13 +5,19 @@ use std::io::{Error as IoError, ErrorKind, IoSlice, Result as IoResult};
 use std::marker::PhantomPinned;
 use std::pin::Pin;
 use std::task::{self, ready, Context, Poll};
+#[cfg(any(feature = "http1", feature = "http2"))]
+use std::time::Duration;
 
 use bytes::{Buf, Bytes};
 
+#[cfg(feature = "http1")]
+use futures_channel::oneshot;
 use http::{Request, Response, Version};
 use hyper::service::Service;
 use pin_project::pin_project;
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+#[cfg(feature = "http1")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio_util::sync::CancellationToken;
 
 use crate::fuse::ArcFusewire;
@@ -21,6 +27,8 @@ use crate::rt::tokio::TokioIo;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+#[cfg(feature = "http1")]
+use super::interim::{InterimIo, InterimService};
 #[cfg(feature = "http2")]
 use crate::rt::tokio::TokioExecutor;
 #[cfg(feature = "http1")]
@@ -37,11 +45,115 @@ const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
 pub struct HttpBuilder {
     #[cfg(feature = "http1")]
     pub(crate) http1: http1::Builder,
+    #[cfg(feature = "http1")]
+    pub(crate) header_read_timeout: Option<Duration>,
+    #[cfg(feature = "http1")]
+    pub(crate) informational_responses: bool,
     #[cfg(feature = "http2")]
     pub(crate) http2: http2::Builder<TokioExecutor>,
     #[cfg(feature = "quinn")]
     pub(crate) quinn: quinn::Builder,
 }
+/// HTTP/2 server settings, it is applied to the server by [`Server::http2_config`].
+///
+/// Fields left as `None` keep hyper's defaults:
+///
+/// - `initial_stream_window_size`: 1 MiB.
+/// - `initial_connection_window_size`: 1 MiB.
+/// - `max_concurrent_streams`: 200.
+/// - `max_frame_size`: 16 KiB.
+/// - `header_table_size`: 4 KiB, the default of HTTP/2 protocol.
+/// - `keep_alive_interval`: disabled.
+///
+/// Raising window sizes improves throughput of large streaming bodies on high latency links, at the cost of
+/// more memory buffered per stream.
+///
+/// [`Server::http2_config`]: crate::Server::http2_config
+#[cfg(feature = "http2")]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Http2Config {
+    /// Initial flow control window size of each stream.
+    pub initial_stream_window_size: Option<u32>,
+    /// Initial flow control window size of the connection.
+    pub initial_connection_window_size: Option<u32>,
+    /// Max number of concurrent streams a client can open on one connection.
+    pub max_concurrent_streams: Option<u32>,
+    /// Max frame size the server is willing to receive.
+    pub max_frame_size: Option<u32>,
+    /// Max size of HPACK header table.
+    pub header_table_size: Option<u32>,
+    /// Interval of HTTP/2 `PING` frames used to keep the connection alive.
+    pub keep_alive_interval: Option<Duration>,
+}
+#[cfg(feature = "http2")]
+impl Http2Config {
+    /// Create new `Http2Config` with hyper's defaults.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Set initial flow control window size of each stream.
+    #[inline]
+    pub fn initial_stream_window_size(mut self, size: u32) -> Self {
+        self.initial_stream_window_size = Some(size);
+        self
+    }
+    /// Set initial flow control window size of the connection.
+    #[inline]
+    pub fn initial_connection_window_size(mut self, size: u32) -> Self {
+        self.initial_connection_window_size = Some(size);
+        self
+    }
+    /// Set max number of concurrent streams a client can open on one connection.
+    #[inline]
+    pub fn max_concurrent_streams(mut self, max: u32) -> Self {
+        self.max_concurrent_streams = Some(max);
+        self
+    }
+    /// Set max frame size the server is willing to receive, it must be between 16 KiB and 16 MiB.
+    #[inline]
+    pub fn max_frame_size(mut self, size: u32) -> Self {
+        self.max_frame_size = Some(size);
+        self
+    }
+    /// Set max size of HPACK header table.
+    #[inline]
+    pub fn header_table_size(mut self, size: u32) -> Self {
+        self.header_table_size = Some(size);
+        self
+    }
+    /// Set interval of HTTP/2 `PING` frames used to keep the connection alive.
+    #[inline]
+    pub fn keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.keep_alive_interval = Some(interval);
+        self
+    }
+
+    pub(crate) fn apply(&self, builder: &mut http2::Builder<TokioExecutor>) {
+        if let Some(size) = self.initial_stream_window_size {
+            builder.initial_stream_window_size(size);
+        }
+        if let Some(size) = self.initial_connection_window_size {
+            builder.initial_connection_window_size(size);
+        }
+        if let Some(max) = self.max_concurrent_streams {
+            builder.max_concurrent_streams(max);
+        }
+        if let Some(size) = self.max_frame_size {
+            builder.max_frame_size(size);
+        }
+        if let Some(size) = self.header_table_size {
+            builder.header_table_size(size);
+        }
+        if let Some(interval) = self.keep_alive_interval {
+            // Keep alive pings need a timer to run.
+            builder.timer(hyper_util::rt::TokioTimer::new());
+            builder.keep_alive_interval(interval);
+        }
+    }
+}
+
 impl Default for HttpBuilder {
     fn default() -> Self {
         Self::new()
@@ -53,6 +165,10 @@ impl HttpBuilder {
         Self {
             #[cfg(feature = "http1")]
             http1: http1::Builder::new(),
+            #[cfg(feature = "http1")]
+            header_read_timeout: None,
+            #[cfg(feature = "http1")]
+            informational_responses: false,
             #[cfg(feature = "http2")]
             http2: http2::Builder::new(crate::rt::tokio::TokioExecutor::new()),
             #[cfg(feature = "quinn")]
@@ -78,19 +194,40 @@ impl HttpBuilder {
         B::Error: Into<Box<dyn StdError + Send + Sync>>,
         I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
     {
+        // Version and head of the first request are read before one deadline, so a client which sends nothing, or
+        // only a part of them, can not hold the connection.
+        #[cfg(feature = "http1")]
+        let deadline = self
+            .header_read_timeout
+            .map(|timeout| (tokio::time::Instant::now() + timeout, timeout));
+
         #[cfg(all(feature = "http1", feature = "http2"))]
-        let (version, socket) = if let Some(fusewire) = &fusewire {
-            tokio::select! {
-                result = read_version(socket) => {
-                    result?
-                },
-                _ = fusewire.fused() => {
-                    tracing::info!("closing connection due to fused");
+        let (version, socket) = {
+            let mut socket = socket;
+            let read = read_before(deadline.map(|(deadline, _)| deadline), read_version(&mut socket));
+            let result = if let Some(fusewire) = &fusewire {
+                tokio::select! {
+                    result = read => result,
+                    _ = fusewire.fused() => {
+                        tracing::info!("closing connection due to fused");
+                        return Ok(());
+                    },
+                }
+            } else {
+                read.await
+            };
+            match result {
+                Some(result) => {
+                    let (version, buf) = result?;
+                    (version, Rewind::new_buffered(buf, socket))
+                }
+                None => {
+                    if let Some((_, timeout)) = deadline {
+                        reject_timed_out(socket, timeout).await;
+                    }
                     return Ok(());
-                },
+                }
             }
-        } else {
-            read_version(socket).await?
         };
         #[cfg(all(not(feature = "http1"), not(feature = "http2")))]
         let version = Version::HTTP_11; // Just make the compiler happy.
@@ -105,42 +242,24 @@ impl HttpBuilder {
                 return Err(std::io::Error::new(std::io::ErrorKind::Other, "http1 feature not enabled").into());
                 #[cfg(feature = "http1")]
                 {
-                    let mut conn = self
-                        .http1
-                        .serve_connection(TokioIo::new(socket), service)
-                        .with_upgrades();
-
-                    if let Some(fusewire) = fusewire {
-                        tokio::select! {
-                            _ = &mut conn => {
-                                // Connection completed successfully.
+                    let mut socket = socket;
+                    let head = match deadline {
+                        Some((deadline, timeout)) => match read_before(Some(deadline), read_head(&mut socket)).await {
+                            Some(head) => head?,
+                            None => {
+                                reject_timed_out(socket, timeout).await;
                                 return Ok(());
-                            },
-                            _ = fusewire.fused() => {
-                                tracing::info!("closing connection due to fused");
-                            },
-                            _ = graceful_stop_token.cancelled() => {
-                                tracing::info!("closing connection due to inactivity");
-
-                                // Init graceful shutdown for connection (`GOAWAY` for `HTTP/2` or disabling `keep-alive` for `HTTP/1`)
-                                Pin::new(&mut conn).graceful_shutdown();
-                                conn.await.ok();
                             }
-                        }
+                        },
+                        None => Vec::new(),
+                    };
+                    let socket = Rewind::new_buffered(Bytes::from(head), socket);
+                    if self.informational_responses {
+                        let (socket, sender) = InterimIo::new(socket);
+                        let service = InterimService { inner: service, sender };
+                        self.serve_http1(socket, service, fusewire, graceful_stop_token).await;
                     } else {
-                        tokio::select! {
-                            _ = &mut conn => {
-                                // Connection completed successfully.
-                                return Ok(());
-                            },
-                            _ = graceful_stop_token.cancelled() => {
-                                tracing::info!("closing connection due to inactivity");
-
-                                // Init graceful shutdown for connection (`GOAWAY` for `HTTP/2` or disabling `keep-alive` for `HTTP/1`)
-                                Pin::new(&mut conn).graceful_shutdown();
-                                conn.await.ok();
-                            }
-                        }
+                        self.serve_http1(socket, service, fusewire, graceful_stop_token).await;
                     }
                 }
             }
@@ -192,23 +311,166 @@ impl HttpBuilder {
 
         Ok(())
     }
+
+    /// Serve an HTTP/1 connection until it is completed or closed.
+    #[cfg(feature = "http1")]
+    async fn serve_http1<I, S, B>(
+        &self,
+        socket: I,
+        service: S,
+        fusewire: Option<ArcFusewire>,
+        graceful_stop_token: CancellationToken,
+    ) where
+        S: Service<Request<HyperBody>, Response = Response<B>> + Send,
+        S::Future: Send + 'static,
+        S::Error: Into<Box<dyn StdError + Send + Sync>>,
+        B: Body + Send + 'static,
+        B::Data: Send,
+        B::Error: Into<Box<dyn StdError + Send + Sync>>,
+        I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        if let Some(timeout) = self.header_read_timeout {
+            // Heads of later requests are limited by hyper, which closes the connection silently, the socket is
+            // taken back to respond `408` as for the first request.
+            let (socket, mut reclaimed) = Reclaim::new(socket);
+            let result = self.drive_http1(socket, service, fusewire, graceful_stop_token).await;
+            if matches!(&result, Err(e) if e.is_timeout()) {
+                if let Ok(Some(socket)) = reclaimed.try_recv() {
+                    reject_timed_out(socket, timeout).await;
+                }
+            }
+        } else {
+            self.drive_http1(socket, service, fusewire, graceful_stop_token)
+                .await
+                .ok();
+        }
+    }
+
+    /// Drive an HTTP/1 connection until it is completed or closed, the error of hyper is returned.
+    #[cfg(feature = "http1")]
+    async fn drive_http1<I, S, B>(
+        &self,
+        socket: I,
+        service: S,
+        fusewire: Option<ArcFusewire>,
+        graceful_stop_token: CancellationToken,
+    ) -> std::result::Result<(), hyper::Error>
+    where
+        S: Service<Request<HyperBody>, Response = Response<B>> + Send,
+        S::Future: Send + 'static,
+        S::Error: Into<Box<dyn StdError + Send + Sync>>,
+        B: Body + Send + 'static,
+        B::Data: Send,
+        B::Error: Into<Box<dyn StdError + Send + Sync>>,
+        I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let mut conn = self
+            .http1
+            .serve_connection(TokioIo::new(socket), service)
+            .with_upgrades();
+
+        if let Some(fusewire) = fusewire {
+            tokio::select! {
+                result = &mut conn => {
+                    // Connection completed.
+                    return result;
+                },
+                _ = fusewire.fused() => {
+                    tracing::info!("closing connection due to fused");
+                },
+                _ = graceful_stop_token.cancelled() => {
+                    tracing::info!("closing connection due to inactivity");
+
+                    // Init graceful shutdown for connection (`GOAWAY` for `HTTP/2` or disabling `keep-alive` for `HTTP/1`)
+                    Pin::new(&mut conn).graceful_shutdown();
+                    conn.await.ok();
+                }
+            }
+        } else {
+            tokio::select! {
+                result = &mut conn => {
+                    // Connection completed.
+                    return result;
+                },
+                _ = graceful_stop_token.cancelled() => {
+                    tracing::info!("closing connection due to inactivity");
+
+                    // Init graceful shutdown for connection (`GOAWAY` for `HTTP/2` or disabling `keep-alive` for `HTTP/1`)
+                    Pin::new(&mut conn).graceful_shutdown();
+                    conn.await.ok();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "http1")]
+const REQUEST_TIMEOUT_RESPONSE: &[u8] =
+    b"HTTP/1.1 408 Request Timeout\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+#[cfg(feature = "http1")]
+const MAX_HEAD_SIZE: usize = 64 * 1024;
+
+/// Await `future` before `deadline`, `None` is returned if the deadline is exceeded.
+#[cfg(feature = "http1")]
+async fn read_before<F: Future>(deadline: Option<tokio::time::Instant>, future: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+/// Respond `408 Request Timeout` and close the connection, writing is also limited by `timeout` since the client
+/// may not read.
+#[cfg(feature = "http1")]
+async fn reject_timed_out<A>(mut socket: A, timeout: Duration)
+where
+    A: AsyncWrite + Unpin,
+{
+    tracing::info!("closing connection due to header read timeout");
+    tokio::time::timeout(timeout, async {
+        socket.write_all(REQUEST_TIMEOUT_RESPONSE).await.ok();
+        socket.shutdown().await.ok();
+    })
+    .await
+    .ok();
+}
+
+/// Read until the head of the first request is fully received, the read bytes are returned so that they can be
+/// rewound. Reading stops at eof or if the head is too large, hyper reports the error in these cases.
+#[cfg(feature = "http1")]
+async fn read_head<A>(reader: &mut A) -> IoResult<Vec<u8>>
+where
+    A: AsyncRead + Unpin,
+{
+    let mut head = Vec::with_capacity(1024);
+    loop {
+        let searched = head.len().saturating_sub(3);
+        if reader.read_buf(&mut head).await? == 0 || head.len() >= MAX_HEAD_SIZE {
+            return Ok(head);
+        }
+        if head[searched..].windows(4).any(|window| window == b"\r\n\r\n") {
+            return Ok(head);
+        }
+    }
 }
 
+/// Read the version of protocol, the read bytes are returned so that they can be rewound.
 #[allow(dead_code)]
 #[allow(clippy::future_not_send)]
-pub(crate) async fn read_version<'a, A>(mut reader: A) -> IoResult<(Version, Rewind<A>)>
+pub(crate) async fn read_version<A>(reader: &mut A) -> IoResult<(Version, Bytes)>
 where
     A: AsyncRead + Unpin,
 {
     let mut buf = [0; 24];
     let (version, buf) = ReadVersion {
-        reader: &mut reader,
+        reader,
         buf: ReadBuf::new(&mut buf),
         version: Version::HTTP_11,
         _pin: PhantomPinned,
     }
     .await?;
-    Ok((version, Rewind::new_buffered(Bytes::from(buf), reader)))
+    Ok((version, Bytes::from(buf)))
 }
 
 #[derive(Debug)]
@@ -318,3 +580,68 @@ where
         self.inner.is_write_vectored()
     }
 }
+
+/// Send the IO back by a channel when it is dropped, so that it can still be written after hyper closes the
+/// connection.
+#[cfg(feature = "http1")]
+struct Reclaim<I> {
+    inner: Option<I>,
+    tx: Option<oneshot::Sender<I>>,
+}
+#[cfg(feature = "http1")]
+impl<I> Reclaim<I>
+where
+    I: Unpin,
+{
+    fn new(inner: I) -> (Self, oneshot::Receiver<I>) {
+        let (tx, rx) = oneshot::channel();
+        (
+            Self {
+                inner: Some(inner),
+                tx: Some(tx),
+            },
+            rx,
+        )
+    }
+    fn inner(self: Pin<&mut Self>) -> Pin<&mut I> {
+        Pin::new(self.get_mut().inner.as_mut().expect("inner is only taken when dropped"))
+    }
+}
+#[cfg(feature = "http1")]
+impl<I> Drop for Reclaim<I> {
+    fn drop(&mut self) {
+        if let (Some(inner), Some(tx)) = (self.inner.take(), self.tx.take()) {
+            tx.send(inner).ok();
+        }
+    }
+}
+#[cfg(feature = "http1")]
+impl<I> AsyncRead for Reclaim<I>
+where
+    I: AsyncRead + Unpin,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<IoResult<()>> {
+        self.inner().poll_read(cx, buf)
+    }
+}
+#[cfg(feature = "http1")]
+impl<I> AsyncWrite for Reclaim<I>
+where
+    I: AsyncWrite + Unpin,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        self.inner().poll_write(cx, buf)
+    }
+    fn poll_write_vectored(self: Pin<&mut Self>, cx: &mut Context<'_>, bufs: &[IoSlice<'_>]) -> Poll<IoResult<usize>> {
+        self.inner().poll_write_vectored(cx, bufs)
+    }
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        self.inner().poll_flush(cx)
+    }
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        self.inner().poll_shutdown(cx)
+    }
+    fn is_write_vectored(&self) -> bool {
+        self.inner.as_ref().is_some_and(|inner| inner.is_write_vectored())
+    }
+}
@@ -0,0 +1,261 @@
+//! PROXY protocol support for [`TcpListener`](super::TcpListener).
+//!
+//! L4 load balancers such as HAProxy or AWS NLB send a PROXY protocol header at the beginning of every connection
+//! to pass the address of the original client. Both the text format (version 1) and the binary format
+//! (version 2) are supported, see the [specification](https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt).
+use std::io::{Error as IoError, ErrorKind, Result as IoResult};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use bytes::{Bytes, BytesMut};
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+
+const V1_PREFIX: &[u8] = b"PROXY ";
+/// Max length of a version 1 header, including the trailing CRLF.
+const V1_MAX_LENGTH: usize = 107;
+const V2_SIGNATURE: &[u8] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// PROXY protocol config of [`TcpListener`](super::TcpListener).
+///
+/// The header is read and consumed before the connection is returned from the acceptor, so it never reaches TLS
+/// handshake or HTTP parsing, and [`Accepted::remote_addr`](super::Accepted::remote_addr) is the address of the
+/// original client. TLS listeners created from the `TcpListener`, such as `openssl` and `acme`, get the same
+/// behavior. Headers are read concurrently, so a slow client does not block accepting other connections.
+///
+/// # Example
+///
+/// ```no_run
+/// use salvo_core::conn::ProxyProtocol;
+/// use salvo_core::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let acceptor = TcpListener::new("0.0.0.0:5800")
+///         .proxy_protocol(ProxyProtocol::new())
+///         .bind()
+///         .await;
+///     Server::new(acceptor).serve(Router::new()).await;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ProxyProtocol {
+    /// Reject connections which do not start with a PROXY protocol header, default is `true`.
+    ///
+    /// If it is `false`, such connections are passed through with the socket peer address as remote address. Since
+    /// a header is still trusted if it is sent, clients which connect directly can forge their remote address, so
+    /// only disable it if the listener can not be reached by clients without passing the proxy.
+    pub required: bool,
+    /// Max duration to wait for the header, the connection is closed if it is exceeded, default is 5 seconds.
+    pub timeout: Duration,
+}
+
+impl Default for ProxyProtocol {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            required: true,
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl ProxyProtocol {
+    /// Create a new `ProxyProtocol`.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets whether connections without a PROXY protocol header are rejected.
+    ///
+    /// **Note:** if it is `false`, clients which connect directly can forge their remote address by sending a
+    /// header, see [`ProxyProtocol::required`](Self::required) field.
+    #[inline]
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// Sets max duration to wait for the header.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Reads the header from `conn`, returns the connection, the bytes read after the header and the address of the
+    /// original client.
+    pub(crate) async fn accept(
+        self,
+        mut conn: TcpStream,
+        peer_addr: SocketAddr,
+    ) -> IoResult<(TcpStream, Bytes, SocketAddr)> {
+        let (remote_addr, rest) = tokio::time::timeout(self.timeout, self.read_header(&mut conn))
+            .await
+            .map_err(|_| IoError::new(ErrorKind::TimedOut, "proxy protocol: read header timed out"))??;
+        Ok((conn, rest, remote_addr.unwrap_or(peer_addr)))
+    }
+
+    /// Reads and consumes the header, the address is `None` if the source address is unknown or the connection is
+    /// passed through.
+    ///
+    /// Data is read in chunks, so bytes after the header, or all bytes of a connection passed through, are returned
+    /// to be read by the inner protocol.
+    async fn read_header(&self, conn: &mut TcpStream) -> IoResult<(Option<SocketAddr>, Bytes)> {
+        let mut buf = BytesMut::with_capacity(V1_MAX_LENGTH);
+        loop {
+            if buf.starts_with(V2_SIGNATURE) {
+                if buf.len() >= 16 {
+                    let end = 16 + u16::from_be_bytes([buf[14], buf[15]]) as usize;
+                    if buf.len() >= end {
+                        let mut header = buf.split_to(end);
+                        let payload = header.split_off(16);
+                        let head = <[u8; 16]>::try_from(&header[..]).expect("slice length is 16");
+                        return Ok((parse_v2(&head, &payload)?, buf.freeze()));
+                    }
+                }
+            } else if buf.starts_with(V1_PREFIX) {
+                let len = buf.len().min(V1_MAX_LENGTH);
+                if let Some(index) = buf[..len].windows(2).position(|w| w == b"\r\n") {
+                    let header = buf.split_to(index + 2);
+                    return Ok((parse_v1(&header)?, buf.freeze()));
+                } else if buf.len() >= V1_MAX_LENGTH {
+                    return Err(invalid_data("header is too long"));
+                }
+            } else if !V2_SIGNATURE.starts_with(&buf) && !V1_PREFIX.starts_with(&buf) {
+                if self.required {
+                    return Err(invalid_data("missing header"));
+                } else {
+                    return Ok((None, buf.freeze()));
+                }
+            }
+            if conn.read_buf(&mut buf).await? == 0 {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+        }
+    }
+}
+
+fn invalid_data(msg: &str) -> IoError {
+    IoError::new(ErrorKind::InvalidData, format!("proxy protocol: {msg}"))
+}
+
+/// Parses a version 1 header like `PROXY TCP4 192.168.0.1 192.168.0.11 56324 443\r\n`.
+fn parse_v1(header: &[u8]) -> IoResult<Option<SocketAddr>> {
+    let header = std::str::from_utf8(header)
+        .ok()
+        .and_then(|h| h.strip_suffix("\r\n"))
+        .ok_or_else(|| invalid_data("invalid v1 header"))?;
+    let mut parts = header.split(' ').skip(1);
+    let proto = parts.next().unwrap_or_default();
+    if proto == "UNKNOWN" {
+        return Ok(None);
+    }
+    let (Some(src_ip), Some(_dst_ip), Some(src_port), Some(_dst_port), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid_data("invalid v1 header"));
+    };
+    let ip = match proto {
+        "TCP4" => src_ip.parse::<Ipv4Addr>().map(IpAddr::V4),
+        "TCP6" => src_ip.parse::<Ipv6Addr>().map(IpAddr::V6),
+        _ => return Err(invalid_data("unsupported v1 protocol")),
+    }
+    .map_err(|_| invalid_data("invalid v1 source address"))?;
+    let port = src_port
+        .parse::<u16>()
+        .map_err(|_| invalid_data("invalid v1 source port"))?;
+    Ok(Some(SocketAddr::new(ip, port)))
+}
+
+/// Parses a version 2 header, `head` is the fixed 16 bytes and `payload` is the addresses and TLVs.
+fn parse_v2(head: &[u8; 16], payload: &[u8]) -> IoResult<Option<SocketAddr>> {
+    if head[12] >> 4 != 2 {
+        return Err(invalid_data("unsupported v2 version"));
+    }
+    match head[12] & 0x0F {
+        // LOCAL command, the connection is made by the proxy itself, such as health checks.
+        0x00 => return Ok(None),
+        0x01 => {}
+        _ => return Err(invalid_data("unsupported v2 command")),
+    }
+    let addr = match head[13] >> 4 {
+        // AF_INET
+        0x1 => {
+            let addrs = payload
+                .get(..12)
+                .ok_or_else(|| invalid_data("v2 address is too short"))?;
+            let ip = Ipv4Addr::from(<[u8; 4]>::try_from(&addrs[..4]).expect("slice length is 4"));
+            SocketAddr::new(ip.into(), u16::from_be_bytes([addrs[8], addrs[9]]))
+        }
+        // AF_INET6
+        0x2 => {
+            let addrs = payload
+                .get(..36)
+                .ok_or_else(|| invalid_data("v2 address is too short"))?;
+            let ip = Ipv6Addr::from(<[u8; 16]>::try_from(&addrs[..16]).expect("slice length is 16"));
+            SocketAddr::new(ip.into(), u16::from_be_bytes([addrs[32], addrs[33]]))
+        }
+        // AF_UNSPEC and AF_UNIX, the source address is not usable as a socket address.
+        _ => return Ok(None),
+    };
+    Ok(Some(addr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v2_header(command: u8, family: u8, addrs: &[u8]) -> ([u8; 16], Vec<u8>) {
+        let mut head = [0; 16];
+        head[..12].copy_from_slice(V2_SIGNATURE);
+        head[12] = 0x20 | command;
+        head[13] = family;
+        head[14..].copy_from_slice(&(addrs.len() as u16).to_be_bytes());
+        (head, addrs.to_vec())
+    }
+
+    #[test]
+    fn test_parse_v1() {
+        assert_eq!(
+            parse_v1(b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 443\r\n").unwrap(),
+            Some(SocketAddr::from(([192, 168, 0, 1], 56324)))
+        );
+        assert_eq!(
+            parse_v1(b"PROXY TCP6 ::1 ::2 8000 443\r\n").unwrap(),
+            Some(SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 8000))
+        );
+        assert_eq!(parse_v1(b"PROXY UNKNOWN\r\n").unwrap(), None);
+        assert!(parse_v1(b"PROXY TCP4 ::1 ::2 8000 443\r\n").is_err());
+        assert!(parse_v1(b"PROXY TCP4 192.168.0.1 192.168.0.11 56324\r\n").is_err());
+        assert!(parse_v1(b"PROXY UDP4 192.168.0.1 192.168.0.11 56324 443\r\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_v2() {
+        let (head, payload) = v2_header(0x01, 0x11, &[10, 0, 0, 1, 10, 0, 0, 2, 0x1F, 0x90, 0x01, 0xBB]);
+        assert_eq!(
+            parse_v2(&head, &payload).unwrap(),
+            Some(SocketAddr::from(([10, 0, 0, 1], 8080)))
+        );
+
+        let mut addrs = [0; 36];
+        addrs[15] = 1;
+        addrs[31] = 2;
+        addrs[32..].copy_from_slice(&[0x1F, 0x90, 0x01, 0xBB]);
+        let (head, mut payload) = v2_header(0x01, 0x21, &addrs);
+        // TLVs after addresses are ignored.
+        payload.extend_from_slice(&[0x04, 0x00, 0x01, 0x00]);
+        assert_eq!(
+            parse_v2(&head, &payload).unwrap(),
+            Some(SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 8080))
+        );
+
+        let (head, payload) = v2_header(0x00, 0x00, &[]);
+        assert_eq!(parse_v2(&head, &payload).unwrap(), None);
+        let (head, payload) = v2_header(0x01, 0x11, &[10, 0, 0, 1]);
+        assert!(parse_v2(&head, &payload).is_err());
+    }
+}
@@ -11,6 +11,7 @@ use std::vec;
 use futures_util::stream::{BoxStream, Stream, StreamExt};
 use futures_util::task::noop_waker_ref;
 use http::uri::Scheme;
+use http::Extensions;
 use salvo_http3::http3_quinn::{self, Endpoint};
 
 use super::H3Connection;
@@ -20,6 +21,24 @@ use crate::fuse::{ArcFuseFactory, FuseInfo, TransProto};
 use crate::http::Version;
 
 /// A wrapper of `Listener` with quinn.
+///
+/// Each HTTP/3 request stream is converted to a [`Request`](crate::Request) whose body is
+/// [`H3ReqBody`](crate::http::body::H3ReqBody), and is handled by the same [`Router`](crate::Router)
+/// as HTTP/1 and HTTP/2 requests. The TLS config can be shared with [`RustlsListener`](crate::conn::RustlsListener):
+///
+/// ```no_run
+/// use salvo_core::conn::rustls::{Keycert, RustlsConfig};
+/// use salvo_core::prelude::*;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let config = RustlsConfig::new(Keycert::new().cert_from_path("cert.pem").unwrap().key_from_path("key.pem").unwrap());
+///     let listener = TcpListener::new(("0.0.0.0", 5800)).rustls(config.clone());
+///     // `Alt-Svc` header is added to HTTP/1 and HTTP/2 responses automatically to advertise HTTP/3.
+///     let acceptor = QuinnListener::new(config, ("0.0.0.0", 5800)).join(listener).bind().await;
+///     Server::new(acceptor).serve(Router::new()).await;
+/// }
+/// ```
 pub struct QuinnListener<S, C, T, E> {
     config_stream: S,
     local_addr: T,
@@ -152,6 +171,7 @@ where
                         remote_addr: remote_addr.into(),
                         http_scheme: self.holdings[0].http_scheme.clone(),
                         http_version: Version::HTTP_3,
+                        extensions: Extensions::new(),
                     });
                 }
                 Err(e) => return Err(IoError::new(ErrorKind::Other, e.to_string())),
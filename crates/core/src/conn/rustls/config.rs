@@ -28,6 +28,8 @@ pub struct Keycert {
     pub cert: Vec<u8>,
     /// OCSP response.
     pub ocsp_resp: Vec<u8>,
+    key_in_memory: bool,
+    cert_in_memory: bool,
 }
 
 impl Default for Keycert {
@@ -44,35 +46,46 @@ impl Keycert {
             key: vec![],
             cert: vec![],
             ocsp_resp: vec![],
+            key_in_memory: false,
+            cert_in_memory: false,
         }
     }
     /// Sets the Tls private key via File Path, returns [`IoError`] if the file cannot be open.
+    ///
+    /// The file is not read if the private key is already set via PEM bytes by [`Keycert::key`], so secrets
+    /// injected in memory take precedence over files.
     #[inline]
     pub fn key_from_path(mut self, path: impl AsRef<Path>) -> IoResult<Self> {
-        let mut file = File::open(path.as_ref())?;
-        file.read_to_end(&mut self.key)?;
+        if !self.key_in_memory {
+            self.key = std::fs::read(path)?;
+        }
         Ok(self)
     }
 
-    /// Sets the Tls private key via bytes slice.
+    /// Sets the Tls private key via PEM bytes, it takes precedence over the file set by `key_from_path`.
     #[inline]
     pub fn key(mut self, key: impl Into<Vec<u8>>) -> Self {
         self.key = key.into();
+        self.key_in_memory = true;
         self
     }
 
     /// Specify the file path for the TLS certificate to use.
+    ///
+    /// The file is not read if the certificate is already set via PEM bytes by [`Keycert::cert`].
     #[inline]
     pub fn cert_from_path(mut self, path: impl AsRef<Path>) -> IoResult<Self> {
-        let mut file = File::open(path)?;
-        file.read_to_end(&mut self.cert)?;
+        if !self.cert_in_memory {
+            self.cert = std::fs::read(path)?;
+        }
         Ok(self)
     }
 
-    /// Sets the Tls certificate via bytes slice
+    /// Sets the Tls certificate via PEM bytes, it takes precedence over the file set by `cert_from_path`.
     #[inline]
     pub fn cert(mut self, cert: impl Into<Vec<u8>>) -> Self {
         self.cert = cert.into();
+        self.cert_in_memory = true;
         self
     }
 
@@ -308,4 +321,4 @@ where
     fn into_stream(self) -> Self {
         self
     }
-}
\ No newline at end of file
+}
@@ -11,7 +11,7 @@ use futures_util::task::noop_waker_ref;
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_rustls::server::TlsStream;
 
-use crate::conn::{Accepted, Acceptor, HandshakeStream, Holding, IntoConfigStream, Listener};
+use crate::conn::{Accepted, Acceptor, AlpnProtocol, HandshakeStream, Holding, IntoConfigStream, Listener};
 use crate::fuse::ArcFuseFactory;
 use crate::http::uri::Scheme;
 use crate::http::{HttpConnection, Version};
@@ -154,15 +154,25 @@ where
             local_addr,
             remote_addr,
             http_version,
+            mut extensions,
             ..
         } = self.inner.accept(fuse_factory).await?;
         let fusewire = conn.fusewire();
+        let alpn_protocol = AlpnProtocol::default();
+        extensions.insert(alpn_protocol.clone());
+        let accept = tls_acceptor.accept(conn);
+        let conn = async move {
+            let tls_stream = accept.await?;
+            alpn_protocol.set(tls_stream.get_ref().1.alpn_protocol());
+            Ok(tls_stream)
+        };
         Ok(Accepted {
-            conn: HandshakeStream::new(tls_acceptor.accept(conn), fusewire),
+            conn: HandshakeStream::new(conn, fusewire),
             local_addr,
             remote_addr,
             http_version,
             http_scheme: Scheme::HTTPS,
+            extensions,
         })
     }
 }
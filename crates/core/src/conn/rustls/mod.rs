@@ -30,7 +30,8 @@ mod tests {
     use tokio_rustls::TlsConnector;
 
     use super::*;
-    use crate::conn::{Accepted, Acceptor, Listener, TcpListener};
+    use crate::conn::{Accepted, Acceptor, ConnectionInfo, Listener, TcpListener};
+    use crate::Request;
 
     #[tokio::test]
     async fn test_rustls_listener() {
@@ -63,4 +64,73 @@ mod tests {
         let Accepted { mut conn, .. } = acceptor.accept(None).await.unwrap();
         assert_eq!(conn.read_i32().await.unwrap(), 518);
     }
+
+    #[tokio::test]
+    async fn test_rustls_alpn_protocol() {
+        let mut acceptor = TcpListener::new("127.0.0.1:0")
+            .rustls(RustlsConfig::new(
+                Keycert::new()
+                    .key_from_path("certs/key.pem")
+                    .unwrap()
+                    .cert_from_path("certs/cert.pem")
+                    .unwrap(),
+            ))
+            .bind()
+            .await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+
+        tokio::spawn(async move {
+            let stream = TcpStream::connect(addr).await.unwrap();
+            let trust_anchor = include_bytes!("../../../certs/chain.pem");
+            let mut client_config = ClientConfig::builder()
+                .with_root_certificates(read_trust_anchor(trust_anchor.as_slice()).unwrap())
+                .with_no_client_auth();
+            client_config.alpn_protocols = vec![b"h2".to_vec()];
+            let connector = TlsConnector::from(Arc::new(client_config));
+            let mut tls_stream = connector
+                .connect(ServerName::try_from("testserver.com").unwrap(), stream)
+                .await
+                .unwrap();
+            tls_stream.write_i32(518).await.unwrap();
+        });
+
+        let Accepted {
+            mut conn,
+            local_addr,
+            remote_addr,
+            http_scheme,
+            extensions,
+            ..
+        } = acceptor.accept(None).await.unwrap();
+        assert_eq!(conn.read_i32().await.unwrap(), 518);
+        let mut req = Request::new();
+        req.extensions_mut().insert(Arc::new(ConnectionInfo::new(
+            local_addr,
+            remote_addr,
+            http_scheme,
+            extensions,
+        )));
+        assert_eq!(req.alpn_protocol(), Some("h2"));
+    }
+
+    #[test]
+    fn test_keycert_in_memory() {
+        let keycert = Keycert::new()
+            .key(include_bytes!("../../../certs/key.pem").as_slice())
+            .cert(include_bytes!("../../../certs/cert.pem").as_slice())
+            // In-memory PEM takes precedence, so the missing files are not read.
+            .key_from_path("certs/missing-key.pem")
+            .unwrap()
+            .cert_from_path("certs/missing-cert.pem")
+            .unwrap();
+        let config: IoResult<ServerConfig> = RustlsConfig::new(keycert).try_into();
+        assert!(config.is_ok());
+
+        let keycert = Keycert::new()
+            .key_from_path("certs/key.pem")
+            .unwrap()
+            .key_from_path("certs/key.pem")
+            .unwrap();
+        assert_eq!(keycert.key, include_bytes!("../../../certs/key.pem"));
+    }
 }
@@ -5,6 +5,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use bytes::Bytes;
 use pin_project::pin_project;
 use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 
@@ -19,6 +20,8 @@ pub struct StraightStream<C> {
     #[pin]
     inner: C,
     fusewire: Option<ArcFusewire>,
+    // Bytes which are read from `inner` in advance, they are read before `inner`.
+    buffered: Bytes,
 }
 
 impl<C> StraightStream<C>
@@ -27,7 +30,17 @@ where
 {
     /// Create a new `StraightStream`.
     pub fn new(inner: C, fusewire: Option<ArcFusewire>) -> Self {
-        Self { inner, fusewire }
+        Self {
+            inner,
+            fusewire,
+            buffered: Bytes::new(),
+        }
+    }
+
+    /// Sets bytes which are read from `inner` in advance, they are read before `inner`.
+    pub(crate) fn buffered(mut self, buffered: Bytes) -> Self {
+        self.buffered = buffered;
+        self
     }
 }
 
@@ -61,6 +74,14 @@ where
 {
     fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<IoResult<()>> {
         let this = self.project();
+        if !this.buffered.is_empty() {
+            let len = this.buffered.len().min(buf.remaining());
+            buf.put_slice(&this.buffered.split_to(len));
+            if let Some(fusewire) = &this.fusewire {
+                fusewire.event(FuseEvent::ReadData(len));
+            }
+            return Poll::Ready(Ok(()));
+        }
         let remaining = buf.remaining();
         match this.inner.poll_read(cx, buf) {
             Poll::Ready(Ok(())) => {
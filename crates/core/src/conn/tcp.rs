@@ -3,9 +3,13 @@ use std::io::{Error as IoError, Result as IoResult};
 use std::net::SocketAddr;
 use std::vec;
 
+use bytes::Bytes;
+use http::Extensions;
+
 use tokio::net::{TcpListener as TokioTcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinSet;
 
-use crate::conn::{Holding, StraightStream};
+use crate::conn::{Holding, ProxyProtocol, StraightStream};
 use crate::fuse::{ArcFuseFactory, FuseInfo, TransProto};
 use crate::http::uri::Scheme;
 use crate::http::Version;
@@ -30,12 +34,24 @@ use crate::conn::acme::AcmeListener;
 /// `TcpListener` is used to create a TCP connection listener.
 pub struct TcpListener<T> {
     local_addr: T,
+    proxy_protocol: Option<ProxyProtocol>,
 }
 impl<T: ToSocketAddrs + Send> TcpListener<T> {
     /// Bind to socket address.
     #[inline]
     pub fn new(local_addr: T) -> Self {
-        TcpListener { local_addr }
+        TcpListener {
+            local_addr,
+            proxy_protocol: None,
+        }
+    }
+
+    /// Reads PROXY protocol header from every accepted connection, the address of the original client is used as
+    /// remote address. TLS listeners created from this listener read the header before TLS handshake.
+    #[inline]
+    pub fn proxy_protocol(mut self, proxy_protocol: ProxyProtocol) -> Self {
+        self.proxy_protocol = Some(proxy_protocol);
+        self
     }
 
     cfg_feature! {
@@ -100,13 +116,18 @@ where
     type Acceptor = TcpAcceptor;
 
     async fn try_bind(self) -> crate::Result<Self::Acceptor> {
-        Ok(TokioTcpListener::bind(self.local_addr).await?.try_into()?)
+        let mut acceptor: TcpAcceptor = TokioTcpListener::bind(self.local_addr).await?.try_into()?;
+        acceptor.proxy_protocol = self.proxy_protocol;
+        Ok(acceptor)
     }
 }
 /// `TcpAcceptor` is used to accept a TCP connection.
 pub struct TcpAcceptor {
     inner: TokioTcpListener,
     holdings: Vec<Holding>,
+    proxy_protocol: Option<ProxyProtocol>,
+    /// Connections whose PROXY protocol header is being read.
+    proxying: JoinSet<IoResult<(TcpStream, Bytes, SocketAddr)>>,
 }
 
 impl TcpAcceptor {
@@ -144,6 +165,8 @@ impl TryFrom<TokioTcpListener> for TcpAcceptor {
         Ok(TcpAcceptor {
             inner,
             holdings: vec![holding],
+            proxy_protocol: None,
+            proxying: JoinSet::new(),
         })
     }
 }
@@ -158,24 +181,43 @@ impl Acceptor for TcpAcceptor {
 
     #[inline]
     async fn accept(&mut self, fuse_factory: Option<ArcFuseFactory>) -> IoResult<Accepted<Self::Conn>> {
-        self.inner.accept().await.map(move |(conn, remote_addr)| {
-            let local_addr = self.holdings[0].local_addr.clone();
-            Accepted {
-                conn: StraightStream::new(
-                    conn,
-                    fuse_factory.map(|f| {
-                        f.create(FuseInfo {
-                            trans_proto: TransProto::Tcp,
-                            remote_addr: remote_addr.into(),
-                            local_addr: local_addr.clone(),
-                        })
-                    }),
-                ),
-                remote_addr: remote_addr.into(),
-                local_addr,
-                http_version: Version::HTTP_11,
-                http_scheme: Scheme::HTTP,
+        let (conn, buffered, remote_addr) = match &self.proxy_protocol {
+            None => {
+                let (conn, remote_addr) = self.inner.accept().await?;
+                (conn, Bytes::new(), remote_addr)
             }
+            Some(proxy_protocol) => loop {
+                tokio::select! {
+                    accepted = self.inner.accept() => {
+                        let (conn, peer_addr) = accepted?;
+                        self.proxying.spawn(proxy_protocol.clone().accept(conn, peer_addr));
+                    }
+                    Some(proxied) = self.proxying.join_next() => match proxied {
+                        Ok(Ok(proxied)) => break proxied,
+                        Ok(Err(e)) => tracing::debug!(error = ?e, "proxy protocol: connection rejected."),
+                        Err(e) => tracing::error!(error = ?e, "proxy protocol: read header task failed."),
+                    }
+                }
+            },
+        };
+        let local_addr = self.holdings[0].local_addr.clone();
+        Ok(Accepted {
+            conn: StraightStream::new(
+                conn,
+                fuse_factory.map(|f| {
+                    f.create(FuseInfo {
+                        trans_proto: TransProto::Tcp,
+                        remote_addr: remote_addr.into(),
+                        local_addr: local_addr.clone(),
+                    })
+                }),
+            )
+            .buffered(buffered),
+            remote_addr: remote_addr.into(),
+            local_addr,
+            extensions: Extensions::new(),
+            http_version: Version::HTTP_11,
+            http_scheme: Scheme::HTTP,
         })
     }
 }
@@ -199,4 +241,93 @@ mod tests {
         let Accepted { mut conn, .. } = acceptor.accept(None).await.unwrap();
         assert_eq!(conn.read_i32().await.unwrap(), 150);
     }
+
+    async fn accept_proxied(acceptor: &mut TcpAcceptor, data: &'static [u8]) -> (SocketAddr, Vec<u8>) {
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream.write_all(data).await.unwrap();
+        });
+        let Accepted {
+            mut conn, remote_addr, ..
+        } = acceptor.accept(None).await.unwrap();
+        let mut body = Vec::new();
+        conn.read_to_end(&mut body).await.unwrap();
+        (remote_addr.into_std().unwrap(), body)
+    }
+
+    #[tokio::test]
+    async fn test_tcp_listener_proxy_protocol() {
+        let mut acceptor = TcpListener::new("127.0.0.1:0")
+            .proxy_protocol(ProxyProtocol::new())
+            .bind()
+            .await;
+
+        let (remote_addr, body) = accept_proxied(
+            &mut acceptor,
+            b"PROXY TCP4 192.168.0.1 192.168.0.11 56324 443\r\nGET / HTTP/1.1\r\n",
+        )
+        .await;
+        assert_eq!(remote_addr, SocketAddr::from(([192, 168, 0, 1], 56324)));
+        assert_eq!(body, b"GET / HTTP/1.1\r\n");
+
+        let (remote_addr, body) = accept_proxied(
+            &mut acceptor,
+            b"\r\n\r\n\0\r\nQUIT\n\x21\x11\x00\x0C\x0A\x00\x00\x01\x0A\x00\x00\x02\x1F\x90\x01\xBB\x16\x03\x01",
+        )
+        .await;
+        assert_eq!(remote_addr, SocketAddr::from(([10, 0, 0, 1], 8080)));
+        assert_eq!(body, b"\x16\x03\x01");
+
+        // Connections without header are rejected, the next proxied connection is still accepted.
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        let mut plain = TcpStream::connect(addr).await.unwrap();
+        plain.write_all(b"GET / HTTP/1.1\r\n").await.unwrap();
+        let (remote_addr, _) = accept_proxied(&mut acceptor, b"PROXY TCP6 ::1 ::1 8000 443\r\n").await;
+        assert_eq!(remote_addr.port(), 8000);
+        // Closed with unread data, the peer may see a reset rather than EOF.
+        assert!(!matches!(plain.read(&mut [0; 8]).await, Ok(len) if len > 0));
+    }
+
+    #[tokio::test]
+    async fn test_tcp_listener_proxy_protocol_optional() {
+        let mut acceptor = TcpListener::new("127.0.0.1:0")
+            .proxy_protocol(ProxyProtocol::new().required(false))
+            .bind()
+            .await;
+        let (remote_addr, body) = accept_proxied(&mut acceptor, b"GET / HTTP/1.1\r\n").await;
+        assert!(remote_addr.ip().is_loopback());
+        assert_eq!(body, b"GET / HTTP/1.1\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_tcp_listener_proxy_protocol_split() {
+        let mut acceptor = TcpListener::new("127.0.0.1:0")
+            .proxy_protocol(ProxyProtocol::new())
+            .bind()
+            .await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            for part in [
+                &b"PRO"[..],
+                b"XY TCP4 192.168.0.1 192.168.0.11 56324 443\r",
+                b"\nGET / HTTP/1.1\r\n",
+            ] {
+                stream.write_all(part).await.unwrap();
+                stream.flush().await.unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+        });
+        let Accepted {
+            mut conn, remote_addr, ..
+        } = acceptor.accept(None).await.unwrap();
+        let mut body = Vec::new();
+        conn.read_to_end(&mut body).await.unwrap();
+        assert_eq!(
+            remote_addr.into_std().unwrap(),
+            SocketAddr::from(([192, 168, 0, 1], 56324))
+        );
+        assert_eq!(body, b"GET / HTTP/1.1\r\n");
+    }
 }
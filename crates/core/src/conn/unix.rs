@@ -1,10 +1,11 @@
 //! UnixListener module
 use std::fs::{set_permissions, Permissions};
-use std::io::Result as IoResult;
+use std::io::{ErrorKind, Result as IoResult};
 use std::path::Path;
 use std::sync::Arc;
 
 use http::uri::Scheme;
+use http::Extensions;
 use nix::unistd::{chown, Gid, Uid};
 use tokio::net::{UnixListener as TokioUnixListener, UnixStream};
 
@@ -25,6 +26,9 @@ pub struct UnixListener<T> {
 #[cfg(unix)]
 impl<T> UnixListener<T> {
     /// Creates a new `UnixListener` bind to the specified path.
+    ///
+    /// If a socket file is left at the path by a server which is not running anymore, it is removed on bind. A
+    /// socket file which is still accepting connections is never removed, and binding fails in that case.
     #[inline]
     pub fn new(path: T) -> UnixListener<T> {
         UnixListener {
@@ -56,6 +60,7 @@ where
     type Acceptor = UnixAcceptor;
 
     async fn try_bind(self) -> crate::Result<Self::Acceptor> {
+        remove_stale_socket(self.path.as_ref())?;
         let inner = match (self.permissions, self.owner) {
             (Some(permissions), Some((uid, gid))) => {
                 let inner = TokioUnixListener::bind(self.path.clone())?;
@@ -88,6 +93,22 @@ where
     }
 }
 
+/// Removes the socket file at `path` if no server is listening on it.
+fn remove_stale_socket(path: &Path) -> IoResult<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => match std::os::unix::net::UnixStream::connect(path) {
+            Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+                tracing::info!(path = ?path, "remove stale unix socket file");
+                std::fs::remove_file(path)
+            }
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
 /// `UnixAcceptor` is used to accept a Unix socket connection.
 pub struct UnixAcceptor {
     inner: TokioUnixListener,
@@ -118,6 +139,7 @@ impl Acceptor for UnixAcceptor {
             remote_addr: remote_addr.clone().into(),
             http_version: Version::HTTP_11,
             http_scheme: Scheme::HTTP,
+            extensions: Extensions::new(),
         }})
     }
 }
@@ -143,4 +165,43 @@ mod tests {
         assert_eq!(conn.read_i32().await.unwrap(), 518);
         std::fs::remove_file(sock_file).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_unix_listener_serve() {
+        use http_body_util::{BodyExt, Empty};
+
+        use crate::prelude::*;
+        use crate::rt::tokio::TokioIo;
+
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let sock_file = std::env::temp_dir().join(format!("salvo-serve-{}.sock", std::process::id()));
+        // A stale socket file, which is left by a stopped server, is removed on bind.
+        drop(std::os::unix::net::UnixListener::bind(&sock_file).unwrap());
+        assert!(sock_file.exists());
+
+        let acceptor = UnixListener::new(sock_file.clone()).bind().await;
+        // The socket file is in use now, so it can not be bound again.
+        assert!(UnixListener::new(sock_file.clone()).try_bind().await.is_err());
+        tokio::spawn(Server::new(acceptor).serve(Router::new().get(hello)));
+
+        let stream = tokio::net::UnixStream::connect(&sock_file).await.unwrap();
+        let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .unwrap();
+        tokio::spawn(conn);
+        let req = hyper::Request::builder()
+            .uri("/")
+            .header("host", "localhost")
+            .body(Empty::<bytes::Bytes>::new())
+            .unwrap();
+        let res = sender.send_request(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "hello");
+        std::fs::remove_file(sock_file).unwrap();
+    }
 }
@@ -1,6 +1,9 @@
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::fmt::{self, Formatter};
+use std::marker::PhantomData;
+
+use crate::http::Deadline;
 
 /// `Depot` is for store temp data of current request.
 ///
@@ -40,6 +43,64 @@ fn type_key<T: 'static>() -> String {
     format!("{:?}", TypeId::of::<T>())
 }
 
+/// A key of [`Depot`] which is bound to the type of its value.
+///
+/// Values stored by a `TypedKey` are got back without downcasting, and a typo in key name is a compile error
+/// instead of a silent `None`. The stored key is combined from the name and the [`TypeId`] of value type, so two
+/// keys with the same name but different types never conflict with each other or with string keys.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_core::TypedKey;
+///
+/// struct User {
+///     name: String,
+/// }
+/// static USER: TypedKey<User> = TypedKey::new("user");
+///
+/// let mut depot = Depot::new();
+/// depot.insert_typed(&USER, User { name: "jobs".into() });
+/// assert_eq!(depot.get_typed(&USER).map(|user| &*user.name), Some("jobs"));
+/// ```
+pub struct TypedKey<T> {
+    name: &'static str,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedKey<T> {
+    /// Create a new `TypedKey` with name.
+    #[inline]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the name of this key.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+impl<T: 'static> TypedKey<T> {
+    #[inline]
+    fn key(&self) -> String {
+        format!("{}::{:?}", self.name, TypeId::of::<T>())
+    }
+}
+
+impl<T> fmt::Debug for TypedKey<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedKey")
+            .field("name", &self.name)
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
 impl Depot {
     /// Creates an empty `Depot`.
     ///
@@ -95,6 +156,20 @@ impl Depot {
         self.get_mut(&type_key::<T>())
     }
 
+    /// Get the [`Deadline`] of current request, it is set by deadline middleware.
+    #[inline]
+    pub fn deadline(&self) -> Option<&Deadline> {
+        self.obtain::<Deadline>().ok()
+    }
+    /// Set the [`Deadline`] of current request, the earlier one is kept if a deadline is already set.
+    #[inline]
+    pub fn set_deadline(&mut self, deadline: Deadline) -> &mut Self {
+        match self.deadline() {
+            Some(current) if *current <= deadline => self,
+            _ => self.inject(deadline),
+        }
+    }
+
     /// Inserts a key-value pair into the depot.
     #[inline]
     pub fn insert<K, V>(&mut self, key: K, value: V) -> &mut Self
@@ -172,6 +247,34 @@ impl Depot {
     pub fn scrape<T: Any + Send + Sync>(&mut self) -> Result<T, Option<Box<dyn Any + Send + Sync>>> {
         self.remove(&type_key::<T>())
     }
+
+    /// Inserts a value into the depot with a [`TypedKey`].
+    #[inline]
+    pub fn insert_typed<T: Any + Send + Sync>(&mut self, key: &TypedKey<T>, value: T) -> &mut Self {
+        self.map.insert(key.key(), Box::new(value));
+        self
+    }
+
+    /// Immutably borrows value stored with a [`TypedKey`] from depot.
+    #[inline]
+    pub fn get_typed<T: Any + Send + Sync>(&self, key: &TypedKey<T>) -> Option<&T> {
+        self.map.get(&key.key()).and_then(|value| value.downcast_ref::<T>())
+    }
+
+    /// Mutably borrows value stored with a [`TypedKey`] from depot.
+    #[inline]
+    pub fn get_typed_mut<T: Any + Send + Sync>(&mut self, key: &TypedKey<T>) -> Option<&mut T> {
+        self.map.get_mut(&key.key()).and_then(|value| value.downcast_mut::<T>())
+    }
+
+    /// Remove value stored with a [`TypedKey`] from depot and returning it.
+    #[inline]
+    pub fn remove_typed<T: Any + Send + Sync>(&mut self, key: &TypedKey<T>) -> Option<T> {
+        self.map
+            .remove(&key.key())
+            .and_then(|value| value.downcast::<T>().ok())
+            .map(|value| *value)
+    }
 }
 
 impl fmt::Debug for Depot {
@@ -199,6 +302,26 @@ mod test {
         assert_eq!(depot.get_mut::<String>("one").unwrap(), &mut "ONE".to_owned());
     }
 
+    #[test]
+    fn test_depot_typed_key() {
+        static COUNT: TypedKey<u32> = TypedKey::new("count");
+        static COUNT_NAME: TypedKey<String> = TypedKey::new("count");
+
+        let mut depot = Depot::new();
+        depot.insert("count", 1u32);
+        depot.inject(2u32);
+        depot.insert_typed(&COUNT, 3);
+        depot.insert_typed(&COUNT_NAME, "three".to_owned());
+        *depot.get_typed_mut(&COUNT).unwrap() += 1;
+
+        assert_eq!(depot.get_typed(&COUNT), Some(&4));
+        assert_eq!(depot.get_typed(&COUNT_NAME).map(|s| &**s), Some("three"));
+        assert_eq!(depot.get::<u32>("count").ok(), Some(&1));
+        assert_eq!(depot.obtain::<u32>().ok(), Some(&2));
+        assert_eq!(depot.remove_typed(&COUNT), Some(4));
+        assert!(depot.get_typed(&COUNT).is_none());
+    }
+
     #[tokio::test]
     async fn test_middleware_use_depot() {
         #[handler]
@@ -0,0 +1,106 @@
+use serde::de::DeserializeOwned;
+
+use crate::extract::{body_parse_error, Extractible, Metadata};
+use crate::http::StatusError;
+use crate::writing::Cbor;
+use crate::Request;
+
+/// Extracts the CBOR body as type `T`.
+///
+/// Content type should be `application/cbor`, otherwise a `415 Unsupported Media Type` error is written. If the body
+/// is not valid CBOR, a `400 Bad Request` error is written, and if it can not be deserialized to `T`, for example a
+/// required field is missing, a `422 Unprocessable Entity` error is written.
+///
+/// ```
+/// # use salvo_core::prelude::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Deserialize, Serialize, Debug)]
+/// struct Payload {
+///     name: String,
+/// }
+///
+/// #[handler]
+/// async fn create(body: Cbor<Payload>) -> Cbor<Payload> {
+///     body
+/// }
+/// ```
+impl<'ex, T> Extractible<'ex> for Cbor<T>
+where
+    T: DeserializeOwned + Send,
+{
+    fn metadata() -> &'ex Metadata {
+        static METADATA: Metadata = Metadata::new("");
+        &METADATA
+    }
+
+    #[allow(refining_impl_trait)]
+    async fn extract(req: &'ex mut Request) -> Result<Self, StatusError> {
+        req.parse_cbor()
+            .await
+            .map(Cbor)
+            .map_err(|e| body_parse_error(e, "cbor", "application/cbor"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[derive(Deserialize, Serialize, Eq, PartialEq, Debug)]
+    struct Payload {
+        name: String,
+        age: u8,
+    }
+
+    #[handler]
+    async fn create(body: Cbor<Payload>) -> Cbor<Payload> {
+        body
+    }
+
+    fn to_vec<T: Serialize>(value: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes).unwrap();
+        bytes
+    }
+
+    #[tokio::test]
+    async fn test_cbor_extract() {
+        let service = Service::new(Router::new().post(create));
+        let payload = Payload {
+            name: "jobs".into(),
+            age: 30,
+        };
+
+        let mut res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header("content-type", "application/cbor", true)
+            .body(to_vec(&payload))
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get("content-type").unwrap(), "application/cbor");
+        let bytes = res.take_bytes(None).await.unwrap();
+        assert_eq!(ciborium::from_reader::<Payload, _>(bytes.as_ref()).unwrap(), payload);
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header("content-type", "application/cbor", true)
+            .body(to_vec(&serde_json::json!({"name": "jobs"})))
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header("content-type", "application/cbor", true)
+            .body(vec![0xa2, 0x64])
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .json(&payload)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+    }
+}
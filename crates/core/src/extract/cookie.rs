@@ -0,0 +1,119 @@
+use cookie::CookieJar;
+
+use crate::extract::{Extractible, Metadata};
+use crate::http::StatusError;
+use crate::Request;
+
+/// Extracts all request cookies into a [`CookieJar`].
+///
+/// Cookies from request are added as original cookies, so when the jar is written back to response, only cookies
+/// added or removed by handler are sent in `Set-Cookie` headers. Use [`CookieJar::signed`] and
+/// [`CookieJar::private`] to verify or decrypt cookies with a [`Key`](cookie::Key).
+///
+/// ```
+/// # use salvo_core::prelude::*;
+/// use salvo_core::http::cookie::{Cookie, CookieJar, SameSite};
+///
+/// #[handler]
+/// async fn visit(mut jar: CookieJar) -> CookieJar {
+///     let count = jar
+///         .get("visits")
+///         .and_then(|cookie| cookie.value().parse::<u32>().ok())
+///         .unwrap_or_default();
+///     let cookie = Cookie::build(("visits", (count + 1).to_string()))
+///         .http_only(true)
+///         .same_site(SameSite::Lax)
+///         .build();
+///     jar.add(cookie);
+///     jar
+/// }
+/// ```
+impl<'ex> Extractible<'ex> for CookieJar {
+    fn metadata() -> &'ex Metadata {
+        static METADATA: Metadata = Metadata::new("");
+        &METADATA
+    }
+
+    #[allow(refining_impl_trait)]
+    async fn extract(req: &'ex mut Request) -> Result<Self, StatusError> {
+        Ok(req.cookies().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cookie::{Cookie, Key};
+
+    use super::*;
+    use crate::http::header::{COOKIE, SET_COOKIE};
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[tokio::test]
+    async fn test_cookie_jar() {
+        #[handler]
+        async fn visit(mut jar: CookieJar) -> CookieJar {
+            let count = jar
+                .get("visits")
+                .and_then(|cookie| cookie.value().parse::<u32>().ok())
+                .unwrap_or_default();
+            jar.add(
+                Cookie::build(("visits", (count + 1).to_string()))
+                    .http_only(true)
+                    .build(),
+            );
+            jar.remove(Cookie::from("stale"));
+            jar
+        }
+
+        let res = TestClient::get("http://127.0.0.1:5801")
+            .add_header(COOKIE, "visits=2; stale=1; theme=dark", true)
+            .send(Router::new().get(visit))
+            .await;
+        let mut cookies = res
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        cookies.sort();
+        assert_eq!(cookies.len(), 2);
+        assert!(cookies[0].starts_with("stale=; Max-Age=0"), "{cookies:?}");
+        assert_eq!(cookies[1], "visits=3; HttpOnly");
+    }
+
+    #[tokio::test]
+    async fn test_private_cookie_jar() {
+        fn key() -> Key {
+            Key::from(&[7; 64])
+        }
+        #[handler]
+        async fn login(mut jar: CookieJar) -> CookieJar {
+            jar.private_mut(&key()).add(Cookie::new("user", "jobs"));
+            jar
+        }
+        #[handler]
+        async fn user(jar: CookieJar) -> String {
+            jar.private(&key())
+                .get("user")
+                .map(|cookie| cookie.value().to_owned())
+                .unwrap_or_default()
+        }
+        let router = Router::new()
+            .push(Router::with_path("login").get(login))
+            .push(Router::with_path("user").get(user));
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801/login").send(&service).await;
+        let cookie = res.headers().get(SET_COOKIE).unwrap().to_str().unwrap().to_owned();
+        assert!(!cookie.contains("jobs"));
+        let content = TestClient::get("http://127.0.0.1:5801/user")
+            .add_header(COOKIE, cookie, true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "jobs");
+    }
+}
@@ -0,0 +1,97 @@
+use serde::Deserialize;
+
+use crate::extract::{body_parse_error, Extractible, Metadata};
+use crate::http::StatusError;
+use crate::writing::Json;
+use crate::Request;
+
+/// Extracts the JSON body as type `T`.
+///
+/// If the request is not JSON, a `415 Unsupported Media Type` error is written. If the body is not valid JSON,
+/// a `400 Bad Request` error is written, and if it is valid JSON but can not be deserialized to `T`, for example
+/// a required field is missing, a `422 Unprocessable Entity` error is written. The error detail tells the reason.
+///
+/// ```
+/// # use salvo_core::prelude::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Deserialize, Serialize, Debug)]
+/// struct Payload {
+///     name: String,
+/// }
+///
+/// #[handler]
+/// async fn create(body: Json<Payload>) -> Json<Payload> {
+///     body
+/// }
+/// ```
+impl<'ex, T> Extractible<'ex> for Json<T>
+where
+    T: Deserialize<'ex> + Send,
+{
+    fn metadata() -> &'ex Metadata {
+        static METADATA: Metadata = Metadata::new("");
+        &METADATA
+    }
+
+    #[allow(refining_impl_trait)]
+    async fn extract(req: &'ex mut Request) -> Result<Self, StatusError> {
+        req.parse_json()
+            .await
+            .map(Json)
+            .map_err(|e| body_parse_error(e, "json", "application/json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[derive(Deserialize, Serialize, Debug)]
+    struct Payload {
+        name: String,
+        age: u8,
+    }
+
+    #[handler]
+    async fn create(body: Json<Payload>) -> Json<Payload> {
+        body
+    }
+
+    #[tokio::test]
+    async fn test_json_extract() {
+        let service = Service::new(Router::new().post(create));
+
+        let content = TestClient::post("http://127.0.0.1:5801/")
+            .json(&serde_json::json!({"name": "jobs", "age": 30}))
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, r#"{"name":"jobs","age":30}"#);
+
+        let mut res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header("accept", "application/json", true)
+            .json(&serde_json::json!({"name": "jobs"}))
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        let error = res.take_json::<serde_json::Value>().await.unwrap();
+        assert_eq!(error["error"]["code"], 422);
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .raw_json("{name")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .text("jobs")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+    }
+}
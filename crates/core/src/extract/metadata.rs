@@ -1,3 +1,4 @@
+use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 use std::vec;
 
@@ -36,6 +37,20 @@ impl FromStr for SourceFrom {
     }
 }
 
+impl Display for SourceFrom {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let from = match self {
+            Self::Param => "param",
+            Self::Query => "query",
+            Self::Header => "header",
+            #[cfg(feature = "cookie")]
+            Self::Cookie => "cookie",
+            Self::Body => "body",
+        };
+        f.write_str(from)
+    }
+}
+
 /// Source parser for a source.
 ///
 /// This parser is used to parse field data, not the request mime type.
@@ -145,6 +160,8 @@ pub struct Field {
     pub decl_name: &'static str,
     /// Field flatten, this field will extracted from request.
     pub flatten: bool,
+    /// Whether the whole body is deserialized as this field, instead of the value of this field in body.
+    pub whole_body: bool,
     /// Field sources.
     pub sources: Vec<Source>,
     /// Field aliaes.
@@ -167,6 +184,7 @@ impl Field {
         Self {
             decl_name,
             flatten: false,
+            whole_body: false,
             sources,
             aliases: vec![],
             rename: None,
@@ -181,6 +199,12 @@ impl Field {
         self
     }
 
+    /// Sets whether the whole body is deserialized as this field.
+    pub fn whole_body(mut self, whole_body: bool) -> Self {
+        self.whole_body = whole_body;
+        self
+    }
+
     /// Sets the metadata to the field type.
     pub fn metadata(mut self, metadata: &'static Metadata) -> Self {
         self.metadata = Some(metadata);
@@ -219,7 +243,7 @@ impl Field {
 
     /// Check is this field has body required.
     pub(crate) fn has_body_required(&self) -> bool {
-        self.sources.iter().any(|s| s.from == SourceFrom::Body)
+        self.whole_body || self.sources.iter().any(|s| s.from == SourceFrom::Body)
     }
 }
 
@@ -61,17 +61,57 @@
 //! ```
 //!
 //! View [full source code](https://github.com/salvo-rs/salvo/blob/main/examples/extract-nested/src/main.rs)
+//!
+//! Sources of fields can also be set by shorthands `#[salvo(extract(param))]`, `#[salvo(extract(query))]` and
+//! `#[salvo(extract(header))]`. Field with `#[salvo(extract(body))]` is deserialized from the whole JSON or form body.
+//! If a field fails to parse, the error tells the name and source of the field:
+//!
+//! ```
+//! # use salvo_core::prelude::*;
+//! # use salvo_core::http::ParseError;
+//! # use serde::Deserialize;
+//! #[derive(Deserialize, Debug)]
+//! struct Post {
+//!     title: String,
+//! }
+//! #[derive(Deserialize, Extractible, Debug)]
+//! struct Params {
+//!     #[salvo(extract(param))]
+//!     id: i64,
+//!     #[salvo(extract(query))]
+//!     page: u32,
+//!     #[salvo(extract(header))]
+//!     authorization: String,
+//!     #[salvo(extract(body))]
+//!     post: Post,
+//! }
+//!
+//! #[handler]
+//! async fn edit(req: &mut Request) -> Result<String, ParseError> {
+//!     let params: Params = req.extract().await?;
+//!     Ok(format!("{} {}", params.id, params.post.title))
+//! }
+//! ```
 
 /// Metadata types.
 pub mod metadata;
 pub use metadata::Metadata;
 mod case;
 pub use case::RenameRule;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod json;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod query;
+pub use query::Query;
+#[cfg(feature = "cookie")]
+mod cookie;
 
 use std::fmt::Debug;
 use std::future::Future;
 
-use crate::http::Request;
+use crate::http::{ParseError, Request, StatusError};
 use crate::Writer;
 
 /// If a type implements this trait, it will give a metadata, this will help request to extracts data to this type.
@@ -99,3 +139,113 @@ pub trait Extractible<'ex> {
         Self::extract(req)
     }
 }
+
+/// Converts error of parsing request body in `format`, such as `json`, to [`StatusError`].
+///
+/// Invalid content type is `415 Unsupported Media Type`, data which is well-formed but can not be deserialized to
+/// the target type is `422 Unprocessable Entity`, and other errors are `400 Bad Request`.
+pub(crate) fn body_parse_error(e: ParseError, format: &str, content_type: &str) -> StatusError {
+    let decoded = match &e {
+        ParseError::InvalidContentType => {
+            return StatusError::unsupported_media_type()
+                .brief(format!("request content type should be `{content_type}`."))
+                .cause(e)
+        }
+        ParseError::PayloadTooLarge => return StatusError::payload_too_large().cause(e),
+        ParseError::SerdeJson(inner) => Some((inner.is_data(), inner.to_string())),
+        #[cfg(feature = "msgpack")]
+        ParseError::MsgPack(inner) => {
+            use rmp_serde::decode::Error as DecodeError;
+            let semantic = matches!(
+                inner,
+                DecodeError::TypeMismatch(_)
+                    | DecodeError::OutOfRange
+                    | DecodeError::LengthMismatch(_)
+                    | DecodeError::Syntax(_)
+            );
+            Some((semantic, inner.to_string()))
+        }
+        #[cfg(feature = "cbor")]
+        ParseError::Cbor(inner) => Some((matches!(inner, ciborium::de::Error::Semantic(..)), inner.to_string())),
+        _ => None,
+    };
+    let brief = format!("parse {format} data failed.");
+    match decoded {
+        Some((true, detail)) => StatusError::unprocessable_entity().brief(brief).detail(detail).cause(e),
+        Some((false, detail)) => StatusError::bad_request().brief(brief).detail(detail).cause(e),
+        None => StatusError::bad_request().brief(brief).cause(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::http::StatusCode;
+
+    #[derive(Deserialize, Debug)]
+    #[allow(dead_code)]
+    struct Payload {
+        name: String,
+    }
+
+    #[test]
+    fn test_body_parse_error() {
+        #[allow(unused_mut)]
+        let mut cases: Vec<(&str, ParseError, StatusCode)> = vec![
+            (
+                "json",
+                ParseError::InvalidContentType,
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ),
+            ("json", ParseError::PayloadTooLarge, StatusCode::PAYLOAD_TOO_LARGE),
+            ("json", ParseError::EmptyBody, StatusCode::BAD_REQUEST),
+            (
+                "json",
+                serde_json::from_str::<Payload>("{}").unwrap_err().into(),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                "json",
+                serde_json::from_str::<Payload>("{name").unwrap_err().into(),
+                StatusCode::BAD_REQUEST,
+            ),
+        ];
+        #[cfg(feature = "msgpack")]
+        cases.extend([
+            (
+                "msgpack",
+                rmp_serde::from_slice::<Payload>(&rmp_serde::to_vec(&1).unwrap())
+                    .unwrap_err()
+                    .into(),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                "msgpack",
+                rmp_serde::from_slice::<Payload>(&[0x81]).unwrap_err().into(),
+                StatusCode::BAD_REQUEST,
+            ),
+        ]);
+        #[cfg(feature = "cbor")]
+        cases.extend([
+            (
+                "cbor",
+                ciborium::from_reader::<Payload, _>(&[0xa0][..]).unwrap_err().into(),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                "cbor",
+                ciborium::from_reader::<Payload, _>(&[0xa2, 0x64][..])
+                    .unwrap_err()
+                    .into(),
+                StatusCode::BAD_REQUEST,
+            ),
+        ]);
+        for (format, e, status) in cases {
+            let description = format!("{format}: {e}");
+            let error = body_parse_error(e, format, "application/octet-stream");
+            assert_eq!(error.code, status, "{description}");
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use serde::Deserialize;
+
+use crate::extract::{body_parse_error, Extractible, Metadata};
+use crate::http::StatusError;
+use crate::writing::MsgPack;
+use crate::Request;
+
+/// Extracts the MessagePack body as type `T`.
+///
+/// Content type should be `application/msgpack`, `application/x-msgpack` or `application/vnd.msgpack`, otherwise
+/// a `415 Unsupported Media Type` error is written. If the body is not valid MessagePack, a `400 Bad Request` error
+/// is written, and if it can not be deserialized to `T`, for example a field has wrong type, a
+/// `422 Unprocessable Entity` error is written.
+///
+/// ```
+/// # use salvo_core::prelude::*;
+/// # use serde::{Deserialize, Serialize};
+/// #[derive(Deserialize, Serialize, Debug)]
+/// struct Payload {
+///     name: String,
+/// }
+///
+/// #[handler]
+/// async fn create(body: MsgPack<Payload>) -> MsgPack<Payload> {
+///     body
+/// }
+/// ```
+impl<'ex, T> Extractible<'ex> for MsgPack<T>
+where
+    T: Deserialize<'ex> + Send,
+{
+    fn metadata() -> &'ex Metadata {
+        static METADATA: Metadata = Metadata::new("");
+        &METADATA
+    }
+
+    #[allow(refining_impl_trait)]
+    async fn extract(req: &'ex mut Request) -> Result<Self, StatusError> {
+        req.parse_msgpack()
+            .await
+            .map(MsgPack)
+            .map_err(|e| body_parse_error(e, "msgpack", "application/msgpack"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[derive(Deserialize, Serialize, Eq, PartialEq, Debug)]
+    struct Payload {
+        name: String,
+        age: u8,
+    }
+
+    #[handler]
+    async fn create(body: MsgPack<Payload>) -> MsgPack<Payload> {
+        body
+    }
+
+    #[tokio::test]
+    async fn test_msgpack_extract() {
+        let service = Service::new(Router::new().post(create));
+        let payload = Payload {
+            name: "jobs".into(),
+            age: 30,
+        };
+
+        let mut res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header("content-type", "application/msgpack", true)
+            .body(rmp_serde::to_vec_named(&payload).unwrap())
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get("content-type").unwrap(), "application/msgpack");
+        let bytes = res.take_bytes(None).await.unwrap();
+        assert_eq!(rmp_serde::from_slice::<Payload>(&bytes).unwrap(), payload);
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header("content-type", "application/x-msgpack", true)
+            .body(rmp_serde::to_vec_named(&("jobs", "thirty")).unwrap())
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header("content-type", "application/msgpack", true)
+            .body(vec![0x82, 0xa4])
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .json(&payload)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt::{self, Formatter};
+use std::ops::{Deref, DerefMut};
+
+use serde::de::DeserializeOwned;
+
+use crate::extract::{Extractible, Metadata};
+use crate::http::{ParseError, StatusError};
+use crate::Request;
+
+/// Extracts the whole url query as type `T`.
+///
+/// Keys like `filter[name]=salvo` are parsed as nested objects. If the query can not be deserialized
+/// to `T`, for example a required field is missing, a `422 Unprocessable Entity` error is written.
+///
+/// ```
+/// # use salvo_core::prelude::*;
+/// # use salvo_core::extract::Query;
+/// # use serde::Deserialize;
+/// #[derive(Deserialize, Debug)]
+/// struct Pager {
+///     page: u32,
+///     size: Option<u32>,
+/// }
+///
+/// #[handler]
+/// async fn list(pager: Query<Pager>) -> String {
+///     format!("page: {}, size: {:?}", pager.page, pager.size)
+/// }
+/// ```
+pub struct Query<T>(pub T);
+
+impl<T> Query<T> {
+    /// Consumes self and returns the inner value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Query<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Query<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> fmt::Debug for Query<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'ex, T> Extractible<'ex> for Query<T>
+where
+    T: DeserializeOwned + Send,
+{
+    fn metadata() -> &'ex Metadata {
+        static METADATA: Metadata = Metadata::new("");
+        &METADATA
+    }
+
+    #[allow(refining_impl_trait)]
+    async fn extract(req: &'ex mut Request) -> Result<Self, StatusError> {
+        req.extract_query().map(Query).map_err(|e| {
+            // Use serde's message without the "Deserialize error" prefix.
+            let detail = if let ParseError::Deserialize(inner) = &e {
+                inner.to_string()
+            } else {
+                e.to_string()
+            };
+            StatusError::unprocessable_entity()
+                .brief("parse query data failed.")
+                .detail(detail)
+                .cause(e)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::http::StatusCode;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[derive(Deserialize, Debug)]
+    struct Pager {
+        page: u32,
+        size: Option<u32>,
+    }
+
+    #[handler]
+    async fn list(pager: Query<Pager>) -> String {
+        format!("page: {}, size: {:?}", pager.page, pager.size)
+    }
+
+    #[tokio::test]
+    async fn test_query_extract() {
+        let service = Service::new(Router::new().get(list));
+
+        let content = TestClient::get("http://127.0.0.1:5801/?page=2&size=10")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "page: 2, size: Some(10)");
+
+        let res = TestClient::get("http://127.0.0.1:5801/?size=10").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+    }
+}
@@ -124,4 +124,126 @@ mod test {
             Some(&HeaderValue::from_static("attachment; filename=attach.file"))
         );
     }
+
+    #[tokio::test]
+    async fn test_named_file_attached_name() {
+        let file = NamedFile::builder("Cargo.toml")
+            .attached_name("report 2024.txt")
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(
+            file.content_disposition(),
+            Some(&HeaderValue::from_static("attachment; filename=\"report 2024.txt\""))
+        );
+        let file = NamedFile::builder("Cargo.toml")
+            .attached_name("résumé \"final\".pdf")
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(
+            file.content_disposition(),
+            Some(&HeaderValue::from_static(
+                "attachment; filename=\"r_sum_ \\\"final\\\".pdf\"; filename*=UTF-8''r%C3%A9sum%C3%A9%20%22final%22.pdf"
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_file() {
+        use crate::http::header::{CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_NONE_MATCH, IF_RANGE, RANGE};
+        use crate::prelude::*;
+        use crate::test::{ResponseExt, TestClient};
+
+        #[handler]
+        async fn send(req: &mut Request, res: &mut Response) {
+            let path = req.query::<String>("path").unwrap();
+            res.send_file(path, req.headers()).await;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("digits.txt");
+        std::fs::write(&path, "0123456789").unwrap();
+        let service = Service::new(Router::new().get(send));
+        let url = format!("http://127.0.0.1:5801/?path={}", path.display());
+
+        let mut res = TestClient::get(&url).send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        let etag = res.headers().get(ETAG).unwrap().clone();
+        assert_eq!(res.take_string().await.unwrap(), "0123456789");
+
+        let res = TestClient::get(&url)
+            .add_header(IF_NONE_MATCH, etag.clone(), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_MODIFIED));
+
+        let mut res = TestClient::get(&url)
+            .add_header(RANGE, "bytes=2-4", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes 2-4/10");
+        assert_eq!(res.take_string().await.unwrap(), "234");
+
+        let mut res = TestClient::get(&url)
+            .add_header(RANGE, "bytes=2-4", true)
+            .add_header(IF_RANGE, "\"stale\"", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "0123456789");
+
+        let mut res = TestClient::get(&url)
+            .add_header(RANGE, "bytes=0-1,-2", true)
+            .add_header(IF_RANGE, etag, true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        let content_type = res.headers().get(CONTENT_TYPE).unwrap().to_str().unwrap().to_owned();
+        let boundary = content_type.strip_prefix("multipart/byteranges; boundary=").unwrap();
+        let content_length = res.headers().get(CONTENT_LENGTH).unwrap().to_str().unwrap().to_owned();
+        let body = res.take_string().await.unwrap();
+        assert_eq!(body.len().to_string(), content_length);
+        assert_eq!(
+            body,
+            format!(
+                "--{boundary}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Range: bytes 0-1/10\r\n\r\n01\r\n\
+                --{boundary}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Range: bytes 8-9/10\r\n\r\n89\r\n\
+                --{boundary}--\r\n"
+            )
+        );
+
+        // Overlapping ranges larger than the file are ignored.
+        let mut res = TestClient::get(&url)
+            .add_header(RANGE, "bytes=0-,0-", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "0123456789");
+
+        let res = TestClient::get(&url)
+            .add_header(RANGE, "bytes=20-", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::RANGE_NOT_SATISFIABLE));
+
+        let missing = format!(
+            "http://127.0.0.1:5801/?path={}",
+            dir.path().join("missing.txt").display()
+        );
+        let res = TestClient::get(missing).send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            // Permission is not checked for root user.
+            if !nix::unistd::Uid::effective().is_root() {
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o200)).unwrap();
+                let res = TestClient::get(&url).send(&service).await;
+                assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+            }
+        }
+    }
 }
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::cmp;
 use std::fs::Metadata;
+use std::io::ErrorKind;
 use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -10,15 +11,31 @@ use std::os::unix::fs::MetadataExt;
 
 use enumflags2::{bitflags, BitFlags};
 use headers::*;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use tokio::fs::File;
 
 use super::{ChunkedFile, ChunkedState};
 use crate::http::header::{CONTENT_DISPOSITION, CONTENT_ENCODING, IF_NONE_MATCH, RANGE};
-use crate::http::{HttpRange, Mime, Request, Response, StatusCode, StatusError};
+use crate::http::{is_token, HttpRange, Mime, RangeBody, Request, Response, StatusCode, StatusError};
 use crate::{async_trait, Depot, Error, Result, Writer};
 
 const CHUNK_SIZE: u64 = 1024 * 1024;
 
+/// Characters which are not `attr-char` in RFC 5987 and must be percent encoded in `filename*` parameter.
+const ATTR_CHAR_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'!')
+    .remove(b'#')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'+')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'^')
+    .remove(b'_')
+    .remove(b'`')
+    .remove(b'|')
+    .remove(b'~');
+
 #[bitflags(default = Etag | LastModified | ContentDisposition)]
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -138,13 +155,17 @@ impl NamedFileBuilder {
     }
 
     /// Build a new `NamedFile` and send it.
+    ///
+    /// If the file does not exist, `404 Not Found` is responded, if it can not be read because of permission,
+    /// `403 Forbidden` is responded.
     pub async fn send(self, req_headers: &HeaderMap, res: &mut Response) {
-        if !self.path.exists() {
-            res.render(StatusError::not_found());
-        } else {
-            match self.build().await {
-                Ok(file) => file.send(req_headers, res).await,
-                Err(_) => res.render(StatusError::internal_server_error()),
+        match self.build().await {
+            Ok(file) => file.send(req_headers, res).await,
+            Err(Error::Io(e)) if e.kind() == ErrorKind::NotFound => res.render(StatusError::not_found()),
+            Err(Error::Io(e)) if e.kind() == ErrorKind::PermissionDenied => res.render(StatusError::forbidden()),
+            Err(e) => {
+                tracing::error!(error = ?e, "open file failed");
+                res.render(StatusError::internal_server_error());
             }
         }
     }
@@ -222,7 +243,7 @@ fn build_content_disposition(
             }
         }
     });
-    let content_disposition = if disposition_type == "attachment" {
+    let content_disposition = if disposition_type == "attachment" || attached_name.is_some() {
         let attached_name = match attached_name {
             Some(attached_name) => Cow::Borrowed(attached_name),
             None => file_path
@@ -232,7 +253,7 @@ fn build_content_disposition(
                 .unwrap_or_else(|| "file".into())
                 .into(),
         };
-        format!("attachment; filename={attached_name}")
+        format!("{disposition_type}; {}", encode_filename(&attached_name))
             .parse::<HeaderValue>()
             .map_err(Error::other)?
     } else {
@@ -240,6 +261,43 @@ fn build_content_disposition(
     };
     Ok(content_disposition)
 }
+/// Encode `filename` parameters of `Content-Disposition` header as described in RFC 6266.
+///
+/// Plain token is used as is, other ASCII names are quoted. Names containing non-ASCII characters get an ASCII
+/// fallback in `filename` and the UTF-8 encoded name in `filename*`.
+pub(crate) fn encode_filename(name: &str) -> String {
+    let quote = |name: &str| {
+        let mut quoted = String::with_capacity(name.len() + 2);
+        quoted.push('"');
+        for c in name.chars() {
+            match c {
+                '"' | '\\' => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                c if c.is_ascii_control() => quoted.push('_'),
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        quoted
+    };
+    if is_token(name) {
+        format!("filename={name}")
+    } else if name.is_ascii() {
+        format!("filename={}", quote(name))
+    } else {
+        let fallback = name
+            .chars()
+            .map(|c| if c.is_ascii() { c } else { '_' })
+            .collect::<String>();
+        format!(
+            "filename={}; filename*=UTF-8''{}",
+            quote(&fallback),
+            utf8_percent_encode(name, ATTR_CHAR_ENCODE_SET)
+        )
+    }
+}
 impl NamedFile {
     /// Create new [`NamedFileBuilder`].
     #[inline]
@@ -455,34 +513,14 @@ impl NamedFile {
         if let Some(lm) = last_modified {
             res.headers_mut().typed_insert(LastModified::from(lm));
         }
-        if let Some(etag) = self.etag() {
-            res.headers_mut().typed_insert(etag);
+        if let Some(etag) = &etag {
+            res.headers_mut().typed_insert(etag.clone());
         }
         res.headers_mut().typed_insert(AcceptRanges::bytes());
 
-        let mut length = self.metadata.len();
         if let Some(content_encoding) = &self.content_encoding {
             res.headers_mut().insert(CONTENT_ENCODING, content_encoding.clone());
         }
-        let mut offset = 0;
-
-        // check for range header
-        let range = req_headers.get(RANGE);
-        if let Some(range) = range {
-            if let Ok(range) = range.to_str() {
-                if let Ok(range) = HttpRange::parse(range, length) {
-                    length = range[0].length;
-                    offset = range[0].start;
-                } else {
-                    res.headers_mut().typed_insert(ContentRange::unsatisfied_bytes(length));
-                    res.status_code(StatusCode::RANGE_NOT_SATISFIABLE);
-                    return;
-                };
-            } else {
-                res.status_code(StatusCode::BAD_REQUEST);
-                return;
-            };
-        }
 
         if precondition_failed {
             res.status_code(StatusCode::PRECONDITION_FAILED);
@@ -492,36 +530,69 @@ impl NamedFile {
             return;
         }
 
-        if offset != 0 || length != self.metadata.len() || range.is_some() {
-            res.status_code(StatusCode::PARTIAL_CONTENT);
-            match ContentRange::bytes(offset..offset + length - 1, self.metadata.len()) {
-                Ok(content_range) => {
-                    res.headers_mut().typed_insert(content_range);
-                }
-                Err(e) => {
-                    tracing::error!(error = ?e, "set file's content ranage failed");
+        // check for range header, it is ignored if `If-Range` does not match current representation.
+        let size = self.metadata.len();
+        let mut ranges = Vec::new();
+        if let Some(range) = req_headers.get(RANGE) {
+            if if_range_passes(etag.as_ref(), last_modified, req_headers) {
+                if let Ok(range) = range.to_str() {
+                    if let Ok(range) = HttpRange::parse(range, size) {
+                        // Abusive ranges are ignored and the whole file is sent.
+                        ranges = HttpRange::coalesce(range, size).unwrap_or_default();
+                    } else {
+                        res.headers_mut().typed_insert(ContentRange::unsatisfied_bytes(size));
+                        res.status_code(StatusCode::RANGE_NOT_SATISFIABLE);
+                        return;
+                    };
+                } else {
+                    res.status_code(StatusCode::BAD_REQUEST);
+                    return;
+                };
+            }
+        }
+
+        match ranges.len() {
+            0 => {
+                let file = self.file.into_std().await;
+                res.status_code(StatusCode::OK);
+                let reader = ChunkedFile {
+                    offset: 0,
+                    state: ChunkedState::File(Some(file)),
+                    total_size: size,
+                    read_size: 0,
+                    buffer_size: self.buffer_size,
+                };
+                res.headers_mut().typed_insert(ContentLength(size));
+                res.stream(reader);
+            }
+            1 => {
+                let file = self.file.into_std().await;
+                let HttpRange { start, length } = ranges[0];
+                res.status_code(StatusCode::PARTIAL_CONTENT);
+                match ContentRange::bytes(start..start + length, size) {
+                    Ok(content_range) => {
+                        res.headers_mut().typed_insert(content_range);
+                    }
+                    Err(e) => {
+                        tracing::error!(error = ?e, "set file's content ranage failed");
+                    }
                 }
+                let reader = ChunkedFile {
+                    offset: start,
+                    total_size: cmp::min(length, size),
+                    read_size: 0,
+                    state: ChunkedState::File(Some(file)),
+                    buffer_size: self.buffer_size,
+                };
+                res.headers_mut().typed_insert(ContentLength(reader.total_size));
+                res.stream(reader);
+            }
+            _ => {
+                RangeBody::new(self.file, ranges, size)
+                    .content_type(self.content_type)
+                    .buffer_size(self.buffer_size)
+                    .write_to(res);
             }
-            let reader = ChunkedFile {
-                offset,
-                total_size: cmp::min(length, self.metadata.len()),
-                read_size: 0,
-                state: ChunkedState::File(Some(self.file.into_std().await)),
-                buffer_size: self.buffer_size,
-            };
-            res.headers_mut().typed_insert(ContentLength(reader.total_size));
-            res.stream(reader);
-        } else {
-            res.status_code(StatusCode::OK);
-            let reader = ChunkedFile {
-                offset,
-                state: ChunkedState::File(Some(self.file.into_std().await)),
-                total_size: length,
-                read_size: 0,
-                buffer_size: self.buffer_size,
-            };
-            res.headers_mut().typed_insert(ContentLength(length));
-            res.stream(reader);
         }
     }
 }
@@ -563,6 +634,14 @@ fn any_match(etag: Option<&ETag>, req_headers: &HeaderMap) -> bool {
     }
 }
 
+/// Returns true if `req_headers` has no `If-Range` header or one which matches `etag` or `last_modified`.
+fn if_range_passes(etag: Option<&ETag>, last_modified: Option<SystemTime>, req_headers: &HeaderMap) -> bool {
+    match req_headers.typed_get::<IfRange>() {
+        None => true,
+        Some(if_range) => !if_range.is_modified(etag, last_modified.map(LastModified::from).as_ref()),
+    }
+}
+
 /// Returns true if `req_headers` doesn't have an `If-None-Match` header matching `req`.
 fn none_match(etag: Option<&ETag>, req_headers: &HeaderMap) -> bool {
     match req_headers.typed_get::<IfNoneMatch>() {
@@ -96,6 +96,39 @@
 //! }
 //! ```
 //!
+//! ## Return values
+//!
+//! Return value of handler is written to [`Response`] by it's [`Writer`](crate::Writer) implementation, any type can
+//! declare how it writes itself by implementing `Writer` or the simpler [`Scribe`](crate::Scribe).
+//!
+//! If the return value does not implement `Writer`, it is written by [`Responder`](crate::Responder), so
+//! `Result<T, E>` where `T` is serializable and `E: Into<StatusError>` writes `T` as json, and `Option<T>` writes
+//! `404 Not Found` for `None`. `(StatusCode, T)` sets the status code and then writes `T`:
+//!
+//! ```
+//! use salvo_core::prelude::*;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct User {
+//!     id: u64,
+//! }
+//!
+//! #[handler]
+//! async fn show(req: &mut Request) -> Result<User, StatusError> {
+//!     let id = req.param::<u64>("id").ok_or_else(StatusError::bad_request)?;
+//!     Ok(User { id })
+//! }
+//! #[handler]
+//! async fn find(req: &mut Request) -> Option<User> {
+//!     req.query::<u64>("id").map(|id| User { id })
+//! }
+//! #[handler]
+//! async fn create() -> (StatusCode, Json<User>) {
+//!     (StatusCode::CREATED, Json(User { id: 2 }))
+//! }
+//! ```
+//!
 //! ## Implement Handler trait directly
 //!
 //! Under certain circumstances, We need to implment `Handler` direclty.
@@ -117,7 +150,11 @@
 //!     }
 //! }
 //! ```
-use crate::http::StatusCode;
+use std::panic::AssertUnwindSafe;
+
+use futures_util::FutureExt;
+
+use crate::http::{ResBody, StatusCode, StatusError};
 use crate::{async_trait, Depot, FlowCtrl, Request, Response};
 
 /// `Handler` is used for handle [`Request`].
@@ -133,9 +170,199 @@ pub trait Handler: Send + Sync + 'static {
     fn type_name(&self) -> &'static str {
         std::any::type_name::<Self>()
     }
+    /// Name of this handler, it is used by [`FlowCtrl::call_next_named`] to find the handler.
+    ///
+    /// Returns `None` by default, use [`Handler::named`] to give a handler a name.
+    fn name(&self) -> Option<&str> {
+        None
+    }
     /// Handle http request.
     #[must_use = "handle future must be used"]
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl);
+
+    /// Wrap this handler with a name, so middlewares can jump to it using [`FlowCtrl::call_next_named`].
+    #[inline]
+    fn named(self, name: impl Into<String>) -> NamedHandler<Self>
+    where
+        Self: Sized,
+    {
+        NamedHandler {
+            inner: self,
+            name: name.into(),
+        }
+    }
+
+    /// Chain `next` after this handler, `next` runs only if this handler did not skip the rest handlers and did not
+    /// write an error or redirection status code.
+    ///
+    /// The combinators are designed for handlers which don't call [`FlowCtrl::call_next`] themselves.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn auth(req: &mut Request, res: &mut Response) {
+    ///     if req.header::<String>("x-token").is_none() {
+    ///         res.render(StatusError::unauthorized());
+    ///     }
+    /// }
+    /// #[handler]
+    /// async fn hello() -> &'static str {
+    ///     "hello"
+    /// }
+    ///
+    /// let router = Router::new().get(auth.and_then(hello));
+    /// ```
+    #[inline]
+    fn and_then<N>(self, next: N) -> AndThen<Self, N>
+    where
+        Self: Sized,
+        N: Handler,
+    {
+        AndThen { first: self, next }
+    }
+
+    /// Run `fallback` if this handler writes a client or server error status code.
+    ///
+    /// Status code and body written by this handler are cleared before `fallback` runs, headers are kept.
+    #[inline]
+    fn or_else<F>(self, fallback: F) -> OrElse<Self, F>
+    where
+        Self: Sized,
+        F: Handler,
+    {
+        OrElse { first: self, fallback }
+    }
+
+    /// Transform the [`StatusError`] written by this handler with `f`.
+    ///
+    /// An error status code without body is converted to [`StatusError`] before transforming, other bodies are
+    /// kept unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn find_user(res: &mut Response) {
+    ///     res.render(StatusError::not_found());
+    /// }
+    ///
+    /// let router = Router::new().get(find_user.map_err(|e: StatusError| e.brief("User not found.")));
+    /// ```
+    #[inline]
+    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized,
+        F: Fn(StatusError) -> StatusError + Send + Sync + 'static,
+    {
+        MapErr { inner: self, f }
+    }
+}
+
+/// Handler created by [`Handler::and_then`].
+#[non_exhaustive]
+pub struct AndThen<H, N> {
+    /// The handler runs first.
+    pub first: H,
+    /// The handler runs if `first` succeeds.
+    pub next: N,
+}
+#[async_trait]
+impl<H, N> Handler for AndThen<H, N>
+where
+    H: Handler,
+    N: Handler,
+{
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let skipped = ctrl.is_skipped();
+        self.first.handle(req, depot, res, ctrl).await;
+        if (skipped || !ctrl.is_skipped()) && !res.is_stamped() {
+            self.next.handle(req, depot, res, ctrl).await;
+        }
+    }
+}
+
+/// Handler created by [`Handler::or_else`].
+#[non_exhaustive]
+pub struct OrElse<H, F> {
+    /// The handler runs first.
+    pub first: H,
+    /// The handler runs if `first` writes an error status code.
+    pub fallback: F,
+}
+#[async_trait]
+impl<H, F> Handler for OrElse<H, F>
+where
+    H: Handler,
+    F: Handler,
+{
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        self.first.handle(req, depot, res, ctrl).await;
+        if res
+            .status_code
+            .map(|code| code.is_client_error() || code.is_server_error())
+            .unwrap_or(false)
+        {
+            res.status_code = None;
+            res.body = ResBody::None;
+            self.fallback.handle(req, depot, res, ctrl).await;
+        }
+    }
+}
+
+/// Handler created by [`Handler::map_err`].
+#[non_exhaustive]
+pub struct MapErr<H, F> {
+    /// The inner handler.
+    pub inner: H,
+    /// The function to transform error.
+    pub f: F,
+}
+#[async_trait]
+impl<H, F> Handler for MapErr<H, F>
+where
+    H: Handler,
+    F: Fn(StatusError) -> StatusError + Send + Sync + 'static,
+{
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        self.inner.handle(req, depot, res, ctrl).await;
+        let error = match res.take_body() {
+            ResBody::Error(e) => Some(e),
+            ResBody::None => res.status_code.and_then(StatusError::from_code),
+            body => {
+                res.body = body;
+                None
+            }
+        };
+        if let Some(error) = error {
+            res.render((self.f)(error));
+        }
+    }
+}
+
+/// Handler with a name, it is created by [`Handler::named`].
+#[non_exhaustive]
+pub struct NamedHandler<H> {
+    /// The inner handler.
+    pub inner: H,
+    /// The name of the handler.
+    pub name: String,
+}
+#[async_trait]
+impl<H> Handler for NamedHandler<H>
+where
+    H: Handler,
+{
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        self.inner.handle(req, depot, res, ctrl).await;
+    }
 }
 
 #[doc(hidden)]
@@ -173,6 +400,28 @@ where
     }
 }
 
+#[doc(hidden)]
+#[non_exhaustive]
+pub struct AfterHoop<H> {
+    pub inner: H,
+}
+#[async_trait]
+impl<H> Handler for AfterHoop<H>
+where
+    H: Handler,
+{
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let result = AssertUnwindSafe(ctrl.call_next(req, depot, res)).catch_unwind().await;
+        if let Err(panic) = result {
+            // Run the hook with the error response the panic will end up as, then keep unwinding.
+            res.render(StatusError::internal_server_error());
+            self.inner.handle(req, depot, res, ctrl).await;
+            std::panic::resume_unwind(panic);
+        }
+        self.inner.handle(req, depot, res, ctrl).await;
+    }
+}
+
 /// `Skipper` is used to check if the request should be skipped.
 ///
 /// `Skipper` is used in many middlewares.
@@ -296,3 +545,71 @@ macro_rules! __for_each_tuple {
 
 __for_each_tuple!(handler_tuple_impls);
 __for_each_tuple!(skipper_tuple_impls);
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[handler]
+    async fn auth(req: &mut Request, res: &mut Response) {
+        if req.query::<String>("token").is_none() {
+            res.render(StatusError::unauthorized());
+        }
+    }
+    #[handler]
+    async fn deny(res: &mut Response, ctrl: &mut FlowCtrl) {
+        res.render("denied");
+        ctrl.skip_rest();
+    }
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+    #[handler]
+    async fn missing(res: &mut Response) {
+        res.status_code(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_handler_combinators() {
+        let router = Router::new()
+            .push(Router::with_path("auth").get(auth.and_then(hello)))
+            .push(Router::with_path("deny").get(deny.and_then(hello)))
+            .push(Router::with_path("fallback").get(missing.or_else(auth.and_then(hello))))
+            .push(
+                Router::with_path("mapped").get(missing.map_err(|e| e.brief("nothing here")).map_err(|mut e| {
+                    e.brief = format!("{}, try later", e.brief);
+                    e
+                })),
+            );
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801/auth").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        let mut res = TestClient::get("http://127.0.0.1:5801/auth?token=1")
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+        let mut res = TestClient::get("http://127.0.0.1:5801/deny?token=1")
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "denied");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/fallback?token=1")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+        let res = TestClient::get("http://127.0.0.1:5801/fallback").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/mapped")
+            .add_header("accept", "application/json", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        let content = res.take_string().await.unwrap();
+        assert!(content.contains(r#""brief":"nothing here, try later""#), "{content}");
+    }
+}
@@ -0,0 +1,95 @@
+//! Helpers for parsing credentials in `Authorization` header.
+//!
+//! They are used by [`Request::bearer_token`] and [`Request::basic_auth`], and also by authentication middlewares
+//! which read credentials from other headers, such as `Proxy-Authorization`.
+//!
+//! [`Request::bearer_token`]: crate::http::Request::bearer_token
+//! [`Request::basic_auth`]: crate::http::Request::basic_auth
+use base64::engine::{general_purpose, Engine};
+
+/// Split credentials value to scheme and parameters, scheme is compared case-insensitively.
+fn strip_scheme<'a>(value: &'a str, scheme: &str) -> Option<&'a str> {
+    let value = value.trim();
+    let (name, params) = value.split_once(|c: char| c.is_ascii_whitespace())?;
+    if name.eq_ignore_ascii_case(scheme) {
+        let params = params.trim();
+        (!params.is_empty()).then_some(params)
+    } else {
+        None
+    }
+}
+
+/// Parse token from `Bearer` credentials, such as `Bearer mF_9.B5f-4.1JqM`.
+///
+/// Returns `None` if the scheme is not `Bearer` or the token is empty or contains whitespace.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::http::auth::parse_bearer_token;
+///
+/// assert_eq!(parse_bearer_token(" bearer  mF_9.B5f-4.1JqM "), Some("mF_9.B5f-4.1JqM"));
+/// assert_eq!(parse_bearer_token("Basic dXNlcjpwYXNz"), None);
+/// ```
+pub fn parse_bearer_token(value: &str) -> Option<&str> {
+    strip_scheme(value, "Bearer").filter(|token| !token.contains(|c: char| c.is_ascii_whitespace()))
+}
+
+/// Parse username and password from `Basic` credentials, such as `Basic dXNlcjpwYXNz`.
+///
+/// Password is `None` if there is no `:` in the decoded credentials. Returns `None` if the scheme is not `Basic`
+/// or the credentials are not valid base64. Credentials are decoded as UTF-8, and as ISO-8859-1 if it is not valid
+/// UTF-8.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::http::auth::parse_basic_auth;
+///
+/// assert_eq!(
+///     parse_basic_auth("BASIC dXNlcjpwYXNz"),
+///     Some(("user".to_owned(), Some("pass".to_owned())))
+/// );
+/// assert_eq!(parse_basic_auth("Basic !!!"), None);
+/// ```
+pub fn parse_basic_auth(value: &str) -> Option<(String, Option<String>)> {
+    let credentials = general_purpose::STANDARD.decode(strip_scheme(value, "Basic")?).ok()?;
+    let credentials = String::from_utf8(credentials)
+        .unwrap_or_else(|e| e.into_bytes().iter().map(|&c| c as char).collect::<String>());
+    match credentials.split_once(':') {
+        Some((username, password)) => Some((username.to_owned(), Some(password.to_owned()))),
+        None => Some((credentials, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bearer_token() {
+        assert_eq!(parse_bearer_token("Bearer abc.def"), Some("abc.def"));
+        assert_eq!(parse_bearer_token("BEARER\tabc.def\r\n"), Some("abc.def"));
+        assert_eq!(parse_bearer_token("Bearer"), None);
+        assert_eq!(parse_bearer_token("Bearer    "), None);
+        assert_eq!(parse_bearer_token("Bearer abc def"), None);
+        assert_eq!(parse_bearer_token("Bearerabc"), None);
+        assert_eq!(parse_bearer_token("Token abc"), None);
+    }
+
+    #[test]
+    fn test_parse_basic_auth() {
+        assert_eq!(
+            parse_basic_auth("basic dXNlcjpwYTpzcw=="),
+            Some(("user".to_owned(), Some("pa:ss".to_owned())))
+        );
+        assert_eq!(parse_basic_auth("Basic dXNlcg=="), Some(("user".to_owned(), None)));
+        assert_eq!(parse_basic_auth("Basic not-base64"), None);
+        assert_eq!(parse_basic_auth("Bearer dXNlcjpwYXNz"), None);
+        // "jörg:pwd" encoded in ISO-8859-1.
+        assert_eq!(
+            parse_basic_auth("Basic avZyZzpwd2Q="),
+            Some(("jörg".to_owned(), Some("pwd".to_owned())))
+        );
+    }
+}
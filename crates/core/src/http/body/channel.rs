@@ -2,6 +2,7 @@ use std::fmt;
 use std::io::{Error as IoError, ErrorKind, Result as IoResult};
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use bytes::Bytes;
 use futures_channel::{mpsc, oneshot};
@@ -13,17 +14,21 @@ use hyper::HeaderMap;
 ///
 /// ## Body Closing
 ///
-/// Note that the request body will always be closed normally when the sender is dropped (meaning
+/// Note that the response body will always be closed normally when the sender is dropped (meaning
 /// that the empty terminating chunk will be sent to the remote). If you desire to close the
 /// connection with an incomplete response (e.g. in the case of an error during asynchronous
-/// processing), call the [`Sender::abort()`] method to abort the body in an abnormal fashion.
+/// processing), call the [`BodySender::abort()`] method to abort the body in an abnormal fashion.
 ///
-/// [`Body::channel()`]: struct.Body.html#method.channel
-/// [`Sender::abort()`]: struct.Sender.html#method.abort
+/// If the body is created by [`ResBody::channel_with_size`](super::ResBody::channel_with_size), the
+/// `Content-Length` is promised to the remote, so dropping the sender before all bytes are sent
+/// aborts the body instead of ending it normally.
 #[must_use = "Sender does nothing unless sent on"]
 pub struct BodySender {
     pub(crate) data_tx: mpsc::Sender<Result<Bytes, IoError>>,
     pub(crate) trailers_tx: Option<oneshot::Sender<HeaderMap>>,
+    pub(crate) size: Option<u64>,
+    pub(crate) sent: u64,
+    pub(crate) finished: bool,
 }
 impl BodySender {
     /// Check to see if this `Sender` can send more data.
@@ -34,6 +39,9 @@ impl BodySender {
     }
 
     /// Returns whether this channel is closed without needing a context.
+    ///
+    /// The channel is closed when the body is dropped, for example the client is disconnected, so
+    /// producers can check it to stop early.
     pub fn is_closed(&self) -> bool {
         self.data_tx.is_closed()
     }
@@ -46,16 +54,51 @@ impl BodySender {
         self.data_tx.disconnect();
     }
 
+    /// Send data on data channel if it is ready, it is used by the `AsyncWrite` implementations.
+    fn poll_send(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        let sent = self.sent + buf.len() as u64;
+        if self.size.is_some_and(|size| sent > size) {
+            return Poll::Ready(Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "data exceeds the size of body",
+            )));
+        }
+        std::task::ready!(self.poll_ready(cx))?;
+        self.data_tx
+            .try_send(Ok(Bytes::copy_from_slice(buf)))
+            .map_err(|e| IoError::new(ErrorKind::Other, format!("failed to send data: {}", e)))?;
+        self.sent = sent;
+        Poll::Ready(Ok(buf.len()))
+    }
+
     async fn ready(&mut self) -> IoResult<()> {
         futures_util::future::poll_fn(|cx| self.poll_ready(cx)).await
     }
 
     /// Send data on data channel when it is ready.
+    ///
+    /// Returns an error if the data exceeds the size promised by
+    /// [`ResBody::channel_with_size`](super::ResBody::channel_with_size).
     pub async fn send_data(&mut self, chunk: impl Into<Bytes> + Send) -> IoResult<()> {
+        let chunk = chunk.into();
+        let sent = self.sent + chunk.len() as u64;
+        if self.size.is_some_and(|size| sent > size) {
+            return Err(IoError::new(ErrorKind::InvalidInput, "data exceeds the size of body"));
+        }
         self.ready().await?;
         self.data_tx
-            .try_send(Ok(chunk.into()))
-            .map_err(|e| IoError::new(ErrorKind::Other, format!("failed to send data: {}", e)))
+            .try_send(Ok(chunk))
+            .map_err(|e| IoError::new(ErrorKind::Other, format!("failed to send data: {}", e)))?;
+        self.sent = sent;
+        Ok(())
+    }
+
+    /// Send data on data channel, returns an error of kind [`ErrorKind::TimedOut`] if the channel
+    /// is not ready in `timeout`, for example the client reads too slowly.
+    pub async fn send_timeout(&mut self, chunk: impl Into<Bytes> + Send, timeout: Duration) -> IoResult<()> {
+        tokio::time::timeout(timeout, self.send_data(chunk))
+            .await
+            .map_err(|_| IoError::new(ErrorKind::TimedOut, "send data timed out"))?
     }
 
     /// Send trailers on trailers channel.
@@ -76,28 +119,46 @@ impl BodySender {
             .clone()
             .try_send(Err(err));
     }
+
+    /// Aborts the body with an error, the remote sees an incomplete response instead of a normal end, the
+    /// HTTP/1 connection is closed and the HTTP/2 stream is reset.
+    pub fn abort(mut self, err: IoError) {
+        self.finished = true;
+        self.send_error(err);
+    }
+
+    /// Ends the body normally.
+    ///
+    /// Returns an error and aborts the body if less data than the size promised by
+    /// [`ResBody::channel_with_size`](super::ResBody::channel_with_size) is sent.
+    pub fn finish(mut self) -> IoResult<()> {
+        self.finished = true;
+        match self.size {
+            Some(size) if self.sent != size => {
+                let msg = format!("body finished after {} of {} bytes", self.sent, size);
+                self.send_error(IoError::new(ErrorKind::UnexpectedEof, msg.clone()));
+                Err(IoError::new(ErrorKind::UnexpectedEof, msg))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
-impl futures_util::AsyncWrite for BodySender {
-    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
-        match self.data_tx.poll_ready(cx) {
-            Poll::Ready(Ok(())) => {
-                let data: Bytes = Bytes::from(buf.to_vec());
-                let len = buf.len();
-                Poll::Ready(
-                    self.data_tx
-                        .try_send(Ok(data))
-                        .map(|_| len)
-                        .map_err(|e| IoError::new(ErrorKind::Other, format!("failed to send data: {}", e))),
-                )
+impl Drop for BodySender {
+    fn drop(&mut self) {
+        if !self.finished {
+            if let Some(size) = self.size.filter(|size| *size != self.sent) {
+                let msg = format!("body sender dropped after {} of {} bytes", self.sent, size);
+                self.send_error(IoError::new(ErrorKind::UnexpectedEof, msg));
             }
-            Poll::Ready(Err(e)) => Poll::Ready(Err(IoError::new(
-                ErrorKind::Other,
-                format!("failed to poll ready: {}", e),
-            ))),
-            Poll::Pending => Poll::Pending,
         }
     }
+}
+
+impl futures_util::AsyncWrite for BodySender {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        self.poll_send(cx, buf)
+    }
 
     fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<IoResult<()>> {
         Poll::Ready(Ok(()))
@@ -114,23 +175,7 @@ impl futures_util::AsyncWrite for BodySender {
 
 impl tokio::io::AsyncWrite for BodySender {
     fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
-        match self.data_tx.poll_ready(cx) {
-            Poll::Ready(Ok(())) => {
-                let data: Bytes = Bytes::from(buf.to_vec());
-                let len = buf.len();
-                Poll::Ready(
-                    self.data_tx
-                        .try_send(Ok(data))
-                        .map(|_| len)
-                        .map_err(|e| IoError::new(ErrorKind::Other, format!("failed to send data: {}", e))),
-                )
-            }
-            Poll::Ready(Err(e)) => Poll::Ready(Err(IoError::new(
-                ErrorKind::Other,
-                format!("failed to poll ready: {}", e),
-            ))),
-            Poll::Pending => Poll::Pending,
-        }
+        self.poll_send(cx, buf)
     }
 
     fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<IoResult<()>> {
@@ -158,4 +203,5 @@ impl fmt::Debug for BodySender {
 pub struct BodyReceiver {
     pub(crate) data_rx: mpsc::Receiver<Result<Bytes, IoError>>,
     pub(crate) trailers_rx: oneshot::Receiver<HeaderMap>,
+    pub(crate) size: Option<u64>,
 }
@@ -6,6 +6,8 @@ mod req;
 #[cfg(feature = "quinn")]
 pub use req::h3::H3ReqBody;
 pub use req::ReqBody;
+mod reader;
+pub use reader::BodyReader;
 mod res;
 pub use hyper::body::Incoming as HyperBody;
 pub use res::ResBody;
@@ -0,0 +1,136 @@
+use std::io::Result as IoResult;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use bytes::{Buf, Bytes};
+use hyper::body::Body;
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
+
+use super::ReqBody;
+
+/// An [`AsyncRead`] view of [`ReqBody`], it is returned by [`Request::body_reader`].
+///
+/// Only one DATA frame is buffered at a time, trailers frames are ignored and body errors are returned as
+/// [`std::io::Error`].
+///
+/// [`Request::body_reader`]: crate::http::Request::body_reader
+#[derive(Debug)]
+pub struct BodyReader {
+    body: ReqBody,
+    chunk: Bytes,
+}
+
+impl BodyReader {
+    /// Create a new `BodyReader`.
+    #[inline]
+    pub fn new(body: ReqBody) -> Self {
+        Self {
+            body,
+            chunk: Bytes::new(),
+        }
+    }
+
+    /// Consumes self and returns the inner body, data in the buffered frame is returned too.
+    #[inline]
+    pub fn into_inner(self) -> (Bytes, ReqBody) {
+        (self.chunk, self.body)
+    }
+}
+
+impl From<ReqBody> for BodyReader {
+    #[inline]
+    fn from(body: ReqBody) -> Self {
+        Self::new(body)
+    }
+}
+
+impl AsyncBufRead for BodyReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<&[u8]>> {
+        let this = self.get_mut();
+        while this.chunk.is_empty() {
+            match ready!(Pin::new(&mut this.body).poll_frame(cx)) {
+                Some(Ok(frame)) => {
+                    // Trailers frames are ignored.
+                    if let Ok(data) = frame.into_data() {
+                        this.chunk = data;
+                    }
+                }
+                Some(Err(e)) => return Poll::Ready(Err(e)),
+                None => break,
+            }
+        }
+        Poll::Ready(Ok(&this.chunk))
+    }
+
+    #[inline]
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().chunk.advance(amt);
+    }
+}
+
+impl AsyncRead for BodyReader {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<IoResult<()>> {
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        let data = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let len = data.len().min(buf.remaining());
+        buf.put_slice(&data[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error as IoError, ErrorKind};
+
+    use futures_util::stream;
+    use http_body_util::StreamBody;
+    use hyper::body::Frame;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+    use super::*;
+    use crate::http::HeaderMap;
+    use crate::BoxedError;
+
+    fn boxed(frames: Vec<Result<Frame<Bytes>, BoxedError>>) -> ReqBody {
+        ReqBody::Boxed {
+            inner: Box::pin(StreamBody::new(stream::iter(frames))),
+            fusewire: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_frames() {
+        let body = boxed(vec![
+            Ok(Frame::data(Bytes::from_static(b"hello "))),
+            Ok(Frame::data(Bytes::new())),
+            Ok(Frame::data(Bytes::from_static(b"world\nsalvo"))),
+            Ok(Frame::trailers(HeaderMap::new())),
+        ]);
+        let mut reader = BodyReader::new(body);
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "hello world\n");
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).await.unwrap();
+        assert_eq!(rest, "salvo");
+
+        let mut content = vec![];
+        BodyReader::new("once".into()).read_to_end(&mut content).await.unwrap();
+        assert_eq!(content, b"once");
+    }
+
+    #[tokio::test]
+    async fn test_read_error() {
+        let body = boxed(vec![
+            Ok(Frame::data(Bytes::from_static(b"hello"))),
+            Err(IoError::new(ErrorKind::ConnectionReset, "reset").into()),
+        ]);
+        let mut content = vec![];
+        let err = BodyReader::new(body).read_to_end(&mut content).await.unwrap_err();
+        assert_eq!(err.to_string(), "reset");
+        assert_eq!(content, b"hello");
+    }
+}
@@ -5,11 +5,13 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use futures_util::stream::Stream;
+use http_body_util::{BodyExt, Collected, LengthLimitError, Limited};
 use hyper::body::{Body, Frame, Incoming, SizeHint};
 
 use bytes::Bytes;
 
 use crate::fuse::{ArcFusewire, FuseEvent};
+use crate::http::ParseError;
 use crate::BoxedError;
 
 pub(crate) type BoxedBody = Pin<Box<dyn Body<Data = Bytes, Error = BoxedError> + Send + Sync + 'static>>;
@@ -79,6 +81,44 @@ impl ReqBody {
     pub fn take(&mut self) -> Self {
         std::mem::replace(self, Self::None)
     }
+
+    /// Reads all frames of this body into `Bytes`, at most `max_size` bytes are read.
+    ///
+    /// `ParseError::PayloadTooLarge` is returned if the size hint or the read size exceeds `max_size`, and
+    /// `ParseError::Io` is returned if reading the underlying stream fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use salvo_core::http::{ParseError, ReqBody};
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let body = ReqBody::from("hello");
+    /// assert_eq!(body.collect_with_limit(8).await.unwrap(), "hello");
+    /// let body = ReqBody::from("hello");
+    /// assert!(matches!(body.collect_with_limit(4).await, Err(ParseError::PayloadTooLarge)));
+    /// # }
+    /// ```
+    pub async fn collect_with_limit(self, max_size: usize) -> Result<Bytes, ParseError> {
+        self.collect_limited(max_size).await.map(Collected::to_bytes)
+    }
+
+    /// Reads all frames with trailers of this body, see [`ReqBody::collect_with_limit`].
+    pub(crate) async fn collect_limited(self, max_size: usize) -> Result<Collected<Bytes>, ParseError> {
+        if Body::size_hint(&self).lower() > max_size as u64 {
+            return Err(ParseError::PayloadTooLarge);
+        }
+        Limited::new(self, max_size).collect().await.map_err(|e| {
+            if e.is::<LengthLimitError>() {
+                ParseError::PayloadTooLarge
+            } else {
+                match e.downcast::<IoError>() {
+                    Ok(e) => ParseError::Io(*e),
+                    Err(e) => ParseError::other(e),
+                }
+            }
+        })
+    }
 }
 
 impl Body for ReqBody {
@@ -303,3 +343,87 @@ impl fmt::Debug for ReqBody {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use futures_util::stream;
+    use http_body_util::StreamBody;
+
+    use super::*;
+    use crate::http::HeaderMap;
+
+    fn boxed(frames: Vec<Result<Frame<Bytes>, BoxedError>>) -> ReqBody {
+        ReqBody::Boxed {
+            inner: Box::pin(StreamBody::new(stream::iter(frames))),
+            fusewire: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_with_limit() {
+        let frames = || {
+            vec![
+                Ok(Frame::data(Bytes::from_static(b"hello "))),
+                Ok(Frame::data(Bytes::from_static(b"world"))),
+                Ok(Frame::trailers(HeaderMap::new())),
+            ]
+        };
+        assert_eq!(boxed(frames()).collect_with_limit(11).await.unwrap(), "hello world");
+        assert!(matches!(
+            boxed(frames()).collect_with_limit(10).await,
+            Err(ParseError::PayloadTooLarge)
+        ));
+        assert!(matches!(
+            ReqBody::from("hello").collect_with_limit(4).await,
+            Err(ParseError::PayloadTooLarge)
+        ));
+        assert_eq!(ReqBody::None.collect_with_limit(0).await.unwrap(), "");
+
+        let body = boxed(vec![
+            Ok(Frame::data(Bytes::from_static(b"hello"))),
+            Err(IoError::new(ErrorKind::ConnectionReset, "reset").into()),
+        ]);
+        match body.collect_with_limit(64).await {
+            Err(ParseError::Io(e)) => assert_eq!(e.to_string(), "reset"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_hyper_body_with_limit() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        use crate::conn::{Acceptor, Listener, TcpListener};
+        use crate::prelude::*;
+
+        #[handler]
+        async fn upload(req: &mut Request) -> String {
+            match req.take_body().collect_with_limit(8).await {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(e) => e.to_string(),
+            }
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(Server::new(acceptor).serve(Router::new().post(upload)));
+
+        async fn send(addr: std::net::SocketAddr, chunks: &str) -> String {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            let request = format!(
+                "POST / HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\nTransfer-Encoding: chunked\r\n\r\n{chunks}"
+            );
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            let _ = stream.read_to_string(&mut response).await;
+            response
+        }
+        let response = send(addr, "3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n").await;
+        assert!(response.ends_with("\r\n\r\nabcde"), "{response}");
+        let response = send(addr, "5\r\nabcde\r\n5\r\nfghij\r\n0\r\n\r\n").await;
+        assert!(response.ends_with("The request's body is too large."), "{response}");
+    }
+}
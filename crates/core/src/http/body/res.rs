@@ -8,7 +8,9 @@ use std::pin::Pin;
 use std::task::{self, ready, Context, Poll};
 
 use futures_channel::{mpsc, oneshot};
-use futures_util::stream::{BoxStream, FusedStream, Stream, TryStreamExt};
+use futures_util::future::poll_fn;
+use futures_util::stream::{BoxStream, FusedStream, Stream, StreamExt, TryStreamExt};
+use http::HeaderMap;
 use hyper::body::{Body, Frame, Incoming, SizeHint};
 use sync_wrapper::SyncWrapper;
 
@@ -82,6 +84,21 @@ impl ResBody {
         matches!(*self, Self::Error(_))
     }
 
+    /// Wrap an arbitrary [`Body`] in a box, frames are passed through without copying.
+    ///
+    /// Size hint of the inner body is kept, so `Content-Length` header is sent if its size is exact.
+    pub fn boxed<B>(body: B) -> Self
+    where
+        B: Body + Send + Sync + 'static,
+        B::Data: Into<Bytes>,
+        B::Error: Into<BoxedError>,
+    {
+        Self::Boxed(Box::pin(http_body_util::BodyExt::map_err(
+            http_body_util::BodyExt::map_frame(body, |frame| frame.map_data(Into::into)),
+            Into::into,
+        )))
+    }
+
     /// Wrap a futures `Stream` in a box inside `Body`.
     pub fn stream<S, O, E>(stream: S) -> Self
     where
@@ -93,22 +110,82 @@ impl ResBody {
         Self::Stream(SyncWrapper::new(Box::pin(mapped)))
     }
 
+    /// Wrap a futures `Stream` whose total size is known in a box inside `Body`.
+    ///
+    /// The size is used as exact size hint of the body, so `Content-Length` header is sent instead of chunked
+    /// transfer encoding. If the stream yields more or less bytes than `size`, the body is aborted.
+    pub fn stream_with_size<S, O, E>(stream: S, size: u64) -> Self
+    where
+        S: Stream<Item = Result<O, E>> + Send + 'static,
+        O: Into<BytesFrame> + 'static,
+        E: Into<BoxedError> + 'static,
+    {
+        Self::stream(stream).with_size(size)
+    }
+
+    /// Wrap the body to announce an exact `size`, it is used as exact size hint of the body, so `Content-Length`
+    /// header is sent instead of chunked transfer encoding.
+    ///
+    /// Bytes are counted when the body is sent, if the body yields more or less bytes than `size`, a warning is
+    /// logged and the body is aborted, so the client can tell the response is incomplete. The body is returned
+    /// as is if its size is already known and equals to `size`, or it is an error body.
+    pub fn with_size(self, size: u64) -> Self {
+        if self.is_error() || self.size() == Some(size) {
+            return self;
+        }
+        Self::Boxed(Box::pin(SizedBody {
+            inner: self,
+            size,
+            sent: 0,
+        }))
+    }
+
     /// Create a `Body` stream with an associated sender half.
     ///
     /// Useful when wanting to stream chunks from another thread.
     pub fn channel() -> (BodySender, Self) {
+        Self::sized_channel(None)
+    }
+
+    /// Create a `Body` stream with an associated sender half, the body has an exact `size` which is sent as
+    /// `Content-Length` header.
+    ///
+    /// If the sender is dropped before `size` bytes are sent, the body is aborted rather than ended normally.
+    pub fn channel_with_size(size: u64) -> (BodySender, Self) {
+        Self::sized_channel(Some(size))
+    }
+
+    fn sized_channel(size: Option<u64>) -> (BodySender, Self) {
         let (data_tx, data_rx) = mpsc::channel(0);
         let (trailers_tx, trailers_rx) = oneshot::channel();
 
         let tx = BodySender {
             data_tx,
             trailers_tx: Some(trailers_tx),
+            size,
+            sent: 0,
+            finished: false,
         };
-        let rx = ResBody::Channel(BodyReceiver { data_rx, trailers_rx });
+        let rx = ResBody::Channel(BodyReceiver {
+            data_rx,
+            trailers_rx,
+            size,
+        });
 
         (tx, rx)
     }
 
+    /// Wrap the body to send `trailers` in a trailers frame after all data frames.
+    ///
+    /// If the body sends trailers itself, such as a channel body which trailers are sent by
+    /// [`BodySender::send_trailers`], `trailers` are merged into them.
+    pub fn with_trailers(self, trailers: HeaderMap) -> Self {
+        Self::Boxed(Box::pin(TrailersBody {
+            inner: self,
+            trailers: Some(trailers),
+        }))
+    }
+
     /// Get body's size.
     #[inline]
     pub fn size(&self) -> Option<u64> {
@@ -129,6 +206,76 @@ impl ResBody {
     pub fn take(&mut self) -> Self {
         std::mem::replace(self, Self::None)
     }
+
+    /// Buffers the body into `Bytes` if it is not larger than `max_size`.
+    ///
+    /// The body is returned in `Err` if it is larger than `max_size`, has trailers, fails to read or is an error
+    /// body. Frames which are already read are put back before the rest of the body, so middlewares can send the
+    /// returned body as is. Unlike [`ReqBody::collect_with_limit`](super::ReqBody::collect_with_limit), which fails
+    /// with [`ParseError`](crate::http::ParseError), nothing of the body is lost when it can not be buffered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use salvo_core::http::ResBody;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let body = ResBody::Chunks(vec!["hello ".into(), "world".into()].into());
+    /// assert_eq!(body.buffer_with_limit(16).await.unwrap(), "hello world");
+    /// let body = ResBody::Chunks(vec!["hello ".into(), "world".into()].into());
+    /// assert!(body.buffer_with_limit(8).await.is_err());
+    /// # }
+    /// ```
+    pub async fn buffer_with_limit(self, max_size: usize) -> Result<Bytes, Self> {
+        match self {
+            Self::None => return Ok(Bytes::new()),
+            Self::Once(bytes) if bytes.len() <= max_size => return Ok(bytes),
+            Self::Chunks(chunks) if self.size().unwrap_or_default() <= max_size as u64 => {
+                return Ok(concat_chunks(chunks.into()))
+            }
+            Self::Once(_) | Self::Chunks(_) | Self::Error(_) => return Err(self),
+            _ => {}
+        }
+        let hint = Body::size_hint(&self);
+        if hint.lower() > max_size as u64 {
+            return Err(self);
+        }
+        let mut body = self;
+        let mut chunks = Vec::new();
+        let mut total = 0;
+        loop {
+            let stop = match poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await {
+                None => return Ok(concat_chunks(chunks)),
+                Some(Ok(frame)) => match frame.into_data() {
+                    Ok(data) => {
+                        total += data.len();
+                        chunks.push(data);
+                        if total <= max_size {
+                            continue;
+                        }
+                        None
+                    }
+                    Err(frame) => Some(Ok(BytesFrame(frame))),
+                },
+                Some(Err(e)) => Some(Err(BoxedError::from(e))),
+            };
+            let read =
+                futures_util::stream::iter(chunks.into_iter().map(|data| Ok(BytesFrame::data(data))).chain(stop));
+            let rest = read.chain(body.map_ok(BytesFrame).map_err(BoxedError::from));
+            return Err(match hint.exact() {
+                Some(size) => Self::stream_with_size(rest, size),
+                None => Self::stream(rest),
+            });
+        }
+    }
+}
+
+fn concat_chunks(mut chunks: Vec<Bytes>) -> Bytes {
+    if chunks.len() == 1 {
+        chunks.pop().unwrap_or_default()
+    } else {
+        chunks.concat().into()
+    }
 }
 
 impl Body for ResBody {
@@ -209,12 +356,105 @@ impl Body for ResBody {
             Self::Hyper(recv) => recv.size_hint(),
             Self::Boxed(recv) => recv.size_hint(),
             Self::Stream(_) => SizeHint::default(),
-            Self::Channel { .. } => SizeHint::default(),
+            Self::Channel(rx) => rx.size.map(SizeHint::with_exact).unwrap_or_default(),
             Self::Error(_) => SizeHint::with_exact(0),
         }
     }
 }
 
+/// Body which sends extra trailers after inner body.
+struct TrailersBody {
+    inner: ResBody,
+    trailers: Option<HeaderMap>,
+}
+impl Body for TrailersBody {
+    type Data = Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, BoxedError>>> {
+        let this = self.get_mut();
+        match ready!(Pin::new(&mut this.inner).poll_frame(cx)) {
+            Some(Ok(frame)) => match frame.into_trailers() {
+                Ok(mut trailers) => {
+                    if let Some(extra) = this.trailers.take() {
+                        trailers.extend(extra);
+                    }
+                    Poll::Ready(Some(Ok(Frame::trailers(trailers))))
+                }
+                Err(frame) => Poll::Ready(Some(Ok(frame))),
+            },
+            Some(Err(e)) => Poll::Ready(Some(Err(e.into()))),
+            None => Poll::Ready(this.trailers.take().map(|trailers| Ok(Frame::trailers(trailers)))),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.trailers.is_none() && self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        // Exact size hint makes HTTP/1.1 response not chunked, then trailers can not be sent.
+        let mut hint = SizeHint::new();
+        hint.set_lower(Body::size_hint(&self.inner).lower());
+        hint
+    }
+}
+
+/// Body with known total size.
+struct SizedBody {
+    inner: ResBody,
+    size: u64,
+    sent: u64,
+}
+impl Body for SizedBody {
+    type Data = Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, BoxedError>>> {
+        let this = self.get_mut();
+        match ready!(Pin::new(&mut this.inner).poll_frame(cx)) {
+            Some(Ok(frame)) => {
+                if let Some(data) = frame.data_ref() {
+                    this.sent += data.len() as u64;
+                    if this.sent > this.size {
+                        tracing::warn!(size = this.size, sent = this.sent, "body exceeds its content length");
+                        return Poll::Ready(Some(Err(IoError::new(
+                            ErrorKind::InvalidData,
+                            "body exceeds its content length",
+                        )
+                        .into())));
+                    }
+                }
+                Poll::Ready(Some(Ok(frame)))
+            }
+            Some(Err(e)) => Poll::Ready(Some(Err(e.into()))),
+            None if this.sent < this.size => {
+                tracing::warn!(
+                    size = this.size,
+                    sent = this.sent,
+                    "body ends before its content length"
+                );
+                Poll::Ready(Some(Err(IoError::new(
+                    ErrorKind::UnexpectedEof,
+                    format!("body finished after {} of {} bytes", this.sent, this.size),
+                )
+                .into())))
+            }
+            None => Poll::Ready(None),
+        }
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.size == 0 && self.inner.is_end_stream()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.size.saturating_sub(self.sent))
+    }
+}
+
 impl Stream for ResBody {
     type Item = IoResult<Frame<Bytes>>;
 
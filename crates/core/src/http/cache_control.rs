@@ -0,0 +1,309 @@
+//! Builder of `Cache-Control` response header.
+use std::fmt::{self, Display, Formatter, Write};
+use std::time::Duration;
+
+use http::HeaderValue;
+
+use super::is_token;
+
+/// Builder of `Cache-Control` response header value, it is set by [`Response::cache_control`].
+///
+/// Directives are rendered as defined in [RFC 9111, Section 5.2](https://www.rfc-editor.org/rfc/rfc9111#section-5.2),
+/// field names of `private` and `no-cache` are sent as quoted string and extension values are quoted when they are
+/// not tokens.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::http::{CacheControl, Response};
+///
+/// let mut res = Response::new();
+/// res.cache_control(CacheControl::new().max_age(3600).s_maxage(86400).stale_while_revalidate(60).public());
+/// assert_eq!(
+///     res.headers().get("cache-control").unwrap(),
+///     "public, max-age=3600, s-maxage=86400, stale-while-revalidate=60"
+/// );
+/// ```
+///
+/// [`Response::cache_control`]: crate::http::Response::cache_control
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CacheControl {
+    public: bool,
+    private: Option<Vec<String>>,
+    no_cache: Option<Vec<String>>,
+    no_store: bool,
+    no_transform: bool,
+    must_revalidate: bool,
+    proxy_revalidate: bool,
+    must_understand: bool,
+    immutable: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+    stale_while_revalidate: Option<u64>,
+    stale_if_error: Option<u64>,
+    extensions: Vec<(String, Option<String>)>,
+}
+
+impl CacheControl {
+    /// Create new empty `CacheControl`.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds `public` directive.
+    #[inline]
+    pub fn public(mut self) -> Self {
+        self.public = true;
+        self
+    }
+    /// Adds `private` directive.
+    #[inline]
+    pub fn private(mut self) -> Self {
+        self.private.get_or_insert_with(Vec::new);
+        self
+    }
+    /// Adds `private` directive which only applies to the given header fields, e.g. `private="Set-Cookie"`.
+    #[inline]
+    pub fn private_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.private
+            .get_or_insert_with(Vec::new)
+            .extend(fields.into_iter().map(Into::into));
+        self
+    }
+    /// Adds `no-cache` directive.
+    #[inline]
+    pub fn no_cache(mut self) -> Self {
+        self.no_cache.get_or_insert_with(Vec::new);
+        self
+    }
+    /// Adds `no-cache` directive which only applies to the given header fields.
+    #[inline]
+    pub fn no_cache_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.no_cache
+            .get_or_insert_with(Vec::new)
+            .extend(fields.into_iter().map(Into::into));
+        self
+    }
+    /// Adds `no-store` directive.
+    #[inline]
+    pub fn no_store(mut self) -> Self {
+        self.no_store = true;
+        self
+    }
+    /// Adds `no-transform` directive.
+    #[inline]
+    pub fn no_transform(mut self) -> Self {
+        self.no_transform = true;
+        self
+    }
+    /// Adds `must-revalidate` directive.
+    #[inline]
+    pub fn must_revalidate(mut self) -> Self {
+        self.must_revalidate = true;
+        self
+    }
+    /// Adds `proxy-revalidate` directive.
+    #[inline]
+    pub fn proxy_revalidate(mut self) -> Self {
+        self.proxy_revalidate = true;
+        self
+    }
+    /// Adds `must-understand` directive.
+    #[inline]
+    pub fn must_understand(mut self) -> Self {
+        self.must_understand = true;
+        self
+    }
+    /// Adds `immutable` directive defined in [RFC 8246](https://www.rfc-editor.org/rfc/rfc8246).
+    #[inline]
+    pub fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+    /// Sets `max-age` directive in seconds.
+    #[inline]
+    pub fn max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+    /// Sets `s-maxage` directive in seconds.
+    #[inline]
+    pub fn s_maxage(mut self, seconds: u64) -> Self {
+        self.s_maxage = Some(seconds);
+        self
+    }
+    /// Sets `stale-while-revalidate` directive in seconds, defined in [RFC 5861](https://www.rfc-editor.org/rfc/rfc5861).
+    #[inline]
+    pub fn stale_while_revalidate(mut self, seconds: u64) -> Self {
+        self.stale_while_revalidate = Some(seconds);
+        self
+    }
+    /// Sets `stale-if-error` directive in seconds, defined in [RFC 5861](https://www.rfc-editor.org/rfc/rfc5861).
+    #[inline]
+    pub fn stale_if_error(mut self, seconds: u64) -> Self {
+        self.stale_if_error = Some(seconds);
+        self
+    }
+    /// Sets `max-age` directive with a [`Duration`], fractions of second are dropped.
+    #[inline]
+    pub fn max_age_duration(self, duration: Duration) -> Self {
+        self.max_age(duration.as_secs())
+    }
+    /// Adds an extension directive, the value is quoted if it is not a token.
+    #[inline]
+    pub fn extension(mut self, name: impl Into<String>, value: Option<String>) -> Self {
+        self.extensions.push((name.into(), value));
+        self
+    }
+
+    /// Returns `true` if no directive is added.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Converts to [`HeaderValue`], it fails if a field name or extension contains invalid characters.
+    pub fn to_header_value(&self) -> Result<HeaderValue, http::header::InvalidHeaderValue> {
+        HeaderValue::from_str(&self.to_string())
+    }
+}
+
+impl Display for CacheControl {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut directives = Directives { f, first: true };
+        if self.public {
+            directives.name("public")?;
+        }
+        if let Some(fields) = &self.private {
+            directives.fields("private", fields)?;
+        }
+        if let Some(fields) = &self.no_cache {
+            directives.fields("no-cache", fields)?;
+        }
+        if self.no_store {
+            directives.name("no-store")?;
+        }
+        if self.no_transform {
+            directives.name("no-transform")?;
+        }
+        if self.must_revalidate {
+            directives.name("must-revalidate")?;
+        }
+        if self.proxy_revalidate {
+            directives.name("proxy-revalidate")?;
+        }
+        if self.must_understand {
+            directives.name("must-understand")?;
+        }
+        if self.immutable {
+            directives.name("immutable")?;
+        }
+        for (name, value) in [
+            ("max-age", self.max_age),
+            ("s-maxage", self.s_maxage),
+            ("stale-while-revalidate", self.stale_while_revalidate),
+            ("stale-if-error", self.stale_if_error),
+        ] {
+            if let Some(value) = value {
+                directives.name(name)?;
+                write!(directives.f, "={value}")?;
+            }
+        }
+        for (name, value) in &self.extensions {
+            directives.name(name)?;
+            if let Some(value) = value {
+                directives.f.write_char('=')?;
+                write_token_or_quoted(directives.f, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Directives<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    first: bool,
+}
+impl Directives<'_, '_> {
+    fn name(&mut self, name: &str) -> fmt::Result {
+        if !self.first {
+            self.f.write_str(", ")?;
+        }
+        self.first = false;
+        self.f.write_str(name)
+    }
+    fn fields(&mut self, name: &str, fields: &[String]) -> fmt::Result {
+        self.name(name)?;
+        if fields.is_empty() {
+            return Ok(());
+        }
+        // Field names are always sent as quoted string, see RFC 9111, Section 5.2.2.4 and 5.2.2.7.
+        self.f.write_str("=\"")?;
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                self.f.write_str(", ")?;
+            }
+            write_escaped(self.f, field)?;
+        }
+        self.f.write_char('"')
+    }
+}
+
+fn write_token_or_quoted(f: &mut Formatter, value: &str) -> fmt::Result {
+    if is_token(value) {
+        f.write_str(value)
+    } else {
+        f.write_char('"')?;
+        write_escaped(f, value)?;
+        f.write_char('"')
+    }
+}
+fn write_escaped(f: &mut Formatter, value: &str) -> fmt::Result {
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_control_display() {
+        assert_eq!(CacheControl::new().to_string(), "");
+        assert_eq!(
+            CacheControl::new().no_store().no_cache().must_revalidate().to_string(),
+            "no-cache, no-store, must-revalidate"
+        );
+        assert_eq!(
+            CacheControl::new()
+                .private_fields(["Set-Cookie", "Authorization"])
+                .max_age(0)
+                .to_string(),
+            "private=\"Set-Cookie, Authorization\", max-age=0"
+        );
+        assert_eq!(
+            CacheControl::new()
+                .immutable()
+                .max_age_duration(Duration::from_millis(31_536_000_500))
+                .extension("community", Some("UCI".into()))
+                .extension("note", Some("say \"hi\"".into()))
+                .extension("x-flag", None)
+                .to_string(),
+            "immutable, max-age=31536000, community=UCI, note=\"say \\\"hi\\\"\", x-flag"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Decode text by the charset of content type.
+use std::borrow::Cow;
+
+use crate::http::ParseError;
+
+/// Returns `true` if the charset is absent or it is UTF-8.
+pub(crate) fn is_utf8(charset: Option<&str>) -> bool {
+    match charset {
+        Some(charset) => charset.eq_ignore_ascii_case("utf-8") || charset.eq_ignore_ascii_case("utf8"),
+        None => true,
+    }
+}
+
+/// Decode `bytes` by `charset`, UTF-8 is used if charset is absent.
+///
+/// UTF-8, US-ASCII and ISO-8859-1 are always supported, other charsets are only supported if `charset` feature is
+/// enabled, [`ParseError::UnsupportedCharset`] is returned for unknown charsets. If `lossy` is `true`, unknown
+/// charsets fall back to UTF-8 and malformed sequences are replaced with `U+FFFD`.
+pub(crate) fn decode<'a>(bytes: &'a [u8], charset: Option<&str>, lossy: bool) -> Result<Cow<'a, str>, ParseError> {
+    let charset = match charset {
+        Some(charset) if !is_utf8(Some(charset)) => charset,
+        _ => return decode_utf8(bytes, lossy),
+    };
+    #[cfg(feature = "charset")]
+    if let Some(encoding) = encoding_rs::Encoding::for_label(charset.as_bytes()) {
+        let (content, malformed) = encoding.decode_without_bom_handling(bytes);
+        if malformed && !lossy {
+            return Err(ParseError::other(format!("content is not valid {}", encoding.name())));
+        }
+        return Ok(content);
+    }
+    if let Some(content) = decode_ascii_compatible(bytes, charset, lossy) {
+        return content;
+    }
+    if lossy {
+        Ok(String::from_utf8_lossy(bytes))
+    } else {
+        Err(ParseError::UnsupportedCharset(charset.to_owned()))
+    }
+}
+
+fn decode_utf8(bytes: &[u8], lossy: bool) -> Result<Cow<'_, str>, ParseError> {
+    if lossy {
+        Ok(String::from_utf8_lossy(bytes))
+    } else {
+        Ok(Cow::Borrowed(std::str::from_utf8(bytes)?))
+    }
+}
+
+/// Decode US-ASCII and ISO-8859-1 without `encoding_rs`, returns `None` for other charsets.
+fn decode_ascii_compatible<'a>(
+    bytes: &'a [u8],
+    charset: &str,
+    lossy: bool,
+) -> Option<Result<Cow<'a, str>, ParseError>> {
+    let charset = charset.to_ascii_lowercase();
+    match charset.as_str() {
+        "us-ascii" | "ascii" => {
+            if !lossy && !bytes.is_ascii() {
+                return Some(Err(ParseError::other("content is not valid US-ASCII")));
+            }
+            Some(decode_utf8(bytes, lossy))
+        }
+        "iso-8859-1" | "iso8859-1" | "iso_8859-1" | "latin1" | "l1" => {
+            if bytes.is_ascii() {
+                Some(decode_utf8(bytes, lossy))
+            } else {
+                Some(Ok(Cow::Owned(bytes.iter().map(|b| char::from(*b)).collect())))
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("中文".as_bytes(), None, false).unwrap(), "中文");
+        assert_eq!(decode("中文".as_bytes(), Some("UTF-8"), false).unwrap(), "中文");
+        assert!(decode(b"\xff", None, false).is_err());
+        assert_eq!(decode(b"a\xff", None, true).unwrap(), "a\u{FFFD}");
+        assert!(matches!(
+            decode(b"abc", Some("no-such-charset"), false),
+            Err(ParseError::UnsupportedCharset(charset)) if charset == "no-such-charset"
+        ));
+        assert_eq!(decode(b"abc", Some("no-such-charset"), true).unwrap(), "abc");
+        assert_eq!(decode(b"caf\xe9", Some("ISO-8859-1"), false).unwrap(), "café");
+        assert_eq!(decode(b"abc", Some("us-ascii"), false).unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_decode_ascii_compatible() {
+        let decode = |bytes, charset, lossy| decode_ascii_compatible(bytes, charset, lossy).unwrap();
+        assert_eq!(decode(b"caf\xe9", "latin1", false).unwrap(), "café");
+        assert_eq!(decode(b"abc", "US-ASCII", false).unwrap(), "abc");
+        assert!(decode(b"caf\xe9", "us-ascii", false).is_err());
+        assert_eq!(decode(b"caf\xe9", "us-ascii", true).unwrap(), "caf\u{FFFD}");
+        assert!(decode_ascii_compatible(b"abc", "gbk", false).is_none());
+    }
+
+    #[cfg(feature = "charset")]
+    #[test]
+    fn test_decode_charset() {
+        assert_eq!(decode(b"\xd6\xd0\xce\xc4", Some("gbk"), false).unwrap(), "中文");
+        assert_eq!(decode(b"caf\xe9", Some("iso-8859-1"), false).unwrap(), "café");
+        assert!(decode(b"\x81", Some("gbk"), false).is_err());
+        assert_eq!(decode(b"\x81", Some("gbk"), true).unwrap(), "\u{FFFD}");
+    }
+}
@@ -0,0 +1,193 @@
+use std::time::SystemTime;
+
+use headers::{ETag, HeaderMapExt, IfMatch, IfModifiedSince, IfNoneMatch, IfUnmodifiedSince};
+use http::header::{IF_MATCH, IF_NONE_MATCH};
+
+use crate::http::{HeaderMap, Method, StatusCode};
+
+/// Result of evaluating conditional request headers, it is returned by [`Request::is_fresh`].
+///
+/// Conditional headers are evaluated in the order defined by
+/// [RFC 7232, Section 6](https://tools.ietf.org/html/rfc7232#section-6):
+/// `If-Match`, `If-Unmodified-Since`, `If-None-Match` and `If-Modified-Since`.
+///
+/// [`Request::is_fresh`]: crate::http::Request::is_fresh
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Precondition {
+    /// All preconditions passed, the request should be processed normally.
+    Passed,
+    /// The client's cached representation is still fresh, respond `304 Not Modified`.
+    NotModified,
+    /// A precondition failed, respond `412 Precondition Failed`.
+    Failed,
+}
+
+impl Precondition {
+    /// Returns the status code should be responded, `None` if the request should be processed normally.
+    #[inline]
+    pub fn status_code(&self) -> Option<StatusCode> {
+        match self {
+            Self::Passed => None,
+            Self::NotModified => Some(StatusCode::NOT_MODIFIED),
+            Self::Failed => Some(StatusCode::PRECONDITION_FAILED),
+        }
+    }
+}
+
+/// Evaluate conditional headers against current resource's `etag` and `last_modified`.
+pub(crate) fn evaluate(
+    method: &Method,
+    headers: &HeaderMap,
+    etag: Option<&ETag>,
+    last_modified: Option<SystemTime>,
+) -> Precondition {
+    // Step 1 and 2: If-Match, or If-Unmodified-Since if If-Match is not present.
+    if headers.contains_key(IF_MATCH) {
+        let passed = match headers.typed_get::<IfMatch>() {
+            Some(if_match) if if_match == IfMatch::any() => true,
+            Some(if_match) => etag.map(|etag| if_match.precondition_passes(etag)).unwrap_or(false),
+            None => false,
+        };
+        if !passed {
+            return Precondition::Failed;
+        }
+    } else if let (Some(since), Some(last_modified)) = (headers.typed_get::<IfUnmodifiedSince>(), last_modified) {
+        if !since.precondition_passes(last_modified) {
+            return Precondition::Failed;
+        }
+    }
+
+    let is_read = *method == Method::GET || *method == Method::HEAD;
+    // Step 3 and 4: If-None-Match, or If-Modified-Since if If-None-Match is not present.
+    if headers.contains_key(IF_NONE_MATCH) {
+        let matched = match headers.typed_get::<IfNoneMatch>() {
+            Some(if_none_match) if if_none_match == IfNoneMatch::any() => true,
+            // `precondition_passes` uses weak comparison, and returns false if etag matched.
+            Some(if_none_match) => etag
+                .map(|etag| !if_none_match.precondition_passes(etag))
+                .unwrap_or(false),
+            None => false,
+        };
+        if matched {
+            return if is_read {
+                Precondition::NotModified
+            } else {
+                Precondition::Failed
+            };
+        }
+    } else if is_read {
+        if let (Some(since), Some(last_modified)) = (headers.typed_get::<IfModifiedSince>(), last_modified) {
+            if !since.is_modified(last_modified) {
+                return Precondition::NotModified;
+            }
+        }
+    }
+    Precondition::Passed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use http::header::{IF_MODIFIED_SINCE, IF_UNMODIFIED_SINCE};
+
+    use super::*;
+    use crate::http::HeaderValue;
+
+    fn headers(pairs: &[(http::header::HeaderName, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    // Sun, 06 Nov 1994 08:49:37 GMT
+    fn last_modified() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(784111777)
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = "\"xyzzy\"".parse::<ETag>().unwrap();
+        let etag = Some(&etag);
+        let check = |method: Method, value| evaluate(&method, &headers(&[(IF_NONE_MATCH, value)]), etag, None);
+        assert_eq!(check(Method::GET, "\"xyzzy\""), Precondition::NotModified);
+        assert_eq!(check(Method::GET, "W/\"xyzzy\""), Precondition::NotModified);
+        assert_eq!(check(Method::HEAD, "\"a\", \"xyzzy\""), Precondition::NotModified);
+        assert_eq!(check(Method::GET, "*"), Precondition::NotModified);
+        assert_eq!(check(Method::GET, "\"other\""), Precondition::Passed);
+        assert_eq!(check(Method::PUT, "*"), Precondition::Failed);
+    }
+
+    #[test]
+    fn test_if_match() {
+        let etag = "\"xyzzy\"".parse::<ETag>().unwrap();
+        let check = |value, etag| evaluate(&Method::PUT, &headers(&[(IF_MATCH, value)]), etag, None);
+        assert_eq!(check("\"xyzzy\"", Some(&etag)), Precondition::Passed);
+        assert_eq!(check("*", None), Precondition::Passed);
+        assert_eq!(check("W/\"xyzzy\"", Some(&etag)), Precondition::Failed);
+        assert_eq!(check("\"other\"", Some(&etag)), Precondition::Failed);
+        assert_eq!(check("\"xyzzy\"", None), Precondition::Failed);
+    }
+
+    #[test]
+    fn test_modified_since_date_formats() {
+        for date in [
+            "Sun, 06 Nov 1994 08:49:37 GMT",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun Nov  6 08:49:37 1994",
+        ] {
+            let headers = headers(&[(IF_MODIFIED_SINCE, date)]);
+            assert_eq!(
+                evaluate(&Method::GET, &headers, None, Some(last_modified())),
+                Precondition::NotModified
+            );
+            assert_eq!(
+                evaluate(&Method::POST, &headers, None, Some(last_modified())),
+                Precondition::Passed
+            );
+            assert_eq!(
+                evaluate(
+                    &Method::GET,
+                    &headers,
+                    None,
+                    Some(last_modified() + Duration::from_secs(1))
+                ),
+                Precondition::Passed
+            );
+        }
+    }
+
+    #[test]
+    fn test_unmodified_since() {
+        let headers = headers(&[(IF_UNMODIFIED_SINCE, "Sun, 06 Nov 1994 08:49:37 GMT")]);
+        assert_eq!(
+            evaluate(&Method::PUT, &headers, None, Some(last_modified())),
+            Precondition::Passed
+        );
+        assert_eq!(
+            evaluate(
+                &Method::PUT,
+                &headers,
+                None,
+                Some(last_modified() + Duration::from_secs(1))
+            ),
+            Precondition::Failed
+        );
+    }
+
+    #[test]
+    fn test_if_none_match_takes_precedence() {
+        let etag = "\"xyzzy\"".parse::<ETag>().unwrap();
+        let headers = headers(&[
+            (IF_NONE_MATCH, "\"other\""),
+            (IF_MODIFIED_SINCE, "Sun, 06 Nov 1994 08:49:37 GMT"),
+        ]);
+        assert_eq!(
+            evaluate(&Method::GET, &headers, Some(&etag), Some(last_modified())),
+            Precondition::Passed
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Request deadline propagated between services.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default header name used to propagate deadline between services, its value is unix time in milliseconds.
+pub const DEADLINE_HEADER: &str = "x-request-deadline";
+
+/// Key in [`Depot`](crate::Depot) for the [`HeaderName`](crate::http::HeaderName) which carries the deadline of
+/// current request, it is set by deadline middleware and proxies forward the deadline in the same header.
+pub const DEADLINE_HEADER_KEY: &str = "::salvo::deadline::header";
+
+/// The point in time when a request should be done.
+///
+/// It is inserted into [`Depot`] by deadline middleware and got by [`Depot::deadline`], time limit of timeout
+/// middleware and upstream calls of proxy are shortened to the remaining budget.
+///
+/// [`Depot`]: crate::Depot
+/// [`Depot::deadline`]: crate::Depot::deadline
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Deadline {
+    at: SystemTime,
+}
+
+impl Deadline {
+    /// Create new `Deadline` at the given time.
+    #[inline]
+    pub fn new(at: SystemTime) -> Self {
+        Self { at }
+    }
+    /// Create new `Deadline` after `timeout` from now.
+    #[inline]
+    pub fn after(timeout: Duration) -> Self {
+        Self::new(SystemTime::now() + timeout)
+    }
+    /// Create new `Deadline` from unix time in milliseconds.
+    #[inline]
+    pub fn from_unix_millis(millis: u64) -> Self {
+        Self::new(UNIX_EPOCH + Duration::from_millis(millis))
+    }
+    /// Parse `Deadline` from header value, which is unix time in milliseconds.
+    #[inline]
+    pub fn parse(value: &str) -> Option<Self> {
+        value.trim().parse::<u64>().ok().map(Self::from_unix_millis)
+    }
+
+    /// Get the time of this deadline.
+    #[inline]
+    pub fn at(&self) -> SystemTime {
+        self.at
+    }
+    /// Get the time of this deadline as unix time in milliseconds.
+    #[inline]
+    pub fn unix_millis(&self) -> u64 {
+        self.at
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default()
+    }
+    /// Get the remaining time budget, it is zero if the deadline has passed.
+    #[inline]
+    pub fn remaining(&self) -> Duration {
+        self.at.duration_since(SystemTime::now()).unwrap_or_default()
+    }
+    /// Returns `true` if the deadline has passed.
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline() {
+        let deadline = Deadline::parse(" 1700000000123 ").unwrap();
+        assert_eq!(deadline.unix_millis(), 1700000000123);
+        assert!(deadline.is_expired());
+        assert_eq!(deadline.remaining(), Duration::ZERO);
+        assert!(Deadline::parse("soon").is_none());
+
+        let deadline = Deadline::after(Duration::from_secs(60));
+        assert!(!deadline.is_expired());
+        assert!(deadline.remaining() > Duration::from_secs(50));
+        assert_eq!(
+            Deadline::parse(&deadline.unix_millis().to_string())
+                .unwrap()
+                .unix_millis(),
+            deadline.unix_millis()
+        );
+    }
+}
@@ -1,6 +1,8 @@
 //! HTTP Errors.
 
 mod parse_error;
+pub(crate) mod problem_detail;
 mod status_error;
 pub use parse_error::{ParseError, ParseResult};
-pub use status_error::{StatusError, StatusResult};
+pub use problem_detail::ProblemDetail;
+pub use status_error::{ErrorExtra, StatusError, StatusResult};
@@ -19,6 +19,10 @@ pub enum ParseError {
     #[error("The request did not have a valid Content-Type header.")]
     InvalidContentType,
 
+    /// The charset of content type is not supported.
+    #[error("Unsupported charset `{0}`.")]
+    UnsupportedCharset(String),
+
     /// The Hyper request's body is empty.
     #[error("The request's body is empty.")]
     EmptyBody,
@@ -50,7 +54,7 @@ pub enum ParseError {
     InvalidHeaderValue(#[from] http::header::InvalidHeaderValue),
 
     /// Deserialize error when parse from request.
-    #[error("Deserialize error.")]
+    #[error("Deserialize error: {0}")]
     Deserialize(#[from] DeError),
 
     /// DuplicateKey.
@@ -65,6 +69,10 @@ pub enum ParseError {
     #[error("The Hyper request Content-Type sub-level Mime was not `FormData`.")]
     NotFormData,
 
+    /// The request's body is larger than the size limit.
+    #[error("The request's body is too large.")]
+    PayloadTooLarge,
+
     /// InvalidRange.
     #[error("InvalidRange")]
     InvalidRange,
@@ -89,6 +97,36 @@ pub enum ParseError {
     #[error("Serde json error: {0}")]
     SerdeJson(#[from] serde_json::error::Error),
 
+    /// MessagePack decode error.
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+    #[error("MessagePack decode error: {0}")]
+    MsgPack(#[from] rmp_serde::decode::Error),
+
+    /// CBOR decode error.
+    #[cfg(feature = "cbor")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+    #[error("CBOR decode error: {0}")]
+    Cbor(#[from] ciborium::de::Error<IoError>),
+
+    /// XML deserialize error.
+    #[cfg(feature = "xml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "xml")))]
+    #[error("XML deserialize error: {0}")]
+    Xml(#[from] quick_xml::DeError),
+
+    /// The typed header is not present.
+    #[cfg(feature = "typed-headers")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "typed-headers")))]
+    #[error("Header `{0}` is missing.")]
+    MissingHeader(http::header::HeaderName),
+
+    /// The typed header is present but it can not be decoded.
+    #[cfg(feature = "typed-headers")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "typed-headers")))]
+    #[error("Header `{0}` is invalid.")]
+    InvalidTypedHeader(http::header::HeaderName),
+
     /// Custom error that does not fall under any other error kind.
     #[error("Other error: {0}")]
     Other(BoxedError),
@@ -104,7 +142,25 @@ impl ParseError {
 #[async_trait]
 impl Writer for ParseError {
     async fn write(self, _req: &mut Request, _depot: &mut Depot, res: &mut Response) {
-        res.render(StatusError::bad_request().brief("parse http data failed.").cause(self));
+        match self {
+            Self::PayloadTooLarge => res.render(StatusError::payload_too_large().cause(self)),
+            Self::UnsupportedCharset(ref charset) => {
+                let brief = format!("unsupported charset `{charset}`.");
+                res.render(StatusError::unsupported_media_type().brief(brief).cause(self));
+            }
+            // Error message is useful for clients to fix the malformed document, and cause is not rendered in release.
+            #[cfg(feature = "xml")]
+            Self::Xml(ref e) => {
+                let brief = format!("parse xml data failed: {e}");
+                res.render(StatusError::bad_request().brief(brief).cause(self));
+            }
+            // The message names the invalid field, such as `items[0].qty`.
+            Self::Deserialize(ref e) => {
+                let brief = format!("parse http data failed: {e}");
+                res.render(StatusError::bad_request().brief(brief).cause(self));
+            }
+            _ => res.render(StatusError::bad_request().brief("parse http data failed.").cause(self)),
+        }
     }
 }
 
@@ -0,0 +1,387 @@
+use std::fmt::{self, Display, Formatter, Write};
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::{Map, Value};
+
+use crate::http::header::{HeaderValue, CONTENT_TYPE};
+use crate::http::{Request, ResBody, StatusCode, StatusError};
+use crate::writing::escape_html;
+use crate::{Response, Scribe};
+
+/// Problem details for HTTP APIs, defined in [RFC 9457](https://www.rfc-editor.org/rfc/rfc9457)
+/// (obsoletes RFC 7807).
+///
+/// It is rendered as JSON with content type `application/problem+json`, use [`ProblemDetail::render_negotiated`] to
+/// render it as XML with content type `application/problem+xml` if the request prefers XML.
+///
+/// ```
+/// use salvo_core::http::errors::ProblemDetail;
+/// use salvo_core::http::StatusCode;
+/// use salvo_core::prelude::*;
+///
+/// #[handler]
+/// async fn create_user(res: &mut Response) {
+///     res.render(
+///         ProblemDetail::new("https://example.com/probs/validation")
+///             .title("Your request is not valid.")
+///             .status(StatusCode::UNPROCESSABLE_ENTITY)
+///             .detail("The field `name` is required."),
+///     );
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ProblemDetail {
+    /// A URI reference that identifies the problem type, `about:blank` if the problem has no additional semantics.
+    pub type_uri: String,
+    /// A short, human-readable summary of the problem type.
+    pub title: Option<String>,
+    /// The HTTP status code generated by the origin server for this occurrence of the problem.
+    pub status: Option<StatusCode>,
+    /// A human-readable explanation specific to this occurrence of the problem.
+    pub detail: Option<String>,
+    /// A URI reference that identifies the specific occurrence of the problem.
+    pub instance: Option<String>,
+    /// Extension members, they are serialized at the top level of the JSON object.
+    pub extensions: Map<String, Value>,
+}
+
+impl Default for ProblemDetail {
+    #[inline]
+    fn default() -> Self {
+        Self::new("about:blank")
+    }
+}
+
+impl ProblemDetail {
+    /// Create new `ProblemDetail` with problem type uri.
+    #[inline]
+    pub fn new(type_uri: impl Into<String>) -> Self {
+        Self {
+            type_uri: type_uri.into(),
+            title: None,
+            status: None,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// Create new `ProblemDetail` with type `about:blank`, status code and it's canonical reason as title.
+    #[inline]
+    pub fn with_status(status: StatusCode) -> Self {
+        let mut problem = Self::default().status(status);
+        problem.title = status.canonical_reason().map(Into::into);
+        problem
+    }
+
+    /// Sets title.
+    #[inline]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets status code.
+    #[inline]
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Sets detail.
+    #[inline]
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets instance.
+    #[inline]
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Adds an extension member. Members named as standard fields are ignored when serializing.
+    #[inline]
+    pub fn extension(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.extensions.insert(name.into(), value.into());
+        self
+    }
+
+    /// Serialize to XML format defined in appendix of RFC 9457.
+    ///
+    /// Arrays in extension members are written as `i` elements, members which names are not valid XML names are
+    /// skipped.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?><problem xmlns="urn:ietf:rfc:7807">"#);
+        write_xml_element(&mut xml, "type", &Value::from(&*self.type_uri));
+        if let Some(title) = &self.title {
+            write_xml_element(&mut xml, "title", &Value::from(&**title));
+        }
+        if let Some(status) = self.status {
+            write_xml_element(&mut xml, "status", &Value::from(status.as_u16()));
+        }
+        if let Some(detail) = &self.detail {
+            write_xml_element(&mut xml, "detail", &Value::from(&**detail));
+        }
+        if let Some(instance) = &self.instance {
+            write_xml_element(&mut xml, "instance", &Value::from(&**instance));
+        }
+        for (name, value) in &self.extensions {
+            if !matches!(name.as_str(), "type" | "title" | "status" | "detail" | "instance") {
+                write_xml_element(&mut xml, name, value);
+            }
+        }
+        xml.push_str("</problem>");
+        xml
+    }
+
+    /// Render as XML with content type `application/problem+xml` if the request prefers XML to JSON, otherwise
+    /// render as JSON.
+    pub fn render_negotiated(self, req: &Request, res: &mut Response) {
+        if preferred_format(req) != Some(ProblemFormat::Xml) {
+            res.render(self);
+            return;
+        }
+        if let Some(status) = self.status {
+            res.status_code(status);
+        }
+        res.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/problem+xml"));
+        res.body(ResBody::Once(self.to_xml().into()));
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ProblemFormat {
+    Json,
+    Xml,
+}
+
+/// Get the problem format the request prefers, it is `None` if the request prefers other formats such as HTML.
+pub(crate) fn preferred_format(req: &Request) -> Option<ProblemFormat> {
+    req.accept().iter().find_map(|mime| {
+        if mime.type_() == mime::STAR || mime.type_() == mime::TEXT && mime.subtype() == mime::HTML {
+            Some(None)
+        } else if mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON) {
+            Some(Some(ProblemFormat::Json))
+        } else if mime.subtype() == mime::XML || mime.suffix() == Some(mime::XML) {
+            Some(Some(ProblemFormat::Xml))
+        } else {
+            None
+        }
+    })?
+}
+
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !name.to_ascii_lowercase().starts_with("xml")
+}
+
+fn write_xml_element(xml: &mut String, name: &str, value: &Value) {
+    if !is_xml_name(name) {
+        return;
+    }
+    let _ = write!(xml, "<{name}>");
+    match value {
+        Value::Null => {}
+        Value::Bool(value) => {
+            let _ = write!(xml, "{value}");
+        }
+        Value::Number(value) => {
+            let _ = write!(xml, "{value}");
+        }
+        Value::String(value) => {
+            xml.push_str(&escape_html(value));
+        }
+        Value::Array(items) => {
+            for item in items {
+                write_xml_element(xml, "i", item);
+            }
+        }
+        Value::Object(members) => {
+            for (name, value) in members {
+                write_xml_element(xml, name, value);
+            }
+        }
+    }
+    let _ = write!(xml, "</{name}>");
+}
+
+impl Serialize for ProblemDetail {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", &self.type_uri)?;
+        if let Some(title) = &self.title {
+            map.serialize_entry("title", title)?;
+        }
+        if let Some(status) = &self.status {
+            map.serialize_entry("status", &status.as_u16())?;
+        }
+        if let Some(detail) = &self.detail {
+            map.serialize_entry("detail", detail)?;
+        }
+        if let Some(instance) = &self.instance {
+            map.serialize_entry("instance", instance)?;
+        }
+        for (name, value) in &self.extensions {
+            if !matches!(name.as_str(), "type" | "title" | "status" | "detail" | "instance") {
+                map.serialize_entry(name, value)?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl Display for ProblemDetail {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "type: {}", self.type_uri)?;
+        if let Some(title) = &self.title {
+            write!(f, ", title: {title}")?;
+        }
+        if let Some(detail) = &self.detail {
+            write!(f, ", detail: {detail}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Scribe for ProblemDetail {
+    #[inline]
+    fn render(self, res: &mut Response) {
+        if let Some(status) = self.status {
+            res.status_code(status);
+        }
+        match serde_json::to_vec(&self) {
+            Ok(bytes) => {
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/problem+json"));
+                res.body(ResBody::Once(bytes.into()));
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "ProblemDetail write error");
+                res.render(StatusError::internal_server_error());
+            }
+        }
+    }
+}
+
+impl From<ProblemDetail> for StatusError {
+    #[inline]
+    fn from(problem: ProblemDetail) -> Self {
+        let code = problem
+            .status
+            .filter(|s| s.is_client_error() || s.is_server_error())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut err = StatusError::from_code(code).unwrap_or_else(StatusError::internal_server_error);
+        if let Some(title) = problem.title {
+            err = err.brief(title);
+        }
+        if let Some(detail) = problem.detail {
+            err = err.detail(detail);
+        }
+        err
+    }
+}
+
+impl From<ProblemDetail> for Response {
+    #[inline]
+    fn from(problem: ProblemDetail) -> Self {
+        let mut res = Response::new();
+        res.render(problem);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[test]
+    fn test_serialize() {
+        let problem = ProblemDetail::new("https://example.com/probs/out-of-credit")
+            .title("You do not have enough credit.")
+            .status(StatusCode::FORBIDDEN)
+            .instance("/account/12345/msgs/abc")
+            .extension("balance", 30)
+            .extension("status", "ignored");
+        assert_eq!(
+            serde_json::to_value(&problem).unwrap(),
+            serde_json::json!({
+                "type": "https://example.com/probs/out-of-credit",
+                "title": "You do not have enough credit.",
+                "status": 403,
+                "instance": "/account/12345/msgs/abc",
+                "balance": 30,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(ProblemDetail::default()).unwrap(),
+            serde_json::json!({"type": "about:blank"})
+        );
+    }
+
+    #[test]
+    fn test_to_xml() {
+        let problem = ProblemDetail::with_status(StatusCode::FORBIDDEN)
+            .detail("balance < cost")
+            .extension("accounts", vec!["/account/12345", "/account/67890"])
+            .extension("invalid name", true);
+        assert_eq!(
+            problem.to_xml(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><problem xmlns="urn:ietf:rfc:7807"><type>about:blank</type><title>Forbidden</title><status>403</status><detail>balance &lt; cost</detail><accounts><i>/account/12345</i><i>/account/67890</i></accounts></problem>"#
+        );
+    }
+
+    #[test]
+    fn test_into_status_error() {
+        let err: StatusError = ProblemDetail::with_status(StatusCode::NOT_FOUND)
+            .detail("no user")
+            .into();
+        assert_eq!(err.code, StatusCode::NOT_FOUND);
+        assert_eq!(err.brief, "Not Found");
+        assert_eq!(err.detail.as_deref(), Some("no user"));
+
+        let err: StatusError = ProblemDetail::new("about:blank").into();
+        assert_eq!(err.code, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_render() {
+        #[handler]
+        async fn invalid(res: &mut Response) {
+            res.render(
+                ProblemDetail::new("https://example.com/probs/validation")
+                    .status(StatusCode::UNPROCESSABLE_ENTITY)
+                    .detail("name is required"),
+            );
+        }
+
+        let mut res = TestClient::get("http://127.0.0.1:5801")
+            .send(Router::new().goal(invalid))
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNPROCESSABLE_ENTITY));
+        assert_eq!(res.content_type().unwrap().to_string(), "application/problem+json");
+        let body = res.take_string().await.unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            serde_json::json!({
+                "type": "https://example.com/probs/validation",
+                "status": 422,
+                "detail": "name is required",
+            })
+        );
+
+        let res: Response = ProblemDetail::with_status(StatusCode::BAD_REQUEST).into();
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+    }
+}
@@ -1,6 +1,7 @@
 use std::error::Error as StdError;
 use std::fmt::{self, Debug, Display, Formatter};
 
+use crate::http::errors::ProblemDetail;
 use crate::http::{ResBody, StatusCode};
 
 use crate::{Response, Scribe};
@@ -24,6 +25,7 @@ macro_rules! default_errors {
                     name: $name.into(),
                     brief: $brief.into(),
                     detail: None,
+                    extra: None,
                     cause: None,
                 }
             }
@@ -31,6 +33,16 @@ macro_rules! default_errors {
     }
 }
 
+/// Machine-readable information of [`StatusError`], it is written to JSON error body.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct ErrorExtra {
+    /// Error code of the application, e.g. `invalid_email`.
+    pub code: Option<String>,
+    /// Structured detail data about the error.
+    pub data: Option<serde_json::Value>,
+}
+
 /// StatusError contains http error information.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -43,6 +55,8 @@ pub struct StatusError {
     pub brief: String,
     /// Detail information about http error.
     pub detail: Option<String>,
+    /// Machine-readable error code and structured data, they are boxed to keep `StatusError` small.
+    pub extra: Option<Box<ErrorExtra>>,
     /// Cause about http error. This field is only used for internal debugging and only used in debug mode.
     pub cause: Option<Box<dyn StdError + Sync + Send + 'static>>,
 }
@@ -58,6 +72,16 @@ impl StatusError {
         self.detail = Some(detail.into());
         self
     }
+    /// Sets error_code field and returns Self.
+    pub fn error_code(mut self, error_code: impl Into<String>) -> Self {
+        self.extra.get_or_insert_with(Default::default).code = Some(error_code.into());
+        self
+    }
+    /// Sets data field and returns Self.
+    pub fn data(mut self, data: impl Into<serde_json::Value>) -> Self {
+        self.extra.get_or_insert_with(Default::default).data = Some(data.into());
+        self
+    }
     /// Sets cause field and returns Self.
     pub fn cause<C>(mut self, cause: C) -> Self
     where
@@ -66,6 +90,19 @@ impl StatusError {
         self.cause = Some(cause.into());
         self
     }
+    /// Returns an iterator over the cause chain, it starts with the cause and follows [`StdError::source`].
+    pub fn causes(&self) -> impl Iterator<Item = &(dyn StdError + 'static)> {
+        let mut next = self.cause.as_deref().map(|cause| cause as &(dyn StdError + 'static));
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = current.source();
+            Some(current)
+        })
+    }
+    /// Returns the message for clients, it is the detail or the brief if there is no detail.
+    pub fn message(&self) -> &str {
+        self.detail.as_deref().unwrap_or(&self.brief)
+    }
 
     default_errors! {
         /// 400 Bad Request
@@ -188,7 +225,11 @@ impl StatusError {
     }
 }
 
-impl StdError for StatusError {}
+impl StdError for StatusError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.cause.as_deref().map(|cause| cause as &(dyn StdError + 'static))
+    }
+}
 
 impl Display for StatusError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -196,6 +237,7 @@ impl Display for StatusError {
         write!(f, "name: {}", self.name)?;
         write!(f, "brief: {:?}", self.brief)?;
         write!(f, "detail: {:?}", self.detail)?;
+        write!(f, "extra: {:?}", self.extra)?;
         write!(f, "cause: {:?}", self.cause)?;
         Ok(())
     }
@@ -247,6 +289,24 @@ impl StatusError {
             _ => None,
         }
     }
+
+    /// Convert to [`ProblemDetail`] which type is `about:blank`, the title is name of the status code and the
+    /// detail is the detail of this error, or the brief if there is no detail. The error code and data are added
+    /// as `error_code` and `data` extension members if they are set.
+    pub fn into_problem(self) -> ProblemDetail {
+        let mut problem = ProblemDetail::with_status(self.code)
+            .title(self.name)
+            .detail(self.detail.unwrap_or(self.brief));
+        if let Some(extra) = self.extra {
+            if let Some(error_code) = extra.code {
+                problem = problem.extension("error_code", error_code);
+            }
+            if let Some(data) = extra.data {
+                problem = problem.extension("data", data);
+            }
+        }
+        problem
+    }
 }
 
 impl Scribe for StatusError {
@@ -1,4 +1,5 @@
 //! form parse module
+use std::borrow::Cow;
 use std::ffi::OsStr;
 use std::io::{Cursor, Write};
 use std::path::{Path, PathBuf};
@@ -6,10 +7,11 @@ use std::path::{Path, PathBuf};
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::engine::Engine;
 use futures_util::StreamExt;
-use http_body_util::BodyExt;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
 use mime::Mime;
-use multer::{Field, Multipart};
+use multer::{Constraints, Field, Multipart, SizeLimit};
 use multimap::MultiMap;
+use percent_encoding::percent_decode;
 use rand::rngs::OsRng;
 use rand::RngCore;
 use tempfile::Builder;
@@ -18,7 +20,7 @@ use tokio::io::AsyncWriteExt;
 
 use crate::http::body::ReqBody;
 use crate::http::header::{HeaderMap, CONTENT_TYPE};
-use crate::http::ParseError;
+use crate::http::{charset, ParseError};
 
 /// The extracted text fields and uploaded files from a `multipart/form-data` request.
 #[derive(Debug)]
@@ -43,16 +45,36 @@ impl FormData {
     }
 
     /// Parse MIME `multipart/*` information from a stream as a [`FormData`].
-    pub(crate) async fn read(headers: &HeaderMap, body: ReqBody) -> Result<FormData, ParseError> {
+    ///
+    /// If `max_size` is set, `ParseError::PayloadTooLarge` is returned when the body exceeds it.
+    pub(crate) async fn read(
+        headers: &HeaderMap,
+        body: ReqBody,
+        max_size: Option<usize>,
+    ) -> Result<FormData, ParseError> {
         let ctype: Option<Mime> = headers
             .get(CONTENT_TYPE)
             .and_then(|h| h.to_str().ok())
             .and_then(|v| v.parse().ok());
         match ctype {
             Some(ctype) if ctype.subtype() == mime::WWW_FORM_URLENCODED => {
-                let data = BodyExt::collect(body).await.map_err(ParseError::other)?.to_bytes();
+                let data = match max_size {
+                    Some(max_size) => Limited::new(body, max_size)
+                        .collect()
+                        .await
+                        .map_err(|e| {
+                            if e.is::<LengthLimitError>() {
+                                ParseError::PayloadTooLarge
+                            } else {
+                                ParseError::other(e)
+                            }
+                        })?
+                        .to_bytes(),
+                    None => BodyExt::collect(body).await.map_err(ParseError::other)?.to_bytes(),
+                };
                 let mut form_data = FormData::new();
-                form_data.fields = form_urlencoded::parse(&data).into_owned().collect();
+                let charset = ctype.get_param(mime::CHARSET).map(|charset| charset.as_str());
+                form_data.fields = parse_urlencoded(&data, charset)?;
                 Ok(form_data)
             }
             Some(ctype) if ctype.type_() == mime::MULTIPART => {
@@ -63,16 +85,30 @@ impl FormData {
                     .and_then(|ct| multer::parse_boundary(ct).ok())
                 {
                     let body = body.map(|f| f.map(|f| f.into_data().unwrap_or_default()));
-                    let mut multipart = Multipart::new(body, boundary);
-                    while let Some(mut field) = multipart.next_field().await? {
-                        if let Some(name) = field.name().map(|s| s.to_owned()) {
-                            if field.headers().get(CONTENT_TYPE).is_some() {
-                                form_data.files.insert(name, FilePart::create(&mut field).await?);
-                            } else {
-                                form_data.fields.insert(name, field.text().await?);
+                    let mut multipart = match max_size {
+                        Some(max_size) => Multipart::with_constraints(
+                            body,
+                            boundary,
+                            Constraints::new().size_limit(SizeLimit::new().whole_stream(max_size as u64)),
+                        ),
+                        None => Multipart::new(body, boundary),
+                    };
+                    let read_fields = async {
+                        while let Some(mut field) = multipart.next_field().await? {
+                            if let Some(name) = field.name().map(|s| s.to_owned()) {
+                                if field.headers().get(CONTENT_TYPE).is_some() {
+                                    form_data.files.insert(name, FilePart::create(&mut field).await?);
+                                } else {
+                                    form_data.fields.insert(name, field.text().await?);
+                                }
                             }
                         }
-                    }
+                        Ok::<_, ParseError>(())
+                    };
+                    read_fields.await.map_err(|e| match e {
+                        ParseError::Multer(multer::Error::StreamSizeExceeded { .. }) => ParseError::PayloadTooLarge,
+                        e => e,
+                    })?;
                 }
                 Ok(form_data)
             }
@@ -80,6 +116,30 @@ impl FormData {
         }
     }
 }
+/// Parse `application/x-www-form-urlencoded` data, percent decoded names and values are decoded by `charset`.
+fn parse_urlencoded(data: &[u8], charset: Option<&str>) -> Result<MultiMap<String, String>, ParseError> {
+    if charset::is_utf8(charset) {
+        return Ok(form_urlencoded::parse(data).into_owned().collect());
+    }
+    let decode = |input: &[u8]| {
+        let input = input
+            .iter()
+            .map(|b| if *b == b'+' { b' ' } else { *b })
+            .collect::<Vec<_>>();
+        let bytes: Cow<'_, [u8]> = percent_decode(&input).into();
+        charset::decode(&bytes, charset, false).map(Cow::into_owned)
+    };
+    let mut fields = MultiMap::new();
+    for pair in data.split(|b| *b == b'&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = match pair.iter().position(|b| *b == b'=') {
+            Some(index) => (&pair[..index], &pair[index + 1..]),
+            None => (pair, &[][..]),
+        };
+        fields.insert(decode(name)?, decode(value)?);
+    }
+    Ok(fields)
+}
+
 impl Default for FormData {
     #[inline]
     fn default() -> Self {
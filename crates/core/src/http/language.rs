@@ -0,0 +1,123 @@
+//! `Accept-Language` parsing and language negotiation.
+
+/// Parse `Accept-Language` header value, language tags are sorted by quality in descending order.
+///
+/// Malformed items are skipped, items with `q=0` are kept so that callers can know the languages are not
+/// acceptable.
+pub(crate) fn parse_accept_language(value: &str) -> Vec<(String, f32)> {
+    let mut languages = value
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let tag = parts.next()?.trim();
+            let valid = tag == "*"
+                || (!tag.is_empty()
+                    && tag.split('-').all(|sub| {
+                        !sub.is_empty() && sub.len() <= 8 && sub.chars().all(|c| c.is_ascii_alphanumeric())
+                    }));
+            if !valid {
+                return None;
+            }
+            let mut quality = 1.0;
+            for param in parts {
+                let (name, value) = param.split_once('=')?;
+                if name.trim().eq_ignore_ascii_case("q") {
+                    quality = value.trim().parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q))?;
+                }
+            }
+            Some((tag.to_owned(), quality))
+        })
+        .collect::<Vec<_>>();
+    // `sort_by` is stable, so languages with the same quality keep their order in header.
+    languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    languages
+}
+
+/// Select the best language from `supported` for language ranges, it uses
+/// [RFC 4647 lookup](https://www.rfc-editor.org/rfc/rfc4647#section-3.4) for each range by priority,
+/// a range also matches supported tags which it is a prefix of, so `zh` matches `zh-CN`.
+///
+/// Returns the first supported language if no one is matched.
+pub(crate) fn lookup_language<'a>(ranges: &[(String, f32)], supported: &[&'a str]) -> Option<&'a str> {
+    let excluded = |tag: &str| {
+        ranges
+            .iter()
+            .any(|(range, q)| *q <= 0.0 && range.eq_ignore_ascii_case(tag))
+    };
+    for (range, _) in ranges.iter().filter(|(_, q)| *q > 0.0) {
+        if range == "*" {
+            if let Some(tag) = supported.iter().find(|tag| !excluded(tag)) {
+                return Some(tag);
+            }
+            continue;
+        }
+        // Lookup: truncate range from the end until a supported tag is matched.
+        let mut prefix = range.as_str();
+        loop {
+            if let Some(tag) = supported
+                .iter()
+                .find(|tag| tag.eq_ignore_ascii_case(prefix) && !excluded(tag))
+            {
+                return Some(tag);
+            }
+            match prefix.rfind('-') {
+                Some(index) => {
+                    prefix = &prefix[..index];
+                    // Single letter subtag such as `x` in `zh-x-private` should be removed too.
+                    if prefix.len() >= 2 && prefix.as_bytes()[prefix.len() - 2] == b'-' {
+                        prefix = &prefix[..prefix.len() - 2];
+                    }
+                }
+                None => break,
+            }
+        }
+        // A more specific supported tag is also acceptable, such as `zh-CN` for `zh`.
+        if let Some(tag) = supported.iter().find(|tag| {
+            tag.len() > range.len()
+                && tag.as_bytes()[range.len()] == b'-'
+                && tag[..range.len()].eq_ignore_ascii_case(range)
+                && !excluded(tag)
+        }) {
+            return Some(tag);
+        }
+    }
+    supported.first().copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(
+            parse_accept_language("fr-CH, fr;q=0.9, en ; q = 0.8, de;q=0.7, *;q=0.5"),
+            vec![
+                ("fr-CH".to_owned(), 1.0),
+                ("fr".to_owned(), 0.9),
+                ("en".to_owned(), 0.8),
+                ("de".to_owned(), 0.7),
+                ("*".to_owned(), 0.5),
+            ]
+        );
+        assert_eq!(
+            parse_accept_language("en;q=0.5, zh-CN, bad tag, de;q=x, ja;q=2"),
+            vec![("zh-CN".to_owned(), 1.0), ("en".to_owned(), 0.5)]
+        );
+        assert!(parse_accept_language("").is_empty());
+    }
+
+    #[test]
+    fn test_lookup_language() {
+        let supported = ["en", "zh-CN", "de"];
+        let lookup = |header: &str| lookup_language(&parse_accept_language(header), &supported);
+        assert_eq!(lookup("zh-cn"), Some("zh-CN"));
+        assert_eq!(lookup("zh"), Some("zh-CN"));
+        assert_eq!(lookup("de-DE-1996"), Some("de"));
+        assert_eq!(lookup("fr, de;q=0.8, en;q=0.9"), Some("en"));
+        assert_eq!(lookup("fr"), Some("en"));
+        assert_eq!(lookup("*, en;q=0"), Some("zh-CN"));
+        assert_eq!(lookup(""), Some("en"));
+        assert_eq!(lookup_language(&[], &[]), None);
+    }
+}
@@ -1,7 +1,13 @@
 //! The http related types and functions.
 
+pub mod auth;
+mod cache_control;
+mod charset;
+mod conditional;
+mod deadline;
 pub mod errors;
 pub mod form;
+mod language;
 mod range;
 pub mod request;
 pub mod response;
@@ -9,12 +15,15 @@ cfg_feature! {
     #![feature = "cookie"]
     pub use cookie;
 }
+pub use cache_control::CacheControl;
+pub use conditional::Precondition;
+pub use deadline::{Deadline, DEADLINE_HEADER, DEADLINE_HEADER_KEY};
 pub use errors::{ParseError, StatusError};
 pub use headers;
 pub use http::method::Method;
 pub use http::{header, method, uri, HeaderMap, HeaderName, HeaderValue, StatusCode};
 pub use mime::{self, Mime};
-pub use range::HttpRange;
+pub use range::{HttpRange, RangeBody};
 pub use request::Request;
 pub mod body;
 pub use body::{Body, ReqBody, ResBody};
@@ -82,12 +91,19 @@ pub fn parse_accept_encoding(header: &str) -> Vec<(String, u8)> {
     vec
 }
 
+/// Returns `true` if `value` is a `token` defined in RFC 9110, Section 5.6.2.
+pub(crate) fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
 #[doc(hidden)]
 #[inline]
 pub fn guess_accept_mime(req: &Request, default_type: Option<Mime>) -> Mime {
     let dmime: Mime = default_type.unwrap_or(mime::TEXT_HTML);
-    let accept = req.accept();
-    accept.first().unwrap_or(&dmime).to_string().parse().unwrap_or(dmime)
+    req.accept().into_iter().next().unwrap_or(dmime)
 }
 
 #[cfg(test)]
@@ -1,4 +1,23 @@
-use crate::http::ParseError;
+//! Range requests support.
+use std::cmp;
+use std::collections::VecDeque;
+use std::io::{Error as IoError, ErrorKind, Result as IoResult, SeekFrom};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use bytes::Bytes;
+use futures_util::stream::Stream;
+use headers::{AcceptRanges, ContentLength, ContentRange, HeaderMapExt};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+use crate::http::header::{HeaderValue, CONTENT_TYPE};
+use crate::http::{Mime, ParseError, Response, StatusCode};
+
+/// Maximum number of ranges which are served in one response.
+const MAX_RANGES: usize = 16;
+const CHUNK_SIZE: u64 = 64 * 1024;
 
 /// HTTP Range header representation.
 #[derive(Clone, Debug, Copy)]
@@ -97,10 +116,246 @@ impl HttpRange {
 
         Ok(ranges)
     }
+
+    /// Prepares satisfiable ranges returned by [`parse`](Self::parse) for serving, `size` is the full size of the
+    /// content.
+    ///
+    /// Ranges are sorted, overlapping or adjacent ranges are merged into one. `None` is returned if the ranges
+    /// should be ignored and the whole content should be sent, which happens when the total length of the requested
+    /// ranges exceeds `size`, or there are still more than 16 ranges after merging.
+    pub fn coalesce(mut ranges: Vec<HttpRange>, size: u64) -> Option<Vec<HttpRange>> {
+        let total = ranges
+            .iter()
+            .try_fold(0u64, |total, range| total.checked_add(range.length))?;
+        if total > size {
+            return None;
+        }
+        ranges.sort_by_key(|range| range.start);
+        let mut merged: Vec<HttpRange> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.start + last.length => {
+                    let end = cmp::max(last.start + last.length, range.start + range.length);
+                    last.length = end - last.start;
+                }
+                _ => merged.push(range),
+            }
+        }
+        (merged.len() <= MAX_RANGES).then_some(merged)
+    }
+}
+
+enum State {
+    Idle,
+    StartSeek { start: u64, length: u64 },
+    Seeking { length: u64 },
+    Reading { remaining: u64 },
+    Done,
+}
+
+/// A stream of bytes that reads the selected ranges from `reader`.
+///
+/// When there are multiple ranges, each range is written as a part of `multipart/byteranges` body. When there is no
+/// range, the whole content is written.
+pub struct RangeBody<R> {
+    reader: R,
+    ranges: VecDeque<HttpRange>,
+    partial: bool,
+    multipart: bool,
+    first: bool,
+    state: State,
+    size: u64,
+    boundary: String,
+    content_type: Option<Mime>,
+    buffer_size: u64,
+}
+
+impl<R> RangeBody<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
+{
+    /// Create a new `RangeBody`, `size` is the full size of `reader`.
+    ///
+    /// `ranges` should be satisfiable, which means they are returned by [`HttpRange::parse`] with the same `size`,
+    /// use [`HttpRange::coalesce`] to merge them before. If `ranges` is empty, the whole content is written.
+    pub fn new(reader: R, ranges: Vec<HttpRange>, size: u64) -> Self {
+        let partial = !ranges.is_empty();
+        let multipart = ranges.len() > 1;
+        let ranges = if ranges.is_empty() {
+            VecDeque::from([HttpRange { start: 0, length: size }])
+        } else {
+            ranges.into()
+        };
+        Self {
+            reader,
+            ranges,
+            partial,
+            multipart,
+            first: true,
+            state: State::Idle,
+            size,
+            boundary: format!("{:016x}{:016x}", OsRng.next_u64(), OsRng.next_u64()),
+            content_type: None,
+            buffer_size: CHUNK_SIZE,
+        }
+    }
+
+    /// Sets the content type of the whole content, it is written in every part of `multipart/byteranges` body.
+    #[inline]
+    pub fn content_type(mut self, content_type: Mime) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    /// Sets the max size of every chunk read from `reader`, the default is 64KiB.
+    #[inline]
+    pub fn buffer_size(mut self, buffer_size: u64) -> Self {
+        self.buffer_size = buffer_size.max(1);
+        self
+    }
+
+    /// Returns the boundary used by `multipart/byteranges` body.
+    #[inline]
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    fn part_header(&self, first: bool, range: &HttpRange) -> String {
+        let delimiter = if first { "" } else { "\r\n" };
+        let content_type = self
+            .content_type
+            .as_ref()
+            .map(|ctype| format!("Content-Type: {ctype}\r\n"))
+            .unwrap_or_default();
+        format!(
+            "{delimiter}--{}\r\n{content_type}Content-Range: bytes {}-{}/{}\r\n\r\n",
+            self.boundary,
+            range.start,
+            range.start + range.length - 1,
+            self.size
+        )
+    }
+
+    fn trailer(&self) -> String {
+        format!("\r\n--{}--\r\n", self.boundary)
+    }
+
+    /// Returns the total length of the body.
+    pub fn content_length(&self) -> u64 {
+        let length = self.ranges.iter().map(|range| range.length).sum::<u64>();
+        if self.multipart {
+            let headers = self
+                .ranges
+                .iter()
+                .enumerate()
+                .map(|(index, range)| self.part_header(index == 0, range).len() as u64)
+                .sum::<u64>();
+            length + headers + self.trailer().len() as u64
+        } else {
+            length
+        }
+    }
+
+    /// Write ranges to response.
+    ///
+    /// If there are multiple ranges, `Content-Type` header is set to `multipart/byteranges`, if there is a single
+    /// range, `Content-Range` header is set, status code is `206 Partial Content` in both cases. Otherwise, the whole
+    /// content is written with `200 OK`.
+    pub fn write_to(self, res: &mut Response) {
+        res.headers_mut().typed_insert(AcceptRanges::bytes());
+        if self.multipart {
+            res.status_code(StatusCode::PARTIAL_CONTENT);
+            let ctype = format!("multipart/byteranges; boundary={}", self.boundary);
+            if let Ok(ctype) = HeaderValue::from_str(&ctype) {
+                res.headers_mut().insert(CONTENT_TYPE, ctype);
+            }
+        } else if let Some(range) = self.ranges.front().filter(|_| self.partial) {
+            res.status_code(StatusCode::PARTIAL_CONTENT);
+            match ContentRange::bytes(range.start..range.start + range.length, self.size) {
+                Ok(content_range) => {
+                    res.headers_mut().typed_insert(content_range);
+                }
+                Err(e) => {
+                    tracing::error!(error = ?e, "set content range failed");
+                }
+            }
+        } else {
+            res.status_code(StatusCode::OK);
+        }
+        res.headers_mut().typed_insert(ContentLength(self.content_length()));
+        res.stream(self);
+    }
+}
+
+impl<R> Stream for RangeBody<R>
+where
+    R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
+{
+    type Item = IoResult<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                State::Idle => {
+                    let Some(range) = this.ranges.pop_front() else {
+                        this.state = State::Done;
+                        if this.multipart {
+                            return Poll::Ready(Some(Ok(Bytes::from(this.trailer()))));
+                        }
+                        continue;
+                    };
+                    this.state = State::StartSeek {
+                        start: range.start,
+                        length: range.length,
+                    };
+                    if this.multipart {
+                        let header = this.part_header(this.first, &range);
+                        this.first = false;
+                        return Poll::Ready(Some(Ok(Bytes::from(header))));
+                    }
+                }
+                State::StartSeek { start, length } => {
+                    if let Err(e) = Pin::new(&mut this.reader).start_seek(SeekFrom::Start(start)) {
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    this.state = State::Seeking { length };
+                }
+                State::Seeking { length } => {
+                    if let Err(e) = ready!(Pin::new(&mut this.reader).poll_complete(cx)) {
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    this.state = State::Reading { remaining: length };
+                }
+                State::Reading { remaining: 0 } => {
+                    this.state = State::Idle;
+                }
+                State::Reading { remaining } => {
+                    let mut buf = vec![0; remaining.min(this.buffer_size) as usize];
+                    let mut read_buf = ReadBuf::new(&mut buf);
+                    if let Err(e) = ready!(Pin::new(&mut this.reader).poll_read(cx, &mut read_buf)) {
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    let len = read_buf.filled().len();
+                    if len == 0 {
+                        return Poll::Ready(Some(Err(IoError::from(ErrorKind::UnexpectedEof))));
+                    }
+                    buf.truncate(len);
+                    this.state = State::Reading {
+                        remaining: remaining - len as u64,
+                    };
+                    return Poll::Ready(Some(Ok(Bytes::from(buf))));
+                }
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io::Cursor;
+
     use super::*;
 
     struct T(&'static str, u64, Vec<HttpRange>);
@@ -267,4 +522,57 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_coalesce() {
+        let ranges = |header: &str| HttpRange::parse(header, 100).unwrap();
+        let spans = |ranges: Vec<HttpRange>| {
+            ranges
+                .into_iter()
+                .map(|range| (range.start, range.length))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            spans(HttpRange::coalesce(ranges("bytes=50-59,0-9,10-19,15-24"), 100).unwrap()),
+            vec![(0, 25), (50, 10)]
+        );
+        assert!(HttpRange::coalesce(ranges("bytes=0-79,20-99"), 100).is_none());
+        let many = (0..20)
+            .map(|i| format!("{}-{}", i * 3, i * 3))
+            .collect::<Vec<_>>()
+            .join(",");
+        assert!(HttpRange::coalesce(ranges(&format!("bytes={many}")), 100).is_none());
+        let adjacent = (0..20).map(|i| format!("{i}-{i}")).collect::<Vec<_>>().join(",");
+        assert_eq!(
+            spans(HttpRange::coalesce(ranges(&format!("bytes={adjacent}")), 100).unwrap()),
+            vec![(0, 20)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_range_body() {
+        use futures_util::StreamExt;
+
+        let ranges = HttpRange::parse("bytes=0-1,-2", 10).unwrap();
+        let body = RangeBody::new(Cursor::new(b"0123456789".to_vec()), ranges, 10)
+            .content_type(mime::TEXT_PLAIN)
+            .buffer_size(1);
+        let boundary = body.boundary().to_owned();
+        let length = body.content_length();
+        let chunks = body.map(|chunk| chunk.unwrap()).collect::<Vec<_>>().await;
+        let content = String::from_utf8(chunks.concat()).unwrap();
+        assert_eq!(content.len() as u64, length);
+        assert_eq!(
+            content,
+            format!(
+                "--{boundary}\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-1/10\r\n\r\n01\r\n\
+                 --{boundary}\r\nContent-Type: text/plain\r\nContent-Range: bytes 8-9/10\r\n\r\n89\r\n--{boundary}--\r\n"
+            )
+        );
+
+        let body = RangeBody::new(Cursor::new(b"0123456789".to_vec()), vec![], 10);
+        assert_eq!(body.content_length(), 10);
+        let chunks = body.map(|chunk| chunk.unwrap()).collect::<Vec<_>>().await;
+        assert_eq!(chunks.concat(), b"0123456789");
+    }
 }
@@ -1,31 +1,39 @@
 //! Http request.
+use std::borrow::Cow;
 use std::error::Error as StdError;
 use std::fmt::{self, Formatter};
-#[cfg(feature = "quinn")]
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use bytes::Bytes;
 #[cfg(feature = "cookie")]
-use cookie::{Cookie, CookieJar};
-use http::header::{AsHeaderName, HeaderMap, HeaderValue, IntoHeaderName, CONTENT_TYPE};
+use cookie::{Cookie, CookieJar, Key};
+use headers::ETag;
+use http::header::{
+    AsHeaderName, HeaderMap, HeaderValue, IntoHeaderName, ACCEPT, ACCEPT_LANGUAGE, AUTHORIZATION, CONTENT_LENGTH,
+    CONTENT_TYPE, EXPECT,
+};
 use http::method::Method;
 pub use http::request::Parts;
-use http::uri::{Scheme, Uri};
+use http::uri::{PathAndQuery, Scheme, Uri};
 use http::Extensions;
-use http_body_util::{BodyExt, Limited};
+use hyper::body::Body;
 use indexmap::IndexMap;
 use multimap::MultiMap;
 use once_cell::sync::OnceCell;
 use parking_lot::RwLock;
-use serde::de::Deserialize;
+use serde::de::{Deserialize, DeserializeOwned};
 
-use crate::conn::SocketAddr;
+use crate::conn::{AlpnProtocol, ConnectionInfo, SocketAddr};
 use crate::extract::{Extractible, Metadata};
 use crate::fuse::TransProto;
-use crate::http::body::ReqBody;
+use crate::http::body::{BodyReader, ReqBody};
 use crate::http::form::{FilePart, FormData};
-use crate::http::{Mime, ParseError, Version};
-use crate::serde::{from_request, from_str_map, from_str_multi_map, from_str_multi_val, from_str_val};
+use crate::http::{auth, charset, conditional, language, Mime, ParseError, Precondition, Version};
+use crate::serde::{
+    from_request, from_str_map, from_str_multi_val, from_str_nested_form_multi_map, from_str_nested_multi_map,
+    from_str_val,
+};
 use crate::Error;
 
 static SECURE_MAX_SIZE: RwLock<usize> = RwLock::new(64 * 1024);
@@ -41,12 +49,49 @@ pub fn set_secure_max_size(size: usize) {
     *lock = size;
 }
 
+#[cfg(feature = "msgpack")]
+fn is_msgpack(ctype: &Mime) -> bool {
+    ctype.type_() == mime::APPLICATION && ["msgpack", "x-msgpack", "vnd.msgpack"].contains(&ctype.subtype().as_str())
+}
+
+#[cfg(feature = "cbor")]
+fn is_cbor(ctype: &Mime) -> bool {
+    ctype.type_() == mime::APPLICATION && ctype.subtype() == "cbor"
+}
+
+#[cfg(feature = "xml")]
+fn is_xml(ctype: &Mime) -> bool {
+    ((ctype.type_() == mime::APPLICATION || ctype.type_() == mime::TEXT) && ctype.subtype() == mime::XML)
+        || ctype.suffix() == Some(mime::XML)
+}
+
+/// Deserialize XML payload, it is decoded by the charset of content type, UTF-8 is used if charset is absent.
+///
+/// [`ParseError::UnsupportedCharset`] is returned if the charset is unknown.
+#[cfg(feature = "xml")]
+fn from_xml_slice<'de, T>(ctype: &Mime, payload: &'de [u8]) -> Result<T, ParseError>
+where
+    T: Deserialize<'de>,
+{
+    let charset = ctype.get_param(mime::CHARSET);
+    match charset::decode(payload, charset.as_ref().map(|charset| charset.as_str()), false)? {
+        Cow::Borrowed(content) => quick_xml::de::from_str::<T>(content).map_err(ParseError::Xml),
+        Cow::Owned(content) => {
+            // Decoded content is not borrowed from payload, so it is deserialized from reader.
+            let mut deserializer = quick_xml::de::Deserializer::from_reader(content.as_bytes());
+            T::deserialize(&mut deserializer).map_err(ParseError::Xml)
+        }
+    }
+}
+
 /// Represents an HTTP request.
 ///
 /// Stores all the properties of the client's request.
 pub struct Request {
     // The requested URL.
     uri: Uri,
+    // The URL received from client, it is saved when `uri` is changed at the first time.
+    original_uri: Option<Uri>,
 
     // The request headers.
     headers: HeaderMap,
@@ -62,11 +107,14 @@ pub struct Request {
     pub(crate) cookies: CookieJar,
 
     pub(crate) params: IndexMap<String, String>,
+    pub(crate) matched_route: Option<String>,
 
     // accept: Option<Vec<Mime>>,
     pub(crate) queries: OnceCell<MultiMap<String, String>>,
     pub(crate) form_data: tokio::sync::OnceCell<FormData>,
     pub(crate) payload: tokio::sync::OnceCell<Bytes>,
+    pub(crate) trailers: Option<HeaderMap>,
+    pub(crate) secure_max_size: Option<usize>,
 
     /// The version of the HTTP protocol used.
     pub(crate) version: Version,
@@ -104,6 +152,7 @@ impl Request {
     pub fn new() -> Request {
         Request {
             uri: Uri::default(),
+            original_uri: None,
             headers: HeaderMap::default(),
             body: ReqBody::default(),
             extensions: Extensions::default(),
@@ -111,9 +160,12 @@ impl Request {
             #[cfg(feature = "cookie")]
             cookies: CookieJar::default(),
             params: IndexMap::new(),
+            matched_route: None,
             queries: OnceCell::new(),
             form_data: tokio::sync::OnceCell::new(),
             payload: tokio::sync::OnceCell::new(),
+            trailers: None,
+            secure_max_size: None,
             version: Version::default(),
             scheme: Scheme::HTTP,
             local_addr: SocketAddr::Unknown,
@@ -164,6 +216,7 @@ impl Request {
         Request {
             queries: OnceCell::new(),
             uri,
+            original_uri: None,
             headers,
             body: body.into(),
             extensions,
@@ -172,8 +225,11 @@ impl Request {
             cookies,
             // accept: None,
             params: IndexMap::new(),
+            matched_route: None,
             form_data: tokio::sync::OnceCell::new(),
             payload: tokio::sync::OnceCell::new(),
+            trailers: None,
+            secure_max_size: None,
             // multipart: OnceCell::new(),
             local_addr: SocketAddr::Unknown,
             remote_addr: SocketAddr::Unknown,
@@ -182,8 +238,33 @@ impl Request {
         }
     }
 
-    /// Strip the request to [`hyper::Request`].
-    #[doc(hidden)]
+    /// Convert to [`hyper::Request`], method, uri, version, headers, extensions and body are moved into it.
+    ///
+    /// Salvo specific data such as path params, parsed form data and trailers are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.headers_mut().insert("x-token", HeaderValue::from_static("abc"));
+    /// let hyper_req = req.into_hyper();
+    /// assert_eq!(hyper_req.headers()["x-token"], "abc");
+    /// ```
+    pub fn into_hyper(mut self) -> hyper::Request<ReqBody> {
+        let mut hyper_req = hyper::Request::new(std::mem::take(&mut self.body));
+        *hyper_req.method_mut() = self.method;
+        *hyper_req.uri_mut() = self.uri;
+        *hyper_req.version_mut() = self.version;
+        *hyper_req.headers_mut() = self.headers;
+        *hyper_req.extensions_mut() = self.extensions;
+        hyper_req
+    }
+
+    /// Strip the request to [`hyper::Request`], headers, extensions and body are taken from this request.
+    ///
+    /// It is useful to call an inner [`hyper`] or `tower` service in a handler, the body is moved without copying.
+    /// Use [`Request::merge_hyper`] to put the data back if needed.
     pub fn strip_to_hyper<QB>(&mut self) -> Result<hyper::Request<QB>, crate::Error>
     where
         QB: TryFrom<ReqBody>,
@@ -206,8 +287,7 @@ impl Request {
             .and_then(|body| builder.body(body).map_err(crate::Error::other))
     }
 
-    /// Merge data from [`hyper::Request`].
-    #[doc(hidden)]
+    /// Replace method, uri, version, headers, extensions and body with data from [`hyper::Request`].
     pub fn merge_hyper(&mut self, hyper_req: hyper::Request<ReqBody>) {
         let (
             http::request::Parts {
@@ -223,6 +303,8 @@ impl Request {
 
         self.method = method;
         self.uri = uri;
+        self.original_uri = None;
+        self.queries = OnceCell::new();
         self.version = version;
         self.headers = headers;
         self.extensions = extensions;
@@ -243,9 +325,38 @@ impl Request {
         &self.uri
     }
 
+    /// Returns the URI received from client, it is not changed by [`Request::set_uri`], [`Request::set_path`]
+    /// or [`Request::uri_mut`], so it is useful for logging after the request is rewritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// # use salvo_core::http::uri::Scheme;
+    /// let hyper_req = salvo_core::hyper::Request::get("/v1/users?page=2").body(ReqBody::None).unwrap();
+    /// let mut req = Request::from_hyper(hyper_req, Scheme::HTTP);
+    /// req.set_path("/users").unwrap();
+    /// assert_eq!(*req.uri(), *"/users?page=2");
+    /// assert_eq!(*req.original_uri(), *"/v1/users?page=2");
+    /// ```
+    #[inline]
+    pub fn original_uri(&self) -> &Uri {
+        self.original_uri.as_ref().unwrap_or(&self.uri)
+    }
+
+    #[inline]
+    fn save_original_uri(&mut self) {
+        if self.original_uri.is_none() {
+            self.original_uri = Some(self.uri.clone());
+        }
+    }
+
     /// Returns a mutable reference to the associated URI.
     ///
-    /// *Notice: If you using this mutable reference to change the uri, you should change the `params` and `queries` manually.*
+    /// The cached `queries` is reset, so it will be parsed from the changed uri when it is accessed next time,
+    /// changes made by [`Request::queries_mut`] are discarded.
+    ///
+    /// *Notice: `params` are set by router when the request is routed, they will not be reset.*
     ///
     /// # Examples
     ///
@@ -257,18 +368,92 @@ impl Request {
     /// ```
     #[inline]
     pub fn uri_mut(&mut self) -> &mut Uri {
+        self.save_original_uri();
+        self.queries = OnceCell::new();
         &mut self.uri
     }
 
-    /// Set the associated URI. `querie` will be reset.
+    /// Set the associated URI. `queries` will be reset.
     ///
-    /// *Notice: `params` will not reset.*
+    /// *Notice: `params` are set by router when the request is routed, they will not be reset.*
     #[inline]
     pub fn set_uri(&mut self, uri: Uri) {
+        self.save_original_uri();
         self.uri = uri;
         self.queries = OnceCell::new();
     }
 
+    /// Set the path of the associated URI, query string is preserved. `queries` will be reset.
+    ///
+    /// *Notice: `params` are set by router when the request is routed, they will not be reset.*
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.set_uri("http://localhost/api/v2/users?id=1".parse().unwrap());
+    /// req.set_path("/users").unwrap();
+    /// assert_eq!(*req.uri(), *"http://localhost/users?id=1");
+    /// assert_eq!(req.query::<u32>("id"), Some(1));
+    /// ```
+    pub fn set_path(&mut self, path: &str) -> Result<(), crate::Error> {
+        let path_and_query = match self.uri.query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path.to_owned(),
+        };
+        let mut parts = self.uri.clone().into_parts();
+        parts.path_and_query = Some(PathAndQuery::try_from(path_and_query)?);
+        let uri = Uri::from_parts(parts).map_err(crate::Error::other)?;
+        self.set_uri(uri);
+        Ok(())
+    }
+
+    /// Returns the raw query string of the associated URI, it is not decoded.
+    ///
+    /// It is useful when the exact query bytes are needed, for example to verify signed urls.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.set_uri("/users?b=2&a=1&b=%20".parse().unwrap());
+    /// assert_eq!(req.raw_query(), Some("b=2&a=1&b=%20"));
+    /// ```
+    #[inline]
+    pub fn raw_query(&self) -> Option<&str> {
+        self.uri.query()
+    }
+
+    /// Set the query string of the associated URI, path is preserved. `queries` will be reset.
+    ///
+    /// The query string is removed if `query` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.set_uri("/users?b=2&a=1".parse().unwrap());
+    /// req.set_query("a=1&b=2").unwrap();
+    /// assert_eq!(*req.uri(), *"/users?a=1&b=2");
+    /// assert_eq!(req.query::<u32>("a"), Some(1));
+    /// ```
+    pub fn set_query(&mut self, query: &str) -> Result<(), crate::Error> {
+        let query = query.trim_start_matches('?');
+        let path_and_query = if query.is_empty() {
+            self.uri.path().to_owned()
+        } else {
+            format!("{}?{query}", self.uri.path())
+        };
+        let mut parts = self.uri.clone().into_parts();
+        parts.path_and_query = Some(PathAndQuery::try_from(path_and_query)?);
+        let uri = Uri::from_parts(parts).map_err(crate::Error::other)?;
+        self.set_uri(uri);
+        Ok(())
+    }
+
     /// Returns a reference to the associated HTTP method.
     ///
     /// # Examples
@@ -320,6 +505,26 @@ impl Request {
         &mut self.scheme
     }
 
+    /// Get information of the connection which the request comes from, it is set by [`Service`](crate::Service)
+    /// before the request is handled.
+    #[inline]
+    pub fn conn_info(&self) -> Option<&ConnectionInfo> {
+        self.extensions.get::<Arc<ConnectionInfo>>().map(|info| &**info)
+    }
+
+    /// Get the protocol negotiated by ALPN during tls handshake, such as `h2` or `http/1.1`.
+    ///
+    /// It is `None` if the connection is not secured by openssl or rustls, or the client does not use ALPN.
+    #[inline]
+    pub fn alpn_protocol(&self) -> Option<&str> {
+        self.conn_info()?
+            .extensions
+            .get::<AlpnProtocol>()?
+            .0
+            .get()
+            .map(|protocol| protocol.as_str())
+    }
+
     /// Get request remote address.
     #[inline]
     pub fn remote_addr(&self) -> &SocketAddr {
@@ -331,6 +536,27 @@ impl Request {
         &mut self.remote_addr
     }
 
+    cfg_feature! {
+        #![feature = "openssl"]
+        /// Get the verified client certificate of the tls connection, it is `None` if the client does not present
+        /// a certificate or client auth is not enabled by [`OpensslConfig::with_client_auth`].
+        ///
+        /// [`OpensslConfig::with_client_auth`]: crate::conn::openssl::OpensslConfig::with_client_auth
+        #[inline]
+        pub fn client_cert(&self) -> Option<&openssl::x509::X509> {
+            self.client_cert_chain().first()
+        }
+        /// Get the verified certificate chain of the client, the client certificate is the first one.
+        #[inline]
+        pub fn client_cert_chain(&self) -> &[openssl::x509::X509] {
+            self.conn_info()
+                .and_then(|info| info.extensions.get::<crate::conn::openssl::ClientCerts>())
+                .and_then(|certs| certs.0.get())
+                .map(|certs| &certs[..])
+                .unwrap_or_default()
+        }
+    }
+
     /// Get request remote address reference.
     #[inline]
     pub fn local_addr(&self) -> &SocketAddr {
@@ -387,6 +613,99 @@ impl Request {
         from_str_multi_val(values).ok()
     }
 
+    cfg_feature! {
+        #![feature = "typed-headers"]
+        /// Get typed header which implements [`Header`](headers::Header).
+        ///
+        /// Returns [`ParseError::MissingHeader`] if the header is absent, and [`ParseError::InvalidTypedHeader`] if
+        /// it can not be decoded, so handlers can decide how to deal with them.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// # use salvo_core::http::*;
+        /// # use salvo_core::http::headers::ContentLength;
+        /// let mut req = Request::default();
+        /// req.headers_mut().insert("content-length", HeaderValue::from_static("10"));
+        /// assert_eq!(req.typed_header::<ContentLength>().unwrap(), ContentLength(10));
+        /// ```
+        #[inline]
+        pub fn typed_header<H>(&self) -> Result<H, ParseError>
+        where
+            H: headers::Header,
+        {
+            let mut values = self.headers.get_all(H::name()).iter().peekable();
+            if values.peek().is_none() {
+                return Err(ParseError::MissingHeader(H::name().clone()));
+            }
+            H::decode(&mut values).map_err(|_| ParseError::InvalidTypedHeader(H::name().clone()))
+        }
+
+        /// Get `If-None-Match` header, returns `None` if it is absent or invalid.
+        ///
+        /// Use [`is_fresh`](Self::is_fresh) to evaluate all conditional headers at once.
+        #[inline]
+        pub fn if_none_match(&self) -> Option<headers::IfNoneMatch> {
+            self.typed_header().ok()
+        }
+
+        /// Get `Cache-Control` header sent by client as [`headers::CacheControl`], such as `no-cache` to bypass
+        /// caches, returns `None` if it is absent or invalid.
+        ///
+        /// It is not [`CacheControl`](crate::http::CacheControl), which only builds the header of responses.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// # use salvo_core::http::*;
+        /// let mut req = Request::default();
+        /// req.headers_mut().insert("cache-control", HeaderValue::from_static("no-cache"));
+        /// assert!(req.typed_cache_control().unwrap().no_cache());
+        /// ```
+        #[inline]
+        pub fn typed_cache_control(&self) -> Option<headers::CacheControl> {
+            self.typed_header().ok()
+        }
+    }
+
+    /// Get bearer token from `Authorization` header, such as `Bearer mF_9.B5f-4.1JqM`.
+    ///
+    /// Scheme is matched case-insensitively, returns `None` if the header is absent or it is not valid bearer
+    /// credentials.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.headers_mut().insert("authorization", HeaderValue::from_static("bearer mF_9.B5f-4.1JqM"));
+    /// assert_eq!(req.bearer_token(), Some("mF_9.B5f-4.1JqM"));
+    /// ```
+    #[inline]
+    pub fn bearer_token(&self) -> Option<&str> {
+        let value = self.headers.get(AUTHORIZATION)?.to_str().ok()?;
+        auth::parse_bearer_token(value)
+    }
+
+    /// Get username and password from `Authorization` header with `Basic` scheme.
+    ///
+    /// Password is `None` if there is no `:` in credentials. Returns `None` if the header is absent or it is not
+    /// valid basic credentials, such as invalid base64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.headers_mut().insert("authorization", HeaderValue::from_static("Basic dXNlcjpwYXNz"));
+    /// assert_eq!(req.basic_auth(), Some(("user".to_owned(), Some("pass".to_owned()))));
+    /// ```
+    #[inline]
+    pub fn basic_auth(&self) -> Option<(String, Option<String>)> {
+        let value = self.headers.get(AUTHORIZATION)?.to_str().ok()?;
+        auth::parse_basic_auth(value)
+    }
+
     /// Modify a header for this request.
     ///
     /// When `overwrite` is set to `true`, If the header is already present, the value will be replaced.
@@ -430,6 +749,36 @@ impl Request {
         self.replace_body(ReqBody::None)
     }
 
+    /// Take body form the request and returns it as an [`AsyncRead`](tokio::io::AsyncRead).
+    ///
+    /// The body is read frame by frame, so it is suitable for big bodies which should not be buffered in memory,
+    /// for example, streaming an upload into a file. Use [`payload`](Self::payload) to read small bodies at once.
+    ///
+    /// *Notice: This method takes body.*
+    ///
+    /// ```
+    /// # use salvo_core::prelude::*;
+    /// # use tokio::io::AsyncReadExt;
+    /// #[handler]
+    /// async fn upload(req: &mut Request) -> Result<String, StatusError> {
+    ///     let mut reader = req.body_reader();
+    ///     let mut buf = [0; 1024];
+    ///     let mut total = 0;
+    ///     loop {
+    ///         let n = reader.read(&mut buf).await.map_err(|_| StatusError::bad_request())?;
+    ///         if n == 0 {
+    ///             break;
+    ///         }
+    ///         total += n;
+    ///     }
+    ///     Ok(format!("received {total} bytes"))
+    /// }
+    /// ```
+    #[inline]
+    pub fn body_reader(&mut self) -> BodyReader {
+        BodyReader::new(self.take_body())
+    }
+
     /// Returns a reference to the associated extensions.
     ///
     /// # Examples
@@ -510,21 +859,47 @@ impl Request {
         &mut self.extensions
     }
 
-    /// Get accept.
+    /// Get media ranges with their quality from `Accept` header, in the order they are sent.
+    ///
+    /// Malformed items are skipped, quality is `1.0` if it is absent or malformed.
+    pub(crate) fn accept_ranges(&self) -> Vec<(Mime, f32)> {
+        self.headers
+            .get_all(ACCEPT)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|v| v.trim().parse::<Mime>().ok())
+            .map(|mime| {
+                let q = mime
+                    .get_param("q")
+                    .and_then(|q| q.as_str().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (mime, q)
+            })
+            .collect()
+    }
+
+    /// Get media types from `Accept` header, they are sorted by quality in descending order and the types with
+    /// `q=0` are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.headers_mut().insert("accept", HeaderValue::from_static("text/html;q=0.8, application/json, */*;q=0"));
+    /// let accept = req.accept();
+    /// assert_eq!(accept.len(), 2);
+    /// assert_eq!(accept[0], mime::APPLICATION_JSON);
+    /// ```
     pub fn accept(&self) -> Vec<Mime> {
-        let mut list: Vec<Mime> = vec![];
-        if let Some(accept) = self.headers.get("accept").and_then(|h| h.to_str().ok()) {
-            let parts: Vec<&str> = accept.split(',').collect();
-            for part in parts {
-                if let Ok(mt) = part.parse() {
-                    list.push(mt);
-                }
-            }
-        }
-        list
+        let mut ranges = self.accept_ranges();
+        ranges.retain(|(_, q)| *q > 0.0);
+        ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        ranges.into_iter().map(|(mime, _)| mime).collect()
     }
 
-    /// Get first accept.
+    /// Get the most preferred media type from `Accept` header.
     #[inline]
     pub fn first_accept(&self) -> Option<Mime> {
         let mut accept = self.accept();
@@ -535,6 +910,69 @@ impl Request {
         }
     }
 
+    /// Get language tags with their quality from `Accept-Language` header, they are sorted by quality in
+    /// descending order.
+    ///
+    /// Malformed items are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.headers_mut().insert("accept-language", HeaderValue::from_static("en;q=0.8, zh-CN"));
+    /// assert_eq!(req.accept_languages(), vec![("zh-CN".to_owned(), 1.0), ("en".to_owned(), 0.8)]);
+    /// ```
+    pub fn accept_languages(&self) -> Vec<(String, f32)> {
+        self.headers
+            .get_all(ACCEPT_LANGUAGE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(language::parse_accept_language)
+            .collect::<Vec<_>>()
+    }
+
+    /// Select the preferred language from `supported` by `Accept-Language` header.
+    ///
+    /// [RFC 4647 lookup](https://www.rfc-editor.org/rfc/rfc4647#section-3.4) is used for each language by quality,
+    /// and a language also matches supported tags which it is a prefix of, so `zh` matches `zh-CN`.
+    /// The first supported language is returned if the header is absent, malformed or nothing is matched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.headers_mut().insert("accept-language", HeaderValue::from_static("fr, zh;q=0.9"));
+    /// assert_eq!(req.preferred_language(&["en", "zh-CN", "de"]), Some("zh-CN"));
+    /// ```
+    pub fn preferred_language<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        let mut languages = self.accept_languages();
+        languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        language::lookup_language(&languages, supported)
+    }
+
+    /// Get the value of `Last-Event-ID` header, which is sent by browsers when they reconnect to a Server-Sent
+    /// Events stream, so the server can resume the stream after the last received event.
+    ///
+    /// `None` is returned if the header is absent, empty or not a valid string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.headers_mut().insert("last-event-id", HeaderValue::from_static("42"));
+    /// assert_eq!(req.last_event_id(), Some("42"));
+    /// ```
+    #[inline]
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.headers
+            .get("last-event-id")
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty())
+    }
+
     /// Get content type.
     #[inline]
     pub fn content_type(&self) -> Option<Mime> {
@@ -544,6 +982,55 @@ impl Request {
             .and_then(|v| v.parse().ok())
     }
 
+    /// Get content length from `Content-Length` header, returns `None` if it is absent or invalid.
+    #[inline]
+    pub fn content_length(&self) -> Option<u64> {
+        self.headers
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+    }
+
+    /// Returns `true` if the request has `Expect: 100-continue` header.
+    ///
+    /// The `100 Continue` informational response is sent automatically when the body is read for the first time,
+    /// so a handler can reject the request, e.g. with `413 Payload Too Large` or `417 Expectation Failed`, before
+    /// the client sends the body by responding without reading the body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.headers_mut().insert("expect", HeaderValue::from_static("100-Continue"));
+    /// assert!(req.expects_continue());
+    /// ```
+    #[inline]
+    pub fn expects_continue(&self) -> bool {
+        self.headers
+            .get(EXPECT)
+            .map(|v| v.as_bytes().eq_ignore_ascii_case(b"100-continue"))
+            .unwrap_or(false)
+    }
+
+    /// Evaluate conditional headers `If-Match`, `If-Unmodified-Since`, `If-None-Match` and `If-Modified-Since`
+    /// against current resource's `etag` and `last_modified`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// # use salvo_core::http::headers::ETag;
+    /// let mut req = Request::default();
+    /// req.headers_mut().insert("if-none-match", HeaderValue::from_static("W/\"v1\""));
+    /// let etag: ETag = "\"v1\"".parse().unwrap();
+    /// assert_eq!(req.is_fresh(Some(&etag), None), Precondition::NotModified);
+    /// ```
+    #[inline]
+    pub fn is_fresh(&self, etag: Option<&ETag>, last_modified: Option<SystemTime>) -> Precondition {
+        conditional::evaluate(&self.method, &self.headers, etag, last_modified)
+    }
+
     cfg_feature! {
         #![feature = "cookie"]
         /// Get `CookieJar` reference.
@@ -564,6 +1051,26 @@ impl Request {
         {
             self.cookies.get(name.as_ref())
         }
+        /// Get a signed `Cookie` verified with `key`, the value of returned cookie is the original value.
+        ///
+        /// Returns `None` if the cookie is absent or it's signature is invalid.
+        #[inline]
+        pub fn signed_cookie<T>(&self, name: T, key: &Key) -> Option<Cookie<'static>>
+        where
+            T: AsRef<str>,
+        {
+            self.cookies.signed(key).get(name.as_ref())
+        }
+        /// Get a private `Cookie` decrypted with `key`, the value of returned cookie is the plaintext value.
+        ///
+        /// Returns `None` if the cookie is absent or it can not be decrypted and authenticated.
+        #[inline]
+        pub fn private_cookie<T>(&self, name: T, key: &Key) -> Option<Cookie<'static>>
+        where
+            T: AsRef<str>,
+        {
+            self.cookies.private(key).get(name.as_ref())
+        }
     }
     /// Get params reference.
     #[inline]
@@ -576,6 +1083,14 @@ impl Request {
         &mut self.params
     }
 
+    /// Get the path pattern of the matched router, such as `/users/<id>`, it is `None` if no router is matched.
+    ///
+    /// Unlike the request path, it does not contain param values, so it is suitable for metrics and tracing.
+    #[inline]
+    pub fn matched_route(&self) -> Option<&str> {
+        self.matched_route.as_deref()
+    }
+
     /// Get param value from params.
     #[inline]
     pub fn param<'de, T>(&'de self, key: &str) -> Option<T>
@@ -593,6 +1108,23 @@ impl Request {
                 .collect()
         })
     }
+    /// Get decoded query pairs in their original order, duplicate keys are preserved.
+    ///
+    /// Unlike [`queries`](Self::queries), pairs are not grouped by key, so they can be used to canonicalize the query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.set_uri("/?b=2&a=1&b=3".parse().unwrap());
+    /// let pairs = req.query_pairs().collect::<Vec<_>>();
+    /// assert_eq!(pairs, vec![("b".into(), "2".into()), ("a".into(), "1".into()), ("b".into(), "3".into())]);
+    /// ```
+    #[inline]
+    pub fn query_pairs(&self) -> impl Iterator<Item = (Cow<'_, str>, Cow<'_, str>)> + '_ {
+        form_urlencoded::parse(self.raw_query().unwrap_or_default().as_bytes())
+    }
     /// Get mutable queries reference.
     pub fn queries_mut(&mut self) -> &mut MultiMap<String, String> {
         let _ = self.queries();
@@ -608,6 +1140,27 @@ impl Request {
         self.queries().get_vec(key).and_then(|vs| from_str_multi_val(vs).ok())
     }
 
+    /// Get all values of a query key, values of `key[]` are also included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use salvo_core::http::*;
+    /// let mut req = Request::default();
+    /// req.set_uri("/?tag=a&tag[]=b&tag=c".parse().unwrap());
+    /// assert_eq!(req.queries_multi("tag"), vec!["a", "c", "b"]);
+    /// ```
+    pub fn queries_multi(&self, key: &str) -> Vec<&str> {
+        let queries = self.queries();
+        queries
+            .get_vec(key)
+            .into_iter()
+            .chain(queries.get_vec(&format!("{key}[]")))
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
     /// Get field data from form.
     #[inline]
     pub async fn form<'de, T>(&'de mut self, key: &str) -> Option<T>
@@ -680,44 +1233,105 @@ impl Request {
             .unwrap_or_default()
     }
 
-    /// Get request payload with default max size limit(64KB).
+    /// Get secure max size of this request's body.
+    ///
+    /// It is the value set by [`set_secure_max_size`](Self::set_secure_max_size), for example set by
+    /// [`Service::secure_max_size`](crate::Service::secure_max_size), or the global [`secure_max_size`] if not set.
+    #[inline]
+    pub fn secure_max_size(&self) -> usize {
+        self.secure_max_size.unwrap_or_else(secure_max_size)
+    }
+
+    /// Sets secure max size of this request's body, it is used by [`payload`](Self::payload),
+    /// [`parse_json`](Self::parse_json), [`form_data`](Self::form_data) and other body parsing methods.
+    #[inline]
+    pub fn set_secure_max_size(&mut self, size: usize) {
+        self.secure_max_size = Some(size);
+    }
+
+    /// Returns `ParseError::PayloadTooLarge` if the `Content-Length` or the body's size hint exceeds `max_size`.
+    fn check_body_size(&self, max_size: usize) -> Result<(), ParseError> {
+        let content_length = self.content_length().unwrap_or_default();
+        if content_length.max(self.body.size_hint().lower()) > max_size as u64 {
+            Err(ParseError::PayloadTooLarge)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get request payload with default max size limit, see [`secure_max_size`](Self::secure_max_size).
     ///
     /// <https://github.com/hyperium/hyper/issues/3111>
     /// *Notice: This method takes body.
     #[inline]
     pub async fn payload(&mut self) -> Result<&Bytes, ParseError> {
-        self.payload_with_max_size(secure_max_size()).await
+        self.payload_with_max_size(self.secure_max_size()).await
     }
 
     /// Get request payload with max size limit.
     ///
+    /// `ParseError::PayloadTooLarge` is returned if the size exceeds the limit, it is checked by
+    /// `Content-Length` header before reading and by the read size while reading.
+    ///
     /// <https://github.com/hyperium/hyper/issues/3111>
     /// *Notice: This method takes body.
     #[inline]
     pub async fn payload_with_max_size(&mut self, max_size: usize) -> Result<&Bytes, ParseError> {
+        if !self.payload.initialized() {
+            self.check_body_size(max_size)?;
+        }
         let body = self.take_body();
-        self.payload
+        let mut trailers = None;
+        let payload = self
+            .payload
             .get_or_try_init(|| async {
-                Ok(Limited::new(body, max_size)
-                    .collect()
-                    .await
-                    .map_err(ParseError::other)?
-                    .to_bytes())
+                let collected = body.collect_limited(max_size).await?;
+                trailers = collected.trailers().cloned();
+                Ok(collected.to_bytes())
             })
-            .await
+            .await;
+        if trailers.is_some() {
+            self.trailers = trailers;
+        }
+        payload
+    }
+
+    /// Get trailers of request, the body is read by [`Request::payload`] if it is not read.
+    ///
+    /// Returns `None` if the client does not send trailers, or the body is failed to read or is taken by other ways,
+    /// such as [`Request::take_body`] or reading `multipart/form-data`.
+    ///
+    /// *Notice: This method takes body.*
+    pub async fn trailers(&mut self) -> Option<&HeaderMap> {
+        self.payload().await.ok()?;
+        self.trailers.as_ref()
     }
 
     /// Get `FormData` reference from request.
     ///
-    /// *Notice: This method takes body and body's size is not limited.
+    /// Size of `application/x-www-form-urlencoded` body is limited by [`secure_max_size`](Self::secure_max_size).
+    /// Size of `multipart/form-data` body is only limited if it is set explicitly by
+    /// [`set_secure_max_size`](Self::set_secure_max_size), since it is usually used to upload files.
+    ///
+    /// *Notice: This method takes body.
     #[inline]
     pub async fn form_data(&mut self) -> Result<&FormData, ParseError> {
         if let Some(ctype) = self.content_type() {
             if ctype.subtype() == mime::WWW_FORM_URLENCODED || ctype.type_() == mime::MULTIPART {
+                let max_size = if ctype.type_() == mime::MULTIPART {
+                    self.secure_max_size
+                } else {
+                    Some(self.secure_max_size())
+                };
+                if let Some(max_size) = max_size {
+                    if !self.form_data.initialized() {
+                        self.check_body_size(max_size)?;
+                    }
+                }
                 let body = self.take_body();
                 let headers = self.headers();
                 self.form_data
-                    .get_or_try_init(|| async { FormData::read(headers, body).await })
+                    .get_or_try_init(|| async { FormData::read(headers, body, max_size).await })
                     .await
             } else {
                 Err(ParseError::NotFormData)
@@ -756,13 +1370,29 @@ impl Request {
     }
 
     /// Parse queries as type `T` from request.
+    ///
+    /// Repeated keys like `tag=a&tag=b` and bracket keys like `ids[]=1&ids[]=2` are parsed as sequences,
+    /// keys like `filter[name]=salvo` are parsed as nested objects. If a single value field receives
+    /// multiple values, an error is returned.
     #[inline]
     pub fn parse_queries<'de, T>(&'de mut self) -> Result<T, ParseError>
     where
         T: Deserialize<'de>,
     {
         let queries = self.queries().iter_all();
-        from_str_multi_map(queries).map_err(ParseError::Deserialize)
+        from_str_nested_multi_map(queries).map_err(ParseError::Deserialize)
+    }
+
+    /// Extract queries as type `T` from request.
+    ///
+    /// Keys with bracket syntax like `filter[name]=salvo` are parsed as nested objects.
+    #[inline]
+    pub fn extract_query<T>(&self) -> Result<T, ParseError>
+    where
+        T: DeserializeOwned,
+    {
+        let queries = self.queries().iter_all();
+        from_str_nested_multi_map(queries).map_err(ParseError::Deserialize)
     }
 
     /// Parse headers as type `T` from request.
@@ -800,7 +1430,7 @@ impl Request {
     where
         T: Deserialize<'de>,
     {
-        self.parse_json_with_max_size(secure_max_size()).await
+        self.parse_json_with_max_size(self.secure_max_size()).await
     }
     /// Parse json body as type `T` from request with max size limit.
     #[inline]
@@ -825,7 +1455,131 @@ impl Request {
         Err(ParseError::InvalidContentType)
     }
 
+    cfg_feature! {
+        #![feature = "msgpack"]
+        /// Parse MessagePack body as type `T` from request with default max size limit.
+        #[inline]
+        pub async fn parse_msgpack<'de, T>(&'de mut self) -> Result<T, ParseError>
+        where
+            T: Deserialize<'de>,
+        {
+            self.parse_msgpack_with_max_size(self.secure_max_size()).await
+        }
+        /// Parse MessagePack body as type `T` from request with max size limit.
+        ///
+        /// Content type should be `application/msgpack`, `application/x-msgpack` or `application/vnd.msgpack`.
+        #[inline]
+        pub async fn parse_msgpack_with_max_size<'de, T>(&'de mut self, max_size: usize) -> Result<T, ParseError>
+        where
+            T: Deserialize<'de>,
+        {
+            match self.content_type() {
+                Some(ctype) if is_msgpack(&ctype) => {
+                    let payload = self.payload_with_max_size(max_size).await?;
+                    rmp_serde::from_slice::<T>(payload).map_err(ParseError::MsgPack)
+                }
+                _ => Err(ParseError::InvalidContentType),
+            }
+        }
+    }
+
+    cfg_feature! {
+        #![feature = "cbor"]
+        /// Parse CBOR body as type `T` from request with default max size limit.
+        #[inline]
+        pub async fn parse_cbor<T>(&mut self) -> Result<T, ParseError>
+        where
+            T: DeserializeOwned,
+        {
+            self.parse_cbor_with_max_size(self.secure_max_size()).await
+        }
+        /// Parse CBOR body as type `T` from request with max size limit.
+        ///
+        /// Content type should be `application/cbor`.
+        #[inline]
+        pub async fn parse_cbor_with_max_size<T>(&mut self, max_size: usize) -> Result<T, ParseError>
+        where
+            T: DeserializeOwned,
+        {
+            match self.content_type() {
+                Some(ctype) if is_cbor(&ctype) => {
+                    let payload = self.payload_with_max_size(max_size).await?;
+                    ciborium::from_reader::<T, _>(payload.as_ref()).map_err(ParseError::Cbor)
+                }
+                _ => Err(ParseError::InvalidContentType),
+            }
+        }
+    }
+
+    cfg_feature! {
+        #![feature = "xml"]
+        /// Parse XML body as type `T` from request with default max size limit.
+        #[inline]
+        pub async fn parse_xml<'de, T>(&'de mut self) -> Result<T, ParseError>
+        where
+            T: Deserialize<'de>,
+        {
+            self.parse_xml_with_max_size(self.secure_max_size()).await
+        }
+        /// Parse XML body as type `T` from request with max size limit.
+        ///
+        /// Content type should be `application/xml`, `text/xml` or ends with `+xml`, body is decoded by the
+        /// charset of content type.
+        #[inline]
+        pub async fn parse_xml_with_max_size<'de, T>(&'de mut self, max_size: usize) -> Result<T, ParseError>
+        where
+            T: Deserialize<'de>,
+        {
+            match self.content_type() {
+                Some(ctype) if is_xml(&ctype) => {
+                    let payload = self.payload_with_max_size(max_size).await?;
+                    from_xml_slice(&ctype, payload)
+                }
+                _ => Err(ParseError::InvalidContentType),
+            }
+        }
+    }
+
+    /// Parse body as text with default max size limit, it is decoded by the charset of content type.
+    ///
+    /// UTF-8 is used if charset is absent, charsets other than UTF-8, US-ASCII and ISO-8859-1 are only supported if
+    /// `charset` feature is enabled.
+    /// [`ParseError::UnsupportedCharset`] is returned if the charset is unknown, and it is rendered as
+    /// `415 Unsupported Media Type`.
+    #[inline]
+    pub async fn parse_text(&mut self) -> Result<String, ParseError> {
+        self.parse_text_with_max_size(self.secure_max_size()).await
+    }
+    /// Parse body as text with max size limit, it is decoded by the charset of content type.
+    #[inline]
+    pub async fn parse_text_with_max_size(&mut self, max_size: usize) -> Result<String, ParseError> {
+        let charset = self.content_charset();
+        let payload = self.payload_with_max_size(max_size).await?;
+        charset::decode(payload, charset.as_deref(), false).map(Cow::into_owned)
+    }
+    /// Parse body as text with default max size limit, it is decoded by the charset of content type.
+    ///
+    /// Unlike [`parse_text`](Self::parse_text), unknown charset falls back to UTF-8, and malformed sequences are
+    /// replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    #[inline]
+    pub async fn parse_text_lossy(&mut self) -> Result<String, ParseError> {
+        let charset = self.content_charset();
+        let payload = self.payload_with_max_size(self.secure_max_size()).await?;
+        charset::decode(payload, charset.as_deref(), true).map(Cow::into_owned)
+    }
+    fn content_charset(&self) -> Option<String> {
+        self.content_type().and_then(|ctype| {
+            ctype
+                .get_param(mime::CHARSET)
+                .map(|charset| charset.as_str().to_owned())
+        })
+    }
+
     /// Parse form body as type `T` from request.
+    ///
+    /// Both `application/x-www-form-urlencoded` and `multipart/form-data` fields are supported, nested
+    /// field names such as `address.city`, `address[city]` or `items[0].qty` are deserialized into nested
+    /// structs and sequences, and booleans accept values of HTML checkboxes, such as `on` and `off`.
     #[inline]
     pub async fn parse_form<'de, T>(&'de mut self) -> Result<T, ParseError>
     where
@@ -833,19 +1587,22 @@ impl Request {
     {
         if let Some(ctype) = self.content_type() {
             if ctype.subtype() == mime::WWW_FORM_URLENCODED || ctype.subtype() == mime::FORM_DATA {
-                return from_str_multi_map(self.form_data().await?.fields.iter_all()).map_err(ParseError::Deserialize);
+                return from_str_nested_form_multi_map(self.form_data().await?.fields.iter_all())
+                    .map_err(ParseError::Deserialize);
             }
         }
         Err(ParseError::InvalidContentType)
     }
 
     /// Parse json body or form body as type `T` from request with default max size.
+    ///
+    /// MessagePack, CBOR and XML bodies are also supported if `msgpack`, `cbor` and `xml` features are enabled.
     #[inline]
     pub async fn parse_body<'de, T>(&'de mut self) -> Result<T, ParseError>
     where
         T: Deserialize<'de>,
     {
-        self.parse_body_with_max_size(secure_max_size()).await
+        self.parse_body_with_max_size(self.secure_max_size()).await
     }
 
     /// Parse json body or form body as type `T` from request with max size.
@@ -855,13 +1612,38 @@ impl Request {
     {
         if let Some(ctype) = self.content_type() {
             if ctype.subtype() == mime::WWW_FORM_URLENCODED || ctype.subtype() == mime::FORM_DATA {
-                return from_str_multi_map(self.form_data().await?.fields.iter_all()).map_err(ParseError::Deserialize);
+                return from_str_nested_form_multi_map(self.form_data().await?.fields.iter_all())
+                    .map_err(ParseError::Deserialize);
             } else if ctype.subtype() == mime::JSON {
                 return self
                     .payload_with_max_size(max_size)
                     .await
                     .and_then(|body| serde_json::from_slice::<T>(body).map_err(ParseError::SerdeJson));
             }
+            #[cfg(feature = "msgpack")]
+            if is_msgpack(&ctype) {
+                return self
+                    .payload_with_max_size(max_size)
+                    .await
+                    .and_then(|body| rmp_serde::from_slice::<T>(body).map_err(ParseError::MsgPack));
+            }
+            #[cfg(feature = "cbor")]
+            if is_cbor(&ctype) {
+                // `ciborium` only deserializes owned data, so it is decoded to `Value` first.
+                return self.payload_with_max_size(max_size).await.and_then(|body| {
+                    ciborium::from_reader::<ciborium::Value, _>(body.as_ref())
+                        .map_err(ParseError::Cbor)?
+                        .deserialized::<T>()
+                        .map_err(ParseError::other)
+                });
+            }
+            #[cfg(feature = "xml")]
+            if is_xml(&ctype) {
+                return self
+                    .payload_with_max_size(max_size)
+                    .await
+                    .and_then(|body| from_xml_slice(&ctype, body));
+            }
         }
         Err(ParseError::InvalidContentType)
     }
@@ -869,6 +1651,7 @@ impl Request {
 
 #[cfg(test)]
 mod tests {
+    use http_body_util::BodyExt;
     use serde::{Deserialize, Serialize};
 
     use super::*;
@@ -899,11 +1682,80 @@ mod tests {
         assert_eq!(man.age, 25);
         assert_eq!(man.wives, vec!["a", "2"]);
         assert_eq!(man.weapons, (69, "stick".into(), "gun".into()));
-        let man = req.parse_queries::<GoodMan>().unwrap();
-        assert_eq!(man.name, "rust");
-        assert_eq!(man.age, 25);
-        assert_eq!(man.wives, "a");
-        assert_eq!(man.weapons, 69);
+        let err = req.parse_queries::<GoodMan>().unwrap_err();
+        assert!(
+            matches!(err, ParseError::Deserialize(e) if e.to_string().starts_with("expected a single value for key"))
+        );
+
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Filter<'a> {
+            name: &'a str,
+        }
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Search<'a> {
+            tag: Vec<&'a str>,
+            ids: Vec<u64>,
+            #[serde(borrow)]
+            filter: Filter<'a>,
+        }
+        let mut req = TestClient::get("http://127.0.0.1:5801/hello?tag=a&tag=b&ids[]=1&ids[]=2&filter[name]=x").build();
+        let search = req.parse_queries::<Search>().unwrap();
+        assert_eq!(search.tag, vec!["a", "b"]);
+        assert_eq!(search.ids, vec![1, 2]);
+        assert_eq!(search.filter, Filter { name: "x" });
+        assert_eq!(req.queries_multi("tag"), vec!["a", "b"]);
+        assert!(req.queries_multi("none").is_empty());
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+    struct Filter {
+        name: String,
+        min_age: Option<u8>,
+    }
+    #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+    struct Search {
+        q: String,
+        page: u32,
+        filter: Filter,
+    }
+
+    #[test]
+    fn test_extract_query() {
+        let req =
+            TestClient::get("http://127.0.0.1:5801/hello?q=rust&page=2&filter[name]=salvo&filter[min_age]=18").build();
+        let search = req.extract_query::<Search>().unwrap();
+        assert_eq!(
+            search,
+            Search {
+                q: "rust".into(),
+                page: 2,
+                filter: Filter {
+                    name: "salvo".into(),
+                    min_age: Some(18)
+                }
+            }
+        );
+
+        let req = TestClient::get("http://127.0.0.1:5801/hello?q=rust&filter[name]=salvo").build();
+        let err = req.extract_query::<Search>().unwrap_err();
+        assert!(matches!(err, ParseError::Deserialize(e) if e.to_string() == "missing field `page`"));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_extract_query_roundtrip(q in ".*", page: u32, name in ".*", min_age: Option<u8>) {
+            let search = Search { q, page, filter: Filter { name, min_age } };
+            let mut serializer = form_urlencoded::Serializer::new(String::new());
+            serializer
+                .append_pair("q", &search.q)
+                .append_pair("page", &search.page.to_string())
+                .append_pair("filter[name]", &search.filter.name);
+            if let Some(min_age) = search.filter.min_age {
+                serializer.append_pair("filter[min_age]", &min_age.to_string());
+            }
+            let req = TestClient::get(format!("http://127.0.0.1:5801/hello?{}", serializer.finish())).build();
+            proptest::prop_assert_eq!(req.extract_query::<Search>().unwrap(), search);
+        }
     }
 
     #[tokio::test]
@@ -917,6 +1769,214 @@ mod tests {
             .build();
         assert_eq!(req.parse_json::<User>().await.unwrap(), User { name: "jobs".into() });
     }
+
+    #[tokio::test]
+    async fn test_parse_text() {
+        let mut req = TestClient::post("http://127.0.0.1:5800/hello")
+            .add_header("content-type", "text/plain", true)
+            .body("hello 中文")
+            .build();
+        assert_eq!(req.parse_text().await.unwrap(), "hello 中文");
+
+        let mut req = TestClient::post("http://127.0.0.1:5800/hello")
+            .add_header("content-type", "text/plain; charset=no-such-charset", true)
+            .body("hello")
+            .build();
+        assert!(matches!(
+            req.parse_text().await,
+            Err(ParseError::UnsupportedCharset(charset)) if charset == "no-such-charset"
+        ));
+        let mut req = TestClient::post("http://127.0.0.1:5800/hello")
+            .add_header("content-type", "text/plain; charset=no-such-charset", true)
+            .body("hello")
+            .build();
+        assert_eq!(req.parse_text_lossy().await.unwrap(), "hello");
+
+        let mut req = TestClient::post("http://127.0.0.1:5800/hello")
+            .add_header(
+                "content-type",
+                "application/x-www-form-urlencoded; charset=iso-8859-1",
+                true,
+            )
+            .body("name=caf%E9&city=New+York")
+            .build();
+        assert_eq!(req.form::<String>("name").await.unwrap(), "café");
+        assert_eq!(req.form::<String>("city").await.unwrap(), "New York");
+    }
+
+    #[cfg(feature = "charset")]
+    #[tokio::test]
+    async fn test_parse_text_charset() {
+        let mut req = TestClient::post("http://127.0.0.1:5800/hello")
+            .add_header("content-type", "text/plain; charset=gbk", true)
+            .body(&b"\xd6\xd0\xce\xc4"[..])
+            .build();
+        assert_eq!(req.parse_text().await.unwrap(), "中文");
+
+        let mut req = TestClient::post("http://127.0.0.1:5800/hello")
+            .add_header("content-type", "application/x-www-form-urlencoded; charset=gbk", true)
+            .body("name=%D6%D0%CE%C4&city=New+York")
+            .build();
+        assert_eq!(req.form::<String>("name").await.unwrap(), "中文");
+        assert_eq!(req.form::<String>("city").await.unwrap(), "New York");
+    }
+
+    #[tokio::test]
+    async fn test_payload_too_large() {
+        let mut req = TestClient::post("http://127.0.0.1:5800/hello")
+            .raw_json(r#"{"name": "jobs"}"#)
+            .build();
+        req.set_secure_max_size(8);
+        assert!(matches!(
+            req.parse_json::<serde_json::Value>().await,
+            Err(ParseError::PayloadTooLarge)
+        ));
+
+        // Content-Length is not set, the size is checked while reading.
+        let mut req = TestClient::post("http://127.0.0.1:5800/hello").build();
+        req.set_secure_max_size(8);
+        *req.body_mut() = ReqBody::Boxed {
+            inner: Box::pin(http_body_util::Full::new(Bytes::from_static(b"name=jobs&age=18")).map_err(|e| e.into())),
+            fusewire: None,
+        };
+        req.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        assert!(matches!(req.form_data().await, Err(ParseError::PayloadTooLarge)));
+
+        #[crate::handler]
+        async fn echo(req: &mut Request) -> Result<String, ParseError> {
+            Ok(String::from_utf8_lossy(req.payload().await?).into_owned())
+        }
+        let service = crate::Service::new(crate::Router::new().post(echo)).secure_max_size(4);
+        let res = TestClient::post("http://127.0.0.1:5800/")
+            .text("hello")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(http::StatusCode::PAYLOAD_TOO_LARGE));
+        let res = TestClient::post("http://127.0.0.1:5800/")
+            .text("hi")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(http::StatusCode::OK));
+    }
+
+    #[tokio::test]
+    async fn test_expect_continue_over_connection() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        use crate::conn::{Acceptor, Listener, TcpListener};
+        use crate::prelude::*;
+
+        #[handler]
+        async fn upload(req: &mut Request, res: &mut Response) {
+            if req.expects_continue() && req.header::<u64>("content-length").unwrap_or_default() > 4 {
+                res.status_code(StatusCode::PAYLOAD_TOO_LARGE);
+                return;
+            }
+            let body = req.payload().await.unwrap().clone();
+            res.write_body(body).unwrap();
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(Server::new(acceptor).serve(Router::new().post(upload)));
+
+        async fn read_head(stream: &mut TcpStream) -> String {
+            let mut head = Vec::new();
+            while !head.ends_with(b"\r\n\r\n") {
+                head.push(stream.read_u8().await.unwrap());
+            }
+            String::from_utf8(head).unwrap().to_lowercase()
+        }
+        fn request_head(len: usize) -> String {
+            format!(
+                "POST / HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\nExpect: 100-continue\r\nContent-Length: {len}\r\n\r\n"
+            )
+        }
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request_head(10).as_bytes()).await.unwrap();
+        let head = read_head(&mut stream).await;
+        assert!(head.starts_with("http/1.1 413"), "{head}");
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request_head(3).as_bytes()).await.unwrap();
+        let head = read_head(&mut stream).await;
+        assert!(head.starts_with("http/1.1 100 continue"), "{head}");
+        stream.write_all(b"abc").await.unwrap();
+        let head = read_head(&mut stream).await;
+        assert!(head.starts_with("http/1.1 200"), "{head}");
+        let mut body = String::new();
+        stream.read_to_string(&mut body).await.unwrap();
+        assert_eq!(body, "abc");
+    }
+
+    #[cfg(feature = "typed-headers")]
+    #[test]
+    fn test_typed_header() {
+        use headers::{Authorization, ContentLength, Range};
+
+        let req = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header("authorization", "Basic dXNlcjpwYXNz", true)
+            .add_header("range", "items=0-1", true)
+            .build();
+        let auth = req
+            .typed_header::<Authorization<headers::authorization::Basic>>()
+            .unwrap();
+        assert_eq!(auth.username(), "user");
+        assert_eq!(auth.password(), "pass");
+        assert!(matches!(
+            req.typed_header::<ContentLength>(),
+            Err(ParseError::MissingHeader(name)) if name == CONTENT_LENGTH
+        ));
+        assert!(matches!(
+            req.typed_header::<Range>(),
+            Err(ParseError::InvalidTypedHeader(name)) if name == http::header::RANGE
+        ));
+
+        let mut res = crate::http::Response::new();
+        res.set_typed_header(ContentLength(3));
+        assert_eq!(res.headers().get(CONTENT_LENGTH).unwrap(), "3");
+
+        let req = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header("if-none-match", "\"v1\"", true)
+            .add_header("cache-control", "no-cache, max-age=0", true)
+            .add_header("content-length", "12", true)
+            .build();
+        let etag: headers::ETag = "\"v1\"".parse().unwrap();
+        assert!(!req.if_none_match().unwrap().precondition_passes(&etag));
+        let cache_control = req.typed_cache_control().unwrap();
+        assert!(cache_control.no_cache());
+        assert_eq!(cache_control.max_age(), Some(std::time::Duration::ZERO));
+        assert_eq!(req.content_length(), Some(12));
+        assert_eq!(Request::new().content_length(), None);
+    }
+    #[tokio::test]
+    async fn test_trailers() {
+        use futures_util::stream;
+        use http_body_util::StreamBody;
+        use hyper::body::Frame;
+
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-checksum", "abc".parse().unwrap());
+        let frames = stream::iter(vec![
+            Ok::<_, crate::BoxedError>(Frame::data(Bytes::from("hello"))),
+            Ok(Frame::trailers(trailers)),
+        ]);
+        let body = ReqBody::Boxed {
+            inner: Box::pin(StreamBody::new(frames)),
+            fusewire: None,
+        };
+        let mut req = Request::from_hyper(hyper::Request::new(body), Scheme::HTTP);
+        assert_eq!(req.trailers().await.unwrap().get("x-checksum").unwrap(), "abc");
+        assert_eq!(req.payload().await.unwrap(), "hello");
+
+        let mut req = TestClient::post("http://127.0.0.1:5801/").body("hello").build();
+        assert!(req.trailers().await.is_none());
+    }
     #[tokio::test]
     async fn test_query() {
         let req = TestClient::get("http://127.0.0.1:5801/hello?name=rust&name=25&name=a&name=2&weapons=98&weapons=gun")
@@ -930,6 +1990,60 @@ mod tests {
         assert_eq!(names, vec!["rust", "25", "a", "2"]);
         assert_eq!(weapons, (98, "gun"));
     }
+    #[test]
+    fn test_raw_query() {
+        let mut req = TestClient::get("http://127.0.0.1:5801/hello?X-Sig=a%2Fb&b=2&a=1&b=1").build();
+        assert_eq!(req.raw_query(), Some("X-Sig=a%2Fb&b=2&a=1&b=1"));
+        assert_eq!(
+            req.query_pairs().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>(),
+            vec!["X-Sig=a/b", "b=2", "a=1", "b=1"]
+        );
+        assert_eq!(req.query::<Vec<u32>>("b"), Some(vec![2, 1]));
+
+        let mut pairs = req
+            .query_pairs()
+            .filter(|(k, _)| k != "X-Sig")
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        let canonical = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish();
+        req.set_query(&canonical).unwrap();
+        assert_eq!(*req.uri(), *"http://127.0.0.1:5801/hello?a=1&b=1&b=2");
+        assert_eq!(req.query::<Vec<u32>>("b"), Some(vec![1, 2]));
+        assert!(req.query::<String>("X-Sig").is_none());
+        assert_eq!(
+            *req.original_uri(),
+            *"http://127.0.0.1:5801/hello?X-Sig=a%2Fb&b=2&a=1&b=1"
+        );
+
+        req.set_query("").unwrap();
+        assert_eq!(*req.uri(), *"http://127.0.0.1:5801/hello");
+        assert!(req.raw_query().is_none());
+        assert!(req.queries().is_empty());
+        assert!(req.set_query("bad query").is_err());
+    }
+    #[test]
+    fn test_rewrite_uri() {
+        let mut req = Request::new();
+        req.set_uri("http://127.0.0.1:5801/v1/hello?name=rust".parse().unwrap());
+        assert_eq!(req.query::<&str>("name"), Some("rust"));
+
+        *req.uri_mut() = "http://127.0.0.1:5801/v1/hello?name=salvo".parse().unwrap();
+        assert_eq!(req.query::<&str>("name"), Some("salvo"));
+
+        req.set_path("/hello").unwrap();
+        assert_eq!(req.uri().path(), "/hello");
+        assert_eq!(req.query::<&str>("name"), Some("salvo"));
+        assert!(req.set_path("/bad path").is_err());
+        assert_eq!(req.uri().path(), "/hello");
+
+        req.set_uri("/world".parse().unwrap());
+        assert!(req.queries().is_empty());
+        assert_eq!(*req.original_uri(), *"/");
+        assert_eq!(*Request::new().original_uri(), *"/");
+    }
     #[tokio::test]
     async fn test_form() {
         let mut req = TestClient::post("http://127.0.0.1:5800/hello?q=rust")
@@ -963,4 +2077,55 @@ file content\r\n\
         let files = req.files("file1").await.unwrap();
         assert_eq!(files[0].name().unwrap(), "err.txt");
     }
+
+    #[tokio::test]
+    async fn test_parse_nested_form() {
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Address {
+            city: String,
+        }
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Item {
+            qty: u32,
+        }
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Order {
+            address: Address,
+            items: Vec<Item>,
+            gift: bool,
+            note: Option<String>,
+        }
+
+        let mut req = TestClient::post("http://127.0.0.1:5800/order")
+            .add_header("content-type", "application/x-www-form-urlencoded", true)
+            .raw_form("address.city=Paris&items%5B0%5D.qty=2&items%5B1%5D.qty=3&gift=on")
+            .build();
+        let order: Order = req.parse_form().await.unwrap();
+        assert_eq!(
+            order,
+            Order {
+                address: Address { city: "Paris".into() },
+                items: vec![Item { qty: 2 }, Item { qty: 3 }],
+                gift: true,
+                note: None,
+            }
+        );
+
+        let mut req: Request = TestClient::post("http://127.0.0.1:5800/order")
+            .add_header(
+                "content-type",
+                "multipart/form-data; boundary=----WebKitFormBoundary0mkL0yrNNupCojyz",
+                true,
+            )
+            .body(
+                "------WebKitFormBoundary0mkL0yrNNupCojyz\r\n\
+Content-Disposition: form-data; name=\"address[city]\"\r\n\r\nParis\r\n\
+------WebKitFormBoundary0mkL0yrNNupCojyz\r\n\
+Content-Disposition: form-data; name=\"items[0].qty\"\r\n\r\nlots\r\n\
+------WebKitFormBoundary0mkL0yrNNupCojyz--\r\n",
+            )
+            .build();
+        let err = req.parse_form::<Order>().await.unwrap_err();
+        assert!(err.to_string().contains("`items[0].qty`"), "{err}");
+    }
 }
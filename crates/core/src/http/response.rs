@@ -2,23 +2,48 @@
 use std::collections::VecDeque;
 use std::fmt::{self, Display, Formatter};
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 #[cfg(feature = "cookie")]
-use cookie::{Cookie, CookieJar};
+use cookie::{Cookie, CookieJar, Key};
 use futures_util::stream::Stream;
-use http::header::{HeaderMap, HeaderValue, IntoHeaderName};
+use headers::{ETag, HeaderMapExt, LastModified};
+use http::header::{
+    HeaderMap, HeaderValue, IntoHeaderName, CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE, TRAILER,
+    VARY,
+};
 pub use http::response::Parts;
-use http::{version::Version, Extensions};
+use http::{version::Version, Extensions, Uri};
 use mime::Mime;
+use serde::Serialize;
 
 use crate::fs::NamedFile;
 use crate::fuse::TransProto;
-use crate::http::{StatusCode, StatusError};
+use crate::http::{CacheControl, Precondition, Request, StatusCode, StatusError};
+use crate::writing::{Redirect, RenderError};
 use crate::{BoxedError, Error, Scribe};
 use bytes::Bytes;
 
 pub use crate::http::body::{BodySender, BytesFrame, ResBody};
 
+/// Wrap body of `res` to send `trailers` after it, and announce their names in `Trailer` header.
+fn attach_trailers(res: &mut hyper::Response<ResBody>, trailers: HeaderMap) {
+    if trailers.is_empty() || res.body().is_error() {
+        return;
+    }
+    let headers = res.headers_mut();
+    if !headers.contains_key(TRAILER) {
+        let names = trailers.keys().map(|name| name.as_str()).collect::<Vec<_>>().join(", ");
+        if let Ok(names) = HeaderValue::from_str(&names) {
+            headers.insert(TRAILER, names);
+        }
+    }
+    // Trailers can only be sent in chunked HTTP/1.1 response.
+    headers.remove(CONTENT_LENGTH);
+    let body = std::mem::take(res.body_mut());
+    *res.body_mut() = body.with_trailers(trailers);
+}
+
 /// Represents an HTTP response
 #[non_exhaustive]
 pub struct Response {
@@ -33,9 +58,25 @@ pub struct Response {
     pub cookies: CookieJar,
     /// The HTTP body.
     pub body: ResBody,
+    /// The HTTP trailers, they are sent after the body.
+    pub trailers: HeaderMap,
     /// Used to store extra data derived from the underlying protocol.
     pub extensions: Extensions,
 }
+/// Encode `cookie` as `Set-Cookie` header value.
+///
+/// Removal cookies, whose `Max-Age` is `0`, are sent with `Expires` of Unix epoch too, so that old browsers which
+/// do not support `Max-Age` also drop them.
+#[cfg(feature = "cookie")]
+pub(crate) fn set_cookie_value(cookie: &Cookie<'static>) -> Option<HeaderValue> {
+    if cookie.max_age().map(|age| age.is_zero()).unwrap_or(false) {
+        let mut removal = cookie.clone();
+        removal.set_expires(cookie::time::OffsetDateTime::UNIX_EPOCH);
+        return removal.encoded().to_string().parse().ok();
+    }
+    cookie.encoded().to_string().parse().ok()
+}
+
 impl Default for Response {
     #[inline]
     fn default() -> Self {
@@ -46,6 +87,8 @@ impl<B> From<hyper::Response<B>> for Response
 where
     B: Into<ResBody>,
 {
+    /// Status code, version, headers, extensions and body are preserved, use [`ResBody::boxed`] to convert
+    /// response whose body is an arbitrary [`Body`](hyper::body::Body) first.
     #[inline]
     fn from(res: hyper::Response<B>) -> Self {
         let (
@@ -53,25 +96,25 @@ where
                 status,
                 version,
                 headers,
-                // extensions,
+                extensions,
                 ..
             },
             body,
         ) = res.into_parts();
+        // Cookies in `Set-Cookie` headers are added as original cookies, so that they are not sent twice.
         #[cfg(feature = "cookie")]
-        // Set the request cookies, if they exist.
-        let cookies = if let Some(header) = headers.get(http::header::SET_COOKIE) {
+        let cookies = {
             let mut cookie_jar = CookieJar::new();
-            if let Ok(header) = header.to_str() {
-                for cookie_str in header.split(';').map(|s| s.trim()) {
-                    if let Ok(cookie) = Cookie::parse_encoded(cookie_str).map(|c| c.into_owned()) {
-                        cookie_jar.add(cookie);
-                    }
+            for header in headers.get_all(http::header::SET_COOKIE) {
+                let cookie = header
+                    .to_str()
+                    .ok()
+                    .and_then(|v| Cookie::parse_encoded(v.to_owned()).ok());
+                if let Some(cookie) = cookie {
+                    cookie_jar.add_original(cookie);
                 }
             }
             cookie_jar
-        } else {
-            CookieJar::new()
         };
 
         Response {
@@ -81,7 +124,8 @@ where
             headers,
             #[cfg(feature = "cookie")]
             cookies,
-            extensions: Extensions::new(),
+            trailers: HeaderMap::new(),
+            extensions,
         }
     }
 }
@@ -97,6 +141,7 @@ impl Response {
             headers: HeaderMap::new(),
             #[cfg(feature = "cookie")]
             cookies: CookieJar::default(),
+            trailers: HeaderMap::new(),
             extensions: Extensions::new(),
         }
     }
@@ -111,6 +156,7 @@ impl Response {
             version: Version::default(),
             headers: HeaderMap::new(),
             cookies,
+            trailers: HeaderMap::new(),
             extensions: Extensions::new(),
         }
     }
@@ -151,6 +197,57 @@ impl Response {
         Ok(self)
     }
 
+    /// Get trailers reference.
+    #[inline]
+    pub fn trailers(&self) -> &HeaderMap {
+        &self.trailers
+    }
+    /// Get mutable trailers reference.
+    #[inline]
+    pub fn trailers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.trailers
+    }
+
+    /// Sets a trailer, it is sent in the trailers frame after the body, existing values of the trailer are replaced.
+    ///
+    /// Names of trailers are announced in the `Trailer` header if it is not set. Trailers are sent in HTTP/2 and
+    /// chunked HTTP/1.1 responses, HTTP/1.1 clients need to send `TE: trailers` header to receive them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::Response;
+    ///
+    /// let mut res = Response::new();
+    /// res.set_trailer("grpc-status", "0").unwrap();
+    /// assert_eq!(res.trailers().get("grpc-status").unwrap(), "0");
+    /// ```
+    #[inline]
+    pub fn set_trailer<N, V>(&mut self, name: N, value: V) -> crate::Result<&mut Self>
+    where
+        N: IntoHeaderName,
+        V: TryInto<HeaderValue>,
+    {
+        let value = value
+            .try_into()
+            .map_err(|_| Error::Other("invalid trailer value".into()))?;
+        self.trailers.insert(name, value);
+        Ok(self)
+    }
+
+    cfg_feature! {
+        #![feature = "typed-headers"]
+        /// Sets typed header which implements [`Header`](headers::Header), existing values of the header are replaced.
+        #[inline]
+        pub fn set_typed_header<H>(&mut self, header: H) -> &mut Self
+        where
+            H: headers::Header,
+        {
+            self.headers.typed_insert(header);
+            self
+        }
+    }
+
     /// Get version.
     #[inline]
     pub fn version(&self) -> Version {
@@ -205,8 +302,10 @@ impl Response {
         false
     }
 
-    /// Convert to hyper response.
-    #[doc(hidden)]
+    /// Convert to [`hyper::Response`], changed cookies are appended as `Set-Cookie` headers, and trailers are
+    /// attached to the body.
+    ///
+    /// If status code is not set, it is `404 Not Found` for empty body and `200 OK` for others.
     #[inline]
     pub fn into_hyper(self) -> hyper::Response<ResBody> {
         let Self {
@@ -218,15 +317,14 @@ impl Response {
             #[cfg(not(feature = "cookie"))]
             headers,
             body,
+            trailers,
             extensions,
             ..
         } = self;
 
         #[cfg(feature = "cookie")]
-        for cookie in cookies.delta() {
-            if let Ok(hv) = cookie.encoded().to_string().parse() {
-                headers.append(http::header::SET_COOKIE, hv);
-            }
+        for hv in cookies.delta().filter_map(set_cookie_value) {
+            headers.append(http::header::SET_COOKIE, hv);
         }
 
         let status_code = status_code.unwrap_or(match &body {
@@ -238,6 +336,7 @@ impl Response {
         *res.extensions_mut() = extensions;
         *res.headers_mut() = headers;
         *res.status_mut() = status_code;
+        attach_trailers(&mut res, trailers);
 
         res
     }
@@ -253,12 +352,30 @@ impl Response {
             // Default to a 404 if no response code was set
             *res.status_mut() = status;
         }
+        attach_trailers(&mut res, std::mem::take(&mut self.trailers));
 
         res
     }
 
-    /// Merge data from [`hyper::Response`].
-    #[doc(hidden)]
+    /// Replace status code, version, headers, extensions and body with data from [`hyper::Response`].
+    ///
+    /// It is useful to write the response of an inner [`hyper`] or `tower` service back, the body is moved
+    /// without copying, wrap an arbitrary [`Body`](hyper::body::Body) with [`ResBody::boxed`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use http_body_util::Full;
+    /// use salvo_core::http::{ResBody, Response, StatusCode};
+    ///
+    /// let mut res = Response::new();
+    /// let inner = hyper::Response::builder()
+    ///     .status(StatusCode::ACCEPTED)
+    ///     .body(Full::new(bytes::Bytes::from("hello")))
+    ///     .unwrap();
+    /// res.merge_hyper(inner.map(ResBody::boxed));
+    /// assert_eq!(res.status_code, Some(StatusCode::ACCEPTED));
+    /// ```
     #[inline]
     pub fn merge_hyper<B>(&mut self, hyper_res: hyper::Response<B>)
     where
@@ -285,6 +402,10 @@ impl Response {
     cfg_feature! {
         #![feature = "cookie"]
         /// Get cookies reference.
+        ///
+        /// Cookies which are added or removed by this response are in [`CookieJar::delta`], each of them is
+        /// appended as a `Set-Cookie` header when the response is sent, so cookies added by different handlers
+        /// never overwrite each other.
         #[inline]
         pub fn cookies(&self) -> &CookieJar {
             &self.cookies
@@ -303,6 +424,34 @@ impl Response {
             self.cookies.get(name.as_ref())
         }
         /// Helper function for add cookie.
+        ///
+        /// Use [`Cookie::build`] to set attributes, `Expires` is formatted as HTTP-date.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use salvo_core::http::cookie::time::{Duration, OffsetDateTime};
+        /// use salvo_core::http::cookie::{Cookie, SameSite};
+        /// use salvo_core::http::Response;
+        ///
+        /// let mut res = Response::new();
+        /// res.add_cookie(
+        ///     Cookie::build(("session", "abc"))
+        ///         .domain("example.com")
+        ///         .path("/")
+        ///         .secure(true)
+        ///         .http_only(true)
+        ///         .same_site(SameSite::Lax)
+        ///         .max_age(Duration::hours(1))
+        ///         .expires(OffsetDateTime::UNIX_EPOCH + Duration::days(1))
+        ///         .build(),
+        /// );
+        /// assert_eq!(
+        ///     res.cookie("session").unwrap().to_string(),
+        ///     "session=abc; HttpOnly; SameSite=Lax; Secure; Path=/; Domain=example.com; Max-Age=3600; \
+        ///      Expires=Fri, 02 Jan 1970 00:00:00 GMT"
+        /// );
+        /// ```
         #[inline]
         pub fn add_cookie(&mut self, cookie: Cookie<'static>)-> &mut Self {
             self.cookies.add(cookie);
@@ -319,7 +468,10 @@ impl Response {
         ///
         /// A "removal" cookie is a cookie that has the same name as the original
         /// cookie but has an empty value, a max-age of 0, and an expiration date
-        /// far in the past.
+        /// of Unix epoch.
+        ///
+        /// If no cookie named `name` is present in the jar, a removal cookie is still emitted so that
+        /// the client drops the cookie it holds.
         ///
         /// Read more about [removal cookies](https://docs.rs/cookie/0.18.0/cookie/struct.CookieJar.html#method.remove).
         #[inline]
@@ -327,9 +479,31 @@ impl Response {
         {
             if let Some(cookie) = self.cookies.get(name).cloned() {
                 self.cookies.remove(cookie);
+            } else {
+                let mut cookie = Cookie::new(name.to_owned(), "");
+                cookie.make_removal();
+                self.cookies.add(cookie);
             }
             self
         }
+        /// Add a signed cookie, it's value is signed with `key` so that it can be verified by
+        /// [`Request::signed_cookie`].
+        ///
+        /// [`Request::signed_cookie`]: crate::http::Request::signed_cookie
+        #[inline]
+        pub fn add_signed_cookie(&mut self, cookie: Cookie<'static>, key: &Key) -> &mut Self {
+            self.cookies.signed_mut(key).add(cookie);
+            self
+        }
+        /// Add a private cookie, it's value is encrypted with `key` so that it can be read by
+        /// [`Request::private_cookie`] only.
+        ///
+        /// [`Request::private_cookie`]: crate::http::Request::private_cookie
+        #[inline]
+        pub fn add_private_cookie(&mut self, cookie: Cookie<'static>, key: &Key) -> &mut Self {
+            self.cookies.private_mut(key).add(cookie);
+            self
+        }
     }
 
     /// Get content type..
@@ -352,6 +526,196 @@ impl Response {
             .and_then(|v| v.parse().ok())
     }
 
+    /// Sets `ETag` header and returns `&mut Self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::Response;
+    /// use salvo_core::http::headers::ETag;
+    ///
+    /// let mut res = Response::new();
+    /// res.set_etag("W/\"v1\"".parse::<ETag>().unwrap());
+    /// assert_eq!(res.headers().get("etag").unwrap(), "W/\"v1\"");
+    /// ```
+    #[inline]
+    pub fn set_etag(&mut self, etag: ETag) -> &mut Self {
+        self.headers.typed_insert(etag);
+        self
+    }
+
+    /// Sets `Last-Modified` header formatted as HTTP-date and returns `&mut Self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use salvo_core::http::Response;
+    ///
+    /// let mut res = Response::new();
+    /// res.set_last_modified(UNIX_EPOCH + Duration::from_secs(784111777));
+    /// assert_eq!(res.headers().get("last-modified").unwrap(), "Sun, 06 Nov 1994 08:49:37 GMT");
+    /// ```
+    #[inline]
+    pub fn set_last_modified(&mut self, time: SystemTime) -> &mut Self {
+        self.headers.typed_insert(LastModified::from(time));
+        self
+    }
+
+    /// Sets `ETag` header from an entity tag string and returns `&mut Self`.
+    ///
+    /// A value which is already an entity tag like `"v1"` or `W/"v1"` is used as is, otherwise it is quoted as a
+    /// strong entity tag. Invalid value is logged and ignored, use [`Response::set_etag`] to set a parsed [`ETag`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::Response;
+    ///
+    /// let mut res = Response::new();
+    /// res.etag("v1");
+    /// assert_eq!(res.headers().get("etag").unwrap(), "\"v1\"");
+    /// res.etag("W/\"v2\"");
+    /// assert_eq!(res.headers().get("etag").unwrap(), "W/\"v2\"");
+    /// ```
+    #[inline]
+    pub fn etag(&mut self, tag: impl AsRef<str>) -> &mut Self {
+        let tag = tag.as_ref();
+        let parsed = if tag.ends_with('"') && (tag.starts_with('"') || tag.starts_with("W/\"")) {
+            tag.parse::<ETag>()
+        } else {
+            format!("\"{tag}\"").parse::<ETag>()
+        };
+        match parsed {
+            Ok(etag) => self.set_etag(etag),
+            Err(e) => {
+                tracing::error!(error = ?e, tag, "invalid etag");
+                self
+            }
+        }
+    }
+
+    /// Evaluates conditional headers of `req` against `ETag` and `Last-Modified` headers of this response.
+    ///
+    /// If the client's cached representation is still fresh, the response is turned into `304 Not Modified`,
+    /// if a precondition failed, it is turned into `412 Precondition Failed`, and the body is removed in both cases.
+    /// `If-None-Match` uses weak comparison and `If-Match` uses strong comparison, see [`Request::is_fresh`].
+    ///
+    /// Call it after `ETag` or `Last-Modified` is set, the returned [`Precondition`] tells the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn user(req: &mut Request, res: &mut Response) {
+    ///     res.etag("v1").render(Json(["salvo"]));
+    ///     res.check_preconditions(req);
+    /// }
+    /// ```
+    ///
+    /// [`Request::is_fresh`]: crate::http::Request::is_fresh
+    pub fn check_preconditions(&mut self, req: &Request) -> Precondition {
+        let etag = self.headers.typed_get::<ETag>();
+        let last_modified = self.headers.typed_get::<LastModified>().map(SystemTime::from);
+        let precondition = req.is_fresh(etag.as_ref(), last_modified);
+        if let Some(code) = precondition.status_code() {
+            self.status_code = Some(code);
+            self.body = ResBody::None;
+            self.headers.remove(CONTENT_TYPE);
+            self.headers.remove(CONTENT_LENGTH);
+        }
+        precondition
+    }
+
+    /// Sets `Cache-Control` header and returns `&mut Self`, an empty [`CacheControl`] removes the header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::{CacheControl, Response};
+    ///
+    /// let mut res = Response::new();
+    /// res.cache_control(CacheControl::new().private().max_age(60));
+    /// assert_eq!(res.headers().get("cache-control").unwrap(), "private, max-age=60");
+    /// ```
+    pub fn cache_control(&mut self, cache_control: CacheControl) -> &mut Self {
+        if cache_control.is_empty() {
+            self.headers.remove(CACHE_CONTROL);
+            return self;
+        }
+        match cache_control.to_header_value() {
+            Ok(value) => {
+                self.headers.insert(CACHE_CONTROL, value);
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, cache_control = %cache_control, "invalid cache control");
+            }
+        }
+        self
+    }
+
+    /// Sets `Cache-Control: no-store` header and returns `&mut Self`.
+    #[inline]
+    pub fn no_store(&mut self) -> &mut Self {
+        self.cache_control(CacheControl::new().no_store())
+    }
+
+    /// Appends header names to `Vary` header and returns `&mut Self`.
+    ///
+    /// Names already in `Vary` are skipped case-insensitively, and `Vary` is set to `*` alone if any name is `*`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::Response;
+    ///
+    /// let mut res = Response::new();
+    /// res.vary(["Accept-Encoding"]);
+    /// res.vary(["accept-encoding", "Origin"]);
+    /// assert_eq!(res.headers().get("vary").unwrap(), "Accept-Encoding, Origin");
+    /// ```
+    pub fn vary<I, S>(&mut self, names: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut values: Vec<String> = self
+            .headers
+            .get_all(VARY)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|name| name.trim().to_owned())
+            .filter(|name| !name.is_empty())
+            .collect();
+        values.extend(names.into_iter().map(|name| name.as_ref().trim().to_owned()));
+        let value = if values.iter().any(|name| name == "*") {
+            "*".to_owned()
+        } else {
+            let mut merged: Vec<String> = Vec::with_capacity(values.len());
+            for name in values {
+                if !name.is_empty() && !merged.iter().any(|m| m.eq_ignore_ascii_case(&name)) {
+                    merged.push(name);
+                }
+            }
+            merged.join(", ")
+        };
+        if value.is_empty() {
+            return self;
+        }
+        match HeaderValue::from_str(&value) {
+            Ok(value) => {
+                self.headers.insert(VARY, value);
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, vary = value, "invalid vary");
+            }
+        }
+        self
+    }
+
     /// Sets status code and returns `&mut Self`.
     ///
     /// # Example
@@ -369,6 +733,63 @@ impl Response {
         self
     }
 
+    /// Returns `true` if the status code is a redirection (`3xx`).
+    #[inline]
+    pub fn is_redirect(&self) -> bool {
+        self.status_code.map(|code| code.is_redirection()).unwrap_or(false)
+    }
+
+    /// Redirect to `uri` with `302 Found` status code.
+    ///
+    /// `Location` header is set and the body is a tiny HTML page with a link to `uri`, for clients which don't
+    /// follow redirects. An error is returned and the response is not changed if `uri` is not a valid URI.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::{Response, StatusCode};
+    ///
+    /// let mut res = Response::new();
+    /// res.redirect("/login").unwrap();
+    /// assert_eq!(res.status_code, Some(StatusCode::FOUND));
+    /// assert_eq!(res.headers().get("location").unwrap(), "/login");
+    /// assert!(res.is_redirect());
+    /// assert!(res.redirect("/bad path").is_err());
+    /// ```
+    #[inline]
+    pub fn redirect(&mut self, uri: impl TryInto<Uri>) -> crate::Result<&mut Self> {
+        self.redirect_with(StatusCode::FOUND, uri)
+    }
+    /// Redirect to `uri` with `301 Moved Permanently` status code, see [`Response::redirect`].
+    ///
+    /// Clients may change the method of the redirected request to `GET`, use [`Redirect::permanent`] for
+    /// `308 Permanent Redirect` which keeps the method.
+    ///
+    /// [`Redirect::permanent`]: crate::writing::Redirect::permanent
+    #[inline]
+    pub fn redirect_permanent(&mut self, uri: impl TryInto<Uri>) -> crate::Result<&mut Self> {
+        self.redirect_with(StatusCode::MOVED_PERMANENTLY, uri)
+    }
+    /// Redirect to `uri` with `303 See Other` status code, see [`Response::redirect`].
+    ///
+    /// The client requests `uri` with `GET` method, which is useful for post/redirect/get pattern.
+    #[inline]
+    pub fn redirect_see_other(&mut self, uri: impl TryInto<Uri>) -> crate::Result<&mut Self> {
+        self.redirect_with(StatusCode::SEE_OTHER, uri)
+    }
+    /// Redirect to `uri` with `307 Temporary Redirect` status code, see [`Response::redirect`].
+    ///
+    /// The client requests `uri` with the same method and body.
+    #[inline]
+    pub fn redirect_temporary(&mut self, uri: impl TryInto<Uri>) -> crate::Result<&mut Self> {
+        self.redirect_with(StatusCode::TEMPORARY_REDIRECT, uri)
+    }
+    fn redirect_with(&mut self, code: StatusCode, uri: impl TryInto<Uri>) -> crate::Result<&mut Self> {
+        let redirect = Redirect::with_status_code(code, uri)?;
+        self.render(redirect.html_body(true));
+        Ok(self)
+    }
+
     /// Render content.
     ///
     /// # Example
@@ -386,6 +807,32 @@ impl Response {
         scribe.render(self);
     }
 
+    /// Render serializable data in format negotiated by request's `Accept` header.
+    ///
+    /// Json is used when `Accept` header is absent or accepts `*/*`, MessagePack, CBOR and XML are used when
+    /// corresponding features are enabled and they are accepted, so are formats registered by
+    /// [`Service::serializer`](crate::Service::serializer). `406 Not Acceptable` is rendered if no format is
+    /// acceptable. See [`Negotiated`](crate::writing::Negotiated) for details.
+    #[inline]
+    pub fn render_negotiated<T>(&mut self, req: &Request, data: T)
+    where
+        T: Serialize + Send,
+    {
+        crate::writing::write_negotiated(req, self, data);
+    }
+
+    /// Marks rendering of the response as failed, it is used by [`Scribe`]s which can not render their data, such as
+    /// [`Json`](crate::writing::Json) which fails to serialize.
+    ///
+    /// The response is set to `500 Internal Server Error`, and the handler set by
+    /// [`Service::render_error_handler`](crate::Service::render_error_handler) is called with the error after
+    /// handlers are done, so that applications can replace the response.
+    pub fn render_failed(&mut self, error: impl Into<BoxedError>) -> &mut Self {
+        self.extensions.insert(RenderError::new(error));
+        self.render(StatusError::internal_server_error());
+        self
+    }
+
     /// Render content with status code.
     #[inline]
     pub fn stuff<P>(&mut self, code: StatusCode, scribe: P)
@@ -396,22 +843,102 @@ impl Response {
         scribe.render(self);
     }
 
-    /// Attempts to send a file. If file not exists, not found error will occur.
+    /// Serialize `value` as json and set it as response's body.
+    ///
+    /// The `content-type` header is set to `application/json; charset=utf-8`, and previous body is replaced.
+    /// If serialization fails, the response is not changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::Response;
+    ///
+    /// let mut res = Response::new();
+    /// res.json(&vec!["hello", "world"]).unwrap();
+    /// ```
+    pub fn json<T>(&mut self, value: &T) -> Result<(), serde_json::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let bytes = serde_json::to_vec(value)?;
+        self.headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/json; charset=utf-8"),
+        );
+        self.body = ResBody::Once(bytes.into());
+        Ok(())
+    }
+
+    /// Serialize `value` as json and set it as response's body with status code.
+    ///
+    /// See [`Response::json`] for more details. Status code is set only if serialization succeeds.
+    #[inline]
+    pub fn json_status<T>(&mut self, code: StatusCode, value: &T) -> Result<(), serde_json::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.json(value)?;
+        self.status_code = Some(code);
+        Ok(())
+    }
+
+    /// Attempts to send a file. If file not exists, not found error will occur, if it can not be read because of
+    /// permission, forbidden error will occur.
     ///
-    /// If you want more settings, you can use `NamedFile::builder` to create a new [`NamedFileBuilder`](crate::fs::NamedFileBuilder).
+    /// `ETag` and `Last-Modified` headers are set, conditional requests are answered with `304 Not Modified` or
+    /// `412 Precondition Failed`, and `Range` requests are answered with `206 Partial Content`, multiple ranges are
+    /// sent as `multipart/byteranges`.
+    ///
+    /// If you want more settings, such as attachment filename, you can use `NamedFile::builder` to create a new
+    /// [`NamedFileBuilder`](crate::fs::NamedFileBuilder).
     pub async fn send_file<P>(&mut self, path: P, req_headers: &HeaderMap)
     where
         P: Into<PathBuf> + Send,
     {
-        let path = path.into();
-        if !path.exists() {
-            self.render(StatusError::not_found());
-        } else {
-            match NamedFile::builder(path).build().await {
-                Ok(file) => file.send(req_headers, self).await,
-                Err(_) => self.render(StatusError::internal_server_error()),
-            }
+        NamedFile::builder(path).send(req_headers, self).await;
+    }
+
+    /// Send an informational (`1xx`) response, such as `103 Early Hints`, before the final response.
+    ///
+    /// It requires [`Server::informational_responses`] to be enabled.
+    ///
+    /// Returns `true` if it is sent. It is only sent on HTTP/1.1 connections, `false` is returned on HTTP/1.0, since
+    /// informational responses are not allowed there, on HTTP/2 and HTTP/3, since hyper does not support sending
+    /// informational responses there, and if anything of the final response has been written. Error is returned if
+    /// `status` is not informational or is `101 Switching Protocols`, or if writing the connection fails.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::header::{HeaderMap, HeaderValue, LINK};
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn index(res: &mut Response) {
+    ///     let mut headers = HeaderMap::new();
+    ///     headers.insert(LINK, HeaderValue::from_static("</style.css>; rel=preload; as=style"));
+    ///     if !res.send_informational(StatusCode::EARLY_HINTS, headers).await.unwrap_or(false) {
+    ///         // Hints are not sent, preload by the final response instead.
+    ///         res.add_header(LINK, "</style.css>; rel=preload; as=style", false).ok();
+    ///     }
+    ///     res.render(Text::Html("<link rel=\"stylesheet\" href=\"/style.css\">"));
+    /// }
+    /// ```
+    ///
+    /// [`Server::informational_responses`]: crate::Server::informational_responses
+    #[cfg_attr(not(feature = "http1"), allow(unused_variables))]
+    pub async fn send_informational(&mut self, status: StatusCode, headers: HeaderMap) -> std::io::Result<bool> {
+        if !status.is_informational() || status == StatusCode::SWITCHING_PROTOCOLS {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("`{status}` is not an informational status code"),
+            ));
+        }
+        #[cfg(feature = "http1")]
+        if let Some(sender) = self.extensions.get::<crate::conn::interim::InterimSender>().cloned() {
+            return sender.send(status, &headers).await;
         }
+        Ok(false)
     }
 
     /// Write bytes data to body. If body is none, a new `ResBody` will created.
@@ -461,6 +988,26 @@ impl Response {
     }
 
     /// Set response's body to stream.
+    ///
+    /// The stream is polled only when the connection is ready to send more data, so a slow client slows down
+    /// the stream instead of buffering it in memory. If the stream yields an error, the body is aborted: HTTP/1
+    /// connection is closed before the final chunk and HTTP/2 stream is reset, so the client can tell the
+    /// response is incomplete.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::convert::Infallible;
+    ///
+    /// use futures_util::{stream, StreamExt};
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn numbers(res: &mut Response) {
+    ///     let chunks = stream::iter(1..=3).map(|n| Ok::<_, Infallible>(format!("{n}\n")));
+    ///     res.stream(chunks);
+    /// }
+    /// ```
     #[inline]
     pub fn stream<S, O, E>(&mut self, stream: S)
     where
@@ -470,6 +1017,90 @@ impl Response {
     {
         self.body = ResBody::stream(stream);
     }
+
+    /// Set response's body to stream whose total size is known.
+    ///
+    /// Works like [`Response::stream`], but `Content-Length` header is sent instead of chunked transfer encoding.
+    /// If the stream yields more or less bytes than `size`, the body is aborted.
+    #[inline]
+    pub fn stream_with_size<S, O, E>(&mut self, stream: S, size: u64)
+    where
+        S: Stream<Item = Result<O, E>> + Send + 'static,
+        O: Into<BytesFrame> + 'static,
+        E: Into<BoxedError> + 'static,
+    {
+        self.body = ResBody::stream_with_size(stream, size);
+    }
+    /// Set response's body to stream, and send it as a file download named `filename`.
+    ///
+    /// `Content-Type` is set to `content_type` and `Content-Disposition` is set to `attachment`, a name containing
+    /// non-ASCII characters is sent in `filename*` parameter encoded as described in
+    /// [RFC 5987](https://www.rfc-editor.org/rfc/rfc5987), with an ASCII fallback in `filename` parameter.
+    /// The stream is polled only when the client is ready to receive more data, see [`Response::stream`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::convert::Infallible;
+    ///
+    /// use futures_util::{stream, StreamExt};
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn export(res: &mut Response) {
+    ///     let rows = stream::iter(["id,name\n", "1,jobs\n"]).map(Ok::<_, Infallible>);
+    ///     res.stream_download(rows, "users.csv", mime::TEXT_CSV_UTF_8);
+    /// }
+    /// ```
+    pub fn stream_download<S, O, E>(&mut self, stream: S, filename: &str, content_type: Mime)
+    where
+        S: Stream<Item = Result<O, E>> + Send + 'static,
+        O: Into<BytesFrame> + 'static,
+        E: Into<BoxedError> + 'static,
+    {
+        match HeaderValue::from_str(content_type.as_ref()) {
+            Ok(value) => {
+                self.headers.insert(CONTENT_TYPE, value);
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, content_type = %content_type, "invalid content type");
+            }
+        }
+        let disposition = format!("attachment; {}", crate::fs::encode_filename(filename));
+        match HeaderValue::from_str(&disposition) {
+            Ok(value) => {
+                self.headers.insert(CONTENT_DISPOSITION, value);
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, disposition, "invalid content disposition");
+            }
+        }
+        self.stream(stream);
+    }
+    /// Sets `Content-Length` header and the exact size hint of body, and returns `&mut Self`.
+    ///
+    /// It is useful for stream and channel bodies whose total size is known, so that they are not sent with
+    /// chunked transfer encoding. Call it after the body is set, if the body yields more or less bytes than `size`,
+    /// a warning is logged and the body is aborted, see [`ResBody::with_size`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::convert::Infallible;
+    ///
+    /// use futures_util::stream;
+    /// use salvo_core::http::Response;
+    ///
+    /// let mut res = Response::new();
+    /// res.stream(stream::iter([Ok::<_, Infallible>("hello "), Ok("world")]));
+    /// res.set_content_length(11);
+    /// assert_eq!(res.headers().get("content-length").unwrap(), "11");
+    /// ```
+    pub fn set_content_length(&mut self, size: u64) -> &mut Self {
+        self.body = self.body.take().with_size(size);
+        self.headers.insert(CONTENT_LENGTH, HeaderValue::from(size));
+        self
+    }
     /// Set response's body to channel.
     #[inline]
     pub fn channel(&mut self) -> BodySender {
@@ -477,6 +1108,13 @@ impl Response {
         self.body = body;
         sender
     }
+    /// Set response's body to channel with an exact `size`, see [`ResBody::channel_with_size`].
+    #[inline]
+    pub fn channel_with_size(&mut self, size: u64) -> BodySender {
+        let (sender, body) = ResBody::channel_with_size(size);
+        self.body = body;
+        sender
+    }
 }
 
 impl fmt::Debug for Response {
@@ -506,6 +1144,151 @@ mod test {
 
     use super::*;
 
+    #[tokio::test]
+    async fn test_stream_over_connection() {
+        use std::io::Error as IoError;
+
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        use crate::conn::{Acceptor, Listener, TcpListener};
+        use crate::prelude::*;
+
+        #[handler]
+        async fn sized(res: &mut Response) {
+            res.stream_with_size(iter([Ok::<_, IoError>("hel"), Ok("lo")]), 5);
+        }
+        #[handler]
+        async fn channel(res: &mut Response) {
+            let mut sender = res.channel();
+            res.set_content_length(6);
+            tokio::spawn(async move {
+                sender.send_data("abc").await.unwrap();
+                sender.send_data("def").await.unwrap();
+            });
+        }
+        #[handler]
+        async fn short(res: &mut Response) {
+            let mut sender = res.channel();
+            res.set_content_length(10);
+            tokio::spawn(async move {
+                sender.send_data("abc").await.unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            });
+        }
+        #[handler]
+        async fn broken(res: &mut Response) {
+            let failure = futures_util::stream::once(async {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                Err(IoError::other("broken"))
+            });
+            res.stream(iter([Ok("partial")]).chain(failure));
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        let router = Router::new()
+            .push(Router::with_path("sized").get(sized))
+            .push(Router::with_path("channel").get(channel))
+            .push(Router::with_path("short").get(short))
+            .push(Router::with_path("broken").get(broken));
+        tokio::spawn(Server::new(acceptor).serve(router));
+
+        async fn fetch(addr: std::net::SocketAddr, path: &str) -> String {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(format!("GET {path} HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n").as_bytes())
+                .await
+                .unwrap();
+            let mut response = Vec::new();
+            let _ = stream.read_to_end(&mut response).await;
+            String::from_utf8(response).unwrap().to_lowercase()
+        }
+
+        let response = fetch(addr, "/sized").await;
+        assert!(response.contains("content-length: 5\r\n"), "{response}");
+        assert!(!response.contains("transfer-encoding"), "{response}");
+        assert!(response.ends_with("\r\n\r\nhello"), "{response}");
+
+        let response = fetch(addr, "/channel").await;
+        assert!(response.contains("content-length: 6\r\n"), "{response}");
+        assert!(!response.contains("transfer-encoding"), "{response}");
+        assert!(response.ends_with("\r\n\r\nabcdef"), "{response}");
+
+        // The connection is closed before the promised length is sent.
+        let response = fetch(addr, "/short").await;
+        assert!(response.contains("content-length: 10\r\n"), "{response}");
+        assert!(response.ends_with("\r\n\r\nabc"), "{response}");
+
+        let response = fetch(addr, "/broken").await;
+        assert!(response.contains("transfer-encoding: chunked\r\n"), "{response}");
+        assert!(response.contains("partial"), "{response}");
+        assert!(!response.ends_with("0\r\n\r\n"), "{response}");
+    }
+
+    #[tokio::test]
+    async fn test_send_informational() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        use crate::conn::{Acceptor, Listener, TcpListener};
+        use crate::http::header::LINK;
+        use crate::prelude::*;
+
+        #[handler]
+        async fn hints(res: &mut Response) {
+            let mut headers = HeaderMap::new();
+            headers.insert(LINK, HeaderValue::from_static("</style.css>; rel=preload; as=style"));
+            let sent = res.send_informational(StatusCode::EARLY_HINTS, headers).await.unwrap();
+            assert!(res
+                .send_informational(StatusCode::SWITCHING_PROTOCOLS, HeaderMap::new())
+                .await
+                .is_err());
+            res.render(format!("sent {sent}"));
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(
+            Server::new(acceptor)
+                .informational_responses(true)
+                .serve(Router::new().get(hints)),
+        );
+
+        async fn fetch(addr: std::net::SocketAddr, version: &str) -> String {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(format!("GET / {version}\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n").as_bytes())
+                .await
+                .unwrap();
+            let mut response = Vec::new();
+            let _ = stream.read_to_end(&mut response).await;
+            String::from_utf8(response).unwrap().to_lowercase()
+        }
+
+        let response = fetch(addr, "HTTP/1.1").await;
+        assert!(
+            response.starts_with(
+                "http/1.1 103 early hints\r\nlink: </style.css>; rel=preload; as=style\r\n\r\nhttp/1.1 200 ok\r\n"
+            ),
+            "{response}"
+        );
+        assert!(response.ends_with("\r\n\r\nsent true"), "{response}");
+
+        // Informational responses are not allowed on HTTP/1.0.
+        let response = fetch(addr, "HTTP/1.0").await;
+        assert!(response.starts_with("http/1.0 200 ok\r\n"), "{response}");
+        assert!(response.ends_with("\r\n\r\nsent false"), "{response}");
+
+        // Informational responses are disabled by default.
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(Server::new(acceptor).serve(Router::new().get(hints)));
+        let response = fetch(addr, "HTTP/1.1").await;
+        assert!(response.starts_with("http/1.1 200 ok\r\n"), "{response}");
+        assert!(response.ends_with("\r\n\r\nsent false"), "{response}");
+    }
+
     #[test]
     fn test_body_empty() {
         let body = ResBody::Once(Bytes::from("hello"));
@@ -540,4 +1323,452 @@ mod test {
 
         assert_eq!("Hello World", &result)
     }
+
+    #[tokio::test]
+    async fn test_trailers() {
+        use http::header::TRAILER;
+        use http_body_util::BodyExt;
+
+        let mut res = Response::new();
+        res.render("hello");
+        res.set_trailer("grpc-status", "0").unwrap();
+        res.set_trailer("grpc-message", "ok").unwrap();
+        let res = res.into_hyper();
+        assert_eq!(res.headers().get(TRAILER).unwrap(), "grpc-status, grpc-message");
+        assert!(res.headers().get(CONTENT_LENGTH).is_none());
+        let collected = BodyExt::collect(res.into_body()).await.unwrap();
+        assert_eq!(collected.trailers().unwrap().get("grpc-status").unwrap(), "0");
+        assert_eq!(collected.to_bytes(), "hello");
+
+        // Trailers sent by channel body are merged.
+        let mut res = Response::new();
+        let mut sender = res.channel();
+        res.set_trailer("x-checksum", "abc").unwrap();
+        tokio::spawn(async move {
+            sender.send_data("data").await.unwrap();
+            let mut trailers = HeaderMap::new();
+            trailers.insert("x-count", HeaderValue::from_static("1"));
+            sender.send_trailers(trailers).await.unwrap();
+        });
+        let collected = BodyExt::collect(res.into_hyper().into_body()).await.unwrap();
+        let trailers = collected.trailers().unwrap().clone();
+        assert_eq!(trailers.get("x-checksum").unwrap(), "abc");
+        assert_eq!(trailers.get("x-count").unwrap(), "1");
+        assert_eq!(collected.to_bytes(), "data");
+    }
+
+    #[tokio::test]
+    async fn test_channel_body() {
+        use std::io::{Error as IoError, ErrorKind};
+        use std::time::Duration;
+
+        use http_body_util::BodyExt;
+
+        // Aborted body ends with an error.
+        let (mut sender, body) = ResBody::channel();
+        tokio::spawn(async move {
+            sender.send_data("partial").await.unwrap();
+            sender.abort(IoError::other("upstream failed"));
+        });
+        assert!(BodyExt::collect(body).await.is_err());
+
+        // Sized body has exact size hint, dropping sender before all bytes are sent is an error.
+        let (mut sender, body) = ResBody::channel_with_size(10);
+        assert_eq!(hyper::body::Body::size_hint(&body).exact(), Some(10));
+        tokio::spawn(async move {
+            sender.send_data("hello").await.unwrap();
+        });
+        assert!(BodyExt::collect(body).await.is_err());
+
+        let (mut sender, body) = ResBody::channel_with_size(5);
+        tokio::spawn(async move {
+            sender.send_data("hello").await.unwrap();
+            assert!(sender.send_data("!").await.is_err());
+            sender.finish().unwrap();
+        });
+        assert_eq!(BodyExt::collect(body).await.unwrap().to_bytes(), "hello");
+
+        // Bytes written through `AsyncWrite` are counted too.
+        let (mut sender, body) = ResBody::channel_with_size(5);
+        tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            sender.write_all(b"hello").await.unwrap();
+            assert!(sender.write_all(b"!").await.is_err());
+            sender.finish().unwrap();
+        });
+        assert_eq!(BodyExt::collect(body).await.unwrap().to_bytes(), "hello");
+        let (mut sender, body) = ResBody::channel_with_size(5);
+        tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            sender.write_all(b"hel").await.unwrap();
+        });
+        assert!(BodyExt::collect(body).await.is_err());
+
+        // Bytes of sized body are counted, both too many and too few bytes are errors.
+        assert!(BodyExt::collect(ResBody::from("hello").with_size(3)).await.is_err());
+        assert!(BodyExt::collect(ResBody::from("hello").with_size(8)).await.is_err());
+        let body = ResBody::stream(iter([Ok::<_, IoError>("hel"), Ok("lo")])).with_size(5);
+        assert_eq!(hyper::body::Body::size_hint(&body).exact(), Some(5));
+        assert_eq!(BodyExt::collect(body).await.unwrap().to_bytes(), "hello");
+        assert!(ResBody::from("hello").with_size(5).is_once());
+
+        // Send times out when nobody reads the body, and sender is closed after body is dropped.
+        let (mut sender, body) = ResBody::channel();
+        sender.send_data("first").await.unwrap();
+        let result = sender.send_timeout("second", Duration::from_millis(10)).await;
+        assert!(matches!(result, Err(e) if e.kind() == ErrorKind::TimedOut));
+        assert!(!sender.is_closed());
+        drop(body);
+        assert!(sender.is_closed());
+    }
+
+    #[test]
+    fn test_json() {
+        let mut res = Response::new();
+        res.write_body("previous").unwrap();
+        res.json_status(StatusCode::CREATED, &serde_json::json!({"name": "jobs"}))
+            .unwrap();
+        assert_eq!(res.status_code, Some(StatusCode::CREATED));
+        assert_eq!(
+            res.headers().get(CONTENT_TYPE).unwrap(),
+            "application/json; charset=utf-8"
+        );
+        assert!(matches!(res.body, ResBody::Once(ref bytes) if bytes.as_ref() == br#"{"name":"jobs"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_buffer_body_with_limit() {
+        use std::io::Error as IoError;
+
+        async fn read_all(mut body: ResBody) -> (String, bool) {
+            let mut content = String::new();
+            while let Some(frame) = body.next().await {
+                match frame {
+                    Ok(frame) => content.push_str(std::str::from_utf8(&frame.into_data().unwrap()).unwrap()),
+                    Err(_) => return (content, true),
+                }
+            }
+            (content, false)
+        }
+        let stream = || ResBody::stream(iter([Ok::<_, IoError>("hello "), Ok("world")]));
+
+        assert_eq!(stream().buffer_with_limit(11).await.unwrap(), "hello world");
+        let Err(body) = stream().buffer_with_limit(8).await else {
+            panic!("body should not be collected");
+        };
+        assert_eq!(read_all(body).await, ("hello world".to_owned(), false));
+
+        let failure = iter([Ok("hello"), Err(IoError::other("broken")), Ok("never")]);
+        let Err(body) = ResBody::stream(failure).buffer_with_limit(64).await else {
+            panic!("body should not be collected");
+        };
+        assert_eq!(read_all(body).await, ("hello".to_owned(), true));
+
+        let (mut sender, body) = ResBody::channel_with_size(10);
+        tokio::spawn(async move {
+            sender.send_data("01234").await.unwrap();
+            sender.send_data("56789").await.unwrap();
+            sender.finish().unwrap();
+        });
+        let Err(body) = body.buffer_with_limit(4).await else {
+            panic!("body should not be collected");
+        };
+        assert_eq!(hyper::body::Body::size_hint(&body).exact(), Some(10));
+        assert_eq!(body.buffer_with_limit(100).await.unwrap(), "0123456789");
+    }
+
+    #[tokio::test]
+    async fn test_hyper_interop() {
+        use http_body_util::{BodyExt, Full};
+        use hyper::service::Service as _;
+
+        use crate::http::ReqBody;
+        use crate::prelude::*;
+        use crate::test::{ResponseExt, TestClient};
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Marker(&'static str);
+
+        #[handler]
+        async fn proxy(req: &mut Request, res: &mut Response) {
+            let inner = hyper::service::service_fn(|req: hyper::Request<ReqBody>| async move {
+                let (parts, body) = req.into_parts();
+                let body = BodyExt::collect(body).await?.to_bytes();
+                let mut res = hyper::Response::new(Full::new(Bytes::from(format!(
+                    "{} {} {:?}",
+                    parts.uri.path(),
+                    parts.headers["x-token"].to_str().unwrap(),
+                    body
+                ))));
+                res.headers_mut()
+                    .insert("set-cookie", HeaderValue::from_static("id=1; Path=/"));
+                res.extensions_mut().insert(Marker("inner"));
+                Ok::<_, BoxedError>(res)
+            });
+            let hyper_res = inner.call(req.strip_to_hyper().unwrap()).await.unwrap();
+            res.merge_hyper(hyper_res.map(ResBody::boxed));
+            assert_eq!(res.extensions.get::<Marker>(), Some(&Marker("inner")));
+            assert_eq!(res.body.size(), None);
+        }
+
+        let service = Service::new(Router::with_path("proxy").post(proxy));
+        let mut res = TestClient::post("http://127.0.0.1:5801/proxy")
+            .add_header("x-token", "abc", true)
+            .body("hello")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get_all("set-cookie").iter().count(), 1);
+        assert_eq!(res.take_string().await.unwrap(), "/proxy abc b\"hello\"");
+
+        let mut hyper_res = hyper::Response::new(ResBody::from("hi"));
+        hyper_res.extensions_mut().insert(Marker("outer"));
+        hyper_res
+            .headers_mut()
+            .insert("set-cookie", HeaderValue::from_static("id=1; Path=/"));
+        let res = Response::from(hyper_res);
+        assert_eq!(res.extensions.get::<Marker>(), Some(&Marker("outer")));
+        #[cfg(feature = "cookie")]
+        assert_eq!(res.cookie("id").unwrap().value(), "1");
+        let hyper_res = res.into_hyper();
+        assert_eq!(hyper_res.headers().get_all("set-cookie").iter().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_redirect() {
+        use crate::http::header::LOCATION;
+        use crate::test::ResponseExt;
+
+        let mut res = Response::new();
+        assert!(!res.is_redirect());
+        for code in [
+            StatusCode::FOUND,
+            StatusCode::MOVED_PERMANENTLY,
+            StatusCode::SEE_OTHER,
+            StatusCode::TEMPORARY_REDIRECT,
+        ] {
+            let mut res = Response::new();
+            let uri = "/login?next=/a&b=1";
+            match code {
+                StatusCode::FOUND => res.redirect(uri),
+                StatusCode::MOVED_PERMANENTLY => res.redirect_permanent(uri),
+                StatusCode::SEE_OTHER => res.redirect_see_other(uri),
+                _ => res.redirect_temporary(uri),
+            }
+            .unwrap();
+            assert_eq!(res.status_code, Some(code));
+            assert!(res.is_redirect());
+            assert_eq!(res.headers().get(LOCATION).unwrap(), "/login?next=/a&b=1");
+            assert_eq!(res.content_type().unwrap().essence_str(), "text/html");
+            assert!(res
+                .take_string()
+                .await
+                .unwrap()
+                .contains(r#"<a href="/login?next=/a&amp;b=1">"#));
+        }
+
+        res.status_code(StatusCode::OK);
+        assert!(res.redirect("http://exa mple.com").is_err());
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert!(res.headers().get(LOCATION).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_download() {
+        let mut res = Response::new();
+        let rows = iter(["id,name\n", "1,jobs\n"]).map(Ok::<_, std::io::Error>);
+        res.stream_download(rows, "报表 2024 \"final\".csv", mime::TEXT_CSV_UTF_8);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/csv; charset=utf-8");
+        assert_eq!(
+            res.headers().get(CONTENT_DISPOSITION).unwrap(),
+            "attachment; filename=\"__ 2024 \\\"final\\\".csv\"; \
+             filename*=UTF-8''%E6%8A%A5%E8%A1%A8%202024%20%22final%22.csv"
+        );
+        assert!(res.body.is_stream());
+        let body = res.take_body().buffer_with_limit(100).await.unwrap();
+        assert_eq!(body, "id,name\n1,jobs\n");
+
+        res.stream_download(iter([Ok::<_, std::io::Error>("a")]), "data.csv", mime::TEXT_CSV);
+        assert_eq!(
+            res.headers().get(CONTENT_DISPOSITION).unwrap(),
+            "attachment; filename=data.csv"
+        );
+    }
+
+    #[test]
+    fn test_caching_headers() {
+        let mut res = Response::new();
+        res.headers_mut()
+            .insert(VARY, HeaderValue::from_static("Origin, accept"));
+        res.vary(["Accept-Encoding", "ORIGIN", "Accept"]);
+        assert_eq!(res.headers().get(VARY).unwrap(), "Origin, accept, Accept-Encoding");
+        res.vary(["*"]);
+        assert_eq!(res.headers().get(VARY).unwrap(), "*");
+
+        res.etag("bad\"tag");
+        assert!(res.headers().get("etag").is_none());
+        res.etag("W/\"v1\"");
+        assert_eq!(res.headers().get("etag").unwrap(), "W/\"v1\"");
+
+        res.no_store();
+        assert_eq!(res.headers().get(CACHE_CONTROL).unwrap(), "no-store");
+        res.cache_control(CacheControl::new());
+        assert!(res.headers().get(CACHE_CONTROL).is_none());
+    }
+
+    #[test]
+    fn test_check_preconditions() {
+        let check = |etag: &str, name: &'static str, value: &'static str| {
+            let mut req = Request::new();
+            req.headers_mut().insert(name, HeaderValue::from_static(value));
+            let mut res = Response::new();
+            res.etag(etag).render("hello");
+            (res.check_preconditions(&req), res)
+        };
+
+        let (precondition, res) = check("v1", "if-none-match", "\"v1\"");
+        assert_eq!(precondition, Precondition::NotModified);
+        assert_eq!(res.status_code, Some(StatusCode::NOT_MODIFIED));
+        assert!(res.body.is_none());
+        assert!(res.headers().get(CONTENT_TYPE).is_none());
+        assert_eq!(res.headers().get("etag").unwrap(), "\"v1\"");
+
+        let (precondition, res) = check("v1", "if-none-match", "\"v0\", \"v2\"");
+        assert_eq!(precondition, Precondition::Passed);
+        assert_eq!(res.status_code, None);
+        assert!(matches!(res.body, ResBody::Once(ref bytes) if bytes == "hello"));
+
+        // `If-None-Match` uses weak comparison, weak and strong tags with the same opaque tag match.
+        let (precondition, _) = check("v1", "if-none-match", "W/\"v1\"");
+        assert_eq!(precondition, Precondition::NotModified);
+        let (precondition, _) = check("W/\"v1\"", "if-none-match", "\"v1\"");
+        assert_eq!(precondition, Precondition::NotModified);
+
+        // `If-Match` uses strong comparison, a weak tag never matches.
+        let (precondition, res) = check("W/\"v1\"", "if-match", "W/\"v1\"");
+        assert_eq!(precondition, Precondition::Failed);
+        assert_eq!(res.status_code, Some(StatusCode::PRECONDITION_FAILED));
+        assert!(res.body.is_none());
+        let (precondition, _) = check("v1", "if-match", "\"v1\"");
+        assert_eq!(precondition, Precondition::Passed);
+
+        let mut req = Request::new();
+        req.headers_mut().insert(
+            "if-modified-since",
+            HeaderValue::from_static("Sun, 06 Nov 1994 08:49:37 GMT"),
+        );
+        let mut res = Response::new();
+        res.set_last_modified(std::time::UNIX_EPOCH + std::time::Duration::from_secs(784111777));
+        assert_eq!(res.check_preconditions(&req), Precondition::NotModified);
+    }
+
+    #[cfg(feature = "cookie")]
+    #[tokio::test]
+    async fn test_set_cookies() {
+        use crate::http::header::{COOKIE, SET_COOKIE};
+        use crate::test::TestClient;
+        use crate::{Router, Service};
+
+        #[crate::handler]
+        async fn theme(res: &mut Response) {
+            res.add_cookie(Cookie::new("theme", "dark"));
+        }
+        #[crate::handler]
+        async fn session(res: &mut Response) {
+            res.add_cookie(
+                Cookie::build(("session", "abc"))
+                    .path("/")
+                    .http_only(true)
+                    .same_site(cookie::SameSite::Strict)
+                    .build(),
+            );
+            res.remove_cookie("legacy").remove_cookie("token");
+            assert_eq!(res.cookies().delta().count(), 4);
+            res.render("ok");
+        }
+        let service = Service::new(Router::new().hoop(theme).get(session));
+
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header(COOKIE, "token=xyz", true)
+            .send(&service)
+            .await;
+        let mut set_cookies = res
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .map(|v| v.to_str().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        set_cookies.sort();
+        assert_eq!(
+            set_cookies,
+            vec![
+                "legacy=; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+                "session=abc; HttpOnly; SameSite=Strict; Path=/",
+                "theme=dark",
+                "token=; Max-Age=0; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+            ]
+        );
+
+        let mut res = Response::new();
+        res.add_cookie(Cookie::new("a", "1")).add_cookie(Cookie::new("b", "2"));
+        res.remove_cookie("c");
+        let res = res.into_hyper();
+        assert_eq!(res.headers().get_all(SET_COOKIE).iter().count(), 3);
+    }
+
+    #[cfg(feature = "cookie")]
+    #[tokio::test]
+    async fn test_signed_cookie() {
+        use crate::http::header::{COOKIE, SET_COOKIE};
+        use crate::test::{ResponseExt, TestClient};
+        use crate::{Request, Router, Service};
+
+        fn key() -> Key {
+            Key::from(&[7; 64])
+        }
+        #[crate::handler]
+        async fn login(res: &mut Response) {
+            res.add_signed_cookie(Cookie::new("user", "jobs"), &key());
+            res.remove_cookie("legacy");
+        }
+        #[crate::handler]
+        async fn me(req: &mut Request, res: &mut Response) {
+            match req.signed_cookie("user", &key()) {
+                Some(cookie) => res.render(cookie.value().to_owned()),
+                None => res.status_code(StatusCode::UNAUTHORIZED).render("invalid"),
+            };
+        }
+        let service = Service::new(
+            Router::new()
+                .push(Router::with_path("login").post(login))
+                .push(Router::with_path("me").get(me)),
+        );
+
+        let res = TestClient::post("http://127.0.0.1:5801/login").send(&service).await;
+        let set_cookies = res
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .map(|v| v.to_str().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        assert!(set_cookies
+            .iter()
+            .any(|c| c.starts_with("legacy=;") && c.contains("Max-Age=0")));
+        let user = set_cookies.iter().find(|c| c.starts_with("user=")).unwrap();
+        let user = user.split(';').next().unwrap().to_owned();
+        assert_ne!(user, "user=jobs");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/me")
+            .add_header(COOKIE, &user, true)
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "jobs");
+
+        let tampered = format!("{}bill", user.trim_end_matches("jobs"));
+        let res = TestClient::get("http://127.0.0.1:5801/me")
+            .add_header(COOKIE, tampered, true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+    }
 }
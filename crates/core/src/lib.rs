@@ -32,7 +32,7 @@ pub mod serde;
 cfg_feature! {
     #![feature ="server"]
     pub mod server;
-    pub use self::server::Server;
+    pub use self::server::{catch_signals, Server};
 }
 mod service;
 pub mod writing;
@@ -51,14 +51,14 @@ cfg_feature! {
 }
 
 pub use self::conn::Listener;
-pub use self::depot::Depot;
+pub use self::depot::{Depot, TypedKey};
 pub use self::error::{BoxedError, Error};
 pub use self::extract::Extractible;
 pub use self::handler::Handler;
 pub use self::http::{Request, Response};
 pub use self::routing::{FlowCtrl, Router};
 pub use self::service::Service;
-pub use self::writing::{Scribe, Writer};
+pub use self::writing::{Responder, Scribe, Writer};
 /// Result type which has `salvo::Error` as it's error type.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -105,13 +105,27 @@ pub mod prelude {
         pub use crate::server::Server;
     }
     pub use crate::service::Service;
-    pub use crate::writing::{Json, Redirect, Scribe, Text, Writer};
+    pub use crate::writing::{Json, Redirect, Responder, Scribe, Text, Writer};
+    cfg_feature! {
+        #![feature = "msgpack"]
+        pub use crate::writing::MsgPack;
+    }
+    cfg_feature! {
+        #![feature = "cbor"]
+        pub use crate::writing::Cbor;
+    }
+    cfg_feature! {
+        #![feature = "xml"]
+        pub use crate::writing::Xml;
+    }
 }
 
 #[doc(hidden)]
 pub mod __private {
     pub use once_cell;
     pub use tracing;
+
+    pub use crate::writing::responder::{ResponderKind, ViaResponder, ViaWriter, Wrap, WriterKind};
 }
 
 #[doc(hidden)]
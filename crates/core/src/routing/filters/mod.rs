@@ -1,7 +1,7 @@
 //! filter module
 //!
 //! This module provides filters for routing requests based on various criteria
-//! such as uri scheme, hostname, port, path, and HTTP method.
+//! such as uri scheme, hostname, port, path, HTTP method and `Accept` header.
 
 mod opts;
 mod others;
@@ -11,7 +11,7 @@ use std::fmt::{self, Formatter};
 
 use self::opts::*;
 use crate::http::uri::Scheme;
-use crate::http::{Method, Request};
+use crate::http::{Method, Mime, Request};
 use crate::routing::PathState;
 
 pub use others::*;
@@ -82,6 +82,12 @@ pub trait Filter: fmt::Debug + Send + Sync + 'static {
 
     /// Filter `Request` and returns false or true.
     fn filter(&self, req: &mut Request, path: &mut PathState) -> bool;
+
+    /// The path pattern which is used to build the matched route, only path filter returns `Some`.
+    #[doc(hidden)]
+    fn path_pattern(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// `FnFilter` accepts a function as it's param, use this function to filter request.
@@ -124,6 +130,12 @@ pub fn port(port: u16) -> PortFilter {
     PortFilter::new(port)
 }
 
+/// Filter request by `Accept` header.
+#[inline]
+pub fn accept(media_type: Mime) -> AcceptFilter {
+    AcceptFilter::new(media_type)
+}
+
 /// Filter request use `PathFilter`.
 #[inline]
 pub fn path(path: impl Into<String>) -> PathFilter {
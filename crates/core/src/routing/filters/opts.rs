@@ -16,7 +16,17 @@ where
 {
     #[inline]
     fn filter(&self, req: &mut Request, state: &mut PathState) -> bool {
+        let missed = state.missed_methods.len();
         if self.first.filter(req, state) {
+            if state.missed_methods.len() > missed {
+                // The first filter passed only because its method was collected, the second one may really match.
+                let first_missed = state.missed_methods.split_off(missed);
+                if self.second.filter(req, state) && state.missed_methods.len() == missed {
+                    return true;
+                }
+                state.missed_methods.truncate(missed);
+                state.missed_methods.extend(first_missed);
+            }
             true
         } else {
             self.second.filter(req, state)
@@ -1,8 +1,9 @@
 use std::fmt::{self, Formatter};
 
 use crate::http::uri::Scheme;
-use crate::http::{Method, Request};
+use crate::http::{Method, Mime, Request};
 use crate::routing::{Filter, PathState};
+use crate::writing::accept_quality;
 
 /// Filter by request method
 #[derive(Clone, PartialEq, Eq)]
@@ -15,8 +16,16 @@ impl MethodFilter {
 }
 impl Filter for MethodFilter {
     #[inline]
-    fn filter(&self, req: &mut Request, _state: &mut PathState) -> bool {
-        req.method() == self.0
+    fn filter(&self, req: &mut Request, state: &mut PathState) -> bool {
+        if req.method() == self.0 {
+            true
+        } else if state.collect_methods {
+            // Keep matching, the method is collected if the rest of the route matches.
+            state.missed_methods.push(self.0.clone());
+            true
+        } else {
+            false
+        }
     }
 }
 impl fmt::Debug for MethodFilter {
@@ -168,3 +177,25 @@ impl fmt::Debug for PortFilter {
         write!(f, "port:{:?}", self.port)
     }
 }
+
+/// Filter by request `Accept` header, it passes if the media type is acceptable, i.e. its quality is not zero.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AcceptFilter(pub Mime);
+impl AcceptFilter {
+    /// Create a new `AcceptFilter`.
+    pub fn new(media_type: Mime) -> Self {
+        Self(media_type)
+    }
+}
+impl Filter for AcceptFilter {
+    #[inline]
+    fn filter(&self, req: &mut Request, _state: &mut PathState) -> bool {
+        accept_quality(req, &self.0) > 0.0
+    }
+}
+impl fmt::Debug for AcceptFilter {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "accept:{}", self.0)
+    }
+}
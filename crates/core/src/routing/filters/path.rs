@@ -860,6 +860,10 @@ impl Filter for PathFilter {
     fn filter(&self, _req: &mut Request, state: &mut PathState) -> bool {
         self.detect(state)
     }
+    #[inline]
+    fn path_pattern(&self) -> Option<&str> {
+        Some(&self.raw_value)
+    }
 }
 impl PathFilter {
     /// Create new `PathFilter`.
@@ -2,8 +2,12 @@
 //! Router can route http requests to different handlers.
 
 pub mod filters;
+mod negotiate;
+mod resource;
 mod router;
 pub use filters::*;
+pub use negotiate::ContentNegotiate;
+pub use resource::Resource;
 pub use router::{DetectMatched, Router};
 
 use std::borrow::Cow;
@@ -11,8 +15,8 @@ use std::sync::Arc;
 
 use indexmap::IndexMap;
 
-use crate::http::{Request, Response};
-use crate::{Depot, Handler};
+use crate::http::{Method, Request, Response};
+use crate::{Depot, Handler, Scribe};
 
 #[doc(hidden)]
 pub type PathParams = IndexMap<String, String>;
@@ -24,6 +28,12 @@ pub struct PathState {
     pub(crate) cursor: (usize, usize),
     pub(crate) params: PathParams,
     pub(crate) end_slash: bool, // For rest match, we want include the last slash.
+    /// Whether methods of routes which match everything but the method of request are collected.
+    pub(crate) collect_methods: bool,
+    /// Methods which are not matched by method filters of current branch, when methods are collected.
+    pub(crate) missed_methods: Vec<Method>,
+    /// Methods of routes which match everything but the method of request.
+    pub(crate) allowed_methods: Vec<Method>,
 }
 impl PathState {
     /// Create new `PathState`.
@@ -47,6 +57,9 @@ impl PathState {
             cursor: (0, 0),
             params: PathParams::new(),
             end_slash,
+            collect_methods: false,
+            missed_methods: vec![],
+            allowed_methods: vec![],
         }
     }
 
@@ -122,6 +135,8 @@ pub enum FlowCtrlStage {
     Catching,
 }
 
+type SkipResponse = Box<dyn FnOnce(&mut Response) + Send + Sync>;
+
 /// `FlowCtrl` is used to control the flow of execute handlers.
 ///
 /// When a request is coming, [`Router`] will detect it and get the matched one.
@@ -132,12 +147,17 @@ pub enum FlowCtrlStage {
 /// **NOTE**: When `Response`'s status code is set, and the status code [`Response::is_stamped()`] is returns false,
 /// all rest handlers will skipped.
 ///
+/// Handlers can be given a name using [`Handler::named`], then a middleware can jump to it with
+/// [`FlowCtrl::call_next_named`], all handlers between them are skipped.
+///
 /// [`Router`]: crate::routing::Router
 #[derive(Default)]
 pub struct FlowCtrl {
     catching: Option<bool>,
     is_ceased: bool,
+    is_skipped: bool,
     cursor: usize,
+    skip_response: Option<SkipResponse>,
     pub(crate) handlers: Vec<Arc<dyn Handler>>,
 }
 
@@ -148,7 +168,9 @@ impl FlowCtrl {
         FlowCtrl {
             catching: None,
             is_ceased: false,
+            is_skipped: false,
             cursor: 0,
+            skip_response: None,
             handlers,
         }
     }
@@ -158,6 +180,12 @@ impl FlowCtrl {
         self.cursor < self.handlers.len() // && !self.handlers.is_empty()
     }
 
+    /// Returns how many handlers are left to be called.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.handlers.len().saturating_sub(self.cursor)
+    }
+
     /// Call next handler. If get next handler and executed, returns true, otherwise returns false.
     ///
     /// If response status code is error or is redirection, all reset handlers will be skipped.
@@ -177,6 +205,7 @@ impl FlowCtrl {
             while let Some(h) = handler.take() {
                 self.cursor += 1;
                 h.handle(req, depot, res, self).await;
+                self.write_skip_response(res);
                 if !self.catching.unwrap_or_default() && res.is_stamped() {
                     self.skip_rest();
                     return true;
@@ -188,10 +217,59 @@ impl FlowCtrl {
         }
     }
 
+    /// Skip all handlers before the handler with the given name, and then call it.
+    ///
+    /// Only the rest handlers are searched, if no handler named `name` is found, nothing is skipped and
+    /// returns false. Otherwise works like [`FlowCtrl::call_next`].
+    pub async fn call_next_named(
+        &mut self,
+        name: &str,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+    ) -> bool {
+        let Some(offset) = self.handlers[self.cursor..].iter().position(|h| h.name() == Some(name)) else {
+            return false;
+        };
+        self.cursor += offset;
+        self.call_next(req, depot, res).await
+    }
+
     /// Skip all reset handlers.
+    ///
+    /// If it is called from the last handler, there is nothing to skip, but the response set by
+    /// [`FlowCtrl::set_response_for_skip`] is still written if the response is empty.
     #[inline]
     pub fn skip_rest(&mut self) {
-        self.cursor = self.handlers.len()
+        self.cursor = self.handlers.len();
+        self.is_skipped = true;
+    }
+
+    /// Set the response which will be written when rest handlers are skipped.
+    ///
+    /// The response is written after the handler which calls [`FlowCtrl::skip_rest`] returns, and only if
+    /// the response has no status code and body yet, so skipping does not produce an empty response.
+    #[inline]
+    pub fn set_response_for_skip<S>(&mut self, scribe: S)
+    where
+        S: Scribe + Send + Sync + 'static,
+    {
+        self.skip_response = Some(Box::new(move |res: &mut Response| res.render(scribe)));
+    }
+
+    #[inline]
+    fn write_skip_response(&mut self, res: &mut Response) {
+        if self.is_skipped && res.status_code.is_none() && res.body.is_none() {
+            if let Some(write) = self.skip_response.take() {
+                write(res);
+            }
+        }
+    }
+
+    /// Check is rest handlers skipped by [`FlowCtrl::skip_rest`].
+    #[inline]
+    pub fn is_skipped(&self) -> bool {
+        self.is_skipped
     }
 
     /// Check is `FlowCtrl` ceased.
@@ -242,4 +320,45 @@ mod tests {
         assert!(access(&service, "127.0.0.1").await.contains("404: Not Found"));
         assert_eq!(access(&service, "localhost").await, "Hello World");
     }
+
+    #[tokio::test]
+    async fn test_call_next_named() {
+        #[handler]
+        async fn auth(req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+            assert_eq!(ctrl.remaining(), 2);
+            if req.query::<bool>("guest").unwrap_or_default() {
+                ctrl.call_next_named("guest", req, depot, res).await;
+            } else if req.query::<bool>("deny").unwrap_or_default() {
+                ctrl.set_response_for_skip(StatusError::forbidden());
+                ctrl.skip_rest();
+            }
+        }
+        #[handler]
+        async fn member(depot: &mut Depot) {
+            depot.insert("member", true);
+        }
+        #[handler]
+        async fn greet(depot: &mut Depot) -> String {
+            format!("member {}", depot.get::<bool>("member").is_ok())
+        }
+
+        let router = Router::new().hoop(auth).hoop(member).get(greet.named("guest"));
+        let service = Service::new(router);
+
+        async fn access(service: &Service, query: &str) -> Response {
+            TestClient::get(format!("http://127.0.0.1:5801/?{query}"))
+                .send(service)
+                .await
+        }
+
+        assert_eq!(access(&service, "").await.take_string().await.unwrap(), "member true");
+        assert_eq!(
+            access(&service, "guest=true").await.take_string().await.unwrap(),
+            "member false"
+        );
+        assert_eq!(
+            access(&service, "deny=true").await.status_code,
+            Some(StatusCode::FORBIDDEN)
+        );
+    }
 }
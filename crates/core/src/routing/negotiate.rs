@@ -0,0 +1,152 @@
+use std::fmt::{self, Formatter};
+use std::sync::Arc;
+
+use crate::http::{Mime, Request, Response, StatusError};
+use crate::writing::accept_quality;
+use crate::{async_trait, Depot, FlowCtrl, Handler};
+
+/// Handler which picks a handler by request's `Accept` header.
+///
+/// The handler of media type with the highest quality value is called, as defined in
+/// [RFC 7231, Section 5.3](https://tools.ietf.org/html/rfc7231#section-5.3), the first added one wins if qualities
+/// are equal. `406 Not Acceptable` is responded if no media type is acceptable, and `Vary: Accept` is always added.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_core::routing::ContentNegotiate;
+///
+/// #[handler]
+/// async fn html() -> Text<&'static str> {
+///     Text::Html("<p>jobs</p>")
+/// }
+/// #[handler]
+/// async fn json() -> Json<&'static str> {
+///     Json("jobs")
+/// }
+///
+/// let router = Router::with_path("users").get(
+///     ContentNegotiate::new()
+///         .add(mime::APPLICATION_JSON, json)
+///         .add(mime::TEXT_HTML, html),
+/// );
+/// ```
+#[derive(Default)]
+pub struct ContentNegotiate {
+    handlers: Vec<(Mime, Arc<dyn Handler>)>,
+}
+
+impl fmt::Debug for ContentNegotiate {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.handlers.iter().map(|(media_type, _)| media_type))
+            .finish()
+    }
+}
+
+impl ContentNegotiate {
+    /// Create a new `ContentNegotiate` without any handler.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add handler for the media type.
+    #[inline]
+    pub fn add<H: Handler>(mut self, media_type: Mime, handler: H) -> Self {
+        self.handlers.push((media_type, Arc::new(handler)));
+        self
+    }
+
+    fn select(&self, req: &Request) -> Option<&Arc<dyn Handler>> {
+        let mut selected: Option<(f32, &Arc<dyn Handler>)> = None;
+        for (media_type, handler) in &self.handlers {
+            let q = accept_quality(req, media_type);
+            if q > 0.0 && selected.map(|(max, _)| q > max).unwrap_or(true) {
+                selected = Some((q, handler));
+            }
+        }
+        selected.map(|(_, handler)| handler)
+    }
+}
+
+#[async_trait]
+impl Handler for ContentNegotiate {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        res.vary(["Accept"]);
+        match self.select(req) {
+            Some(handler) => handler.handle(req, depot, res, ctrl).await,
+            None => res.render(StatusError::not_acceptable()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::header::{ACCEPT, VARY};
+    use crate::prelude::*;
+    use crate::routing::ContentNegotiate;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[handler]
+    async fn html() -> &'static str {
+        "html"
+    }
+    #[handler]
+    async fn json() -> &'static str {
+        "json"
+    }
+
+    async fn access(service: &Service, path: &str, accept: &str) -> Response {
+        TestClient::get(format!("http://127.0.0.1:5801/{path}"))
+            .add_header(ACCEPT, accept, true)
+            .send(service)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_content_negotiate() {
+        let router = Router::with_path("users").get(
+            ContentNegotiate::new()
+                .add(mime::APPLICATION_JSON, json)
+                .add(mime::TEXT_HTML, html),
+        );
+        let service = Service::new(router);
+
+        let mut res = access(&service, "users", "text/html, application/json;q=0.9").await;
+        assert_eq!(res.headers().get(VARY).unwrap(), "Accept");
+        assert_eq!(res.take_string().await.unwrap(), "html");
+        let mut res = access(&service, "users", "text/*;q=0.5, application/json").await;
+        assert_eq!(res.take_string().await.unwrap(), "json");
+        let mut res = access(&service, "users", "*/*").await;
+        assert_eq!(res.take_string().await.unwrap(), "json");
+        let mut res = access(&service, "users", "application/*;q=0.2, text/html;q=0.1").await;
+        assert_eq!(res.take_string().await.unwrap(), "json");
+        let res = access(&service, "users", "image/png, text/*;q=0.5, text/html;q=0").await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_ACCEPTABLE));
+    }
+
+    #[tokio::test]
+    async fn test_router_accept() {
+        let router = Router::with_path("users")
+            .accept(mime::TEXT_HTML, Router::new().get(html))
+            .accept(mime::APPLICATION_JSON, Router::new().get(json));
+        let service = Service::new(router);
+
+        let mut res = access(&service, "users", "text/html, application/json;q=0.9").await;
+        assert_eq!(res.take_string().await.unwrap(), "html");
+        let mut res = access(&service, "users", "application/json").await;
+        assert_eq!(res.take_string().await.unwrap(), "json");
+        let res = access(&service, "users", "image/png").await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        let service = Service::new(
+            Router::with_path("users")
+                .accept(mime::TEXT_HTML, Router::new().get(html))
+                .push(Router::new().get(json)),
+        );
+        let mut res = access(&service, "users", "image/png").await;
+        assert_eq!(res.take_string().await.unwrap(), "json");
+    }
+}
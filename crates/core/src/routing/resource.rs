@@ -0,0 +1,217 @@
+use std::fmt::{self, Formatter};
+use std::sync::Arc;
+
+use super::filters::MethodFilter;
+use super::Router;
+use crate::http::Method;
+use crate::Handler;
+
+/// Handlers of a REST resource, which are added to a router by [`Router::resource`].
+///
+/// | Handler   | Method           | Path        |
+/// |-----------|------------------|-------------|
+/// | `index`   | `GET`            | `/`         |
+/// | `create`  | `POST`           | `/`         |
+/// | `show`    | `GET`            | `/<id>`     |
+/// | `update`  | `PUT` or `PATCH` | `/<id>`     |
+/// | `delete`  | `DELETE`         | `/<id>`     |
+///
+/// If [`Service::method_not_allowed`](crate::Service::method_not_allowed) is enabled, requests to these paths with a
+/// method which has no handler are responded with `405 Method Not Allowed`, and the `Allow` header lists the supported
+/// methods.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_core::routing::Resource;
+///
+/// #[handler]
+/// async fn list_users() -> &'static str {
+///     "users"
+/// }
+/// #[handler]
+/// async fn show_user(req: &mut Request) -> String {
+///     format!("user {}", req.param::<u64>("id").unwrap_or_default())
+/// }
+///
+/// let router = Router::with_path("users").resource(Resource::new().index(list_users).show(show_user));
+/// ```
+#[non_exhaustive]
+pub struct Resource {
+    id_param: String,
+    index: Option<Arc<dyn Handler>>,
+    create: Option<Arc<dyn Handler>>,
+    show: Option<Arc<dyn Handler>>,
+    update: Option<Arc<dyn Handler>>,
+    delete: Option<Arc<dyn Handler>>,
+}
+
+impl Default for Resource {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Resource {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Resource")
+            .field("id_param", &self.id_param)
+            .field("index", &self.index.is_some())
+            .field("create", &self.create.is_some())
+            .field("show", &self.show.is_some())
+            .field("update", &self.update.is_some())
+            .field("delete", &self.delete.is_some())
+            .finish()
+    }
+}
+
+impl Resource {
+    /// Create a new `Resource` without any handler, member path param name is `id`.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            id_param: "id".into(),
+            index: None,
+            create: None,
+            show: None,
+            update: None,
+            delete: None,
+        }
+    }
+
+    /// Sets the param name of member path, default is `id`.
+    #[inline]
+    pub fn id_param(mut self, name: impl Into<String>) -> Self {
+        self.id_param = name.into();
+        self
+    }
+
+    /// Sets handler to list the resources, it handles `GET /`.
+    #[inline]
+    pub fn index<H: Handler>(mut self, handler: H) -> Self {
+        self.index = Some(Arc::new(handler));
+        self
+    }
+
+    /// Sets handler to create a resource, it handles `POST /`.
+    #[inline]
+    pub fn create<H: Handler>(mut self, handler: H) -> Self {
+        self.create = Some(Arc::new(handler));
+        self
+    }
+
+    /// Sets handler to show a resource, it handles `GET /<id>`.
+    #[inline]
+    pub fn show<H: Handler>(mut self, handler: H) -> Self {
+        self.show = Some(Arc::new(handler));
+        self
+    }
+
+    /// Sets handler to update a resource, it handles `PUT /<id>` and `PATCH /<id>`.
+    #[inline]
+    pub fn update<H: Handler>(mut self, handler: H) -> Self {
+        self.update = Some(Arc::new(handler));
+        self
+    }
+
+    /// Sets handler to delete a resource, it handles `DELETE /<id>`.
+    #[inline]
+    pub fn delete<H: Handler>(mut self, handler: H) -> Self {
+        self.delete = Some(Arc::new(handler));
+        self
+    }
+
+    pub(crate) fn attach(self, router: Router) -> Router {
+        let Resource {
+            id_param,
+            index,
+            create,
+            show,
+            update,
+            delete,
+        } = self;
+        let router = push_methods(router, [(Method::POST, create), (Method::GET, index)]);
+        let member = push_methods(
+            Router::with_path(format!("<{id_param}>")),
+            [
+                (Method::GET, show),
+                (Method::PUT, update.clone()),
+                (Method::PATCH, update),
+                (Method::DELETE, delete),
+            ],
+        );
+        if member.routers.is_empty() {
+            router
+        } else {
+            router.push(member)
+        }
+    }
+}
+
+/// Pushes a child router for every method which has a handler.
+fn push_methods<const N: usize>(mut router: Router, methods: [(Method, Option<Arc<dyn Handler>>); N]) -> Router {
+    for (method, handler) in methods {
+        if let Some(handler) = handler {
+            let mut child = Router::with_filter(MethodFilter(method));
+            child.goal = Some(handler);
+            router = router.push(child);
+        }
+    }
+    router
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::http::header::ALLOW;
+    use crate::prelude::*;
+    use crate::routing::Resource;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[handler]
+    async fn index() -> &'static str {
+        "index"
+    }
+    #[handler]
+    async fn show(req: &mut Request) -> String {
+        format!("show {}", req.param::<u64>("user_id").unwrap())
+    }
+    #[handler]
+    async fn update(req: &mut Request) -> String {
+        format!("{} {}", req.method(), req.param::<u64>("user_id").unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_resource() {
+        let router = Router::with_path("users").resource(
+            Resource::new()
+                .id_param("user_id")
+                .index(index)
+                .show(show)
+                .update(update),
+        );
+        let service = Service::new(router).method_not_allowed(true);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/users").send(&service).await;
+        assert_eq!(res.take_string().await.unwrap(), "index");
+        let mut res = TestClient::get("http://127.0.0.1:5801/users/7").send(&service).await;
+        assert_eq!(res.take_string().await.unwrap(), "show 7");
+        let mut res = TestClient::patch("http://127.0.0.1:5801/users/7").send(&service).await;
+        assert_eq!(res.take_string().await.unwrap(), "PATCH 7");
+        let res = TestClient::head("http://127.0.0.1:5801/users/7").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+
+        let res = TestClient::post("http://127.0.0.1:5801/users").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_eq!(res.headers().get(ALLOW).unwrap(), "GET, HEAD");
+        let res = TestClient::delete("http://127.0.0.1:5801/users/7").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_eq!(res.headers().get(ALLOW).unwrap(), "GET, HEAD, PUT, PATCH");
+
+        let res = TestClient::delete("http://127.0.0.1:5801/users/7/posts")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+    }
+}
@@ -3,9 +3,10 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use super::filters;
-use super::{Filter, FnFilter, PathFilter, PathState};
-use crate::handler::{Handler, WhenHoop};
+use super::{Filter, FnFilter, PathFilter, PathState, Resource};
+use crate::handler::{AfterHoop, Handler, WhenHoop};
 use crate::http::uri::Scheme;
+use crate::http::Mime;
 use crate::{Depot, Request};
 
 /// Router struct is used for route request to different handlers.
@@ -101,6 +102,8 @@ pub struct Router {
 pub struct DetectMatched {
     pub hoops: Vec<Arc<dyn Handler>>,
     pub goal: Arc<dyn Handler>,
+    /// Path patterns of the matched routers joined together, such as `/users/<id>`.
+    pub route: String,
 }
 
 impl Default for Router {
@@ -159,6 +162,13 @@ impl Router {
 
     /// Detect current router is matched for current request.
     pub fn detect(&self, req: &mut Request, path_state: &mut PathState) -> Option<DetectMatched> {
+        let missed = path_state.missed_methods.len();
+        let matched = self.detect_branch(req, path_state);
+        path_state.missed_methods.truncate(missed);
+        matched
+    }
+
+    fn detect_branch(&self, req: &mut Request, path_state: &mut PathState) -> Option<DetectMatched> {
         for filter in &self.filters {
             if !filter.filter(req, path_state) {
                 return None;
@@ -171,6 +181,7 @@ impl Router {
                     return Some(DetectMatched {
                         hoops: [&self.hoops[..], &dm.hoops[..]].concat(),
                         goal: dm.goal.clone(),
+                        route: self.route(Some(&dm.route)),
                     });
                 } else {
                     path_state.cursor = original_cursor;
@@ -179,15 +190,45 @@ impl Router {
         }
         if let Some(goal) = self.goal.clone() {
             if path_state.is_ended() {
+                if let Some(method) = path_state.missed_methods.first() {
+                    // Everything but the method is matched.
+                    if path_state.missed_methods.iter().all(|m| m == method)
+                        && !path_state.allowed_methods.contains(method)
+                    {
+                        let method = method.clone();
+                        path_state.allowed_methods.push(method);
+                    }
+                    return None;
+                }
                 return Some(DetectMatched {
                     hoops: self.hoops.clone(),
                     goal,
+                    route: self.route(None),
                 });
             }
         }
         None
     }
 
+    /// Joins path patterns of current router with the route of matched child.
+    fn route(&self, child: Option<&str>) -> String {
+        let mut route = String::new();
+        for pattern in self.filters.iter().filter_map(|filter| filter.path_pattern()) {
+            let pattern = pattern.trim_matches('/');
+            if !pattern.is_empty() {
+                route.push('/');
+                route.push_str(pattern);
+            }
+        }
+        if let Some(child) = child.filter(|child| *child != "/") {
+            route.push_str(child);
+        }
+        if route.is_empty() {
+            route.push('/');
+        }
+        route
+    }
+
     /// Insert a router at the begining of current router, shifting all routers after it to the right.
     #[inline]
     pub fn unshift(mut self, router: Router) -> Self {
@@ -221,6 +262,43 @@ impl Router {
         Router::new().hoop(hoop)
     }
 
+    /// Add a handler which runs before other handlers of current router, it is an alias of [`Router::hoop`].
+    #[inline]
+    pub fn before<H: Handler>(self, hoop: H) -> Self {
+        self.hoop(hoop)
+    }
+
+    /// Add a handler which runs after all other handlers of current router or it's descendants, like a `finally`
+    /// block.
+    ///
+    /// It runs regardless of the outcome, even when other handlers write an error or skip the rest handlers, and
+    /// can read the final [`Response`](crate::http::Response). If a handler panics, it runs with a
+    /// `500 Internal Server Error` response and then the panic continues. Hooks added later run later.
+    ///
+    /// The status code is still `None` if no handler set it, [`Service`](crate::Service) sends it as `200 OK`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn audit(req: &mut Request, res: &mut Response) {
+    ///     tracing::info!(path = req.uri().path(), status = ?res.status_code, "audit");
+    /// }
+    /// #[handler]
+    /// async fn hello() -> &'static str {
+    ///     "hello"
+    /// }
+    ///
+    /// let router = Router::new().after(audit).get(hello);
+    /// ```
+    #[inline]
+    pub fn after<H: Handler>(mut self, hook: H) -> Self {
+        self.hoops.insert(0, Arc::new(AfterHoop { inner: hook }));
+        self
+    }
+
     /// Add a handler as middleware, it will run the handler in current router or it's descendants
     /// handle the request. This middleware only effective when the filter return true.
     #[inline]
@@ -234,6 +312,8 @@ impl Router {
 
     /// Add a handler as middleware, it will run the handler in current router or it's descendants
     /// handle the request.
+    ///
+    /// Hoops run in the order they are added, hoops of parent routers run before hoops of child routers.
     #[inline]
     pub fn hoop<H: Handler>(mut self, hoop: H) -> Self {
         self.hoops.push(Arc::new(hoop));
@@ -242,6 +322,28 @@ impl Router {
 
     /// Add a handler as middleware, it will run the handler in current router or it's descendants
     /// handle the request. This middleware only effective when the filter return true.
+    ///
+    /// The filter is called at request time, when the hoop's turn comes. Conditional hoops keep their position
+    /// among unconditional hoops in the order they are added. If the filter returns `false`, the hoop is bypassed
+    /// and [`FlowCtrl`](crate::FlowCtrl) proceeds to the next handler.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::http::header::AUTHORIZATION;
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn auth(req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl) {
+    ///     if req.header::<String>(AUTHORIZATION).as_deref() != Some("Bearer secret") {
+    ///         res.render(StatusError::unauthorized());
+    ///         ctrl.skip_rest();
+    ///     }
+    /// }
+    ///
+    /// // Authorization header is only checked when it is present.
+    /// let router = Router::new().hoop_when(auth, |req, _| req.headers().contains_key(AUTHORIZATION));
+    /// ```
     #[inline]
     pub fn hoop_when<H, F>(mut self, hoop: H, filter: F) -> Self
     where
@@ -397,6 +499,46 @@ impl Router {
     pub fn options<H: Handler>(self, goal: H) -> Self {
         self.push(Router::with_filter(filters::options()).goal(goal))
     }
+
+    /// Add routes of a REST [`Resource`] as children of current router.
+    ///
+    /// Collection routes are matched by current router's path and member routes are matched by `<id>` under it.
+    #[inline]
+    pub fn resource(self, resource: Resource) -> Self {
+        resource.attach(self)
+    }
+
+    /// Add `router` as a child router which only matches requests accepting `media_type` by [`AcceptFilter`].
+    ///
+    /// Child routers are matched in order, so when several media types are acceptable the first added one is used,
+    /// and a child router pushed after them without accept filter is the fallback. Use [`ContentNegotiate`] to pick
+    /// the handler by quality values of `Accept` header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// #[handler]
+    /// async fn html() -> Text<&'static str> {
+    ///     Text::Html("<p>jobs</p>")
+    /// }
+    /// #[handler]
+    /// async fn json() -> Json<&'static str> {
+    ///     Json("jobs")
+    /// }
+    ///
+    /// let router = Router::with_path("users")
+    ///     .accept(mime::TEXT_HTML, Router::new().get(html))
+    ///     .push(Router::new().get(json));
+    /// ```
+    ///
+    /// [`AcceptFilter`]: super::filters::AcceptFilter
+    /// [`ContentNegotiate`]: super::ContentNegotiate
+    #[inline]
+    pub fn accept(self, media_type: Mime, router: Router) -> Self {
+        self.push(router.filter(filters::accept(media_type)))
+    }
 }
 
 const SYMBOL_DOWN: &str = "│";
@@ -467,6 +609,133 @@ mod tests {
 
     #[handler]
     async fn fake_handler(_res: &mut Response) {}
+    #[tokio::test]
+    async fn test_router_before_after() {
+        use std::sync::Mutex;
+
+        use futures_util::FutureExt;
+
+        use crate::prelude::*;
+        use crate::test::ResponseExt;
+
+        static EVENTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        #[handler]
+        async fn open() {
+            EVENTS.lock().unwrap().push("open".into());
+        }
+        #[handler]
+        async fn close(res: &mut Response) {
+            EVENTS
+                .lock()
+                .unwrap()
+                .push(format!("close {}", res.status_code.unwrap_or(StatusCode::OK).as_u16()));
+        }
+        #[handler]
+        async fn audit() {
+            EVENTS.lock().unwrap().push("audit".into());
+        }
+        #[handler]
+        async fn auth(res: &mut Response, ctrl: &mut FlowCtrl) {
+            res.render(StatusError::forbidden());
+            ctrl.skip_rest();
+        }
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+        #[handler]
+        async fn crash() {
+            panic!("crash");
+        }
+
+        let router = Router::new()
+            .before(open)
+            .after(close)
+            .after(audit)
+            .push(Router::with_path("hello").get(hello))
+            .push(Router::with_path("admin").hoop(auth).get(hello))
+            .push(Router::with_path("crash").get(crash));
+        let service = Service::new(router);
+
+        let content = TestClient::get("http://127.0.0.1:5801/hello")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "hello");
+        assert_eq!(*EVENTS.lock().unwrap(), ["open", "close 200", "audit"]);
+
+        EVENTS.lock().unwrap().clear();
+        let res = TestClient::get("http://127.0.0.1:5801/admin").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+        assert_eq!(*EVENTS.lock().unwrap(), ["open", "close 403", "audit"]);
+
+        EVENTS.lock().unwrap().clear();
+        let result = std::panic::AssertUnwindSafe(TestClient::get("http://127.0.0.1:5801/crash").send(&service))
+            .catch_unwind()
+            .await;
+        assert!(result.is_err());
+        assert_eq!(*EVENTS.lock().unwrap(), ["open", "close 500", "audit"]);
+    }
+
+    #[tokio::test]
+    async fn test_router_hoop_when() {
+        use crate::http::header::AUTHORIZATION;
+        use crate::prelude::*;
+        use crate::test::ResponseExt;
+
+        #[handler]
+        async fn first(res: &mut Response) {
+            res.render("first ");
+        }
+        #[handler]
+        async fn auth(req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl) {
+            if req.header::<String>(AUTHORIZATION).as_deref() == Some("Bearer secret") {
+                res.render("auth ");
+            } else {
+                res.render(StatusError::unauthorized());
+                ctrl.skip_rest();
+            }
+        }
+        #[handler]
+        async fn last(res: &mut Response) {
+            res.render("last ");
+        }
+        #[handler]
+        async fn hello(res: &mut Response) {
+            res.render("hello");
+        }
+
+        let router = Router::new()
+            .hoop(first)
+            .hoop_when(auth, |req, _| req.headers().contains_key(AUTHORIZATION))
+            .hoop(last)
+            .get(hello);
+        let service = Service::new(router);
+
+        let content = TestClient::get("http://127.0.0.1:5801")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "first last hello");
+        let content = TestClient::get("http://127.0.0.1:5801")
+            .add_header(AUTHORIZATION, "Bearer secret", true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "first auth last hello");
+        let res = TestClient::get("http://127.0.0.1:5801")
+            .add_header(AUTHORIZATION, "Bearer wrong", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+    }
+
     #[test]
     fn test_router_debug() {
         let router = Router::default()
@@ -509,6 +778,29 @@ mod tests {
         );
     }
     #[test]
+    fn test_router_detect_route() {
+        let router = Router::default().push(
+            Router::with_path("users")
+                .push(Router::with_path("<id>").push(Router::with_path("emails").get(fake_handler)))
+                .push(Router::with_path("/<id>/articles/<aid>/").get(fake_handler)),
+        );
+        let mut req = TestClient::get("http://local.host/users/12/emails").build();
+        let mut path_state = PathState::new(req.uri().path());
+        let matched = router.detect(&mut req, &mut path_state).unwrap();
+        assert_eq!(matched.route, "/users/<id>/emails");
+
+        let mut req = TestClient::get("http://local.host/users/12/articles/3").build();
+        let mut path_state = PathState::new(req.uri().path());
+        let matched = router.detect(&mut req, &mut path_state).unwrap();
+        assert_eq!(matched.route, "/users/<id>/articles/<aid>");
+
+        let router = Router::new().get(fake_handler);
+        let mut req = TestClient::get("http://local.host/").build();
+        let mut path_state = PathState::new(req.uri().path());
+        let matched = router.detect(&mut req, &mut path_state).unwrap();
+        assert_eq!(matched.route, "/");
+    }
+    #[test]
     fn test_router_detect1() {
         let router = Router::default().push(
             Router::with_path("users")
@@ -7,7 +7,9 @@ use serde::de::{
 };
 use serde::forward_to_deserialize_any;
 
+mod nested;
 mod request;
+pub use nested::{from_str_nested_form_multi_map, from_str_nested_multi_map};
 pub use request::from_request;
 
 #[inline]
@@ -0,0 +1,579 @@
+use std::borrow::Cow;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use indexmap::IndexMap;
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error as DeError, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use super::{CowValue, MapDeserializer, SeqDeserializer, ValError, VecValue};
+
+/// Parse multi map as type `T`, keys like `user[name]` will be parsed as nested maps.
+///
+/// Keys like `ids[]` are treated as `ids`, and keys like `users[0][name]` will be parsed as sequence if `T` wants
+/// a sequence. If a single value is wanted but the key has multiple values, an error is returned.
+///
+/// Dots in keys are kept as is, use [`from_str_nested_form_multi_map`] for form fields.
+#[inline]
+pub fn from_str_nested_multi_map<'de, I, T, K, C, V>(input: I) -> Result<T, ValError>
+where
+    I: IntoIterator<Item = (K, C)> + 'de,
+    T: Deserialize<'de>,
+    K: Into<Cow<'de, str>> + Hash + std::cmp::Eq + 'de,
+    C: IntoIterator<Item = V> + 'de,
+    V: Into<Cow<'de, str>> + std::cmp::Eq + 'de,
+{
+    from_nested_multi_map(input, false)
+}
+
+/// Parse form fields as type `T`, works like [`from_str_nested_multi_map`], in addition:
+///
+/// - Keys like `address.city` and `items[0].qty` are also parsed as nested maps.
+/// - Booleans accept values of HTML checkboxes, such as `on`, `off`, `yes` and `no`.
+#[inline]
+pub fn from_str_nested_form_multi_map<'de, I, T, K, C, V>(input: I) -> Result<T, ValError>
+where
+    I: IntoIterator<Item = (K, C)> + 'de,
+    T: Deserialize<'de>,
+    K: Into<Cow<'de, str>> + Hash + std::cmp::Eq + 'de,
+    C: IntoIterator<Item = V> + 'de,
+    V: Into<Cow<'de, str>> + std::cmp::Eq + 'de,
+{
+    from_nested_multi_map(input, true)
+}
+
+fn from_nested_multi_map<'de, I, T, K, C, V>(input: I, form: bool) -> Result<T, ValError>
+where
+    I: IntoIterator<Item = (K, C)> + 'de,
+    T: Deserialize<'de>,
+    K: Into<Cow<'de, str>> + Hash + std::cmp::Eq + 'de,
+    C: IntoIterator<Item = V> + 'de,
+    V: Into<Cow<'de, str>> + std::cmp::Eq + 'de,
+{
+    from_nested_multi_map_seed(input, form, PhantomData::<T>)
+}
+
+/// Parse multi map by `seed`, keys are parsed like [`from_str_nested_multi_map`], and also like
+/// [`from_str_nested_form_multi_map`] if `form` is `true`.
+pub(crate) fn from_nested_multi_map_seed<'de, I, K, C, V, S>(
+    input: I,
+    form: bool,
+    seed: S,
+) -> Result<S::Value, ValError>
+where
+    I: IntoIterator<Item = (K, C)> + 'de,
+    K: Into<Cow<'de, str>> + Hash + std::cmp::Eq + 'de,
+    C: IntoIterator<Item = V> + 'de,
+    V: Into<Cow<'de, str>> + std::cmp::Eq + 'de,
+    S: DeserializeSeed<'de>,
+{
+    let mut root = IndexMap::new();
+    for (key, values) in input {
+        let key = key.into();
+        let mut path = split_key(key.clone(), form);
+        if path.len() > 1 && path.last().map(|s| s.is_empty()).unwrap_or_default() {
+            path.pop();
+        }
+        insert_values(&mut root, key, path, values.into_iter().map(Into::into), form)?;
+    }
+    seed.deserialize(NestedValue::Map(root))
+}
+
+/// Split key `a[b][c]` to path `["a", "b", "c"]`.
+///
+/// If `dotted` is `true`, `a.b.c` is also split, both notations can be mixed, such as `items[0].qty`.
+/// Malformed keys are treated as plain keys.
+fn split_key(key: Cow<'_, str>, dotted: bool) -> Vec<Cow<'_, str>> {
+    let seps: &[char] = if dotted { &['[', '.'] } else { &['['] };
+    let segments = |key: &str| -> Option<Vec<(usize, usize)>> {
+        let start = key.find(seps)?;
+        if start == 0 {
+            return None;
+        }
+        let mut ranges = vec![(0, start)];
+        let mut rest = start;
+        while rest < key.len() {
+            match key.as_bytes()[rest] {
+                b'[' => {
+                    let end = rest + key[rest..].find(']')?;
+                    if key[rest + 1..end].contains('[') {
+                        return None;
+                    }
+                    ranges.push((rest + 1, end));
+                    rest = end + 1;
+                }
+                b'.' if dotted => {
+                    let start = rest + 1;
+                    let end = key[start..].find(seps).map_or(key.len(), |i| start + i);
+                    if end == start {
+                        return None;
+                    }
+                    ranges.push((start, end));
+                    rest = end;
+                }
+                _ => return None,
+            }
+        }
+        Some(ranges)
+    };
+    let Some(ranges) = segments(&key) else {
+        return vec![key];
+    };
+    match key {
+        Cow::Borrowed(key) => ranges.into_iter().map(|(s, e)| Cow::Borrowed(&key[s..e])).collect(),
+        Cow::Owned(key) => ranges
+            .into_iter()
+            .map(|(s, e)| Cow::Owned(key[s..e].to_owned()))
+            .collect(),
+    }
+}
+
+/// Parse boolean value of HTML checkbox.
+fn parse_checkbox(value: &str) -> Result<bool, ValError> {
+    if ["true", "on", "1", "yes"].iter().any(|v| value.eq_ignore_ascii_case(v)) {
+        Ok(true)
+    } else if ["false", "off", "0", "no"]
+        .iter()
+        .any(|v| value.eq_ignore_ascii_case(v))
+    {
+        Ok(false)
+    } else {
+        Err(DeError::custom(format!("invalid boolean value `{value}`")))
+    }
+}
+
+fn insert_values<'de>(
+    map: &mut IndexMap<Cow<'de, str>, NestedValue<'de>>,
+    key: Cow<'de, str>,
+    path: Vec<Cow<'de, str>>,
+    values: impl Iterator<Item = Cow<'de, str>>,
+    form: bool,
+) -> Result<(), ValError> {
+    let conflict = || DeError::custom(format!("conflicting key `{key}`"));
+    let mut path = path.into_iter().peekable();
+    let mut map = map;
+    while let Some(segment) = path.next() {
+        if path.peek().is_none() {
+            let leaf = map.entry(segment).or_insert_with(|| NestedValue::Leaf {
+                key: key.clone(),
+                values: vec![],
+                form,
+            });
+            match leaf {
+                NestedValue::Leaf { values: leaf, .. } => leaf.extend(values),
+                NestedValue::Map(_) => return Err(conflict()),
+            }
+            return Ok(());
+        }
+        match map.entry(segment).or_insert_with(|| NestedValue::Map(IndexMap::new())) {
+            NestedValue::Map(child) => map = child,
+            NestedValue::Leaf { .. } => return Err(conflict()),
+        }
+    }
+    Ok(())
+}
+
+/// Add the key path to error of leaf value, so that users can know which field is invalid.
+fn invalid_value(key: &str, e: ValError) -> ValError {
+    DeError::custom(format!("invalid value for key `{key}`: {e}"))
+}
+
+#[derive(Debug)]
+enum NestedValue<'de> {
+    Leaf {
+        // The full key in input, it is used in error messages.
+        key: Cow<'de, str>,
+        values: Vec<Cow<'de, str>>,
+        // Whether the value comes from form fields.
+        form: bool,
+    },
+    Map(IndexMap<Cow<'de, str>, NestedValue<'de>>),
+}
+
+impl<'de> NestedValue<'de> {
+    #[inline]
+    fn single(self) -> Result<Self, ValError> {
+        match &self {
+            Self::Leaf { key, values, .. } if values.len() > 1 => Err(DeError::custom(format!(
+                "expected a single value for key `{key}`, found {} values",
+                values.len()
+            ))),
+            _ => Ok(self),
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValError> for NestedValue<'de> {
+    type Deserializer = Self;
+
+    #[inline]
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+macro_rules! forward_nested_value {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                match self.single()? {
+                    Self::Leaf { key, values, .. } => VecValue(values.into_iter().map(CowValue))
+                        .$method($($arg,)* visitor)
+                        .map_err(|e| invalid_value(&key, e)),
+                    Self::Map(map) => {
+                        MapDeserializer::new(map.into_iter().map(|(k, v)| (CowValue(k), v))).$method($($arg,)* visitor)
+                    }
+                }
+            }
+        )*
+    }
+}
+
+impl<'de> Deserializer<'de> for NestedValue<'de> {
+    type Error = ValError;
+
+    #[inline]
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Self::Leaf { values, .. } if values.len() > 1 => {
+                visitor.visit_seq(SeqDeserializer::new(values.into_iter().map(CowValue)))
+            }
+            Self::Leaf { values, .. } => VecValue(values.into_iter().map(CowValue)).deserialize_any(visitor),
+            Self::Map(map) => visitor.visit_map(MapDeserializer::new(map.into_iter().map(|(k, v)| (CowValue(k), v)))),
+        }
+    }
+
+    #[inline]
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Self::Leaf { key, values, .. } => VecValue(values.into_iter().map(CowValue))
+                .deserialize_seq(visitor)
+                .map_err(|e| invalid_value(&key, e)),
+            Self::Map(map) => {
+                // Keys like `users[0][name]`, sort items by index.
+                let mut items = map
+                    .into_iter()
+                    .map(|(k, v)| {
+                        k.parse::<usize>()
+                            .map(|idx| (idx, v))
+                            .map_err(|_| DeError::custom(format!("expected sequence index, found `{k}`")))
+                    })
+                    .collect::<Result<Vec<_>, ValError>>()?;
+                items.sort_by_key(|(idx, _)| *idx);
+                visitor.visit_seq(SeqDeserializer::new(items.into_iter().map(|(_, v)| v)))
+            }
+        }
+    }
+
+    #[inline]
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    #[inline]
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.single()? {
+            Self::Leaf {
+                key,
+                values,
+                form: true,
+            } => match values.first() {
+                Some(value) => visitor.visit_bool(parse_checkbox(value).map_err(|e| invalid_value(&key, e))?),
+                None => Err(DeError::custom("expected vec not empty")),
+            },
+            Self::Leaf { key, values, .. } => VecValue(values.into_iter().map(CowValue))
+                .deserialize_bool(visitor)
+                .map_err(|e| invalid_value(&key, e)),
+            Self::Map(map) => {
+                MapDeserializer::new(map.into_iter().map(|(k, v)| (CowValue(k), v))).deserialize_bool(visitor)
+            }
+        }
+    }
+
+    forward_nested_value! {
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_unit(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+    }
+
+    forward_to_deserialize_any! {
+        struct
+        ignored_any
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use multimap::MultiMap;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn test_split_key() {
+        assert_eq!(split_key("a".into(), false), vec!["a"]);
+        assert_eq!(split_key("a[b][c]".into(), false), vec!["a", "b", "c"]);
+        assert_eq!(split_key("[a]".into(), false), vec!["[a]"]);
+        assert_eq!(split_key("a[b".into(), false), vec!["a[b"]);
+        assert_eq!(split_key("a[b]c]".into(), false), vec!["a[b]c]"]);
+        assert_eq!(split_key("a[b[c]]".into(), false), vec!["a[b[c]]"]);
+        assert_eq!(split_key("a[]".into(), false), vec!["a", ""]);
+        assert_eq!(split_key("a.b.c".into(), false), vec!["a.b.c"]);
+        assert_eq!(split_key("a.b[c]".into(), false), vec!["a.b", "c"]);
+        assert_eq!(split_key("a[b].c".into(), false), vec!["a[b].c"]);
+
+        assert_eq!(split_key("a[b][c]".into(), true), vec!["a", "b", "c"]);
+        assert_eq!(split_key("a.b.c".into(), true), vec!["a", "b", "c"]);
+        assert_eq!(split_key("items[0].qty".into(), true), vec!["items", "0", "qty"]);
+        assert_eq!(split_key("a.b[c]".into(), true), vec!["a", "b", "c"]);
+        assert_eq!(split_key("a.".into(), true), vec!["a."]);
+        assert_eq!(split_key(".a".into(), true), vec![".a"]);
+        assert_eq!(split_key("a..b".into(), true), vec!["a..b"]);
+    }
+
+    #[test]
+    fn test_de_nested_multi_map() {
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Filter {
+            name: String,
+            age: Option<u8>,
+        }
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Search<'a> {
+            q: &'a str,
+            filter: Filter,
+            tags: Vec<String>,
+            extra: HashMap<String, i64>,
+        }
+
+        let mut map = MultiMap::new();
+        map.insert("q", "rust");
+        map.insert("filter[name]", "salvo");
+        map.insert("filter[age]", "3");
+        map.insert("tags", "web");
+        map.insert("tags", "http");
+        map.insert("extra[a]", "1");
+        map.insert("extra[b]", "2");
+        let search: Search = from_str_nested_multi_map(map).unwrap();
+        assert_eq!(search.q, "rust");
+        assert_eq!(
+            search.filter,
+            Filter {
+                name: "salvo".into(),
+                age: Some(3)
+            }
+        );
+        assert_eq!(search.tags, vec!["web", "http"]);
+        assert_eq!(search.extra.get("b"), Some(&2));
+    }
+
+    #[test]
+    fn test_de_nested_multi_map_conflict() {
+        let mut map = MultiMap::new();
+        map.insert("a", "1");
+        map.insert("a[b]", "2");
+        let err = from_str_nested_multi_map::<_, HashMap<String, String>, _, _, _>(map).unwrap_err();
+        assert!(err.to_string().starts_with("conflicting key"));
+    }
+
+    #[test]
+    fn test_de_nested_multi_map_seq() {
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct User {
+            name: String,
+        }
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Data {
+            ids: Vec<i64>,
+            users: Vec<User>,
+        }
+
+        let mut map = MultiMap::new();
+        map.insert("ids[]", "1");
+        map.insert("ids[]", "2");
+        map.insert("users[1][name]", "lily");
+        map.insert("users[0][name]", "jobs");
+        let data: Data = from_str_nested_multi_map(map).unwrap();
+        assert_eq!(data.ids, vec![1, 2]);
+        assert_eq!(
+            data.users,
+            vec![User { name: "jobs".into() }, User { name: "lily".into() }]
+        );
+    }
+
+    #[test]
+    fn test_de_nested_multi_map_single() {
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Data {
+            name: String,
+        }
+
+        let mut map = MultiMap::new();
+        map.insert("name", "jobs");
+        map.insert("name", "lily");
+        let err = from_str_nested_multi_map::<_, Data, _, _, _>(map).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected a single value for key `name`, found 2 values"
+        );
+
+        let mut map = MultiMap::new();
+        map.insert("tags", "a");
+        map.insert("tags", "b");
+        map.insert("page", "1");
+        let data: HashMap<String, serde_json::Value> = from_str_nested_multi_map(map).unwrap();
+        assert_eq!(data["tags"], serde_json::json!(["a", "b"]));
+        assert_eq!(data["page"], serde_json::json!("1"));
+    }
+
+    #[test]
+    fn test_de_nested_multi_map_dotted() {
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Address {
+            city: String,
+            zip: Option<String>,
+        }
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Item {
+            qty: u32,
+        }
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Order {
+            address: Address,
+            items: Vec<Item>,
+            gift: bool,
+            express: Option<bool>,
+            note: Option<String>,
+        }
+
+        let mut map = MultiMap::new();
+        map.insert("address.city", "Paris");
+        map.insert("items[0].qty", "2");
+        map.insert("items[1].qty", "5");
+        map.insert("gift", "on");
+        map.insert("express", "0");
+        let order: Order = from_str_nested_form_multi_map(map).unwrap();
+        assert_eq!(
+            order,
+            Order {
+                address: Address {
+                    city: "Paris".into(),
+                    zip: None,
+                },
+                items: vec![Item { qty: 2 }, Item { qty: 5 }],
+                gift: true,
+                express: Some(false),
+                note: None,
+            }
+        );
+
+        let mut map = MultiMap::new();
+        map.insert("address.city", "Paris");
+        map.insert("items[0].qty", "two");
+        map.insert("gift", "on");
+        let err = from_str_nested_form_multi_map::<_, Order, _, _, _>(map).unwrap_err();
+        assert!(err.to_string().starts_with("invalid value for key `items[0].qty`"));
+
+        let mut map = MultiMap::new();
+        map.insert("address.city", "Paris");
+        map.insert("gift", "maybe");
+        let err = from_str_nested_form_multi_map::<_, Order, _, _, _>(map).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value for key `gift`: invalid boolean value `maybe`"
+        );
+    }
+
+    #[test]
+    fn test_de_nested_multi_map_literal() {
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Data {
+            #[serde(rename = "a.b")]
+            ab: String,
+            flag: Option<bool>,
+        }
+
+        let mut map = MultiMap::new();
+        map.insert("utm.source", "x");
+        map.insert("filter[name]", "salvo");
+        let data: HashMap<String, serde_json::Value> = from_str_nested_multi_map(map).unwrap();
+        assert_eq!(data["utm.source"], serde_json::json!("x"));
+        assert_eq!(data["filter"], serde_json::json!({"name": "salvo"}));
+
+        let mut map = MultiMap::new();
+        map.insert("a.b", "1");
+        map.insert("flag", "true");
+        let data: Data = from_str_nested_multi_map(map).unwrap();
+        assert_eq!(
+            data,
+            Data {
+                ab: "1".into(),
+                flag: Some(true)
+            }
+        );
+
+        let mut map = MultiMap::new();
+        map.insert("a.b", "1");
+        map.insert("flag", "on");
+        assert!(from_str_nested_multi_map::<_, Data, _, _, _>(map).is_err());
+    }
+}
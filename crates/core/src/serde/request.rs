@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt::Display;
 
 use indexmap::IndexMap;
 use multimap::MultiMap;
@@ -15,6 +16,7 @@ use crate::http::header::HeaderMap;
 use crate::http::ParseError;
 use crate::Request;
 
+use super::nested::from_nested_multi_map_seed;
 use super::{CowValue, VecValue};
 
 pub async fn from_request<'de, T>(req: &'de mut Request, metadata: &'de Metadata) -> Result<T, ParseError>
@@ -67,9 +69,11 @@ pub(crate) struct RequestDeserializer<'de> {
     cookies: &'de cookie::CookieJar,
     headers: &'de HeaderMap,
     payload: Option<Payload<'de>>,
+    json_body: Option<&'de str>,
     metadata: &'de Metadata,
     field_index: isize,
     field_flatten: bool,
+    field_whole_body: bool,
     field_source: Option<&'de Source>,
     field_str_value: Option<&'de str>,
     field_vec_value: Option<Vec<CowValue<'de>>>,
@@ -79,6 +83,7 @@ impl<'de> RequestDeserializer<'de> {
     /// Construct a new `RequestDeserializer<I, E>`.
     pub(crate) fn new(request: &'de Request, metadata: &'de Metadata) -> Result<RequestDeserializer<'de>, ParseError> {
         let mut payload = None;
+        let mut json_body = None;
 
         if metadata.has_body_required() {
             if let Some(ctype) = request.content_type() {
@@ -89,12 +94,14 @@ impl<'de> RequestDeserializer<'de> {
                     mime::JSON => {
                         if let Some(data) = request.payload.get() {
                             if !data.is_empty() {
+                                let data = std::str::from_utf8(data)?;
+                                json_body = Some(data);
                                 // https://github.com/serde-rs/json/issues/903
-                                payload = match serde_json::from_slice::<HashMap<&str, &RawValue>>(data) {
+                                payload = match serde_json::from_str::<HashMap<&str, &RawValue>>(data) {
                                     Ok(map) => Some(Payload::JsonMap(map)),
                                     Err(e) => {
                                         tracing::warn!(error = ?e, "`RequestDeserializer` serde parse json payload failed");
-                                        Some(Payload::JsonStr(std::str::from_utf8(data)?))
+                                        Some(Payload::JsonStr(data))
                                     }
                                 };
                             }
@@ -111,9 +118,11 @@ impl<'de> RequestDeserializer<'de> {
             #[cfg(feature = "cookie")]
             cookies: request.cookies(),
             payload,
+            json_body,
             metadata,
             field_index: -1,
             field_flatten: false,
+            field_whole_body: false,
             field_source: None,
             field_str_value: None,
             field_vec_value: None,
@@ -158,18 +167,37 @@ impl<'de> RequestDeserializer<'de> {
                 #[cfg(feature = "cookie")]
                 cookies: self.cookies,
                 payload: self.payload.clone(),
+                json_body: self.json_body,
                 metadata,
                 field_index: -1,
                 field_flatten: false,
+                field_whole_body: false,
                 field_source: None,
                 field_str_value: None,
                 field_vec_value: None,
             })
+        } else if self.field_whole_body {
+            let field = &self.metadata.fields[self.field_index as usize];
+            let field_error =
+                |e: &dyn Display| ValError::custom(format!("field `{}` from body: {}", field.decl_name, e));
+            match (&self.payload, self.json_body) {
+                (Some(Payload::FormData(form_data)), _) => {
+                    from_nested_multi_map_seed(form_data.fields.iter_all(), true, seed).map_err(|e| field_error(&e))
+                }
+                (_, Some(value)) => {
+                    let mut value = serde_json::Deserializer::new(serde_json::de::StrRead::new(value));
+                    seed.deserialize(&mut value).map_err(|e| field_error(&e))
+                }
+                _ => Err(field_error(&"parse value error")),
+            }
         } else {
             let source = self
                 .field_source
                 .take()
                 .expect("`MapAccess::next_value` called before next_key");
+            let field = &self.metadata.fields[self.field_index as usize];
+            let field_error =
+                |e: &dyn Display| ValError::custom(format!("field `{}` from {}: {}", field.decl_name, source.from, e));
 
             let parser = self.real_parser(source);
             if source.from == SourceFrom::Body && parser == SourceParser::Json {
@@ -179,14 +207,14 @@ impl<'de> RequestDeserializer<'de> {
                     .expect("MapAccess::next_value called before next_key");
                 let mut value = serde_json::Deserializer::new(serde_json::de::StrRead::new(value));
 
-                seed.deserialize(&mut value)
-                    .map_err(|_| ValError::custom("parse value error"))
+                seed.deserialize(&mut value).map_err(|e| field_error(&e))
             } else if let Some(value) = self.field_str_value.take() {
-                seed.deserialize(CowValue(value.into()))
+                seed.deserialize(CowValue(value.into())).map_err(|e| field_error(&e))
             } else if let Some(value) = self.field_vec_value.take() {
                 seed.deserialize(VecValue(value.into_iter()))
+                    .map_err(|e| field_error(&e))
             } else {
-                Err(ValError::custom("parse value error"))
+                Err(field_error(&"parse value error"))
             }
         }
     }
@@ -197,6 +225,10 @@ impl<'de> RequestDeserializer<'de> {
             self.field_flatten = true;
             return true;
         }
+        if field.whole_body {
+            self.field_whole_body = true;
+            return self.payload.is_some();
+        }
         let sources = if !field.sources.is_empty() {
             &field.sources
         } else if !self.metadata.default_sources.is_empty() {
@@ -377,6 +409,7 @@ impl<'de> RequestDeserializer<'de> {
             self.field_index += 1;
             let field = &self.metadata.fields[self.field_index as usize];
             self.field_flatten = field.flatten;
+            self.field_whole_body = false;
             self.field_str_value = None;
             self.field_vec_value = None;
 
@@ -703,6 +736,61 @@ mod tests {
             }
         );
     }
+    #[tokio::test]
+    async fn test_de_request_with_source_attrs() {
+        #[derive(Deserialize, Eq, PartialEq, Debug)]
+        struct Dto {
+            title: String,
+            tags: Vec<String>,
+        }
+        #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
+        struct Params {
+            #[salvo(extract(param))]
+            id: i64,
+            #[salvo(extract(query))]
+            page: u32,
+            #[salvo(extract(header))]
+            authorization: String,
+            #[salvo(extract(body))]
+            payload: Dto,
+        }
+        let mut req = TestClient::post("http://127.0.0.1:5800/posts/7")
+            .query("page", "2")
+            .add_header("authorization", "Bearer token", true)
+            .json(&serde_json::json!({"title": "hello", "tags": ["rust"]}))
+            .build();
+        req.params.insert("id".into(), "7".into());
+        let data: Params = req.extract().await.unwrap();
+        assert_eq!(
+            data,
+            Params {
+                id: 7,
+                page: 2,
+                authorization: "Bearer token".into(),
+                payload: Dto {
+                    title: "hello".into(),
+                    tags: vec!["rust".into()]
+                }
+            }
+        );
+
+        let mut req = TestClient::post("http://127.0.0.1:5800/posts/7")
+            .query("page", "2")
+            .add_header("authorization", "Bearer token", true)
+            .form(&[("title", "hello"), ("tags[]", "rust"), ("tags[]", "web")])
+            .build();
+        req.params.insert("id".into(), "7".into());
+        let data: Params = req.extract().await.unwrap();
+        assert_eq!(data.payload.tags, vec!["rust".to_owned(), "web".to_owned()]);
+
+        let mut req = TestClient::post("http://127.0.0.1:5800/posts/7")
+            .query("page", "second")
+            .build();
+        req.params.insert("id".into(), "7".into());
+        let err = req.extract::<Params>().await.err().unwrap();
+        assert!(err.to_string().contains("field `page` from query"), "{err}");
+    }
+
     #[tokio::test]
     async fn test_de_request_with_extract_rename_all() {
         #[derive(Deserialize, Extractible, Eq, PartialEq, Debug)]
@@ -1,4 +1,5 @@
 //! Server module
+use std::future::Future;
 use std::io::Result as IoResult;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -87,6 +88,7 @@ pub struct Server<A> {
     acceptor: A,
     builder: HttpBuilder,
     fuse_factory: Option<ArcFuseFactory>,
+    alt_svc_h3: Option<HeaderValue>,
     tx_cmd: UnboundedSender<ServerCommand>,
     rx_cmd: UnboundedReceiver<ServerCommand>,
 }
@@ -116,6 +118,7 @@ impl<A: Acceptor + Send> Server<A> {
             acceptor,
             builder,
             fuse_factory: None,
+            alt_svc_h3: None,
             tx_cmd,
             rx_cmd,
         }
@@ -130,6 +133,19 @@ impl<A: Acceptor + Send> Server<A> {
         self
     }
 
+    /// Advertise HTTP/3 on `port` to clients using `Alt-Svc` header.
+    ///
+    /// If the acceptor holds an HTTP/3 listener, for example a [`QuinnListener`] joined with a
+    /// [`TcpListener`], the header is added automatically. Use this function when HTTP/3 is served
+    /// by another server, so h1/h2 clients can discover it.
+    ///
+    /// [`QuinnListener`]: crate::conn::quinn::QuinnListener
+    /// [`TcpListener`]: crate::conn::TcpListener
+    pub fn alt_svc_h3(mut self, port: u16) -> Self {
+        self.alt_svc_h3 = Some(alt_svc_h3_value(port));
+        self
+    }
+
     /// Get a [`ServerHandle`] to stop server.
     pub fn handle(&self) -> ServerHandle {
         ServerHandle {
@@ -165,6 +181,46 @@ impl<A: Acceptor + Send> Server<A> {
         pub fn http1_mut(&mut self) -> &mut http1::Builder {
             &mut self.builder.http1
         }
+
+        /// Set the timeout for reading HTTP/1 request headers, it is disabled by default.
+        ///
+        /// It protects the server from slow-loris clients which send nothing or dribble request headers. If the
+        /// head of a request is not fully received in time, `408 Request Timeout` is responded and the connection is
+        /// closed, before the request reaches the [`Router`]:
+        ///
+        /// - For the first request, the time starts when the connection is accepted, it includes detecting the
+        ///   protocol version, so a client which sends only a part of HTTP/2 preface is also closed.
+        /// - For later requests on a keep-alive connection, the time starts when the connection becomes idle after
+        ///   the previous response, it is limited by hyper's header read timeout.
+        ///
+        /// Once a connection is detected as HTTP/2, only [`Http2Config::keep_alive_interval`] can close a dead
+        /// connection, there is no timeout for headers of HTTP/2 streams.
+        ///
+        /// This is unrelated to timeout middleware, which limits the time spent by handlers.
+        ///
+        /// [`Router`]: crate::Router
+        /// [`Http2Config::keep_alive_interval`]: crate::conn::Http2Config::keep_alive_interval
+        pub fn header_read_timeout(mut self, timeout: Duration) -> Self {
+            self.builder.header_read_timeout = Some(timeout);
+            self.builder
+                .http1
+                .timer(hyper_util::rt::TokioTimer::new())
+                .header_read_timeout(timeout);
+            self
+        }
+
+        /// Enable informational (`1xx`) responses sent by [`Response::send_informational`] on HTTP/1.1, it is
+        /// disabled by default.
+        ///
+        /// hyper can not send them, so each HTTP/1 connection is wrapped to write them directly before the final
+        /// response, which adds a lock to every read and write. If it is disabled, `send_informational` returns
+        /// `false`.
+        ///
+        /// [`Response::send_informational`]: crate::Response::send_informational
+        pub fn informational_responses(mut self, enabled: bool) -> Self {
+            self.builder.informational_responses = enabled;
+            self
+        }
     }
 
     cfg_feature! {
@@ -173,6 +229,31 @@ impl<A: Acceptor + Send> Server<A> {
         pub fn http2_mut(&mut self) -> &mut http2::Builder<crate::rt::tokio::TokioExecutor> {
             &mut self.builder.http2
         }
+
+        /// Apply [`Http2Config`] to http2 protocol.
+        ///
+        /// # Example
+        ///
+        /// ```no_run
+        /// use salvo_core::conn::Http2Config;
+        /// use salvo_core::prelude::*;
+        ///
+        /// #[tokio::main]
+        /// async fn main() {
+        ///     let acceptor = TcpListener::new("0.0.0.0:5800").bind().await;
+        ///     let config = Http2Config::new()
+        ///         .initial_stream_window_size(4 * 1024 * 1024)
+        ///         .initial_connection_window_size(8 * 1024 * 1024)
+        ///         .max_concurrent_streams(500);
+        ///     Server::new(acceptor).http2_config(config).serve(Router::new()).await;
+        /// }
+        /// ```
+        ///
+        /// [`Http2Config`]: crate::conn::Http2Config
+        pub fn http2_config(mut self, config: crate::conn::Http2Config) -> Self {
+            config.apply(&mut self.builder.http2);
+            self
+        }
     }
 
     cfg_feature! {
@@ -219,6 +300,7 @@ impl<A: Acceptor + Send> Server<A> {
             mut acceptor,
             builder,
             fuse_factory,
+            mut alt_svc_h3,
             mut rx_cmd,
             ..
         } = self;
@@ -227,17 +309,11 @@ impl<A: Acceptor + Send> Server<A> {
         let force_stop_token = CancellationToken::new();
         let graceful_stop_token = CancellationToken::new();
 
-        let mut alt_svc_h3 = None;
         for holding in acceptor.holdings() {
             tracing::info!("listening {}", holding);
-            if holding.http_versions.contains(&Version::HTTP_3) {
+            if alt_svc_h3.is_none() && holding.http_versions.contains(&Version::HTTP_3) {
                 if let Some(addr) = holding.local_addr.clone().into_std() {
-                    let port = addr.port();
-                    alt_svc_h3 = Some(
-                        format!(r#"h3=":{port}"; ma=2592000,h3-29=":{port}"; ma=2592000"#)
-                            .parse::<HeaderValue>()
-                            .expect("Parse alt-svc header failed."),
-                    );
+                    alt_svc_h3 = Some(alt_svc_h3_value(addr.port()));
                 }
             }
         }
@@ -248,13 +324,13 @@ impl<A: Acceptor + Send> Server<A> {
             tokio::select! {
                 accepted = acceptor.accept(fuse_factory.clone()) => {
                     match accepted {
-                        Ok(Accepted { conn, local_addr, remote_addr, http_scheme, ..}) => {
+                        Ok(Accepted { conn, local_addr, remote_addr, http_scheme, extensions, ..}) => {
                             alive_connections.fetch_add(1, Ordering::Release);
 
                             let service = service.clone();
                             let alive_connections = alive_connections.clone();
                             let notify = notify.clone();
-                            let handler = service.hyper_handler(local_addr, remote_addr, http_scheme, conn.fusewire(), alt_svc_h3.clone());
+                            let handler = service.hyper_handler(local_addr, remote_addr, http_scheme, extensions, conn.fusewire(), alt_svc_h3.clone());
                             let builder = builder.clone();
 
                             let force_stop_token = force_stop_token.clone();
@@ -323,6 +399,79 @@ impl<A: Acceptor + Send> Server<A> {
     }
 }
 
+fn alt_svc_h3_value(port: u16) -> HeaderValue {
+    format!(r#"h3=":{port}"; ma=2592000,h3-29=":{port}"; ma=2592000"#)
+        .parse::<HeaderValue>()
+        .expect("Parse alt-svc header failed.")
+}
+
+/// Returns a future which resolves when the process receives a shutdown signal.
+///
+/// On Unix it waits for `SIGINT` and `SIGTERM`, on Windows it waits for `Ctrl-C`, `Ctrl-Break` and closing of the
+/// console. Signal listeners are registered when this function is called rather than when the future is first
+/// polled, so a signal which arrives in between is not missed, and the future can be used in `tokio::select!`.
+///
+/// The future resolves on the first signal. Once a listener is registered, the signal no longer terminates the
+/// process by default, so later signals are ignored unless `catch_signals` is called again, which is a common way
+/// to stop the server forcibly when the user presses `Ctrl-C` twice.
+///
+/// # Panics
+///
+/// Panics if it is not called from within a tokio runtime, or the signal listeners can not be registered.
+///
+/// # Example
+///
+/// ```no_run
+/// use salvo_core::prelude::*;
+/// use salvo_core::server::catch_signals;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let acceptor = TcpListener::new("127.0.0.1:5800").bind().await;
+///     let server = Server::new(acceptor);
+///     let handle = server.handle();
+///     tokio::spawn(async move {
+///         catch_signals().await;
+///         handle.stop_graceful(std::time::Duration::from_secs(30));
+///         catch_signals().await;
+///         handle.stop_forcible();
+///     });
+///     server.serve(Router::new()).await;
+/// }
+/// ```
+pub fn catch_signals() -> impl Future<Output = ()> + Send + 'static {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut interrupt = signal(SignalKind::interrupt()).expect("failed to listen SIGINT");
+        let mut terminate = signal(SignalKind::terminate()).expect("failed to listen SIGTERM");
+        async move {
+            tokio::select! {
+                _ = interrupt.recv() => tracing::info!("SIGINT received"),
+                _ = terminate.recv() => tracing::info!("SIGTERM received"),
+            }
+        }
+    }
+    #[cfg(windows)]
+    {
+        use tokio::signal::windows::{ctrl_break, ctrl_c, ctrl_close};
+        let mut ctrl_c = ctrl_c().expect("failed to listen Ctrl-C");
+        let mut ctrl_break = ctrl_break().expect("failed to listen Ctrl-Break");
+        let mut ctrl_close = ctrl_close().expect("failed to listen console close");
+        async move {
+            tokio::select! {
+                _ = ctrl_c.recv() => tracing::info!("Ctrl-C received"),
+                _ = ctrl_break.recv() => tracing::info!("Ctrl-Break received"),
+                _ = ctrl_close.recv() => tracing::info!("console close received"),
+            }
+        }
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        std::future::pending()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde::Serialize;
@@ -396,4 +545,192 @@ mod tests {
             .unwrap();
         assert!(result.contains("<code>404</code>"));
     }
+
+    #[tokio::test]
+    async fn test_conn_info() {
+        use std::io::Result as IoResult;
+
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        use crate::conn::tcp::TcpAcceptor;
+        use crate::conn::{Accepted, Acceptor, Holding};
+        use crate::fuse::ArcFuseFactory;
+
+        #[derive(Clone)]
+        struct Tag(&'static str);
+        struct TaggedAcceptor(TcpAcceptor);
+        impl Acceptor for TaggedAcceptor {
+            type Conn = <TcpAcceptor as Acceptor>::Conn;
+
+            fn holdings(&self) -> &[Holding] {
+                self.0.holdings()
+            }
+            async fn accept(&mut self, fuse_factory: Option<ArcFuseFactory>) -> IoResult<Accepted<Self::Conn>> {
+                let mut accepted = self.0.accept(fuse_factory).await?;
+                accepted.extensions.insert(Tag("tagged"));
+                Ok(accepted)
+            }
+        }
+
+        #[handler]
+        async fn hello(req: &mut Request) -> String {
+            let info = req.conn_info().unwrap();
+            format!(
+                "{} {}",
+                info.extensions.get::<Tag>().unwrap().0,
+                info.remote_addr.to_string() == req.remote_addr().to_string()
+            )
+        }
+
+        let acceptor = TaggedAcceptor(TcpListener::new("127.0.0.1:0").bind().await);
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(Server::new(acceptor).serve(Router::new().get(hello)));
+
+        for _ in 0..2 {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(b"GET / HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            assert!(response.ends_with("tagged true"), "{response}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_header_read_timeout() {
+        use std::time::{Duration, Instant};
+
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpStream;
+
+        use crate::conn::Acceptor;
+
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        let server = Server::new(acceptor).header_read_timeout(Duration::from_millis(200));
+        tokio::spawn(server.serve(Router::new().get(hello)));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: 127.0.0.1\r\n")
+            .await
+            .unwrap();
+        let now = Instant::now();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(now.elapsed() < Duration::from_secs(2));
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout"), "{response}");
+
+        // Nothing is sent, or only a part of HTTP/2 preface, so the protocol version is unknown.
+        for data in [&b""[..], b"PRI * HTTP/2.0\r\n"] {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream.write_all(data).await.unwrap();
+            let now = Instant::now();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            assert!(now.elapsed() < Duration::from_secs(2));
+            assert!(response.starts_with("HTTP/1.1 408 Request Timeout"), "{response}");
+        }
+
+        // Head of the next request on a keep-alive connection is limited as the first one.
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\nGET / HTTP/1.1\r\n")
+            .await
+            .unwrap();
+        let now = Instant::now();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(now.elapsed() < Duration::from_secs(2));
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("HTTP/1.1 408 Request Timeout\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"), "{response}");
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(
+            response.starts_with("HTTP/1.1 200 OK") && response.ends_with("hello"),
+            "{response}"
+        );
+    }
+
+    #[cfg(feature = "http2")]
+    #[tokio::test]
+    async fn test_http2_max_concurrent_streams() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::Duration;
+
+        use http_body_util::Empty;
+        use tokio::net::TcpStream;
+
+        use crate::conn::{Acceptor, Http2Config};
+        use crate::rt::tokio::{TokioExecutor, TokioIo};
+
+        static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+        static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+        #[handler]
+        async fn slow() -> &'static str {
+            let active = ACTIVE.fetch_add(1, Ordering::SeqCst) + 1;
+            PEAK.fetch_max(active, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            ACTIVE.fetch_sub(1, Ordering::SeqCst);
+            "slow"
+        }
+
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        let server = Server::new(acceptor).http2_config(Http2Config::new().max_concurrent_streams(1));
+        tokio::spawn(server.serve(Router::new().get(slow)));
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let (sender, conn) = hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(stream))
+            .await
+            .unwrap();
+        tokio::spawn(conn);
+        let send = |mut sender: hyper::client::conn::http2::SendRequest<Empty<bytes::Bytes>>| async move {
+            let req = hyper::Request::get(format!("http://{addr}/"))
+                .body(Empty::new())
+                .unwrap();
+            sender.send_request(req).await.unwrap().status()
+        };
+
+        // Wait the server settings to be received by the client.
+        assert_eq!(send(sender.clone()).await, StatusCode::OK);
+        PEAK.store(0, Ordering::SeqCst);
+        let statuses = futures_util::future::join_all((0..3).map(|_| send(sender.clone()))).await;
+        assert!(statuses.iter().all(|status| *status == StatusCode::OK));
+        assert_eq!(PEAK.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_catch_signals() {
+        use std::time::Duration;
+
+        use crate::server::catch_signals;
+
+        let signals = catch_signals();
+        // The listener is registered before the future is polled, so the signal is not missed.
+        let status = std::process::Command::new("kill")
+            .args(["-TERM", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        tokio::time::timeout(Duration::from_secs(5), signals)
+            .await
+            .expect("signal is not caught");
+    }
 }
@@ -1,21 +1,28 @@
+use std::any::Any;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{ready, Context, Poll};
 
 use headers::HeaderValue;
-use http::header::{ALT_SVC, CONTENT_TYPE};
+use http::header::{ALLOW, ALT_SVC, CONTENT_LENGTH, CONTENT_TYPE};
 use http::uri::Scheme;
+use http::Extensions;
 use hyper::service::Service as HyperService;
 use hyper::{Method, Request as HyperRequest, Response as HyperResponse};
 
 use crate::catcher::{write_error_default, Catcher};
-use crate::conn::SocketAddr;
+use crate::conn::{ConnectionInfo, SocketAddr};
 use crate::fuse::ArcFusewire;
 use crate::handler::{Handler, WhenHoop};
-use crate::http::body::{ReqBody, ResBody};
-use crate::http::{Mime, Request, Response, StatusCode};
+use crate::http::body::{Body, Frame, ReqBody, ResBody, SizeHint};
+use crate::http::{HeaderMap, Mime, Request, Response, StatusCode};
 use crate::routing::{FlowCtrl, PathState, Router};
-use crate::Depot;
+use crate::writing::{RenderError, SerializeFn, Serializers};
+use crate::{BoxedError, Depot};
+
+/// Handler of render errors, it is set by [`Service::render_error_handler`].
+pub(crate) type RenderErrorHandler = Arc<dyn Fn(&Request, &mut Response, &RenderError) + Send + Sync>;
 
 /// Service http request.
 #[non_exhaustive]
@@ -28,6 +35,41 @@ pub struct Service {
     pub hoops: Vec<Arc<dyn Handler>>,
     /// The allowed media types of this service.
     pub allowed_media_types: Arc<Vec<Mime>>,
+    /// The default max size of request bodies of this service.
+    pub secure_max_size: Option<usize>,
+    /// Whether `HEAD` requests are handled by `GET` routes if no `HEAD` route matches. Default is `true`.
+    pub auto_head: bool,
+    /// Whether `405 Method Not Allowed` is responded when the path is matched by routes of other methods.
+    /// Default is `false`.
+    pub method_not_allowed: bool,
+    data: Arc<Vec<SharedData>>,
+    serializers: Serializers,
+    render_error_handler: Option<RenderErrorHandler>,
+}
+
+/// Application state shared by all requests, it is injected into every [`Depot`] as `Arc<T>`.
+#[derive(Clone)]
+pub(crate) struct SharedData {
+    value: Arc<dyn Any + Send + Sync>,
+    inject: fn(&(dyn Any + Send + Sync), &mut Depot),
+}
+impl SharedData {
+    #[inline]
+    fn new<T: Any + Send + Sync>(value: T) -> Self {
+        fn inject<T: Any + Send + Sync>(value: &(dyn Any + Send + Sync), depot: &mut Depot) {
+            if let Some(value) = value.downcast_ref::<Arc<T>>() {
+                depot.inject(Arc::clone(value));
+            }
+        }
+        Self {
+            value: Arc::new(Arc::new(value)),
+            inject: inject::<T>,
+        }
+    }
+    #[inline]
+    fn inject_to(&self, depot: &mut Depot) {
+        (self.inject)(&*self.value, depot);
+    }
 }
 
 impl Service {
@@ -42,6 +84,12 @@ impl Service {
             catcher: None,
             hoops: vec![],
             allowed_media_types: Arc::new(vec![]),
+            secure_max_size: None,
+            auto_head: true,
+            method_not_allowed: false,
+            data: Arc::new(vec![]),
+            serializers: Serializers::default(),
+            render_error_handler: None,
         }
     }
 
@@ -120,6 +168,142 @@ impl Service {
         self
     }
 
+    /// Sets default max size of request bodies, it overrides the global [`secure_max_size`].
+    ///
+    /// Body parsing methods like [`Request::payload`] and [`Request::parse_json`] return
+    /// `ParseError::PayloadTooLarge`, which is written as `413 Payload Too Large`, if the body exceeds it.
+    ///
+    /// [`secure_max_size`]: crate::http::request::secure_max_size
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use salvo_core::prelude::*;
+    /// let service = Service::new(Router::new()).secure_max_size(1024 * 1024);
+    /// ```
+    #[inline]
+    pub fn secure_max_size(mut self, size: usize) -> Self {
+        self.secure_max_size = Some(size);
+        self
+    }
+
+    /// Sets whether `HEAD` requests are handled by `GET` routes if no `HEAD` route matches. Default is `true`.
+    ///
+    /// The `GET` handler runs as usual, status code and headers are kept, `Content-Length` is set if the body size
+    /// is known, but the body is not sent. Streaming bodies are dropped without being polled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use salvo_core::prelude::*;
+    /// let service = Service::new(Router::new()).auto_head(false);
+    /// ```
+    #[inline]
+    pub fn auto_head(mut self, auto_head: bool) -> Self {
+        self.auto_head = auto_head;
+        self
+    }
+
+    /// Sets whether `405 Method Not Allowed` is responded when no route matches the request, but the path is matched
+    /// by routes of other methods. Default is `false`.
+    ///
+    /// The `Allow` header lists the methods of these routes. If it is disabled, `404 Not Found` is responded.
+    /// The methods are collected while detecting the route, so the router is still walked only once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use salvo_core::prelude::*;
+    /// let service = Service::new(Router::new()).method_not_allowed(true);
+    /// ```
+    #[inline]
+    pub fn method_not_allowed(mut self, enabled: bool) -> Self {
+        self.method_not_allowed = enabled;
+        self
+    }
+
+    /// Adds application state shared by all requests, such as a database pool.
+    ///
+    /// The value is wrapped in an [`Arc`], and the `Arc<T>` is injected into every [`Depot`] before any handler
+    /// runs, so it can be got by `depot.obtain::<Arc<T>>()`. Only the `Arc` is cloned for every request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use salvo_core::prelude::*;
+    /// struct Config {
+    ///     name: &'static str,
+    /// }
+    ///
+    /// #[handler]
+    /// async fn hello(depot: &mut Depot) -> String {
+    ///     let name = depot.obtain::<Arc<Config>>().map(|config| config.name).unwrap_or_default();
+    ///     format!("Hello {name}")
+    /// }
+    ///
+    /// let service = Service::new(Router::new().get(hello)).data(Config { name: "salvo" });
+    /// ```
+    #[inline]
+    pub fn data<T: Any + Send + Sync>(mut self, value: T) -> Self {
+        Arc::make_mut(&mut self.data).push(SharedData::new(value));
+        self
+    }
+
+    /// Registers a serializer for `media_type`, it is used by [`Negotiated`] and [`Response::render_negotiated`]
+    /// when the media type is accepted by the request.
+    ///
+    /// If a serializer is already registered for the media type, it will be replaced.
+    ///
+    /// [`Negotiated`]: crate::writing::Negotiated
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use salvo_core::prelude::*;
+    /// # use salvo_core::BoxedError;
+    /// fn to_text(value: &serde_json::Value) -> Result<Vec<u8>, BoxedError> {
+    ///     Ok(value.to_string().into_bytes())
+    /// }
+    ///
+    /// let service = Service::new(Router::new()).serializer("text/x-json".parse().unwrap(), to_text);
+    /// ```
+    #[inline]
+    pub fn serializer(mut self, media_type: Mime, serializer: SerializeFn) -> Self {
+        self.serializers.insert(media_type, serializer);
+        self
+    }
+
+    /// Sets handler which is called when rendering or writing the response fails, so applications can respond
+    /// their standard error, record metrics or log the request.
+    ///
+    /// Failures before headers are sent, which are reported by [`Response::render_failed`], are handled after
+    /// all handlers are done, the response is `500 Internal Server Error` by default and the handler can replace
+    /// it. Failures of streaming bodies happen after headers were sent, [`RenderError::headers_sent`] returns
+    /// `true` and the response passed to the handler only contains the sent status and headers, changes to it are
+    /// ignored and the body is aborted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    ///
+    /// let service = Service::new(Router::new()).render_error_handler(|req, res, error| {
+    ///     tracing::error!(uri = %req.uri(), error = %error, "render failed");
+    ///     if !error.headers_sent() {
+    ///         res.render(Json(serde_json::json!({ "code": "render_failed" })));
+    ///     }
+    /// });
+    /// ```
+    #[inline]
+    pub fn render_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&Request, &mut Response, &RenderError) + Send + Sync + 'static,
+    {
+        self.render_error_handler = Some(Arc::new(handler));
+        self
+    }
+
     #[doc(hidden)]
     #[inline]
     pub fn hyper_handler(
@@ -127,17 +311,22 @@ impl Service {
         local_addr: SocketAddr,
         remote_addr: SocketAddr,
         http_scheme: Scheme,
+        extensions: Extensions,
         fusewire: Option<ArcFusewire>,
         alt_svc_h3: Option<HeaderValue>,
     ) -> HyperHandler {
         HyperHandler {
-            local_addr,
-            remote_addr,
-            http_scheme,
+            conn_info: Arc::new(ConnectionInfo::new(local_addr, remote_addr, http_scheme, extensions)),
             router: self.router.clone(),
             catcher: self.catcher.clone(),
             hoops: self.hoops.clone(),
             allowed_media_types: self.allowed_media_types.clone(),
+            secure_max_size: self.secure_max_size,
+            auto_head: self.auto_head,
+            method_not_allowed: self.method_not_allowed,
+            data: self.data.clone(),
+            serializers: self.serializers.clone(),
+            render_error_handler: self.render_error_handler.clone(),
             fusewire,
             alt_svc_h3,
         }
@@ -151,6 +340,7 @@ impl Service {
             request.local_addr.clone(),
             request.remote_addr.clone(),
             request.scheme.clone(),
+            Extensions::new(),
             None,
             None,
         )
@@ -172,13 +362,17 @@ where
 #[doc(hidden)]
 #[derive(Clone)]
 pub struct HyperHandler {
-    pub(crate) local_addr: SocketAddr,
-    pub(crate) remote_addr: SocketAddr,
-    pub(crate) http_scheme: Scheme,
+    pub(crate) conn_info: Arc<ConnectionInfo>,
     pub(crate) router: Arc<Router>,
     pub(crate) catcher: Option<Arc<Catcher>>,
     pub(crate) hoops: Vec<Arc<dyn Handler>>,
     pub(crate) allowed_media_types: Arc<Vec<Mime>>,
+    pub(crate) secure_max_size: Option<usize>,
+    pub(crate) auto_head: bool,
+    pub(crate) method_not_allowed: bool,
+    pub(crate) data: Arc<Vec<SharedData>>,
+    pub(crate) serializers: Serializers,
+    pub(crate) render_error_handler: Option<RenderErrorHandler>,
     pub(crate) fusewire: Option<ArcFusewire>,
     pub(crate) alt_svc_h3: Option<HeaderValue>,
 }
@@ -187,39 +381,90 @@ impl HyperHandler {
     pub fn handle(&self, mut req: Request) -> impl Future<Output = Response> {
         let catcher = self.catcher.clone();
         let allowed_media_types = self.allowed_media_types.clone();
-        req.local_addr = self.local_addr.clone();
-        req.remote_addr = self.remote_addr.clone();
+        req.local_addr = self.conn_info.local_addr.clone();
+        req.remote_addr = self.conn_info.remote_addr.clone();
+        req.extensions.insert(self.conn_info.clone());
+        if !self.serializers.0.is_empty() {
+            req.extensions.insert(self.serializers.clone());
+        }
+        if req.secure_max_size.is_none() {
+            req.secure_max_size = self.secure_max_size;
+        }
         #[cfg(not(feature = "cookie"))]
         let mut res = Response::new();
         #[cfg(feature = "cookie")]
         let mut res = Response::with_cookies(req.cookies.clone());
+        #[cfg(feature = "http1")]
+        if let Some(sender) = req.extensions.remove::<crate::conn::interim::InterimSender>() {
+            res.extensions.insert(sender);
+        }
         if let Some(alt_svc_h3) = &self.alt_svc_h3 {
             if !res.headers().contains_key(ALT_SVC) {
                 res.headers_mut().insert(ALT_SVC, alt_svc_h3.clone());
             }
         }
         let mut depot = Depot::new();
+        for data in self.data.iter() {
+            data.inject_to(&mut depot);
+        }
         let mut path_state = PathState::new(req.uri().path());
         let router = self.router.clone();
 
         let hoops = self.hoops.clone();
+        let auto_head = self.auto_head;
+        let method_not_allowed = self.method_not_allowed;
+        let render_error_handler = self.render_error_handler.clone();
         async move {
-            if let Some(dm) = router.detect(&mut req, &mut path_state) {
+            path_state.collect_methods = method_not_allowed;
+            let mut detected = router.detect(&mut req, &mut path_state);
+            let mut allowed = std::mem::take(&mut path_state.allowed_methods);
+            if detected.is_none() && auto_head && req.method() == Method::HEAD {
+                // Fall back to `GET` routes, handlers still see the `HEAD` method.
+                *req.method_mut() = Method::GET;
+                path_state = PathState::new(req.uri().path());
+                detected = router.detect(&mut req, &mut path_state);
+                *req.method_mut() = Method::HEAD;
+            }
+            if let Some(dm) = detected {
                 req.params = path_state.params;
+                req.matched_route = Some(dm.route);
                 let mut ctrl = FlowCtrl::new([&hoops[..], &dm.hoops[..], &[dm.goal]].concat());
                 ctrl.call_next(&mut req, &mut depot, &mut res).await;
                 if res.status_code.is_none() {
                     res.status_code = Some(StatusCode::OK);
                 }
-            } else if !hoops.is_empty() {
-                req.params = path_state.params;
-                let mut ctrl = FlowCtrl::new(hoops);
-                ctrl.call_next(&mut req, &mut depot, &mut res).await;
-                if res.status_code.is_none() {
-                    res.status_code = Some(StatusCode::NOT_FOUND);
-                }
             } else {
-                res.status_code(StatusCode::NOT_FOUND);
+                if auto_head && allowed.contains(&Method::GET) && !allowed.contains(&Method::HEAD) {
+                    allowed.push(Method::HEAD);
+                }
+                allowed.sort_by_key(method_order);
+                let status = if allowed.is_empty() {
+                    StatusCode::NOT_FOUND
+                } else {
+                    StatusCode::METHOD_NOT_ALLOWED
+                };
+                if !hoops.is_empty() {
+                    req.params = path_state.params;
+                    let mut ctrl = FlowCtrl::new(hoops);
+                    ctrl.call_next(&mut req, &mut depot, &mut res).await;
+                    if res.status_code.is_none() {
+                        res.status_code = Some(status);
+                    }
+                } else {
+                    res.status_code(status);
+                }
+                if res.status_code == Some(StatusCode::METHOD_NOT_ALLOWED) && !res.headers().contains_key(ALLOW) {
+                    let allow = allowed.iter().map(Method::as_str).collect::<Vec<_>>().join(", ");
+                    if let Ok(allow) = HeaderValue::from_str(&allow) {
+                        res.headers_mut().insert(ALLOW, allow);
+                    }
+                }
+            }
+
+            if let Some(error) = res.extensions.remove::<RenderError>() {
+                if let Some(handler) = &render_error_handler {
+                    handler(&req, &mut res, &error);
+                }
             }
 
             let status = res.status_code.unwrap_or(StatusCode::NOT_FOUND);
@@ -265,9 +510,8 @@ impl HyperHandler {
                     write_error_default(&req, &mut res, None);
                 }
             }
-            #[cfg(debug_assertions)]
-            if Method::HEAD == *req.method() && !res.body.is_none() {
-                tracing::warn!("request with head method should not have body: https://developer.mozilla.org/en-US/docs/Web/HTTP/Methods/HEAD");
+            if Method::HEAD == *req.method() {
+                strip_head_body(&mut res);
             }
             #[cfg(feature = "quinn")]
             {
@@ -292,11 +536,94 @@ impl HyperHandler {
                     res.extensions.insert(Arc::new(stream));
                 }
             }
+            if let Some(handler) = render_error_handler {
+                if res.body.is_hyper() || res.body.is_boxed() || res.body.is_stream() || res.body.is_channel() {
+                    // Unread request body is not needed by the handler, drop it rather than holding the connection.
+                    req.take_body();
+                    let body = WatchedBody {
+                        inner: res.take_body(),
+                        req: Some(req),
+                        status: res.status_code.unwrap_or(StatusCode::OK),
+                        headers: res.headers.clone(),
+                        handler,
+                    };
+                    res.body = ResBody::Boxed(Box::pin(body));
+                }
+            }
             res
         }
     }
 }
 
+/// Body which calls render error handler when the body fails after headers were sent.
+struct WatchedBody {
+    inner: ResBody,
+    req: Option<Request>,
+    status: StatusCode,
+    headers: HeaderMap,
+    handler: RenderErrorHandler,
+}
+impl Body for WatchedBody {
+    type Data = bytes::Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        match ready!(Pin::new(&mut this.inner).poll_frame(cx)) {
+            Some(Err(e)) => {
+                let error = RenderError::with_headers_sent(e);
+                if let Some(req) = this.req.take() {
+                    let mut res = Response::new();
+                    res.status_code = Some(this.status);
+                    res.headers = std::mem::take(&mut this.headers);
+                    (this.handler)(&req, &mut res, &error);
+                }
+                Poll::Ready(Some(Err(error.into())))
+            }
+            frame => Poll::Ready(frame.map(|frame| frame.map_err(Into::into))),
+        }
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Sort key of methods in `Allow` header, well known methods come first in their usual order.
+fn method_order(method: &Method) -> usize {
+    [
+        Method::GET,
+        Method::HEAD,
+        Method::POST,
+        Method::PUT,
+        Method::DELETE,
+        Method::PATCH,
+        Method::OPTIONS,
+    ]
+    .iter()
+    .position(|m| m == method)
+    .unwrap_or(usize::MAX)
+}
+
+/// Drops the body of response to `HEAD` request, `Content-Length` is set if the body size is known.
+///
+/// Streaming bodies are dropped without being polled, so no work is wasted generating bytes nobody receives.
+fn strip_head_body(res: &mut Response) {
+    let body = std::mem::take(&mut res.body);
+    if body.is_none() || body.is_error() || res.headers().contains_key(CONTENT_LENGTH) {
+        return;
+    }
+    if let Some(size) = Body::size_hint(&body).exact() {
+        res.headers_mut().insert(CONTENT_LENGTH, HeaderValue::from(size));
+    }
+}
+
 impl<B> HyperService<HyperRequest<B>> for HyperHandler
 where
     B: Into<ReqBody>,
@@ -311,7 +638,11 @@ where
         #[cfg(not(feature = "fix-http1-request-uri"))] req: HyperRequest<B>,
         #[cfg(feature = "fix-http1-request-uri")] mut req: HyperRequest<B>,
     ) -> Self::Future {
-        let scheme = req.uri().scheme().cloned().unwrap_or_else(|| self.http_scheme.clone());
+        let scheme = req
+            .uri()
+            .scheme()
+            .cloned()
+            .unwrap_or_else(|| self.conn_info.http_scheme.clone());
         // https://github.com/hyperium/hyper/issues/1310
         #[cfg(feature = "fix-http1-request-uri")]
         if req.uri().scheme().is_none() {
@@ -338,7 +669,9 @@ where
 
 #[cfg(test)]
 mod tests {
+    use crate::http::header::ALLOW;
     use crate::prelude::*;
+    use crate::routing::{filters, Filter};
     use crate::test::{ResponseExt, TestClient};
 
     #[tokio::test]
@@ -398,4 +731,187 @@ mod tests {
         let content = access(&service, "3").await;
         assert_eq!(content, "before1before2before3");
     }
+
+    #[tokio::test]
+    async fn test_service_data() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        // Not `Clone`, it is shared by all requests.
+        #[derive(Default)]
+        struct PgPool {
+            acquired: AtomicUsize,
+        }
+        #[handler]
+        async fn query(depot: &mut Depot) -> String {
+            let pool = depot.obtain::<Arc<PgPool>>().unwrap();
+            let count = pool.acquired.fetch_add(1, Ordering::SeqCst) + 1;
+            format!("{} {count}", depot.obtain::<Arc<&str>>().unwrap())
+        }
+
+        let service = Service::new(Router::new().get(query))
+            .data(PgPool::default())
+            .data("db");
+        for i in 1..=2 {
+            let content = TestClient::get("http://127.0.0.1:5801")
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, format!("db {i}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_head() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        use bytes::Bytes;
+
+        use crate::http::header::CONTENT_LENGTH;
+
+        static POLLED: AtomicBool = AtomicBool::new(false);
+
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+        #[handler]
+        async fn head_hello(res: &mut Response) {
+            res.add_header("x-head", "1", true).unwrap();
+        }
+        #[handler]
+        async fn stream(res: &mut Response) {
+            let body = futures_util::stream::poll_fn(|_| {
+                POLLED.store(true, Ordering::SeqCst);
+                std::task::Poll::Ready(None::<Result<Bytes, std::io::Error>>)
+            });
+            res.stream(body);
+        }
+        let router = Router::new()
+            .push(Router::with_path("hello").get(hello))
+            .push(Router::with_path("explicit").get(hello).head(head_hello))
+            .push(Router::with_path("stream").get(stream));
+        let service = Service::new(router);
+
+        let mut res = TestClient::head("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get(CONTENT_LENGTH).unwrap(), "5");
+        assert_eq!(res.headers().get("content-type").unwrap(), "text/plain; charset=utf-8");
+        assert_eq!(res.take_string().await.unwrap(), "");
+
+        let res = TestClient::head("http://127.0.0.1:5801/explicit").send(&service).await;
+        assert_eq!(res.headers().get("x-head").unwrap(), "1");
+
+        let res = TestClient::head("http://127.0.0.1:5801/stream").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert!(res.body.is_none());
+        assert!(!POLLED.load(Ordering::SeqCst));
+
+        let service = Service::new(Router::with_path("hello").get(hello)).auto_head(false);
+        let res = TestClient::head("http://127.0.0.1:5801/hello").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        assert!(res.headers().get(ALLOW).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_method_not_allowed() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+        let router = Router::new()
+            .push(Router::with_path("users").get(hello).post(hello))
+            .push(Router::with_path("users/<id>").delete(hello))
+            .push(
+                Router::with_path("posts")
+                    .filter(filters::get().or(filters::post()))
+                    .goal(hello),
+            );
+
+        let service = Service::new(router).method_not_allowed(true);
+        let res = TestClient::put("http://127.0.0.1:5801/users").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_eq!(res.headers().get(ALLOW).unwrap(), "GET, HEAD, POST");
+        let res = TestClient::get("http://127.0.0.1:5801/users/1").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        assert_eq!(res.headers().get(ALLOW).unwrap(), "DELETE");
+        let res = TestClient::post("http://127.0.0.1:5801/posts").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        let res = TestClient::put("http://127.0.0.1:5801/posts").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::METHOD_NOT_ALLOWED));
+        let res = TestClient::get("http://127.0.0.1:5801/comments").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        assert!(res.headers().get(ALLOW).is_none());
+
+        let service = Service::new(service.router());
+        let res = TestClient::put("http://127.0.0.1:5801/users").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+        assert!(res.headers().get(ALLOW).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_render_error_handler() {
+        use std::collections::HashMap;
+        use std::io::Error as IoError;
+        use std::sync::{Arc, Mutex};
+
+        use futures_util::stream;
+
+        #[handler]
+        async fn invalid_json(res: &mut Response) {
+            // Json object keys must be strings.
+            res.render(Json(HashMap::from([(vec![1u8], 1u8)])));
+        }
+        #[handler]
+        async fn broken_stream(res: &mut Response) {
+            res.stream(stream::iter([
+                Ok::<_, IoError>("partial"),
+                Err(IoError::other("stream failed")),
+            ]));
+        }
+
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let router = Router::new()
+            .push(Router::with_path("json").get(invalid_json))
+            .push(Router::with_path("stream").get(broken_stream));
+        let service = Service::new(router).render_error_handler({
+            let errors = errors.clone();
+            move |req, res, error| {
+                errors
+                    .lock()
+                    .unwrap()
+                    .push((req.uri().path().to_owned(), res.status_code, error.headers_sent()));
+                if !error.headers_sent() {
+                    let request_id = req.header::<String>("x-request-id").unwrap_or_default();
+                    res.render(Json(serde_json::json!({ "request_id": request_id })));
+                }
+            }
+        });
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/json")
+            .add_header("x-request-id", "abc", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+        assert_eq!(res.take_string().await.unwrap(), r#"{"request_id":"abc"}"#);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/stream").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert!(res.take_string().await.is_err());
+
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![
+                ("/json".to_owned(), Some(StatusCode::INTERNAL_SERVER_ERROR), false),
+                ("/stream".to_owned(), Some(StatusCode::OK), true),
+            ]
+        );
+
+        // Without handler, failed rendering is still `500 Internal Server Error`.
+        let service = Service::new(Router::new().get(invalid_json));
+        let res = TestClient::get("http://127.0.0.1:5801/").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+    }
 }
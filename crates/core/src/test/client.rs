@@ -8,6 +8,11 @@ use super::request::RequestBuilder;
 pub struct TestClient;
 
 impl TestClient {
+    /// Create a new `RequestBuilder` with the given method and this TestClient's settings applied on it.
+    pub fn request(method: Method, url: impl AsRef<str>) -> RequestBuilder {
+        RequestBuilder::new(url, method)
+    }
+
     /// Create a new `RequestBuilder` with the GET method and this TestClient's settings applied on it.
     pub fn get(url: impl AsRef<str>) -> RequestBuilder {
         RequestBuilder::new(url, Method::GET)
@@ -1,4 +1,57 @@
 //! Test utils for unit tests.
+//!
+//! Requests are sent to a [`Router`](crate::Router), [`Service`](crate::Service) or [`Handler`](crate::Handler)
+//! in process, the full hoop chain and catchers are executed without binding any socket.
+//!
+//! # Example
+//!
+//! ```
+//! use salvo_core::http::Method;
+//! use salvo_core::prelude::*;
+//! use salvo_core::test::{ResponseExt, TestClient};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct User {
+//!     id: u64,
+//!     name: String,
+//! }
+//!
+//! #[handler]
+//! async fn auth(req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl) {
+//!     if req.header::<&str>("authorization") != Some("Bearer secret") {
+//!         res.render(StatusError::unauthorized());
+//!         ctrl.skip_rest();
+//!     }
+//! }
+//! #[handler]
+//! async fn update_user(req: &mut Request) -> Result<Json<User>, StatusError> {
+//!     let mut user = req.parse_json::<User>().await.map_err(|_| StatusError::bad_request())?;
+//!     user.id = req.param("id").unwrap_or_default();
+//!     if req.query::<bool>("shout").unwrap_or_default() {
+//!         user.name = user.name.to_uppercase();
+//!     }
+//!     Ok(Json(user))
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let service = Service::new(Router::with_path("users/<id>").hoop(auth).put(update_user));
+//!
+//! let mut res = TestClient::put("/users/7")
+//!     .query("shout", true)
+//!     .bearer_auth("secret")
+//!     .json(&User { id: 0, name: "jobs".into() })
+//!     .send(&service)
+//!     .await;
+//! assert_eq!(res.status_code, Some(StatusCode::OK));
+//! let user = res.take_json::<User>().await.unwrap();
+//! assert_eq!(user, User { id: 7, name: "JOBS".into() });
+//!
+//! let res = TestClient::request(Method::PUT, "/users/7").send(&service).await;
+//! assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+//! # }
+//! ```
 
 mod client;
 mod request;
@@ -29,15 +29,24 @@ pub struct RequestBuilder {
 }
 
 impl RequestBuilder {
-    /// Create a new `RequestBuilder` with the base URL and the given method.
+    /// Create a new `RequestBuilder` with the URL and the given method.
+    ///
+    /// The URL can be a path which starts with `/`, such as `/users?page=2`, then it is relative to
+    /// `http://127.0.0.1`.
     ///
     /// # Panics
-    /// Panics if the base url is invalid or if the method is CONNECT.
+    /// Panics if the url is invalid or if the method is CONNECT.
     pub fn new<U>(url: U, method: Method) -> Self
     where
         U: AsRef<str>,
     {
-        let url = Url::parse(url.as_ref()).expect("invalid url");
+        let url = url.as_ref();
+        let url = if url.starts_with('/') {
+            Url::parse("http://127.0.0.1").and_then(|base| base.join(url))
+        } else {
+            Url::parse(url)
+        }
+        .expect("invalid url");
         Self {
             url,
             method,
@@ -239,10 +248,10 @@ impl RequestBuilder {
             let values = response
                 .cookies
                 .delta()
-                .filter_map(|c| c.encoded().to_string().parse().ok())
+                .filter_map(crate::http::response::set_cookie_value)
                 .collect::<Vec<_>>();
             for hv in values {
-                response.headers_mut().insert(header::SET_COOKIE, hv);
+                response.headers_mut().append(header::SET_COOKIE, hv);
             }
             response
         }
@@ -8,7 +8,6 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use futures_util::future::{BoxFuture, FutureExt};
-use http_body_util::BodyExt;
 use hyper::body::{Body, Bytes};
 use tower::buffer::Buffer;
 use tower::{Layer, Service, ServiceExt};
@@ -79,7 +78,7 @@ where
                 return;
             }
         }
-        .map(|res| ResBody::Boxed(Box::pin(res.map_frame(|f| f.map_data(|data| data.into())).map_err(|e|e.into()))));
+        .map(ResBody::boxed);
 
         res.merge_hyper(hyper_res);
     }
@@ -216,7 +215,7 @@ where
                 return;
             }
         }
-        .map(|res| ResBody::Boxed(Box::pin(res.map_frame(|f| f.map_data(|data| data.into())).map_err(|e|e.into()))));
+        .map(ResBody::boxed);
         let origin_depot = depot;
         let origin_ctrl = ctrl;
 
@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use super::Scribe;
+use crate::http::header::{HeaderValue, CONTENT_TYPE};
+use crate::http::Response;
+
+/// Write serializable content to response as CBOR content. It will set `content-type` to `application/cbor`.
+///
+/// It can also be used as handler argument to extract CBOR body, see [`Extractible`](crate::extract::Extractible).
+pub struct Cbor<T>(pub T);
+
+impl<T> Scribe for Cbor<T>
+where
+    T: Serialize + Send,
+{
+    fn render(self, res: &mut Response) {
+        let mut bytes = Vec::new();
+        match ciborium::into_writer(&self.0, &mut bytes) {
+            Ok(()) => {
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/cbor"));
+                res.write_body(bytes).ok();
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "Cbor write error");
+                res.render_failed(e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::http::ParseError;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[tokio::test]
+    async fn test_cbor_roundtrip() {
+        #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+        struct User {
+            name: String,
+            age: u8,
+        }
+        #[handler]
+        async fn echo(req: &mut Request) -> Result<Cbor<User>, ParseError> {
+            let user = req.parse_body::<User>().await?;
+            Ok(Cbor(user))
+        }
+
+        let router = Router::new().push(Router::with_path("echo").post(echo));
+        let user = User {
+            name: "jobs".into(),
+            age: 28,
+        };
+        let mut body = Vec::new();
+        ciborium::into_writer(&user, &mut body).unwrap();
+        let mut res = TestClient::post("http://127.0.0.1:5800/echo")
+            .add_header(CONTENT_TYPE, "application/cbor", true)
+            .body(body)
+            .send(router)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/cbor");
+        let bytes = res.take_bytes(None).await.unwrap();
+        assert_eq!(ciborium::from_reader::<User, _>(&bytes[..]).unwrap(), user);
+    }
+}
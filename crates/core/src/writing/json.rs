@@ -1,31 +1,81 @@
+use std::ops::{Deref, DerefMut};
+
 use async_trait::async_trait;
 use serde::Serialize;
 
 use super::Scribe;
 use crate::http::header::{HeaderValue, CONTENT_TYPE};
-use crate::http::{Response, StatusError};
+use crate::http::Response;
 
 /// Write serializable content to response as json content. It will set `content-type` to `application/json; charset=utf-8`.
+///
+/// It can also be used as handler argument to extract json body, see [`Extractible`](crate::extract::Extractible).
 pub struct Json<T>(pub T);
 
+impl<T> Json<T> {
+    /// Create [`PrettyJson`] which writes indented json, it is easier to read for human than compact json.
+    #[inline]
+    pub fn pretty(value: T) -> PrettyJson<T> {
+        PrettyJson(value)
+    }
+
+    /// Consumes self and returns the inner value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 #[async_trait]
 impl<T> Scribe for Json<T>
 where
     T: Serialize + Send,
 {
     fn render(self, res: &mut Response) {
-        match serde_json::to_vec(&self.0) {
-            Ok(bytes) => {
-                res.headers_mut().insert(
-                    CONTENT_TYPE,
-                    HeaderValue::from_static("application/json; charset=utf-8"),
-                );
-                res.write_body(bytes).ok();
-            }
-            Err(e) => {
-                tracing::error!(error = ?e, "JsonContent write error");
-                res.render(StatusError::internal_server_error());
-            }
+        write_json(serde_json::to_vec(&self.0), res);
+    }
+}
+
+/// Write serializable content to response as indented json content, it is created by [`Json::pretty`].
+pub struct PrettyJson<T>(pub T);
+
+impl<T> Scribe for PrettyJson<T>
+where
+    T: Serialize + Send,
+{
+    fn render(self, res: &mut Response) {
+        write_json(serde_json::to_vec_pretty(&self.0), res);
+    }
+}
+
+fn write_json(result: serde_json::Result<Vec<u8>>, res: &mut Response) {
+    match result {
+        Ok(bytes) => {
+            res.headers_mut().insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static("application/json; charset=utf-8"),
+            );
+            res.write_body(bytes).ok();
+        }
+        Err(e) => {
+            tracing::error!(error = ?e, "JsonContent write error");
+            res.render_failed(e);
         }
     }
 }
@@ -56,4 +106,17 @@ mod tests {
             "application/json; charset=utf-8"
         );
     }
+
+    #[tokio::test]
+    async fn test_write_pretty_json() {
+        #[handler]
+        async fn test() -> PrettyJson<serde_json::Value> {
+            Json::pretty(serde_json::json!({"name": "jobs"}))
+        }
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/test")
+            .send(Router::with_path("test").get(test))
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "{\n  \"name\": \"jobs\"\n}");
+    }
 }
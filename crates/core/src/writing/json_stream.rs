@@ -0,0 +1,170 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+
+use super::Scribe;
+use crate::http::header::{HeaderValue, CONTENT_TYPE};
+use crate::http::Response;
+
+/// Write items of a stream to response as json without buffering the whole content.
+///
+/// Items are serialized one by one when the body is sent, as a json array with content type
+/// `application/json; charset=utf-8` by [`JsonStream::array`], or as newline delimited json with content type
+/// `application/x-ndjson` by [`JsonStream::ndjson`]. Iterators can be converted to stream by
+/// [`futures_util::stream::iter`].
+///
+/// If an item fails to serialize, the body is aborted, so the client gets an incomplete response rather than
+/// invalid json which looks complete.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::stream;
+/// use salvo_core::prelude::*;
+/// use salvo_core::writing::JsonStream;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Row {
+///     id: u64,
+/// }
+///
+/// #[handler]
+/// async fn export() -> JsonStream<impl futures_util::Stream<Item = Row> + Send> {
+///     JsonStream::array(stream::iter((0..1_000_000).map(|id| Row { id })))
+/// }
+/// ```
+pub struct JsonStream<S> {
+    stream: S,
+    ndjson: bool,
+}
+
+impl<S> JsonStream<S> {
+    /// Create `JsonStream` which writes items as a json array.
+    #[inline]
+    pub fn array(stream: S) -> Self {
+        Self { stream, ndjson: false }
+    }
+
+    /// Create `JsonStream` which writes items as newline delimited json, one item per line.
+    ///
+    /// Every item is sent as soon as the stream yields it, so it suits log streaming and real-time feeds consumed
+    /// by other services, while SSE is more convenient for browsers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::stream;
+    /// use salvo_core::prelude::*;
+    /// use salvo_core::writing::JsonStream;
+    ///
+    /// #[handler]
+    /// async fn logs(res: &mut Response) {
+    ///     let lines = stream::iter(["started", "finished"].map(|msg| serde_json::json!({ "msg": msg })));
+    ///     res.render(JsonStream::ndjson(lines));
+    /// }
+    /// ```
+    #[inline]
+    pub fn ndjson(stream: S) -> Self {
+        Self { stream, ndjson: true }
+    }
+}
+
+impl<S, T> Scribe for JsonStream<S>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Serialize + Send + 'static,
+{
+    fn render(self, res: &mut Response) {
+        let Self { stream, ndjson } = self;
+        if ndjson {
+            res.headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static("application/x-ndjson"));
+            res.stream(stream.map(|item| serialize(&item, b"", b"\n")));
+        } else {
+            res.headers_mut().insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static("application/json; charset=utf-8"),
+            );
+            let items = stream
+                .enumerate()
+                .map(|(index, item)| serialize(&item, if index == 0 { b"" } else { b"," }, b""));
+            let open = stream::once(async { Ok(Bytes::from_static(b"[")) });
+            let close = stream::once(async { Ok(Bytes::from_static(b"]")) });
+            res.stream(open.chain(items).chain(close));
+        }
+    }
+}
+
+fn serialize<T: Serialize>(item: &T, prefix: &[u8], suffix: &[u8]) -> Result<Bytes, serde_json::Error> {
+    let mut writer = BytesMut::new().writer();
+    writer.get_mut().put_slice(prefix);
+    serde_json::to_writer(&mut writer, item).map_err(|e| {
+        tracing::error!(error = ?e, "JsonStream serialize item failed");
+        e
+    })?;
+    writer.get_mut().put_slice(suffix);
+    Ok(writer.into_inner().freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures_util::stream;
+    use serde::Serialize;
+
+    use super::*;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[derive(Serialize)]
+    struct Row {
+        id: u64,
+    }
+
+    #[tokio::test]
+    async fn test_json_stream() {
+        #[handler]
+        async fn array() -> JsonStream<impl Stream<Item = Row> + Send> {
+            JsonStream::array(stream::iter((1..=3).map(|id| Row { id })))
+        }
+        #[handler]
+        async fn empty() -> JsonStream<impl Stream<Item = Row> + Send> {
+            JsonStream::array(stream::iter(Vec::new()))
+        }
+        #[handler]
+        async fn ndjson() -> JsonStream<impl Stream<Item = Row> + Send> {
+            JsonStream::ndjson(stream::iter((1..=2).map(|id| Row { id })))
+        }
+        let router = Router::new()
+            .push(Router::with_path("array").get(array))
+            .push(Router::with_path("empty").get(empty))
+            .push(Router::with_path("ndjson").get(ndjson));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/array").send(&service).await;
+        assert_eq!(res.content_type().unwrap().essence_str(), "application/json");
+        assert_eq!(res.take_string().await.unwrap(), r#"[{"id":1},{"id":2},{"id":3}]"#);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/empty").send(&service).await;
+        assert_eq!(res.take_string().await.unwrap(), "[]");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/ndjson").send(&service).await;
+        assert_eq!(res.content_type().unwrap().essence_str(), "application/x-ndjson");
+        assert_eq!(res.take_string().await.unwrap(), "{\"id\":1}\n{\"id\":2}\n");
+    }
+
+    #[tokio::test]
+    async fn test_json_stream_serialize_error() {
+        #[handler]
+        async fn broken() -> JsonStream<impl Stream<Item = HashMap<Vec<u8>, u8>> + Send> {
+            // Json object keys must be strings, so the second item fails to serialize.
+            JsonStream::array(stream::iter([HashMap::new(), HashMap::from([(vec![1], 1)])]))
+        }
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .send(Router::new().get(broken))
+            .await;
+        assert!(res.take_string().await.is_err());
+    }
+}
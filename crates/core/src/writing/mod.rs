@@ -1,17 +1,43 @@
 //! Writer trait and it's implements.
 
 mod json;
+mod json_stream;
+mod negotiate;
 mod redirect;
+mod render_error;
+pub(crate) mod responder;
 mod seek;
 mod text;
 
 use http::StatusCode;
-pub use json::Json;
+pub use json::{Json, PrettyJson};
+pub use json_stream::JsonStream;
+pub(crate) use negotiate::{accept_quality, write_negotiated, Serializers};
+pub use negotiate::{Negotiated, SerializeFn};
 pub use redirect::Redirect;
+pub use render_error::RenderError;
+pub use responder::Responder;
 pub use seek::ReadSeeker;
+pub(crate) use text::escape_html;
 pub use text::Text;
 
-use crate::http::header::{HeaderValue, CONTENT_TYPE};
+cfg_feature! {
+    #![feature = "msgpack"]
+    mod msgpack;
+    pub use msgpack::MsgPack;
+}
+cfg_feature! {
+    #![feature = "cbor"]
+    mod cbor;
+    pub use cbor::Cbor;
+}
+cfg_feature! {
+    #![feature = "xml"]
+    mod xml;
+    pub use xml::Xml;
+}
+
+use crate::http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use crate::{async_trait, Depot, Request, Response};
 
 /// `Writer` is a trait allows you to implement custom writing logic for different data types.
@@ -20,6 +46,11 @@ use crate::{async_trait, Depot, Request, Response};
 /// [`Response`] object.
 ///
 /// There are several built-in implementations of the `Writer` trait.
+///
+/// Values returned from handlers are written by `Writer`, so handlers can return data such as [`Json`],
+/// `(StatusCode, T)`, `(StatusCode, HeaderMap, T)`, `Option<T>` which writes `404 Not Found` for `None`, and
+/// `Result<T, E>` which writes the error by it's `Writer`, instead of mutating [`Response`]. Values which do not
+/// implement `Writer` are written by [`Responder`].
 #[async_trait]
 pub trait Writer {
     /// Write data to [`Response`].
@@ -80,6 +111,47 @@ where
     }
 }
 
+/// Set the status code and then write the data, so an error status set by the data, for example a
+/// serialization failure, is not overwritten.
+///
+/// ```
+/// use salvo_core::prelude::*;
+///
+/// #[handler]
+/// async fn create() -> (StatusCode, Json<&'static str>) {
+///     (StatusCode::CREATED, Json("created"))
+/// }
+/// ```
+#[async_trait]
+impl<T> Writer for (StatusCode, T)
+where
+    T: Writer + Send,
+{
+    #[inline]
+    async fn write(self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let (status, data) = self;
+        res.status_code(status);
+        data.write(req, depot, res).await;
+    }
+}
+
+/// Set the status code, write the data and then add the headers, so the headers in tuple take precedence, but an
+/// error status set by the data is not overwritten.
+#[async_trait]
+impl<T> Writer for (StatusCode, HeaderMap, T)
+where
+    T: Writer + Send,
+{
+    #[inline]
+    async fn write(self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let (status, headers, data) = self;
+        res.status_code(status);
+        data.write(req, depot, res).await;
+        // Existing values of the headers are replaced.
+        res.headers_mut().extend(headers);
+    }
+}
+
 #[allow(clippy::unit_arg)]
 impl Scribe for () {
     #[inline]
@@ -117,6 +189,16 @@ impl Scribe for String {
         res.write_body(self).ok();
     }
 }
+/// Cookies added or removed in the jar are written to response as `Set-Cookie` headers.
+#[cfg(feature = "cookie")]
+impl Scribe for cookie::CookieJar {
+    #[inline]
+    fn render(self, res: &mut Response) {
+        for cookie in self.delta() {
+            res.cookies.add(cookie.clone());
+        }
+    }
+}
 impl Scribe for std::convert::Infallible {
     #[inline]
     fn render(self, _res: &mut Response) {}
@@ -154,4 +236,79 @@ mod tests {
         assert_eq!(res.take_string().await.unwrap(), "hello");
         assert_eq!(res.headers().get("content-type").unwrap(), "text/plain; charset=utf-8");
     }
+
+    #[tokio::test]
+    async fn test_write_tuple() {
+        use crate::http::header::{HeaderMap, HeaderValue, CONTENT_TYPE, LOCATION};
+
+        #[handler]
+        async fn created() -> (StatusCode, Json<&'static str>) {
+            (StatusCode::CREATED, Json("created"))
+        }
+        #[handler]
+        async fn moved() -> (StatusCode, HeaderMap, &'static str) {
+            let mut headers = HeaderMap::new();
+            headers.insert(LOCATION, HeaderValue::from_static("/new"));
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+            (StatusCode::MOVED_PERMANENTLY, headers, "<a href=\"/new\">moved</a>")
+        }
+        #[handler]
+        async fn invalid() -> (StatusCode, Json<std::collections::HashMap<Vec<u8>, u8>>) {
+            // Json object keys must be strings.
+            (StatusCode::CREATED, Json([(vec![1], 1)].into()))
+        }
+        let router = Router::new()
+            .push(Router::with_path("created").get(created))
+            .push(Router::with_path("moved").get(moved))
+            .push(Router::with_path("invalid").get(invalid));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/created").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::CREATED));
+        assert_eq!(res.take_string().await.unwrap(), r#""created""#);
+
+        let res = TestClient::get("http://127.0.0.1:5800/moved").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::MOVED_PERMANENTLY));
+        assert_eq!(res.headers().get(LOCATION).unwrap(), "/new");
+        assert_eq!(res.headers().get_all(CONTENT_TYPE).iter().count(), 1);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/html");
+
+        let res = TestClient::get("http://127.0.0.1:5800/invalid").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    #[tokio::test]
+    async fn test_write_option_and_result() {
+        #[handler]
+        async fn find(req: &mut Request) -> Option<&'static str> {
+            req.query::<bool>("found").unwrap_or_default().then_some("found")
+        }
+        #[handler]
+        async fn check(req: &mut Request) -> Result<(StatusCode, &'static str), StatusError> {
+            if req.query::<bool>("ok").unwrap_or_default() {
+                Ok((StatusCode::ACCEPTED, "accepted"))
+            } else {
+                Err(StatusError::conflict())
+            }
+        }
+        let router = Router::new()
+            .push(Router::with_path("find").get(find))
+            .push(Router::with_path("check").get(check));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/find?found=true")
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "found");
+        let res = TestClient::get("http://127.0.0.1:5800/find").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/check?ok=true")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::ACCEPTED));
+        assert_eq!(res.take_string().await.unwrap(), "accepted");
+        let res = TestClient::get("http://127.0.0.1:5800/check").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::CONFLICT));
+    }
 }
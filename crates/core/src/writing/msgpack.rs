@@ -0,0 +1,69 @@
+use serde::Serialize;
+
+use super::Scribe;
+use crate::http::header::{HeaderValue, CONTENT_TYPE};
+use crate::http::Response;
+
+/// Write serializable content to response as MessagePack content. It will set `content-type` to `application/msgpack`.
+///
+/// Structs are serialized as maps with field names, so they can be read by other MessagePack implementations.
+///
+/// It can also be used as handler argument to extract MessagePack body, see [`Extractible`](crate::extract::Extractible).
+pub struct MsgPack<T>(pub T);
+
+impl<T> Scribe for MsgPack<T>
+where
+    T: Serialize + Send,
+{
+    fn render(self, res: &mut Response) {
+        match rmp_serde::to_vec_named(&self.0) {
+            Ok(bytes) => {
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/msgpack"));
+                res.write_body(bytes).ok();
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "MsgPack write error");
+                res.render_failed(e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::http::ParseError;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[tokio::test]
+    async fn test_msgpack_roundtrip() {
+        #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+        struct User {
+            name: String,
+            age: u8,
+        }
+        #[handler]
+        async fn echo(req: &mut Request) -> Result<MsgPack<User>, ParseError> {
+            let user = req.parse_body::<User>().await?;
+            Ok(MsgPack(user))
+        }
+
+        let router = Router::new().push(Router::with_path("echo").post(echo));
+        let user = User {
+            name: "jobs".into(),
+            age: 28,
+        };
+        let mut res = TestClient::post("http://127.0.0.1:5800/echo")
+            .add_header(CONTENT_TYPE, "application/msgpack", true)
+            .body(rmp_serde::to_vec_named(&user).unwrap())
+            .send(router)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/msgpack");
+        let bytes = res.take_bytes(None).await.unwrap();
+        assert_eq!(rmp_serde::from_slice::<User>(&bytes).unwrap(), user);
+    }
+}
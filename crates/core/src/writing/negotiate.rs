@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use mime::Mime;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{Json, Writer};
+use crate::http::header::{HeaderValue, CONTENT_TYPE};
+use crate::http::{Request, Response, StatusError};
+use crate::{async_trait, BoxedError, Depot};
+
+/// Function used to serialize data for a media type, data is converted to [`Value`] before passed to it.
+pub type SerializeFn = fn(&Value) -> Result<Vec<u8>, BoxedError>;
+
+/// Serializers registered by [`Service::serializer`](crate::Service::serializer), they are stored in request's
+/// extensions by the service.
+#[derive(Clone, Default)]
+pub(crate) struct Serializers(pub(crate) Arc<Vec<(Mime, SerializeFn)>>);
+
+impl Serializers {
+    pub(crate) fn insert(&mut self, media_type: Mime, serializer: SerializeFn) {
+        let serializers = Arc::make_mut(&mut self.0);
+        serializers.retain(|(mime, _)| mime.essence_str() != media_type.essence_str());
+        serializers.push((media_type, serializer));
+    }
+
+    fn find(&self, accept: &Mime) -> Option<(Mime, SerializeFn)> {
+        self.0
+            .iter()
+            .find(|(mime, _)| {
+                mime.type_() == accept.type_() && (accept.subtype() == mime::STAR || mime.subtype() == accept.subtype())
+            })
+            .map(|(mime, serializer)| (mime.clone(), *serializer))
+    }
+}
+
+/// Write serializable content to response in format negotiated by request's `Accept` header.
+///
+/// Json is used when `Accept` header is absent or accepts `*/*` or `application/*`. MessagePack, CBOR and XML are
+/// supported if `msgpack`, `cbor` and `xml` features are enabled, other formats registered by
+/// [`Service::serializer`](crate::Service::serializer) are used when they are accepted. If no format is
+/// acceptable, `406 Not Acceptable` is written.
+///
+/// [`Response::render_negotiated`] works the same way for handlers which write the response directly.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_core::writing::Negotiated;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// #[handler]
+/// async fn show() -> Negotiated<User> {
+///     Negotiated(User { name: "jobs".into() })
+/// }
+/// ```
+pub struct Negotiated<T>(pub T);
+
+/// Get quality of `media_type` by request's `Accept` header, it is `1.0` if the header is absent.
+///
+/// Quality is taken from the most specific media range which matches `media_type`, as defined in
+/// [RFC 7231, Section 5.3.2](https://tools.ietf.org/html/rfc7231#section-5.3.2), and it is `0.0` if no range
+/// matches.
+pub(crate) fn accept_quality(req: &Request, media_type: &Mime) -> f32 {
+    let ranges = req.accept_ranges();
+    if ranges.is_empty() {
+        return 1.0;
+    }
+    let mut best: Option<(u8, f32)> = None;
+    for (range, q) in ranges {
+        let specificity = if range.type_() == mime::STAR {
+            0
+        } else if range.type_() != media_type.type_() {
+            continue;
+        } else if range.subtype() == mime::STAR {
+            1
+        } else if range.subtype() != media_type.subtype() {
+            continue;
+        } else {
+            2
+        };
+        if best.map(|(s, _)| specificity > s).unwrap_or(true) {
+            best = Some((specificity, q));
+        }
+    }
+    best.map(|(_, q)| q).unwrap_or(0.0)
+}
+
+fn is_json(mime: &Mime) -> bool {
+    mime.type_() == mime::STAR
+        || (mime.type_() == mime::APPLICATION && (mime.subtype() == mime::STAR || mime.subtype() == mime::JSON))
+}
+
+#[async_trait]
+impl<T> Writer for Negotiated<T>
+where
+    T: Serialize + Send,
+{
+    async fn write(self, req: &mut Request, _depot: &mut Depot, res: &mut Response) {
+        write_negotiated(req, res, self.0);
+    }
+}
+
+pub(crate) fn write_negotiated<T: Serialize + Send>(req: &Request, res: &mut Response, data: T) {
+    if req.accept_ranges().is_empty() {
+        res.render(Json(data));
+        return;
+    }
+    let serializers = req.extensions().get::<Serializers>();
+    for accept in req.accept() {
+        if is_json(&accept) {
+            res.render(Json(data));
+            return;
+        }
+        #[cfg(feature = "msgpack")]
+        if accept.type_() == mime::APPLICATION
+            && ["msgpack", "x-msgpack", "vnd.msgpack"].contains(&accept.subtype().as_str())
+        {
+            res.render(super::MsgPack(data));
+            return;
+        }
+        #[cfg(feature = "cbor")]
+        if accept.type_() == mime::APPLICATION && accept.subtype() == "cbor" {
+            res.render(super::Cbor(data));
+            return;
+        }
+        #[cfg(feature = "xml")]
+        if (accept.type_() == mime::APPLICATION || accept.type_() == mime::TEXT) && accept.subtype() == mime::XML {
+            res.render(super::Xml(data));
+            return;
+        }
+        if let Some((mime, serializer)) = serializers.and_then(|serializers| serializers.find(&accept)) {
+            let bytes = serde_json::to_value(&data)
+                .map_err(BoxedError::from)
+                .and_then(|value| serializer(&value));
+            match bytes {
+                Ok(bytes) => match HeaderValue::from_str(mime.as_ref()) {
+                    Ok(ctype) => {
+                        res.headers_mut().insert(CONTENT_TYPE, ctype);
+                        res.write_body(bytes).ok();
+                    }
+                    Err(e) => {
+                        tracing::error!(error = ?e, "invalid content type");
+                        res.render_failed(e);
+                    }
+                },
+                Err(e) => {
+                    tracing::error!(error = ?e, media_type = %mime, "Negotiated write error");
+                    res.render_failed(e);
+                }
+            }
+            return;
+        }
+    }
+    res.render(StatusError::not_acceptable());
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+    use crate::http::header::ACCEPT;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[derive(Serialize, Debug)]
+    struct User {
+        name: String,
+    }
+
+    #[handler]
+    async fn show() -> Negotiated<User> {
+        Negotiated(User { name: "jobs".into() })
+    }
+
+    fn to_text(value: &Value) -> Result<Vec<u8>, BoxedError> {
+        Ok(format!("name={}", value["name"].as_str().unwrap_or_default()).into_bytes())
+    }
+
+    async fn get(service: &Service, accept: Option<&str>) -> Response {
+        let mut client = TestClient::get("http://127.0.0.1:5800/");
+        if let Some(accept) = accept {
+            client = client.add_header(ACCEPT, accept, true);
+        }
+        client.send(service).await
+    }
+
+    #[tokio::test]
+    async fn test_negotiated() {
+        let service = Service::new(Router::new().get(show)).serializer("text/x-user".parse().unwrap(), to_text);
+
+        for accept in [
+            None,
+            Some(""),
+            Some("*/*"),
+            Some("application/*"),
+            Some("application/json"),
+        ] {
+            let mut res = get(&service, accept).await;
+            assert_eq!(
+                res.headers().get(CONTENT_TYPE).unwrap(),
+                "application/json; charset=utf-8"
+            );
+            assert_eq!(res.take_string().await.unwrap(), r#"{"name":"jobs"}"#);
+        }
+
+        let mut res = get(&service, Some("text/html, text/x-user;q=0.9, application/json;q=0.5")).await;
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/x-user");
+        assert_eq!(res.take_string().await.unwrap(), "name=jobs");
+
+        let mut res = get(&service, Some("text/*")).await;
+        assert_eq!(res.take_string().await.unwrap(), "name=jobs");
+
+        #[cfg(feature = "msgpack")]
+        {
+            let res = get(&service, Some("application/msgpack")).await;
+            assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/msgpack");
+        }
+
+        #[cfg(feature = "xml")]
+        {
+            let mut res = get(&service, Some("text/xml, application/json;q=0.5")).await;
+            assert_eq!(
+                res.headers().get(CONTENT_TYPE).unwrap(),
+                "application/xml; charset=utf-8"
+            );
+            assert!(res
+                .take_string()
+                .await
+                .unwrap()
+                .ends_with("<User><name>jobs</name></User>"));
+        }
+
+        let res = get(&service, Some("application/json;q=0, image/png")).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_ACCEPTABLE));
+
+        // Serializers are registered per service.
+        let service = Service::new(Router::new().get(show));
+        let res = get(&service, Some("text/x-user")).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_ACCEPTABLE));
+    }
+
+    #[tokio::test]
+    async fn test_render_negotiated() {
+        #[handler]
+        async fn show(req: &mut Request, res: &mut Response) {
+            res.render_negotiated(req, User { name: "jobs".into() });
+        }
+        let service = Service::new(Router::new().get(show)).serializer("text/x-user".parse().unwrap(), to_text);
+
+        let mut res = get(&service, None).await;
+        assert_eq!(res.take_string().await.unwrap(), r#"{"name":"jobs"}"#);
+
+        let mut res = get(&service, Some("text/x-user")).await;
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/x-user");
+        assert_eq!(res.take_string().await.unwrap(), "name=jobs");
+
+        let res = get(&service, Some("image/png")).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_ACCEPTABLE));
+    }
+}
@@ -1,14 +1,29 @@
-use super::Scribe;
-use crate::http::header::{HeaderValue, LOCATION};
+use super::{escape_html, Scribe};
+use crate::http::header::{HeaderValue, CONTENT_TYPE, LOCATION};
 use crate::http::uri::Uri;
 use crate::http::{Response, StatusCode};
 use crate::Error;
 
 /// Response that redirects the request to another location.
+///
+/// It can be returned from handler or rendered by [`Response::render`], only the status code and `Location`
+/// header are set, other headers of response are kept.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+///
+/// #[handler]
+/// async fn logout() -> Redirect {
+///     Redirect::see_other("/login")
+/// }
+/// ```
 #[derive(Clone, Debug)]
 pub struct Redirect {
     status_code: StatusCode,
     location: HeaderValue,
+    html_body: bool,
 }
 
 impl Redirect {
@@ -28,6 +43,19 @@ impl Redirect {
         Self::with_status_code(StatusCode::SEE_OTHER, uri).expect("invalid uri")
     }
 
+    /// Create a new [`Redirect`] that uses a [`303 See Other`][mdn] status code.
+    ///
+    /// This is the same as [`Redirect::other`].
+    ///
+    /// # Panics
+    ///
+    /// If `uri` isn't a valid [`Uri`].
+    ///
+    /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/303
+    pub fn see_other(uri: impl TryInto<Uri>) -> Self {
+        Self::other(uri)
+    }
+
     /// Create a new [`Redirect`] that uses a [`307 Temporary Redirect`][mdn] status code.
     ///
     /// # Panics
@@ -67,6 +95,9 @@ impl Redirect {
     }
 
     /// Create a new [`Redirect`] that uses a status code.
+    ///
+    /// Returns error if `status_code` is not a redirection status code or `uri` is not a valid `Location` value,
+    /// use it instead of the panicking constructors when `uri` comes from user input.
     pub fn with_status_code(status_code: StatusCode, uri: impl TryInto<Uri>) -> Result<Self, Error> {
         if !status_code.is_redirection() {
             return Err(Error::other("not a redirection status code"));
@@ -80,15 +111,83 @@ impl Redirect {
                 .and_then(|uri| {
                     HeaderValue::try_from(uri.to_string()).map_err(|_| Error::other("URI isn't a valid header value"))
                 })?,
+            html_body: false,
         })
     }
+
+    /// Sets whether a tiny HTML body with a link to the location is written, for clients which don't follow
+    /// redirects. Default is `false`.
+    pub fn html_body(mut self, enabled: bool) -> Self {
+        self.html_body = enabled;
+        self
+    }
 }
 
 impl Scribe for Redirect {
     #[inline]
     fn render(self, res: &mut Response) {
-        let Self { status_code, location } = self;
+        let Self {
+            status_code,
+            location,
+            html_body,
+        } = self;
         res.status_code(status_code);
+        if html_body {
+            let location = String::from_utf8_lossy(location.as_bytes());
+            let href = escape_html(&location);
+            res.headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+            res.body(
+                format!(r#"<!DOCTYPE html><html><body>Redirecting to <a href="{href}">{href}</a>.</body></html>"#)
+                    .into(),
+            );
+        }
         res.headers_mut().insert(LOCATION, location);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::http::header::LOCATION;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[tokio::test]
+    async fn test_redirect() {
+        #[handler]
+        async fn login() -> Redirect {
+            Redirect::see_other("/login")
+        }
+        #[handler]
+        async fn moved(res: &mut Response) {
+            res.add_header("x-moved", "1", true).unwrap();
+            res.render(Redirect::permanent("/new?a=1&b=2").html_body(true));
+        }
+        let router = Router::new()
+            .push(Router::with_path("logout").get(login))
+            .push(Router::with_path("old").get(moved));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/logout").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::SEE_OTHER));
+        assert_eq!(res.headers().get(LOCATION).unwrap(), "/login");
+        assert_eq!(res.take_string().await.unwrap(), "");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/old").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::PERMANENT_REDIRECT));
+        assert_eq!(res.headers().get(LOCATION).unwrap(), "/new?a=1&b=2");
+        assert_eq!(res.headers().get("x-moved").unwrap(), "1");
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            r#"<!DOCTYPE html><html><body>Redirecting to <a href="/new?a=1&amp;b=2">/new?a=1&amp;b=2</a>.</body></html>"#
+        );
+    }
+
+    #[test]
+    fn test_invalid_redirect() {
+        assert!(Redirect::with_status_code(StatusCode::FOUND, "/ok").is_ok());
+        assert!(Redirect::with_status_code(StatusCode::OK, "/ok").is_err());
+        assert!(Redirect::with_status_code(StatusCode::FOUND, "/bad path").is_err());
+        assert!(Redirect::with_status_code(StatusCode::FOUND, "/bad\npath").is_err());
+    }
+}
@@ -0,0 +1,66 @@
+use std::error::Error as StdError;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+use crate::BoxedError;
+
+/// Error of rendering or writing a response, it is passed to the handler set by
+/// [`Service::render_error_handler`].
+///
+/// Rendering fails before headers are sent, for example when [`Json`] can not serialize data, and the response
+/// can still be replaced. Streaming bodies fail while they are sent, after headers were sent, and only the body
+/// can be aborted, [`RenderError::headers_sent`] tells them apart.
+///
+/// [`Service::render_error_handler`]: crate::Service::render_error_handler
+/// [`Json`]: super::Json
+#[derive(Clone, Debug)]
+pub struct RenderError {
+    error: Arc<dyn StdError + Send + Sync>,
+    headers_sent: bool,
+}
+
+impl RenderError {
+    /// Create a new `RenderError` which happens before headers are sent.
+    #[inline]
+    pub fn new(error: impl Into<BoxedError>) -> Self {
+        Self {
+            error: Arc::from(error.into()),
+            headers_sent: false,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn with_headers_sent(error: impl Into<BoxedError>) -> Self {
+        Self {
+            error: Arc::from(error.into()),
+            headers_sent: true,
+        }
+    }
+
+    /// Get the underlying error.
+    #[inline]
+    pub fn error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        &*self.error
+    }
+
+    /// Returns `true` if the error happens while body is sent, status and headers were already sent, so the
+    /// response can not be changed any more and the body is aborted.
+    #[inline]
+    pub fn headers_sent(&self) -> bool {
+        self.headers_sent
+    }
+}
+
+impl Display for RenderError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl StdError for RenderError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&*self.error)
+    }
+}
@@ -0,0 +1,203 @@
+use std::marker::PhantomData;
+
+use serde::Serialize;
+
+use super::{Json, Scribe, Writer};
+use crate::http::{StatusCode, StatusError};
+use crate::{async_trait, Depot, Request, Response};
+
+/// `Responder` declares how a value returned from handler is written to [`Response`].
+///
+/// Return values which implement [`Writer`] are written by it, other return values are written by `Responder`. It
+/// is implemented for:
+///
+/// - `Result<T, E>` where `T: Serialize` and `E: Into<StatusError>`, `Ok` value is written as [`Json`] and `Err`
+///   value is rendered as [`StatusError`].
+/// - `Option<T>` where `T: Serialize`, `Some` value is written as [`Json`] and `None` writes `404 Not Found`.
+/// - `(StatusCode, T)` where `T: Responder`, the status code is set and then `T` is responded.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct User {
+///     id: u64,
+/// }
+///
+/// #[handler]
+/// async fn show(req: &mut Request) -> Result<User, StatusError> {
+///     let id = req.param::<u64>("id").ok_or_else(StatusError::bad_request)?;
+///     Ok(User { id })
+/// }
+/// #[handler]
+/// async fn find(req: &mut Request) -> Option<User> {
+///     req.query::<u64>("id").map(|id| User { id })
+/// }
+/// #[handler]
+/// async fn create() -> (StatusCode, Option<User>) {
+///     (StatusCode::CREATED, Some(User { id: 2 }))
+/// }
+/// ```
+#[async_trait]
+pub trait Responder {
+    /// Write self to response.
+    async fn respond(self, req: &mut Request, depot: &mut Depot, res: &mut Response);
+}
+
+#[async_trait]
+impl<T, E> Responder for Result<T, E>
+where
+    T: Serialize + Send,
+    E: Into<StatusError> + Send,
+{
+    #[inline]
+    async fn respond(self, _req: &mut Request, _depot: &mut Depot, res: &mut Response) {
+        match self {
+            Ok(data) => Json(data).render(res),
+            Err(e) => res.render(e.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> Responder for Option<T>
+where
+    T: Serialize + Send,
+{
+    #[inline]
+    async fn respond(self, _req: &mut Request, _depot: &mut Depot, res: &mut Response) {
+        match self {
+            Some(data) => Json(data).render(res),
+            None => {
+                res.status_code(StatusCode::NOT_FOUND);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T> Responder for (StatusCode, T)
+where
+    T: Responder + Send,
+{
+    #[inline]
+    async fn respond(self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let (status, data) = self;
+        res.status_code(status);
+        data.respond(req, depot, res).await;
+    }
+}
+
+// Handler macro picks `Writer` for return value if it is implemented, otherwise `Responder`, by autoref
+// specialization, since `Responder` can not be implemented for all `Writer` types without conflicts.
+#[doc(hidden)]
+pub struct Wrap<T>(PhantomData<T>);
+impl<T> Wrap<T> {
+    #[inline]
+    pub fn of(_value: &T) -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub struct WriterKind;
+impl WriterKind {
+    #[inline]
+    pub async fn respond<T: Writer>(self, value: T, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        value.write(req, depot, res).await;
+    }
+}
+
+#[doc(hidden)]
+pub struct ResponderKind;
+impl ResponderKind {
+    #[inline]
+    pub async fn respond<T: Responder>(self, value: T, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        value.respond(req, depot, res).await;
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaWriter {
+    #[inline]
+    fn __responder_kind(&self) -> WriterKind {
+        WriterKind
+    }
+}
+impl<T: Writer> ViaWriter for &Wrap<T> {}
+
+#[doc(hidden)]
+pub trait ViaResponder {
+    #[inline]
+    fn __responder_kind(&self) -> ResponderKind {
+        ResponderKind
+    }
+}
+impl<T> ViaResponder for Wrap<T> {}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[derive(Serialize)]
+    struct User {
+        id: u64,
+    }
+
+    #[tokio::test]
+    async fn test_respond() {
+        #[handler]
+        async fn show(req: &mut Request) -> Result<User, StatusError> {
+            let id = req.query::<u64>("id").ok_or_else(StatusError::bad_request)?;
+            Ok(User { id })
+        }
+        #[handler]
+        async fn find(req: &mut Request) -> Option<User> {
+            req.query::<u64>("id").map(|id| User { id })
+        }
+        #[handler]
+        async fn create() -> (StatusCode, Option<User>) {
+            (StatusCode::CREATED, Some(User { id: 2 }))
+        }
+        #[handler]
+        fn text() -> Result<&'static str, StatusError> {
+            Ok("hello")
+        }
+        let router = Router::new()
+            .push(Router::with_path("show").get(show))
+            .push(Router::with_path("find").get(find))
+            .push(Router::with_path("create").post(create))
+            .push(Router::with_path("text").get(text));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/show?id=1").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(
+            res.headers().get("content-type").unwrap(),
+            "application/json; charset=utf-8"
+        );
+        assert_eq!(res.take_string().await.unwrap(), r#"{"id":1}"#);
+        let res = TestClient::get("http://127.0.0.1:5800/show").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/find?id=3").send(&service).await;
+        assert_eq!(res.take_string().await.unwrap(), r#"{"id":3}"#);
+        let res = TestClient::get("http://127.0.0.1:5800/find").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+
+        let mut res = TestClient::post("http://127.0.0.1:5800/create").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::CREATED));
+        assert_eq!(res.take_string().await.unwrap(), r#"{"id":2}"#);
+
+        // `Writer` is preferred, so `&str` is still written as plain text.
+        let mut res = TestClient::get("http://127.0.0.1:5800/text").send(&service).await;
+        assert_eq!(res.headers().get("content-type").unwrap(), "text/plain; charset=utf-8");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+    }
+}
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use super::Scribe;
 use crate::http::header::{HeaderValue, CONTENT_TYPE};
 use crate::http::Response;
@@ -70,6 +72,25 @@ impl<'a> Scribe for Text<&'a String> {
     }
 }
 
+/// Escapes special characters of `value`, so that it can be used as text or attribute value in HTML and XML.
+pub(crate) fn escape_html(value: &str) -> Cow<'_, str> {
+    if !value.contains(['<', '>', '&', '"', '\'']) {
+        return Cow::Borrowed(value);
+    }
+    let mut escaped = String::with_capacity(value.len() + 16);
+    for c in value.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
@@ -77,6 +98,15 @@ mod tests {
     use super::*;
     use crate::test::{ResponseExt, TestClient};
 
+    #[test]
+    fn test_escape_html() {
+        assert!(matches!(escape_html("hello"), Cow::Borrowed("hello")));
+        assert_eq!(
+            escape_html(r#"<a href="/?a=1&b='2'">"#),
+            "&lt;a href=&quot;/?a=1&amp;b=&#39;2&#39;&quot;&gt;"
+        );
+    }
+
     #[tokio::test]
     async fn test_write_str() {
         #[handler]
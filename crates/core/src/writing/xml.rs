@@ -0,0 +1,100 @@
+use serde::Serialize;
+
+use super::Scribe;
+use crate::http::header::{HeaderValue, CONTENT_TYPE};
+use crate::http::Response;
+
+/// Write serializable content to response as XML content. It will set `content-type` to
+/// `application/xml; charset=utf-8`.
+///
+/// The content starts with XML declaration, the root element is named by the type name of `T`.
+pub struct Xml<T>(pub T);
+
+impl<T> Scribe for Xml<T>
+where
+    T: Serialize + Send,
+{
+    fn render(self, res: &mut Response) {
+        match quick_xml::se::to_string(&self.0) {
+            Ok(content) => {
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/xml; charset=utf-8"));
+                res.write_body(format!(r#"<?xml version="1.0" encoding="UTF-8"?>{content}"#))
+                    .ok();
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "Xml write error");
+                res.render_failed(e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::http::ParseError;
+    use crate::prelude::*;
+    use crate::test::{ResponseExt, TestClient};
+
+    #[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+    struct User {
+        name: String,
+        age: u8,
+    }
+
+    #[handler]
+    async fn echo(req: &mut Request) -> Result<Xml<User>, ParseError> {
+        let user = req.parse_body::<User>().await?;
+        Ok(Xml(user))
+    }
+
+    #[tokio::test]
+    async fn test_xml_roundtrip() {
+        let router = Router::new().push(Router::with_path("echo").post(echo));
+        let mut res = TestClient::post("http://127.0.0.1:5800/echo")
+            .add_header(CONTENT_TYPE, "application/xml", true)
+            .body("<User><name>jobs</name><age>28</age></User>")
+            .send(router)
+            .await;
+        assert_eq!(
+            res.headers().get(CONTENT_TYPE).unwrap(),
+            "application/xml; charset=utf-8"
+        );
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><User><name>jobs</name><age>28</age></User>"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_xml_charset() {
+        let router = Router::new().push(Router::with_path("echo").post(echo));
+        // "Jürgen" encoded in ISO-8859-1.
+        let mut body = b"<User><name>J".to_vec();
+        body.push(0xFC);
+        body.extend_from_slice(b"rgen</name><age>28</age></User>");
+        let mut res = TestClient::post("http://127.0.0.1:5800/echo")
+            .add_header(CONTENT_TYPE, "text/xml; charset=ISO-8859-1", true)
+            .body(body)
+            .send(router)
+            .await;
+        assert!(res.take_string().await.unwrap().contains("<name>Jürgen</name>"));
+    }
+
+    #[tokio::test]
+    async fn test_xml_malformed() {
+        let router = Router::new().push(Router::with_path("echo").post(echo));
+        let mut res = TestClient::post("http://127.0.0.1:5800/echo")
+            .add_header(CONTENT_TYPE, "application/xml", true)
+            .add_header("accept", "application/json", true)
+            .body("<User><name>jobs</name><age>old</age></User>")
+            .send(router)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_REQUEST));
+        let body = res.take_string().await.unwrap();
+        assert!(body.contains("parse xml data failed: invalid digit"));
+    }
+}
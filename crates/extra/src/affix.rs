@@ -1,5 +1,33 @@
 //! affix middleware is used to add any data to depot.
 //!
+//! It is a convenient way to share application state, such as a database pool or configuration, with all
+//! handlers. Values are cloned into depot on every request, so they must be `Clone`, large state should be
+//! wrapped in [`Arc`](std::sync::Arc) to make cloning cheap and to share the same instance.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use salvo_core::prelude::*;
+//! use salvo_extra::affix;
+//!
+//! struct AppState {
+//!     name: String,
+//! }
+//!
+//! #[handler]
+//! async fn hello(depot: &mut Depot) -> String {
+//!     let state = depot.obtain::<Arc<AppState>>().unwrap();
+//!     format!("{} {}", state.name, depot.get::<&str>("version").unwrap())
+//! }
+//!
+//! let state = Arc::new(AppState { name: "salvo".into() });
+//! let router = Router::new()
+//!     .hoop(affix::inject(state).insert("version", "1.0"))
+//!     .get(hello);
+//! ```
+//!
 //! Read more: <https://salvo.rs>
 
 use std::any::TypeId;
@@ -25,13 +53,13 @@ where
     }
 }
 
-/// Inject a value into depot.
+/// Inject a value into depot, it can be got by [`Depot::obtain`] with the value type.
 #[inline]
 pub fn inject<V: Send + Sync + Clone + 'static>(value: V) -> AffixList {
     insert(format!("{:?}", TypeId::of::<V>()), value)
 }
 
-/// Insert a key-value pair into depot.
+/// Insert a key-value pair into depot, the value can be got by [`Depot::get`] with the key.
 #[inline]
 pub fn insert<K, V>(key: K, value: V) -> AffixList
 where
@@ -106,4 +134,34 @@ mod tests {
             .await;
         assert_eq!(content.unwrap(), "salvo:powerful");
     }
+
+    #[tokio::test]
+    async fn test_affix_shared_state() {
+        #[handler]
+        async fn first(depot: &mut Depot) -> String {
+            let user = depot.obtain::<Arc<User>>().unwrap();
+            format!("{:p}", Arc::as_ptr(user))
+        }
+        #[handler]
+        async fn second(depot: &mut Depot) -> String {
+            let user = depot.obtain::<Arc<User>>().unwrap();
+            format!("{:p}", Arc::as_ptr(user))
+        }
+        let user = Arc::new(User {
+            name: "salvo".to_string(),
+        });
+        let expected = format!("{:p}", Arc::as_ptr(&user));
+        let router = Router::with_hoop(inject(user))
+            .push(Router::with_path("first").get(first))
+            .push(Router::with_path("second").get(second));
+        let service = Service::new(router);
+        for path in ["first", "second", "first"] {
+            let content = TestClient::get(format!("http://127.0.0.1:5800/{path}"))
+                .send(&service)
+                .await
+                .take_string()
+                .await;
+            assert_eq!(content.unwrap(), expected);
+        }
+    }
 }
@@ -1,9 +1,32 @@
 //! basic auth middleware.
 //!
+//! Credentials are checked by a [`BasicAuthValidator`], which is implemented for async closures taking username
+//! and password, so they can be looked up in a database. On success the username is stored into depot and can be
+//! got by [`BasicAuthDepotExt::basic_auth_username`], otherwise `401 Unauthorized` is responded with a
+//! `WWW-Authenticate` header and the rest handlers are skipped.
+//!
+//! # Example
+//!
+//! ```
+//! use salvo_core::prelude::*;
+//! use salvo_extra::basic_auth::{BasicAuth, BasicAuthDepotExt};
+//!
+//! #[handler]
+//! async fn hello(depot: &mut Depot) -> String {
+//!     format!("Hello {}", depot.basic_auth_username().unwrap())
+//! }
+//!
+//! let auth = BasicAuth::new(|username: String, password: String| async move {
+//!     username == "root" && password == "pwd"
+//! })
+//! .realm("admin");
+//! let router = Router::with_hoop(auth).get(hello);
+//! ```
+//!
 //! Read more: <https://salvo.rs>
 use std::future::Future;
 
-use base64::engine::{general_purpose, Engine};
+use salvo_core::http::auth::parse_basic_auth;
 use salvo_core::http::header::{HeaderName, AUTHORIZATION, PROXY_AUTHORIZATION};
 use salvo_core::http::{Request, Response, StatusCode};
 use salvo_core::{async_trait, Depot, Error, FlowCtrl, Handler};
@@ -16,6 +39,37 @@ pub trait BasicAuthValidator: Send + Sync {
     /// Validate is that username and password is right.
     fn validate(&self, username: &str, password: &str, depot: &mut Depot) -> impl Future<Output = bool> + Send;
 }
+
+impl<F, Fut> BasicAuthValidator for F
+where
+    F: Fn(String, String) -> Fut + Send + Sync,
+    Fut: Future<Output = bool> + Send,
+{
+    fn validate(&self, username: &str, password: &str, _depot: &mut Depot) -> impl Future<Output = bool> + Send {
+        self(username.to_owned(), password.to_owned())
+    }
+}
+
+/// A [`BasicAuthValidator`] which accepts only one fixed username and password, it is created by
+/// [`BasicAuth::with_credentials`].
+#[derive(Clone, Debug)]
+pub struct FixedCredentials {
+    username: String,
+    password: String,
+}
+
+impl BasicAuthValidator for FixedCredentials {
+    async fn validate(&self, username: &str, password: &str, _depot: &mut Depot) -> bool {
+        // Both are always compared so that the time taken does not tell which one is wrong.
+        let username_matched = constant_time_eq(username.as_bytes(), self.username.as_bytes());
+        let password_matched = constant_time_eq(password.as_bytes(), self.password.as_bytes());
+        username_matched & password_matched
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
 /// BasicAuthDepotExt
 pub trait BasicAuthDepotExt {
     /// Get basic auth username reference.
@@ -49,6 +103,13 @@ where
         }
     }
 
+    /// Sets realm which is sent in `WWW-Authenticate` header, default is `"realm"`.
+    #[inline]
+    pub fn realm(mut self, realm: impl Into<String>) -> Self {
+        self.realm = realm.into();
+        self
+    }
+
     #[doc(hidden)]
     #[inline]
     pub fn set_header_names(mut self, header_names: impl Into<Vec<HeaderName>>) -> Self {
@@ -80,6 +141,17 @@ where
     }
 }
 
+impl BasicAuth<FixedCredentials> {
+    /// Create new `BasicAuth` which accepts only the given username and password.
+    #[inline]
+    pub fn with_credentials(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self::new(FixedCredentials {
+            username: username.into(),
+            password: password.into(),
+        })
+    }
+}
+
 #[doc(hidden)]
 #[inline]
 pub fn ask_credentials(res: &mut Response, realm: impl AsRef<str>) {
@@ -104,18 +176,11 @@ pub fn parse_credentials(req: &Request, header_names: &[HeaderName]) -> Result<(
         }
     }
 
-    if authorization.starts_with("Basic") {
-        if let Some((_, auth)) = authorization.split_once(' ') {
-            let auth = general_purpose::STANDARD.decode(auth).map_err(Error::other)?;
-            let auth = auth.iter().map(|&c| c as char).collect::<String>();
-            if let Some((username, password)) = auth.split_once(':') {
-                return Ok((username.to_owned(), password.to_owned()));
-            } else {
-                return Err(Error::other("`authorization` has bad format"));
-            }
-        }
+    match parse_basic_auth(authorization) {
+        Some((username, Some(password))) => Ok((username, password)),
+        Some((_, None)) => Err(Error::other("`authorization` has bad format")),
+        None => Err(Error::other("parse http header failed")),
     }
-    Err(Error::other("parse http header failed"))
 }
 
 #[async_trait]
@@ -179,4 +244,46 @@ mod tests {
             .unwrap();
         assert!(content.contains("Unauthorized"));
     }
+
+    #[tokio::test]
+    async fn test_basic_auth_closure() {
+        #[handler]
+        async fn whoami(depot: &mut Depot) -> String {
+            depot.basic_auth_username().cloned().unwrap_or_default()
+        }
+        let auth = BasicAuth::new(|username: String, password: String| async move {
+            username.starts_with("user") && password == "pwd"
+        })
+        .realm("admin area");
+        let service = Service::new(Router::with_hoop(auth).goal(whoami));
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/")
+            .basic_auth("user1", Some("pwd"))
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "user1");
+
+        let res = TestClient::get("http://127.0.0.1:5800/").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(
+            res.headers().get("www-authenticate").unwrap(),
+            "Basic realm=\"admin area\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_basic_auth_with_credentials() {
+        let service = Service::new(Router::with_hoop(BasicAuth::with_credentials("root", "pwd")).goal(hello));
+        for (username, password, status) in [
+            ("root", "pwd", StatusCode::OK),
+            ("root", "pw", StatusCode::UNAUTHORIZED),
+            ("admin", "pwd", StatusCode::UNAUTHORIZED),
+        ] {
+            let res = TestClient::get("http://127.0.0.1:5800/")
+                .basic_auth(username, Some(password))
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code.unwrap_or(StatusCode::OK), status);
+        }
+    }
 }
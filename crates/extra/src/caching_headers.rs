@@ -9,9 +9,27 @@ behavior, please use the combined [`CachingHeaders`] handler.
 use etag::EntityTag;
 use salvo_core::http::header::{ETAG, IF_NONE_MATCH};
 use salvo_core::http::headers::{self, HeaderMapExt};
-use salvo_core::http::{ResBody, StatusCode};
+use salvo_core::http::{Method, ResBody, StatusCode};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Request, Response};
 
+/// Key used to store content hash in [`Depot`], [`ETag`] uses it to create weak etag without reading body.
+pub const ETAG_CONTENT_HASH_KEY: &str = "::salvo::caching_headers::etag_content_hash";
+
+/// How [`ETag`] creates etags for responses without `ETag` header.
+#[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ETagMode {
+    /// Use content hash in [`Depot`] if it exists, otherwise hash the buffered body.
+    #[default]
+    Auto,
+    /// Create strong etags from the xxh3 hash of the body, the body must be fully buffered,
+    /// streamed bodies get no etag.
+    Strong,
+    /// Create weak etags from content hash in [`Depot`] only, the body is never read, so it works for
+    /// streamed bodies.
+    Weak,
+}
+
 /**
 # Etag and If-None-Match header handler
 
@@ -23,10 +41,25 @@ header, compares these values and sends a
 [`304 not modified`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/304) status,
 omitting the response body.
 
+It only applies to `GET` and `HEAD` requests with `200 OK` responses.
+
+## Etag sources
+
+The etag is taken from the following sources in order:
+
+1. `ETag` header set by other handlers.
+2. Content hash stored in [`Depot`] with key [`ETAG_CONTENT_HASH_KEY`], a weak etag `W/"<hash>"`
+   is created from it, so streamed bodies are supported without buffering.
+3. Fast xxh3 hash of the response body, a strong etag is created from it, this requires the body is
+   fully buffered.
+
+Use [`ETag::mode`] to only use one of the last two sources, [`ETagMode::Strong`] only hashes buffered
+bodies and [`ETagMode::Weak`] only uses the content hash in [`Depot`].
+
 ## Streamed bodies
 
 Note that this handler does not currently provide an etag trailer for
-streamed bodies, but may do so in the future.
+streamed bodies, use [`ETAG_CONTENT_HASH_KEY`] instead.
 
 ## Strong vs weak comparison
 
@@ -41,6 +74,7 @@ Read more: <https://salvo.rs>
 #[derive(Default, Clone, Copy, Debug)]
 pub struct ETag {
     strong: bool,
+    mode: ETagMode,
 }
 
 impl ETag {
@@ -57,13 +91,22 @@ impl ETag {
         self.strong = true;
         self
     }
+
+    /// Sets how etags are created for responses without `ETag` header, default is [`ETagMode::Auto`].
+    pub fn mode(mut self, mode: ETagMode) -> Self {
+        self.mode = mode;
+        self
+    }
 }
 
 #[async_trait]
 impl Handler for ETag {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
         ctrl.call_next(req, depot, res).await;
-        if ctrl.is_ceased() {
+        if ctrl.is_ceased()
+            || !matches!(*req.method(), Method::GET | Method::HEAD)
+            || res.status_code.unwrap_or(StatusCode::OK) != StatusCode::OK
+        {
             return;
         }
 
@@ -73,40 +116,46 @@ impl Handler for ETag {
             .and_then(|etag| etag.to_str().ok())
             .and_then(|etag| etag.parse::<EntityTag>().ok());
 
-        let etag = req
+        let etag = res
             .headers()
             .get(ETAG)
             .and_then(|etag| etag.to_str().ok())
             .and_then(|etag| etag.parse().ok())
             .or_else(|| {
-                let etag = match &res.body {
-                    ResBody::Once(bytes) => Some(EntityTag::from_data(bytes)),
-                    ResBody::Chunks(bytes) => {
-                        let tags = bytes
-                            .iter()
-                            .map(|item| EntityTag::from_data(item).tag().to_owned())
-                            .collect::<Vec<_>>()
-                            .concat();
-                        Some(EntityTag::from_data(tags.as_bytes()))
-                    }
-                    ResBody::Stream(_) => {
-                        tracing::debug!("etag not supported for streaming body");
-                        None
-                    }
-                    ResBody::None => {
-                        tracing::debug!("etag not supported for empty body");
-                        None
+                let hash = match self.mode {
+                    ETagMode::Strong => None,
+                    ETagMode::Auto | ETagMode::Weak => depot.get::<String>(ETAG_CONTENT_HASH_KEY).ok(),
+                };
+                let etag = if let Some(hash) = hash {
+                    EntityTag::checked_weak(hash).ok()
+                } else if self.mode == ETagMode::Weak {
+                    tracing::debug!("no content hash in depot for weak etag");
+                    None
+                } else {
+                    match &res.body {
+                        ResBody::Once(bytes) => Some(EntityTag::from_data(bytes)),
+                        ResBody::Chunks(bytes) => {
+                            let tags = bytes
+                                .iter()
+                                .map(|item| EntityTag::from_data(item).tag().to_owned())
+                                .collect::<Vec<_>>()
+                                .concat();
+                            Some(EntityTag::from_data(tags.as_bytes()))
+                        }
+                        ResBody::Stream(_) => {
+                            tracing::debug!("etag not supported for streaming body");
+                            None
+                        }
+                        ResBody::None => {
+                            tracing::debug!("etag not supported for empty body");
+                            None
+                        }
+                        _ => None,
                     }
-                    _ => None,
                 };
 
                 if let Some(etag) = &etag {
-                    match etag.to_string().parse::<headers::ETag>() {
-                        Ok(etag) => res.headers_mut().typed_insert(etag),
-                        Err(e) => {
-                            tracing::error!(error = ?e, "failed to parse etag");
-                        }
-                    }
+                    res.etag(etag.to_string());
                 }
                 etag
             });
@@ -216,4 +265,66 @@ mod tests {
         assert_eq!(respone.status_code, Some(StatusCode::NOT_MODIFIED));
         assert!(respone.body.is_none());
     }
+
+    #[tokio::test]
+    async fn test_etag_sources() {
+        #[handler]
+        async fn stream(depot: &mut Depot, res: &mut Response) {
+            depot.insert(ETAG_CONTENT_HASH_KEY, "v1".to_owned());
+            res.stream(tokio_stream::iter(vec![Ok::<_, std::io::Error>("Hello World")]));
+        }
+        #[handler]
+        async fn tagged(res: &mut Response) {
+            res.headers_mut().insert(ETAG, HeaderValue::from_static("\"custom\""));
+            res.render("Hello World");
+        }
+        let router = Router::with_hoop(ETag::new())
+            .push(Router::with_path("stream").get(stream))
+            .push(Router::with_path("tagged").get(tagged))
+            .push(Router::with_path("hello").get(hello).post(hello));
+        let service = Service::new(router);
+
+        let respone = TestClient::get("http://127.0.0.1:5800/stream").send(&service).await;
+        assert_eq!(respone.headers().get(ETAG).unwrap(), "W/\"v1\"");
+        let respone = TestClient::get("http://127.0.0.1:5800/stream")
+            .add_header(IF_NONE_MATCH, "W/\"v1\"", true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.status_code, Some(StatusCode::NOT_MODIFIED));
+
+        let respone = TestClient::get("http://127.0.0.1:5800/tagged")
+            .add_header(IF_NONE_MATCH, "\"custom\"", true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.status_code, Some(StatusCode::NOT_MODIFIED));
+
+        let respone = TestClient::post("http://127.0.0.1:5800/hello").send(&service).await;
+        assert!(respone.headers().get(ETAG).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_etag_modes() {
+        #[handler]
+        async fn hashed(depot: &mut Depot, res: &mut Response) {
+            depot.insert(ETAG_CONTENT_HASH_KEY, "v1".to_owned());
+            res.render("Hello World");
+        }
+        let routes = || {
+            Router::new()
+                .push(Router::with_path("hashed").get(hashed))
+                .push(Router::with_path("hello").get(hello))
+        };
+
+        let service = Service::new(Router::with_hoop(ETag::new().mode(ETagMode::Strong)).push(routes()));
+        let respone = TestClient::get("http://127.0.0.1:5800/hashed").send(&service).await;
+        let etag = respone.headers().get(ETAG).unwrap().to_str().unwrap();
+        assert!(!etag.starts_with("W/"));
+        assert_eq!(etag, EntityTag::from_data(b"Hello World").to_string());
+
+        let service = Service::new(Router::with_hoop(ETag::new().mode(ETagMode::Weak)).push(routes()));
+        let respone = TestClient::get("http://127.0.0.1:5800/hashed").send(&service).await;
+        assert_eq!(respone.headers().get(ETAG).unwrap(), "W/\"v1\"");
+        let respone = TestClient::get("http://127.0.0.1:5800/hello").send(&service).await;
+        assert!(respone.headers().get(ETAG).is_none());
+    }
 }
@@ -0,0 +1,133 @@
+//! Request deadline middleware.
+//!
+//! Read more: <https://salvo.rs>
+use salvo_core::http::{
+    header::HeaderName, Deadline, Request, Response, StatusError, DEADLINE_HEADER, DEADLINE_HEADER_KEY,
+};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+/// A middleware that parses the deadline propagated by upstream services.
+///
+/// The header value is unix time in milliseconds, the parsed [`Deadline`] is set to depot and can be got by
+/// [`Depot::deadline`], and the header name is saved in depot with [`DEADLINE_HEADER_KEY`], so that proxies forward
+/// the deadline in the same header. If the deadline has already passed, `504 Gateway Timeout` is responded and the rest of the
+/// handler chain is skipped. Invalid header value is ignored.
+///
+/// [`Timeout`](crate::timeout::Timeout) middleware uses the remaining budget of deadline if it is shorter than its
+/// own time limit.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::deadline::RequestDeadline;
+///
+/// #[handler]
+/// async fn hello(depot: &mut Depot) -> String {
+///     match depot.deadline() {
+///         Some(deadline) => format!("remaining: {:?}", deadline.remaining()),
+///         None => "no deadline".into(),
+///     }
+/// }
+///
+/// let router = Router::new().hoop(RequestDeadline::new()).get(hello);
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RequestDeadline {
+    /// The header name for deadline. Default is `x-request-deadline`.
+    pub header_name: HeaderName,
+}
+
+impl RequestDeadline {
+    /// Create new `RequestDeadline` middleware.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            header_name: HeaderName::from_static(DEADLINE_HEADER),
+        }
+    }
+
+    /// Set the header name for deadline.
+    #[inline]
+    pub fn header_name(mut self, name: HeaderName) -> Self {
+        self.header_name = name;
+        self
+    }
+}
+
+impl Default for RequestDeadline {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Handler for RequestDeadline {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let deadline = req
+            .headers()
+            .get(&self.header_name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Deadline::parse);
+        if let Some(deadline) = deadline {
+            if deadline.is_expired() {
+                res.render(StatusError::gateway_timeout().brief("Request deadline exceeded."));
+                ctrl.skip_rest();
+                return;
+            }
+            depot.set_deadline(deadline);
+            depot.insert(DEADLINE_HEADER_KEY, self.header_name.clone());
+        }
+        ctrl.call_next(req, depot, res).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn remaining(depot: &mut Depot) -> String {
+        depot
+            .deadline()
+            .map(|deadline| deadline.remaining().as_secs().to_string())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_request_deadline() {
+        let service = Service::new(Router::new().hoop(RequestDeadline::new()).get(remaining));
+
+        let deadline = Deadline::after(Duration::from_secs(30));
+        let content = TestClient::get("http://127.0.0.1:5801")
+            .add_header(DEADLINE_HEADER, deadline.unix_millis().to_string(), true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert!(content == "29" || content == "30", "{content}");
+
+        let content = TestClient::get("http://127.0.0.1:5801")
+            .add_header(DEADLINE_HEADER, "invalid", true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "");
+
+        let res = TestClient::get("http://127.0.0.1:5801")
+            .add_header(DEADLINE_HEADER, "1700000000000", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::GATEWAY_TIMEOUT));
+    }
+}
@@ -10,11 +10,52 @@ use salvo_core::http::{Request, ResBody, Response};
 use salvo_core::writing::Redirect;
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
-/// Middleware for force redirect to http uri.
-#[derive(Default)]
+/// Path prefix of ACME HTTP-01 challenge requests.
+const ACME_CHALLENGE_PATH: &str = "/.well-known/acme-challenge/";
+
+/// Middleware for force redirect to https uri.
+///
+/// Requests are redirected with `308 Permanent Redirect`, path and query are preserved. ACME HTTP-01 challenge
+/// requests under `/.well-known/acme-challenge/` are passed through by default, so it can be used together with
+/// `AcmeListener::http01_challege` on the plaintext listener.
+///
+/// # Example
+///
+/// ```ignore
+/// use salvo_core::prelude::*;
+/// use salvo_extra::force_https::ForceHttps;
+///
+/// #[handler]
+/// async fn hello() -> &'static str {
+///     "Hello World"
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut router = Router::new().get(hello);
+///     let listener = TcpListener::new("0.0.0.0:443")
+///         .acme()
+///         .add_domain("example.com")
+///         .http01_challege(&mut router)
+///         .join(TcpListener::new("0.0.0.0:80"));
+///     let acceptor = listener.bind().await;
+///     let service = Service::new(router).hoop(ForceHttps::new().https_port(443));
+///     Server::new(acceptor).serve(service).await;
+/// }
+/// ```
 pub struct ForceHttps {
     https_port: Option<u16>,
     skipper: Option<Box<dyn Skipper>>,
+    acme_challenge_passthrough: bool,
+}
+impl Default for ForceHttps {
+    fn default() -> Self {
+        Self {
+            https_port: None,
+            skipper: None,
+            acme_challenge_passthrough: true,
+        }
+    }
 }
 impl ForceHttps {
     /// Create new `ForceHttps` middleware.
@@ -37,12 +78,22 @@ impl ForceHttps {
             ..self
         }
     }
+
+    /// Sets whether ACME HTTP-01 challenge requests should be passed through without redirecting, default is `true`.
+    pub fn acme_challenge_passthrough(self, passthrough: bool) -> Self {
+        Self {
+            acme_challenge_passthrough: passthrough,
+            ..self
+        }
+    }
 }
 
 #[async_trait]
 impl Handler for ForceHttps {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
         if req.uri().scheme() == Some(&Scheme::HTTPS)
+            || *req.scheme() == Scheme::HTTPS
+            || (self.acme_challenge_passthrough && req.uri().path().starts_with(ACME_CHALLENGE_PATH))
             || self
                 .skipper
                 .as_ref()
@@ -79,7 +130,7 @@ fn redirect_host(host: &str, https_port: Option<u16>) -> Cow<'_, str> {
 mod tests {
     use salvo_core::http::header::{HOST, LOCATION};
     use salvo_core::prelude::*;
-    use salvo_core::test::TestClient;
+    use salvo_core::test::{ResponseExt, TestClient};
 
     use super::*;
 
@@ -109,4 +160,33 @@ mod tests {
             Some(&"https://127.0.0.1:1234/".parse().unwrap())
         );
     }
+
+    #[tokio::test]
+    async fn test_acme_challenge_passthrough() {
+        #[handler]
+        async fn challenge(req: &mut Request) -> String {
+            req.param::<String>("token").unwrap_or_default()
+        }
+        let router = Router::new()
+            .push(Router::with_path(".well-known/acme-challenge/<token>").goal(challenge))
+            .push(Router::with_path("hello").goal(hello));
+        let service = Service::new(router).hoop(ForceHttps::new());
+
+        let response = TestClient::get("http://127.0.0.1:5800/hello?name=jobs")
+            .add_header(HOST, "example.com", true)
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code, Some(StatusCode::PERMANENT_REDIRECT));
+        assert_eq!(
+            response.headers().get(LOCATION),
+            Some(&"https://example.com/hello?name=jobs".parse().unwrap())
+        );
+
+        let mut response = TestClient::get("http://127.0.0.1:5800/.well-known/acme-challenge/abc")
+            .add_header(HOST, "example.com", true)
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code, Some(StatusCode::OK));
+        assert_eq!(response.take_string().await.unwrap(), "abc");
+    }
 }
@@ -0,0 +1,281 @@
+//! Middleware which resolves the real client address from `Forwarded` and `X-Forwarded-For` headers.
+//!
+//! Behind reverse proxies, [`Request::remote_addr`] is the address of the nearest proxy. [`ForwardedHeader`]
+//! walks the forwarded addresses from right to left, each hop is accepted only if the address it is received from
+//! is a trusted proxy, so a client can not spoof its address by sending these headers itself. Use
+//! [`ProxyProtocol`](salvo_core::conn::ProxyProtocol) instead if the proxy works on TCP layer.
+//!
+//! Read more: <https://salvo.rs>
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use salvo_core::http::header::{HeaderName, FORWARDED};
+use salvo_core::http::uri::Scheme;
+use salvo_core::http::{Request, Response};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+#[doc(no_inline)]
+pub use ipnet::IpNet;
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+
+/// A middleware which sets [`Request::remote_addr`] to the real client address and the scheme of request to the
+/// one used by the client, from headers added by trusted proxies.
+///
+/// `Forwarded` header defined in [RFC 7239](https://www.rfc-editor.org/rfc/rfc7239) is used if it is present,
+/// otherwise `X-Forwarded-For` and `X-Forwarded-Proto` are used. The address of the first untrusted hop from right
+/// is the client, and its port is `0` if the proxy does not send it. Headers are ignored if the request is not
+/// received from a trusted proxy. [`Request::conn_info`] still holds the address of the connection.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::forwarded_header::ForwardedHeader;
+///
+/// #[handler]
+/// async fn hello(req: &mut Request) -> String {
+///     format!("Hello {}", req.remote_addr())
+/// }
+///
+/// let forwarded = ForwardedHeader::new().trusted_proxies(["10.0.0.0/8".parse().unwrap()]);
+/// let router = Router::with_hoop(forwarded).get(hello);
+/// ```
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ForwardedHeader {
+    /// Networks of trusted proxies, default is empty, so nothing is trusted.
+    pub trusted_proxies: Vec<IpNet>,
+}
+
+impl ForwardedHeader {
+    /// Create new `ForwardedHeader` middleware.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets networks of trusted proxies, such as `10.0.0.0/8`.
+    #[inline]
+    pub fn trusted_proxies(mut self, proxies: impl IntoIterator<Item = IpNet>) -> Self {
+        self.trusted_proxies = proxies.into_iter().collect();
+        self
+    }
+
+    /// Adds a network of trusted proxies.
+    #[inline]
+    pub fn add_trusted_proxy(mut self, proxy: impl Into<IpNet>) -> Self {
+        self.trusted_proxies.push(proxy.into());
+        self
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(ip) => ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(ip)),
+            ip => ip,
+        };
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+}
+
+/// A hop added by a proxy, `addr` is `None` if the node is unknown or obfuscated.
+#[derive(Debug, PartialEq)]
+struct Hop {
+    addr: Option<SocketAddr>,
+    proto: Option<String>,
+}
+
+fn parse_forwarded(req: &Request) -> Vec<Hop> {
+    let mut hops = Vec::new();
+    for value in req.headers().get_all(FORWARDED) {
+        let Ok(value) = value.to_str() else {
+            return Vec::new();
+        };
+        for element in value.split(',') {
+            let mut hop = Hop {
+                addr: None,
+                proto: None,
+            };
+            for pair in element.split(';') {
+                let Some((key, value)) = pair.split_once('=') else {
+                    continue;
+                };
+                let value = value.trim().trim_matches('"');
+                match key.trim().to_ascii_lowercase().as_str() {
+                    "for" => hop.addr = parse_node(value),
+                    "proto" => hop.proto = Some(value.to_ascii_lowercase()),
+                    _ => {}
+                }
+            }
+            hops.push(hop);
+        }
+    }
+    hops
+}
+
+fn parse_x_forwarded(req: &Request) -> Vec<Hop> {
+    let values = |name| {
+        req.headers()
+            .get_all(name)
+            .iter()
+            .flat_map(|v| v.to_str().unwrap_or_default().split(','))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>()
+    };
+    let mut hops = values(X_FORWARDED_FOR)
+        .into_iter()
+        .map(|node| Hop {
+            addr: parse_node(node),
+            proto: None,
+        })
+        .collect::<Vec<_>>();
+    let protos = values(X_FORWARDED_PROTO);
+    if protos.len() == hops.len() {
+        for (hop, proto) in hops.iter_mut().zip(protos) {
+            hop.proto = Some(proto.to_ascii_lowercase());
+        }
+    } else if let (Some(hop), Some(proto)) = (hops.last_mut(), protos.last()) {
+        // Only the nearest proxy is known to set the protocol.
+        hop.proto = Some(proto.to_ascii_lowercase());
+    }
+    hops
+}
+
+/// Parses a node like `192.0.2.43`, `192.0.2.43:47011`, `[2001:db8::1]:4711` or `2001:db8::1`.
+fn parse_node(node: &str) -> Option<SocketAddr> {
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(SocketAddr::new(ip, 0));
+    }
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr);
+    }
+    // Obfuscated port, such as `192.0.2.43:_abc` or `[2001:db8::1]:_abc`.
+    let ip = match node.strip_prefix('[') {
+        Some(node) => IpAddr::V6(node.split_once(']')?.0.parse::<Ipv6Addr>().ok()?),
+        None => IpAddr::V4(node.split_once(':')?.0.parse::<Ipv4Addr>().ok()?),
+    };
+    Some(SocketAddr::new(ip, 0))
+}
+
+#[async_trait]
+impl Handler for ForwardedHeader {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        if let Some(peer) = req.remote_addr().clone().into_std() {
+            if self.is_trusted(peer.ip()) {
+                let hops = if req.headers().contains_key(FORWARDED) {
+                    parse_forwarded(req)
+                } else {
+                    parse_x_forwarded(req)
+                };
+                let mut client = peer;
+                let mut index = hops.len();
+                while index > 0 && self.is_trusted(client.ip()) {
+                    match hops[index - 1].addr {
+                        Some(addr) => {
+                            client = addr;
+                            index -= 1;
+                        }
+                        None => break,
+                    }
+                }
+                if index < hops.len() {
+                    *req.remote_addr_mut() = client.into();
+                    match hops[index..].iter().find_map(|hop| hop.proto.as_deref()) {
+                        Some("https") => *req.scheme_mut() = Scheme::HTTPS,
+                        Some("http") => *req.scheme_mut() = Scheme::HTTP,
+                        _ => {}
+                    }
+                }
+            }
+        }
+        ctrl.call_next(req, depot, res).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn whoami(req: &mut Request) -> String {
+        format!("{} {}", req.remote_addr().clone().into_std().unwrap(), req.scheme())
+    }
+
+    async fn access(service: &Service, peer: &str, headers: &[(&'static str, &'static str)]) -> String {
+        let mut client = TestClient::get("http://127.0.0.1:5801/");
+        for (name, value) in headers {
+            client = client.add_header(*name, *value, false);
+        }
+        let mut req = client.build();
+        *req.remote_addr_mut() = peer.parse::<SocketAddr>().unwrap().into();
+        service.handle(req).await.take_string().await.unwrap()
+    }
+
+    fn service() -> Service {
+        let forwarded = ForwardedHeader::new()
+            .trusted_proxies(["10.0.0.0/8".parse().unwrap()])
+            .add_trusted_proxy("::1/128".parse::<IpNet>().unwrap());
+        Service::new(Router::with_hoop(forwarded).get(whoami))
+    }
+
+    #[test]
+    fn test_parse_node() {
+        assert_eq!(parse_node("192.0.2.43"), Some(([192, 0, 2, 43], 0).into()));
+        assert_eq!(parse_node("192.0.2.43:47011"), Some(([192, 0, 2, 43], 47011).into()));
+        assert_eq!(parse_node("192.0.2.43:_abc"), Some(([192, 0, 2, 43], 0).into()));
+        assert_eq!(
+            parse_node("[2001:db8::1]:4711"),
+            Some("[2001:db8::1]:4711".parse().unwrap())
+        );
+        assert_eq!(parse_node("2001:db8::1"), Some("[2001:db8::1]:0".parse().unwrap()));
+        assert_eq!(parse_node("unknown"), None);
+        assert_eq!(parse_node("_hidden"), None);
+    }
+
+    #[tokio::test]
+    async fn test_x_forwarded_for() {
+        let service = service();
+        let headers = [
+            ("x-forwarded-for", "203.0.113.7, 10.0.0.2"),
+            ("x-forwarded-proto", "https"),
+        ];
+        assert_eq!(access(&service, "10.0.0.1:80", &headers).await, "203.0.113.7:0 https");
+        // Multiple header lines are joined in order.
+        let headers = [("x-forwarded-for", "203.0.113.7"), ("x-forwarded-for", "10.0.0.2:8080")];
+        assert_eq!(access(&service, "[::1]:80", &headers).await, "203.0.113.7:0 http");
+    }
+
+    #[tokio::test]
+    async fn test_forwarded() {
+        let service = service();
+        let headers = [(
+            "forwarded",
+            r#"for=198.51.100.17;proto=http, For="[2001:db8:cafe::17]:4711";proto=https;by=10.0.0.2"#,
+        )];
+        assert_eq!(
+            access(&service, "10.0.0.1:80", &headers).await,
+            "[2001:db8:cafe::17]:4711 https"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spoofed_headers() {
+        let service = service();
+        // Request is not received from a trusted proxy, headers are ignored.
+        let headers = [("x-forwarded-for", "10.0.0.5"), ("x-forwarded-proto", "https")];
+        assert_eq!(
+            access(&service, "203.0.113.7:5000", &headers).await,
+            "203.0.113.7:5000 http"
+        );
+        // Client prepends a spoofed address, only hops added by trusted proxies are used.
+        let headers = [("x-forwarded-for", "1.2.3.4, 203.0.113.7, 10.0.0.2")];
+        assert_eq!(access(&service, "10.0.0.1:80", &headers).await, "203.0.113.7:0 http");
+        // Hops stop at unknown node.
+        let headers = [("forwarded", "for=198.51.100.17, for=unknown")];
+        assert_eq!(access(&service, "10.0.0.1:80", &headers).await, "10.0.0.1:80 http");
+    }
+}
@@ -0,0 +1,192 @@
+//! Health check handler, it is used as liveness or readiness probe of Kubernetes.
+//!
+//! Read more: <https://salvo.rs>
+use std::fmt::{self, Debug, Display, Formatter};
+use std::future::Future;
+
+use futures_util::future::{join_all, BoxFuture};
+use serde_json::{json, Map, Value};
+
+use salvo_core::http::{Request, Response, StatusCode};
+use salvo_core::writing::Json;
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+type HealthCheck = Box<dyn Fn() -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
+/// Kind of health probe.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum HealthProbe {
+    /// Responds `200 OK` as long as the process is alive, checks are not run.
+    Liveness,
+    /// Runs all checks, responds `503 Service Unavailable` if any check fails.
+    Readiness,
+}
+
+/// Health check handler.
+///
+/// All checks are run concurrently, it responds `200 OK` with body
+/// `{"status": "ok", "checks": {"db": "ok"}}` if all checks pass, otherwise it responds
+/// `503 Service Unavailable` with the error of the first failing check in registration order.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::health::HealthHandler;
+///
+/// async fn ping_db() -> Result<(), String> {
+///     Ok(())
+/// }
+///
+/// let router = Router::new()
+///     .push(Router::with_path("livez").get(HealthHandler::liveness()))
+///     .push(Router::with_path("readyz").get(HealthHandler::readiness().add_check("db", ping_db)));
+/// ```
+pub struct HealthHandler {
+    probe: HealthProbe,
+    checks: Vec<(String, HealthCheck)>,
+}
+
+impl Debug for HealthHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HealthHandler")
+            .field("probe", &self.probe)
+            .field("checks", &self.checks.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Default for HealthHandler {
+    #[inline]
+    fn default() -> Self {
+        Self::readiness()
+    }
+}
+
+impl HealthHandler {
+    /// Create new `HealthHandler` with probe kind.
+    #[inline]
+    pub fn new(probe: HealthProbe) -> Self {
+        Self {
+            probe,
+            checks: Vec::new(),
+        }
+    }
+    /// Create new liveness `HealthHandler`, it always responds `200 OK`.
+    #[inline]
+    pub fn liveness() -> Self {
+        Self::new(HealthProbe::Liveness)
+    }
+    /// Create new readiness `HealthHandler`, it runs all checks.
+    #[inline]
+    pub fn readiness() -> Self {
+        Self::new(HealthProbe::Readiness)
+    }
+
+    /// Get probe kind.
+    #[inline]
+    pub fn probe(&self) -> HealthProbe {
+        self.probe
+    }
+
+    /// Add a named check, the check is failed if it returns an error.
+    #[inline]
+    pub fn add_check<F, Fut, E>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        let check = move || -> BoxFuture<'static, Result<(), String>> {
+            let fut = check();
+            Box::pin(async move { fut.await.map_err(|e| e.to_string()) })
+        };
+        self.checks.push((name.into(), Box::new(check)));
+        self
+    }
+}
+
+#[async_trait]
+impl Handler for HealthHandler {
+    async fn handle(&self, _req: &mut Request, _depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
+        if self.probe == HealthProbe::Liveness {
+            res.render(Json(json!({ "status": "ok" })));
+            return;
+        }
+        let results = join_all(self.checks.iter().map(|(_, check)| check())).await;
+        let mut checks = Map::new();
+        let mut error = None;
+        for ((name, _), result) in self.checks.iter().zip(results) {
+            match result {
+                Ok(()) => {
+                    checks.insert(name.clone(), Value::from("ok"));
+                }
+                Err(e) => {
+                    if error.is_none() {
+                        error = Some(format!("{name}: {e}"));
+                    }
+                    checks.insert(name.clone(), Value::from(e));
+                }
+            }
+        }
+        if let Some(error) = error {
+            res.status_code(StatusCode::SERVICE_UNAVAILABLE);
+            res.render(Json(json!({ "status": "error", "error": error, "checks": checks })));
+        } else {
+            res.render(Json(json!({ "status": "ok", "checks": checks })));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_health() {
+        let router = Router::new()
+            .push(Router::with_path("livez").get(HealthHandler::liveness().add_check("db", || async { Err("down") })))
+            .push(
+                Router::with_path("readyz").get(
+                    HealthHandler::readiness()
+                        .add_check("db", || async { Ok::<_, String>(()) })
+                        .add_check("disk", || async { Ok::<_, String>(()) }),
+                ),
+            )
+            .push(
+                Router::with_path("failing").get(
+                    HealthHandler::readiness()
+                        .add_check("db", || async { Ok::<_, String>(()) })
+                        .add_check("upstream", || async { Err("connection refused") })
+                        .add_check("disk", || async { Err("no space left") }),
+                ),
+            );
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/livez").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_json::<Value>().await.unwrap(), json!({ "status": "ok" }));
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/readyz").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(
+            res.take_json::<Value>().await.unwrap(),
+            json!({ "status": "ok", "checks": { "db": "ok", "disk": "ok" } })
+        );
+
+        let mut res = TestClient::get("http://127.0.0.1:5800/failing").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(
+            res.take_json::<Value>().await.unwrap(),
+            json!({
+                "status": "error",
+                "error": "upstream: connection refused",
+                "checks": { "db": "ok", "upstream": "connection refused", "disk": "no space left" }
+            })
+        );
+    }
+}
@@ -71,3 +71,27 @@ cfg_feature! {
     #![feature = "request-id"]
     pub mod request_id;
 }
+cfg_feature! {
+    #![feature = "range"]
+    pub mod range;
+}
+cfg_feature! {
+    #![feature = "locale"]
+    pub mod locale;
+}
+cfg_feature! {
+    #![feature = "health"]
+    pub mod health;
+}
+cfg_feature! {
+    #![feature = "deadline"]
+    pub mod deadline;
+}
+cfg_feature! {
+    #![feature = "template"]
+    pub mod template;
+}
+cfg_feature! {
+    #![feature = "forwarded-header"]
+    pub mod forwarded_header;
+}
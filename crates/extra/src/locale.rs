@@ -0,0 +1,149 @@
+//! Locale middleware, it selects the preferred language by `Accept-Language` header.
+//!
+//! Read more: <https://salvo.rs>
+use salvo_core::http::{Request, Response};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+/// Key for the selected language in depot.
+pub const LANGUAGE_KEY: &str = "::salvo::locale::language";
+
+/// Extension trait for getting the selected language from depot.
+pub trait LocaleDepotExt {
+    /// Get the language selected by [`Locale`].
+    fn language(&self) -> Option<&str>;
+}
+
+impl LocaleDepotExt for Depot {
+    #[inline]
+    fn language(&self) -> Option<&str> {
+        self.get::<String>(LANGUAGE_KEY).ok().map(|s| s.as_str())
+    }
+}
+
+/// A middleware that selects the preferred language from supported languages by [`Request::preferred_language`],
+/// and inserts it into depot so that downstream handlers and templates can use it.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::locale::{Locale, LocaleDepotExt};
+///
+/// #[handler]
+/// async fn hello(depot: &mut Depot) -> String {
+///     match depot.language() {
+///         Some("zh-CN") => "你好".into(),
+///         _ => "Hello".into(),
+///     }
+/// }
+///
+/// let router = Router::new()
+///     .hoop(Locale::new(["en", "zh-CN"]).default_language("zh-CN"))
+///     .get(hello);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Locale {
+    supported: Vec<String>,
+}
+
+impl Locale {
+    /// Create new `Locale` middleware, the first supported language is used as the default language unless
+    /// [`Locale::default_language`] is set.
+    #[inline]
+    pub fn new<I, S>(supported: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            supported: supported.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Set the default language which is selected if `Accept-Language` header is absent or nothing is matched.
+    ///
+    /// The default language is moved to the front of supported languages, it is added if it is not supported yet,
+    /// so it is also selected for `*`.
+    #[inline]
+    pub fn default_language(mut self, language: impl Into<String>) -> Self {
+        let language = language.into();
+        self.supported.retain(|s| !s.eq_ignore_ascii_case(&language));
+        self.supported.insert(0, language);
+        self
+    }
+}
+
+#[async_trait]
+impl Handler for Locale {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let supported = self.supported.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        if let Some(language) = req.preferred_language(&supported) {
+            depot.insert(LANGUAGE_KEY, language.to_owned());
+        }
+        ctrl.call_next(req, depot, res).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn hello(depot: &mut Depot) -> String {
+        depot.language().unwrap_or_default().to_owned()
+    }
+
+    #[tokio::test]
+    async fn test_locale() {
+        let service = Service::new(Router::new().hoop(Locale::new(["en", "zh-CN", "de"])).get(hello));
+        for (header, expected) in [
+            (Some("zh;q=0.9, fr"), "zh-CN"),
+            (Some("de-AT ; q=0.8, en;q=0.7"), "de"),
+            (Some(";;;"), "en"),
+            (None, "en"),
+        ] {
+            let mut client = TestClient::get("http://127.0.0.1:5801/");
+            if let Some(header) = header {
+                client = client.add_header("accept-language", header, true);
+            }
+            let content = client.send(&service).await.take_string().await.unwrap();
+            assert_eq!(content, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_locale_default_language() {
+        let locale = Locale::new(["en", "de", "fr"]).default_language("fr");
+        let service = Service::new(Router::new().hoop(locale).get(hello));
+        for (header, expected) in [
+            (Some("de-CH, en;q=0.5"), "de"),
+            (Some("ja, zh;q=0.8"), "fr"),
+            (Some("*, fr;q=0"), "en"),
+            (None, "fr"),
+        ] {
+            let mut client = TestClient::get("http://127.0.0.1:5801/");
+            if let Some(header) = header {
+                client = client.add_header("accept-language", header, true);
+            }
+            let content = client.send(&service).await.take_string().await.unwrap();
+            assert_eq!(content, expected);
+        }
+
+        let service = Service::new(
+            Router::new()
+                .hoop(Locale::new(["en"]).default_language("it"))
+                .get(hello),
+        );
+        let content = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("accept-language", "it-IT", true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "it");
+    }
+}
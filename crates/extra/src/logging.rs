@@ -1,32 +1,145 @@
-//! Simple logging middleware.
+//! Logging middlewares.
+//!
+//! [`Logger`] records a span and an event for every request, [`AccessLog`] emits one access log line for every
+//! completed request.
 //!
 //! Read more: <https://salvo.rs>
-use std::time::Instant;
+use std::fmt::{self, Formatter};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
 
+use serde_json::{Map, Value};
+use time::OffsetDateTime;
 use tracing::{Instrument, Level};
 
+use salvo_core::http::body::{Body, Frame, SizeHint};
+use salvo_core::http::header::{CONTENT_LENGTH, REFERER, USER_AGENT};
+use salvo_core::http::uri::Uri;
 use salvo_core::http::{Request, ResBody, Response, StatusCode};
-use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+use salvo_core::hyper::body::Bytes;
+use salvo_core::{async_trait, BoxedError, Depot, FlowCtrl, Handler};
+
+macro_rules! dyn_span {
+    ($level:expr, $($fields:tt)*) => {
+        match $level {
+            Level::TRACE => tracing::span!(Level::TRACE, $($fields)*),
+            Level::DEBUG => tracing::span!(Level::DEBUG, $($fields)*),
+            Level::INFO => tracing::span!(Level::INFO, $($fields)*),
+            Level::WARN => tracing::span!(Level::WARN, $($fields)*),
+            Level::ERROR => tracing::span!(Level::ERROR, $($fields)*),
+        }
+    };
+}
+macro_rules! dyn_event {
+    ($level:expr, $($fields:tt)*) => {
+        match $level {
+            Level::TRACE => tracing::event!(Level::TRACE, $($fields)*),
+            Level::DEBUG => tracing::event!(Level::DEBUG, $($fields)*),
+            Level::INFO => tracing::event!(Level::INFO, $($fields)*),
+            Level::WARN => tracing::event!(Level::WARN, $($fields)*),
+            Level::ERROR => tracing::event!(Level::ERROR, $($fields)*),
+        }
+    };
+}
 
 /// A simple logger middleware.
-#[derive(Default, Debug)]
-pub struct Logger {}
+///
+/// A `Request` span with `method`, `path`, `remote_addr` and `version` fields is entered while the rest of the
+/// chain runs, so logs emitted by handlers are correlated with the request. A `Response` event with `status`,
+/// `duration` and `size` fields is emitted after the rest of the chain is done.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::logging::Logger;
+/// use tracing::Level;
+///
+/// let router = Router::new().hoop(Logger::new().level(Level::DEBUG).sample(10).remote_addr(false));
+/// ```
+#[derive(Debug)]
+pub struct Logger {
+    level: Level,
+    sample: u64,
+    counter: AtomicU64,
+    remote_addr: bool,
+    version: bool,
+    size: bool,
+}
+impl Default for Logger {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl Logger {
     /// Create new `Logger` middleware.
     #[inline]
     pub fn new() -> Self {
-        Logger {}
+        Logger {
+            level: Level::INFO,
+            sample: 1,
+            counter: AtomicU64::new(0),
+            remote_addr: true,
+            version: true,
+            size: true,
+        }
+    }
+
+    /// Sets level of the span and event, the default is `INFO`.
+    #[inline]
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Only logs one of every `n` requests, the default is `1`, which means every request is logged.
+    #[inline]
+    pub fn sample(mut self, n: u64) -> Self {
+        self.sample = n.max(1);
+        self
+    }
+
+    /// Sets whether `remote_addr` field is recorded, the default is `true`.
+    #[inline]
+    pub fn remote_addr(mut self, enabled: bool) -> Self {
+        self.remote_addr = enabled;
+        self
+    }
+
+    /// Sets whether `version` field is recorded, the default is `true`.
+    #[inline]
+    pub fn version(mut self, enabled: bool) -> Self {
+        self.version = enabled;
+        self
+    }
+
+    /// Sets whether `size` field is recorded, the default is `true`.
+    ///
+    /// Size is unknown for streaming body without `Content-Length` header, and it is not recorded.
+    #[inline]
+    pub fn size(mut self, enabled: bool) -> Self {
+        self.size = enabled;
+        self
     }
 }
 
 #[async_trait]
 impl Handler for Logger {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        let span = tracing::span!(
-            Level::INFO,
+        if self.sample > 1 && self.counter.fetch_add(1, Ordering::Relaxed) % self.sample != 0 {
+            ctrl.call_next(req, depot, res).await;
+            return;
+        }
+        let remote_addr = self.remote_addr.then(|| req.remote_addr().to_string());
+        let version = self.version.then(|| format!("{:?}", req.version()));
+        let span = dyn_span!(
+            self.level,
             "Request",
-            remote_addr = %req.remote_addr().to_string(),
-            version = ?req.version(),
+            remote_addr = remote_addr,
+            version = version,
             method = %req.method(),
             path = %req.uri(),
         );
@@ -36,38 +149,316 @@ impl Handler for Logger {
             ctrl.call_next(req, depot, res).await;
             let duration = now.elapsed();
 
-            let status = res.status_code.unwrap_or(match &res.body {
-                ResBody::None => StatusCode::NOT_FOUND,
-                ResBody::Error(e) => e.code,
-                _ => StatusCode::OK,
-            });
-            tracing::info!(
-                %status,
-                ?duration,
-                "Response"
-            );
+            let status = response_status(res);
+            let size = if self.size { response_size(res) } else { None };
+            dyn_event!(self.level, %status, ?duration, size, "Response");
         }
         .instrument(span)
         .await
     }
 }
 
+/// Status code which will be sent to the client.
+fn response_status(res: &Response) -> StatusCode {
+    res.status_code.unwrap_or(match &res.body {
+        ResBody::None => StatusCode::NOT_FOUND,
+        ResBody::Error(e) => e.code,
+        _ => StatusCode::OK,
+    })
+}
+
+/// Body size, it is unknown for streaming body without `Content-Length` header.
+fn response_size(res: &Response) -> Option<u64> {
+    res.body.size().or_else(|| {
+        res.headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+    })
+}
+
+/// Output format of [`AccessLog`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum AccessLogFormat {
+    /// Apache Combined Log Format, followed by the duration in microseconds and the custom fields as
+    /// `key="value"` pairs.
+    ///
+    /// `127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] "GET /hello?q=1 HTTP/1.1" 200 5 "-" "curl/8.0" 1034`
+    #[default]
+    Combined,
+    /// One JSON object per request, with `remote_ip`, `method`, `path`, `query`, `status`, `bytes`,
+    /// `duration_ms` and the custom fields as keys. Suitable for log aggregators.
+    Json,
+}
+
+/// Closure used to add custom fields to access log.
+pub type AccessLogFields = Box<dyn Fn(&Request, &Response) -> Vec<(String, String)> + Send + Sync>;
+
+/// Access log middleware, emits one `tracing` event for every request, so the output goes to whatever subscriber
+/// is configured.
+///
+/// The line is emitted when the response body is completely sent or dropped, so the duration covers sending the
+/// body, and the size is the number of body bytes actually sent, which is smaller than the body size if the client
+/// disconnects early. Responses without body are logged as soon as the rest of the chain is done.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::logging::{AccessLog, AccessLogFormat};
+///
+/// let access_log = AccessLog::new()
+///     .format(AccessLogFormat::Json)
+///     .fields(|req, _res| {
+///         let host = req.header::<String>("host").unwrap_or_default();
+///         vec![("host".to_owned(), host)]
+///     });
+/// let router = Router::new().hoop(access_log);
+/// ```
+pub struct AccessLog {
+    level: Level,
+    format: AccessLogFormat,
+    fields: Vec<AccessLogFields>,
+}
+impl fmt::Debug for AccessLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessLog")
+            .field("level", &self.level)
+            .field("format", &self.format)
+            .field("fields", &self.fields.len())
+            .finish()
+    }
+}
+impl Default for AccessLog {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl AccessLog {
+    /// Create new `AccessLog` middleware with Combined Log Format.
+    #[inline]
+    pub fn new() -> Self {
+        AccessLog {
+            level: Level::INFO,
+            format: AccessLogFormat::Combined,
+            fields: vec![],
+        }
+    }
+
+    /// Sets level of the event, the default is `INFO`.
+    #[inline]
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets output format, the default is [`AccessLogFormat::Combined`].
+    #[inline]
+    pub fn format(mut self, format: AccessLogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Adds a closure which returns custom fields, it is called after the rest of the chain is done.
+    #[inline]
+    pub fn fields<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Request, &Response) -> Vec<(String, String)> + Send + Sync + 'static,
+    {
+        self.fields.push(Box::new(f));
+        self
+    }
+
+    fn entry(&self, req: &Request, res: &Response, started: OffsetDateTime) -> AccessEntry {
+        let header = |name| {
+            req.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.replace('"', "\\\""))
+                .unwrap_or_else(|| "-".into())
+        };
+        AccessEntry {
+            level: self.level,
+            format: self.format,
+            remote_ip: req.remote_addr().clone().into_std().map(|addr| addr.ip().to_string()),
+            method: req.method().to_string(),
+            uri: req.uri().clone(),
+            version: format!("{:?}", req.version()),
+            status: response_status(res),
+            referer: header(REFERER),
+            user_agent: header(USER_AGENT),
+            started,
+            fields: self.fields.iter().flat_map(|f| f(req, res)).collect(),
+        }
+    }
+}
+
+/// Everything in an access log line, except body size and duration which are known after the body is sent.
+struct AccessEntry {
+    level: Level,
+    format: AccessLogFormat,
+    remote_ip: Option<String>,
+    method: String,
+    uri: Uri,
+    version: String,
+    status: StatusCode,
+    referer: String,
+    user_agent: String,
+    started: OffsetDateTime,
+    fields: Vec<(String, String)>,
+}
+impl AccessEntry {
+    fn emit(self, bytes: Option<u64>, duration: Duration) {
+        let line = self.line(bytes, duration);
+        dyn_event!(self.level, "{}", line);
+    }
+
+    fn line(&self, bytes: Option<u64>, duration: Duration) -> String {
+        let Self {
+            remote_ip,
+            method,
+            uri,
+            version,
+            status,
+            referer,
+            user_agent,
+            started,
+            fields,
+            ..
+        } = self;
+        match self.format {
+            AccessLogFormat::Combined => {
+                let month = started.month().to_string();
+                let mut line = format!(
+                    "{} - - [{:02}/{}/{}:{:02}:{:02}:{:02} +0000] \"{} {} {}\" {} {} \"{}\" \"{}\" {}",
+                    remote_ip.as_deref().unwrap_or("-"),
+                    started.day(),
+                    &month[..3],
+                    started.year(),
+                    started.hour(),
+                    started.minute(),
+                    started.second(),
+                    method,
+                    uri.path_and_query().map(|p| p.as_str()).unwrap_or("/"),
+                    version,
+                    status.as_u16(),
+                    bytes.map(|b| b.to_string()).unwrap_or_else(|| "-".into()),
+                    referer,
+                    user_agent,
+                    duration.as_micros(),
+                );
+                for (key, value) in fields {
+                    line.push_str(&format!(" {key}=\"{}\"", value.replace('"', "\\\"")));
+                }
+                line
+            }
+            AccessLogFormat::Json => {
+                let mut map = Map::new();
+                map.insert("remote_ip".into(), remote_ip.clone().into());
+                map.insert("method".into(), method.clone().into());
+                map.insert("path".into(), uri.path().into());
+                map.insert("query".into(), uri.query().into());
+                map.insert("status".into(), status.as_u16().into());
+                map.insert("bytes".into(), bytes.into());
+                map.insert("duration_ms".into(), (duration.as_secs_f64() * 1000.0).into());
+                for (key, value) in fields {
+                    map.insert(key.clone(), value.clone().into());
+                }
+                Value::Object(map).to_string()
+            }
+        }
+    }
+}
+
+/// Response body which counts sent bytes and emits the access log when it is finished or dropped.
+struct AccessLogBody {
+    inner: ResBody,
+    entry: Option<AccessEntry>,
+    now: Instant,
+    sent: u64,
+}
+impl AccessLogBody {
+    fn finish(&mut self) {
+        if let Some(entry) = self.entry.take() {
+            entry.emit(Some(self.sent), self.now.elapsed());
+        }
+    }
+}
+impl Body for AccessLogBody {
+    type Data = Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, BoxedError>>> {
+        let this = self.get_mut();
+        let frame = ready!(Pin::new(&mut this.inner).poll_frame(cx));
+        match &frame {
+            Some(Ok(frame)) => {
+                if let Some(data) = frame.data_ref() {
+                    this.sent += data.len() as u64;
+                }
+            }
+            Some(Err(_)) | None => this.finish(),
+        }
+        Poll::Ready(frame.map(|frame| frame.map_err(Into::into)))
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+impl Drop for AccessLogBody {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[async_trait]
+impl Handler for AccessLog {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let started = OffsetDateTime::now_utc();
+        let now = Instant::now();
+        ctrl.call_next(req, depot, res).await;
+        let entry = self.entry(req, res, started);
+        if res.body.is_none() || res.body.is_error() {
+            entry.emit(response_size(res), now.elapsed());
+        } else {
+            let body = AccessLogBody {
+                inner: res.take_body(),
+                entry: Some(entry),
+                now,
+                sent: 0,
+            };
+            res.body = ResBody::boxed(body);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use http_body_util::BodyExt;
     use salvo_core::prelude::*;
     use salvo_core::test::{ResponseExt, TestClient};
     use tracing_test::traced_test;
 
     use super::*;
 
+    #[handler]
+    async fn hello() -> &'static str {
+        tracing::info!("in hello");
+        "hello"
+    }
+
     #[tokio::test]
     #[traced_test]
     async fn test_log() {
-        #[handler]
-        async fn hello() -> &'static str {
-            "hello"
-        }
-
         let router = Router::new()
             .hoop(Logger::new())
             .push(Router::with_path("hello").get(hello));
@@ -79,5 +470,125 @@ mod tests {
             .await
             .unwrap();
         assert!(logs_contain("duration"));
+        assert!(logs_contain("status=200 OK"));
+        assert!(logs_contain("size=5"));
+        assert!(logs_contain("method=GET"));
+        assert!(logs_contain("path=http://127.0.0.1:5801/hello"));
+        // Handler logs are emitted inside the request span.
+        assert!(logs_contain("Request{remote_addr="));
+        assert!(logs_contain("in hello"));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_access_log_combined() {
+        let router = Router::new()
+            .hoop(AccessLog::new().fields(|_req, res| vec![("kind".into(), format!("{:?}", res.body.size()))]))
+            .push(Router::with_path("hello").get(hello));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/hello?q=1")
+            .add_header("user-agent", "salvo-test", true)
+            .send(router)
+            .await;
+        // The line is emitted after the body is sent.
+        assert!(!logs_contain("GET /hello?q=1"));
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+        assert!(logs_contain(
+            r#"+0000] "GET /hello?q=1 HTTP/1.1" 200 5 "-" "salvo-test" "#
+        ));
+        assert!(logs_contain(r#"kind="Some(5)""#));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_access_log_json() {
+        let router = Router::new().push(Router::with_path("hello").get(hello));
+        // Hoops on service also run for requests which do not match any route.
+        let service = Service::new(router).hoop(AccessLog::new().format(AccessLogFormat::Json));
+
+        TestClient::get("http://127.0.0.1:5801/hello?q=1")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        TestClient::get("http://127.0.0.1:5801/missing").send(&service).await;
+        logs_assert(|lines: &[&str]| {
+            let lines = lines
+                .iter()
+                .filter(|line| line.contains("duration_ms"))
+                .collect::<Vec<_>>();
+            if lines.len() != 2 {
+                return Err(format!("expected 2 access logs, got {}", lines.len()));
+            }
+            if !lines[0].contains(r#""method":"GET""#)
+                || !lines[0].contains(r#""path":"/hello""#)
+                || !lines[0].contains(r#""query":"q=1""#)
+                || !lines[0].contains(r#""status":200"#)
+                || !lines[0].contains(r#""bytes":5"#)
+            {
+                return Err(format!("unexpected fields: {}", lines[0]));
+            }
+            if !lines[1].contains(r#""status":404"#) || !lines[1].contains(r#""query":null"#) {
+                return Err(format!("unexpected fields: {}", lines[1]));
+            }
+            Ok(())
+        });
+    }
+
+    #[handler]
+    async fn streaming(res: &mut Response) {
+        let chunks = ["hello", " ", "world"].map(Ok::<_, std::io::Error>);
+        res.stream(futures_util::stream::iter(chunks));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_access_log_body_dropped() {
+        let router = Router::new()
+            .hoop(AccessLog::new())
+            .push(Router::with_path("chunks").get(streaming));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/chunks").send(router).await;
+        let frame = res.body.frame().await.unwrap().unwrap();
+        assert_eq!(frame.into_data().unwrap(), "hello");
+        assert!(!logs_contain("GET /chunks"));
+        // Client disconnects before the whole body is sent.
+        drop(res);
+        assert!(logs_contain(r#""GET /chunks HTTP/1.1" 200 5 "-""#));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_log_options() {
+        let router = Router::new()
+            .hoop(
+                Logger::new()
+                    .level(Level::DEBUG)
+                    .sample(2)
+                    .remote_addr(false)
+                    .size(false),
+            )
+            .push(Router::with_path("hello").get(hello));
+        let service = Service::new(router);
+        for _ in 0..4 {
+            TestClient::get("http://127.0.0.1:5801/hello").send(&service).await;
+        }
+        logs_assert(|lines: &[&str]| {
+            let responses = lines
+                .iter()
+                .filter(|line| line.contains("Response"))
+                .collect::<Vec<_>>();
+            if responses.len() != 2 {
+                return Err(format!("expected 2 responses, got {}", responses.len()));
+            }
+            if responses
+                .iter()
+                .any(|line| !line.contains("DEBUG") || line.contains("remote_addr") || line.contains("size="))
+            {
+                return Err("unexpected fields".into());
+            }
+            Ok(())
+        });
     }
 }
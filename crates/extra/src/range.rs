@@ -0,0 +1,144 @@
+//! Range requests support.
+//!
+//! [`range_response`] serves any [`AsyncRead`] + [`AsyncSeek`] source with
+//! [range requests](https://www.rfc-editor.org/rfc/rfc9110#name-range-requests) support.
+//!
+//! Read more: <https://salvo.rs>
+use salvo_core::http::header::RANGE;
+use salvo_core::http::headers::{ContentRange, HeaderMapExt};
+use salvo_core::http::{HttpRange, Request, Response, StatusCode};
+use tokio::io::{AsyncRead, AsyncSeek};
+
+pub use salvo_core::http::RangeBody;
+
+/// Create a response for `reader` according to the request's `Range` header, `size` is the full size of `reader`,
+/// such as `metadata.len()` of a file.
+///
+/// - If there is no `Range` header, the whole content is written with `200 OK`.
+/// - If there is a single range, the range is written with `206 Partial Content` and `Content-Range` header.
+/// - If there are multiple ranges, they are written as `multipart/byteranges` body with `206 Partial Content`,
+///   overlapping or adjacent ranges are merged, see [`HttpRange::coalesce`].
+/// - If the `Range` header is invalid or unsatisfiable, `416 Range Not Satisfiable` is returned.
+/// - If the requested ranges are abusive, such as their total length exceeds `size`, the `Range` header is ignored.
+///
+/// # Example
+///
+/// ```no_run
+/// use salvo_core::prelude::*;
+/// use salvo_extra::range::range_response;
+///
+/// #[handler]
+/// async fn video(req: &mut Request, res: &mut Response) -> Result<(), StatusError> {
+///     let file = tokio::fs::File::open("video.mp4")
+///         .await
+///         .map_err(|_| StatusError::not_found())?;
+///     let metadata = file.metadata().await.map_err(|_| StatusError::internal_server_error())?;
+///     *res = range_response(req, file, metadata.len());
+///     Ok(())
+/// }
+/// ```
+pub fn range_response<R>(req: &Request, reader: R, size: u64) -> Response
+where
+    R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
+{
+    let mut res = Response::new();
+    let Some(range) = req.headers().get(RANGE) else {
+        RangeBody::new(reader, vec![], size).write_to(&mut res);
+        return res;
+    };
+    match range.to_str().ok().and_then(|range| HttpRange::parse(range, size).ok()) {
+        Some(ranges) if !ranges.is_empty() => {
+            let ranges = HttpRange::coalesce(ranges, size).unwrap_or_default();
+            RangeBody::new(reader, ranges, size).write_to(&mut res);
+        }
+        _ => {
+            res.status_code(StatusCode::RANGE_NOT_SATISFIABLE);
+            res.headers_mut().typed_insert(ContentRange::unsatisfied_bytes(size));
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use salvo_core::http::header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE};
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn content(req: &mut Request, res: &mut Response) {
+        *res = range_response(req, Cursor::new(b"0123456789".to_vec()), 10);
+    }
+
+    async fn get(range: Option<&str>) -> Response {
+        let mut client = TestClient::get("http://127.0.0.1:5801/");
+        if let Some(range) = range {
+            client = client.add_header(RANGE, range, true);
+        }
+        client.send(Router::new().get(content)).await
+    }
+
+    #[tokio::test]
+    async fn test_range_response() {
+        let mut res = get(None).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get(ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(res.take_string().await.unwrap(), "0123456789");
+
+        let mut res = get(Some("bytes=2-5")).await;
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes 2-5/10");
+        assert_eq!(res.take_string().await.unwrap(), "2345");
+
+        let mut res = get(Some("bytes=-3")).await;
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes 7-9/10");
+        assert_eq!(res.take_string().await.unwrap(), "789");
+
+        for range in ["bytes=20-30", "items=0-1", "bytes=5-2"] {
+            let res = get(Some(range)).await;
+            assert_eq!(res.status_code, Some(StatusCode::RANGE_NOT_SATISFIABLE));
+            assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes */10");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multiple_ranges() {
+        let mut res = get(Some("bytes=0-1, 8-")).await;
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        let ctype = res.headers().get(CONTENT_TYPE).unwrap().to_str().unwrap().to_owned();
+        let boundary = ctype.strip_prefix("multipart/byteranges; boundary=").unwrap();
+        let length: usize = res
+            .headers()
+            .get(CONTENT_LENGTH)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let body = res.take_string().await.unwrap();
+        assert_eq!(body.len(), length);
+        assert_eq!(
+            body,
+            format!(
+                "--{boundary}\r\nContent-Range: bytes 0-1/10\r\n\r\n01\r\n\
+                 --{boundary}\r\nContent-Range: bytes 8-9/10\r\n\r\n89\r\n--{boundary}--\r\n"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_abusive_ranges() {
+        let mut res = get(Some("bytes=0-1,1-2,2-3")).await;
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes 0-3/10");
+        assert_eq!(res.take_string().await.unwrap(), "0123");
+
+        let mut res = get(Some("bytes=0-,0-,0-")).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "0123456789");
+    }
+}
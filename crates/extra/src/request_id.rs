@@ -1,32 +1,85 @@
 //! Request id middleware.
 //!
 //! Read more: <https://salvo.rs>
+use tracing::Instrument;
 use ulid::Ulid;
+use uuid::Uuid;
 
 use salvo_core::http::{header::HeaderName, Request, Response};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
-/// Key for incoming flash messages in depot.
-pub const REQUST_ID_KEY: &str = "::salvo::request_id";
+/// Key for request id in depot.
+pub const REQUEST_ID_KEY: &str = "::salvo::request_id";
+
+/// Key for request id in depot.
+#[deprecated(since = "0.67.3", note = "use `REQUEST_ID_KEY` instead")]
+pub const REQUST_ID_KEY: &str = REQUEST_ID_KEY;
+
+/// Extension trait for getting request id from depot.
+pub trait RequestIdDepotExt {
+    /// Get the request id set by [`RequestId`].
+    fn request_id(&self) -> Option<&str>;
+}
+
+impl RequestIdDepotExt for Depot {
+    #[inline]
+    fn request_id(&self) -> Option<&str> {
+        self.get::<String>(REQUEST_ID_KEY).ok().map(|s| s.as_str())
+    }
+}
+
+/// Returns `true` if the incoming request id is safe to be reused, it must be 1 to 128 visible ASCII characters.
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 128 && id.bytes().all(|b| b.is_ascii_graphic())
+}
 
 /// A middleware for generate request id.
+///
+/// The request id is set to the request header, inserted into depot and can be got by
+/// [`RequestIdDepotExt::request_id`], and it is also sent in the response header. The rest of the handler chain runs
+/// in a `request` span with `request_id` field, so that all downstream logs are correlated.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::http::header::HeaderName;
+/// use salvo_core::prelude::*;
+/// use salvo_extra::request_id::{RequestId, RequestIdDepotExt};
+///
+/// #[handler]
+/// async fn hello(depot: &mut Depot) -> String {
+///     format!("request id: {}", depot.request_id().unwrap_or_default())
+/// }
+///
+/// let router = Router::new()
+///     .hoop(
+///         RequestId::new()
+///             .header_name(HeaderName::from_static("x-correlation-id"))
+///             .overwrite(false),
+///     )
+///     .get(hello);
+/// ```
 #[non_exhaustive]
 pub struct RequestId {
     /// The header name for request id.
     pub header_name: HeaderName,
-    /// Whether overwrite exists request id. Default is `true`
+    /// Whether overwrite exists request id. Default is `true`.
+    ///
+    /// If it is `false`, valid request id sent by client is propagated, a new one is generated only if it is absent
+    /// or invalid.
     pub overwrite: bool,
-    /// The generator for request id.
+    /// The generator for request id, default is [`UuidGenerator`] which generates uuid v4, use [`UlidGenerator`] for
+    /// sortable ulid.
     pub generator: Box<dyn IdGenerator + Send + Sync>,
 }
 
 impl RequestId {
-    /// Create new `CatchPanic` middleware.
+    /// Create new `RequestId` middleware.
     pub fn new() -> Self {
         Self {
             header_name: HeaderName::from_static("x-request-id"),
             overwrite: true,
-            generator: Box::new(UlidGenerator::new()),
+            generator: Box::new(UuidGenerator::new()),
         }
     }
 
@@ -37,6 +90,8 @@ impl RequestId {
     }
 
     /// Set whether overwrite exists request id. Default is `true`.
+    ///
+    /// Set it to `false` to propagate valid request id sent by client.
     pub fn overwrite(mut self, overwrite: bool) -> Self {
         self.overwrite = overwrite;
         self
@@ -72,8 +127,8 @@ where
 
 /// A generator for generate request id with ulid.
 #[derive(Default, Debug)]
-pub struct UlidGenerator{}
-impl UlidGenerator{
+pub struct UlidGenerator {}
+impl UlidGenerator {
     /// Create new `UlidGenerator`.
     pub fn new() -> Self {
         Self {}
@@ -85,14 +140,120 @@ impl IdGenerator for UlidGenerator {
     }
 }
 
+/// A generator for generate request id with random uuid (version 4).
+#[derive(Default, Debug)]
+pub struct UuidGenerator {}
+impl UuidGenerator {
+    /// Create new `UuidGenerator`.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+impl IdGenerator for UuidGenerator {
+    fn generate(&self, _req: &mut Request, _depot: &mut Depot) -> String {
+        Uuid::new_v4().to_string()
+    }
+}
+
 #[async_trait]
 impl Handler for RequestId {
-    async fn handle(&self, req: &mut Request, depot: &mut Depot, _res: &mut Response, _ctrl: &mut FlowCtrl) {
-        if !self.overwrite && req.headers().contains_key(REQUST_ID_KEY) {
-            return;
-        }
-        let id = self.generator.generate(req, depot);
-        req.add_header(self.header_name.clone(), &id, true).ok();
-        depot.insert(REQUST_ID_KEY, id);
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let incoming = if self.overwrite {
+            None
+        } else {
+            req.header::<String>(self.header_name.clone())
+                .filter(|id| is_valid_request_id(id))
+        };
+        let id = match incoming {
+            Some(id) => id,
+            None => {
+                let id = self.generator.generate(req, depot);
+                req.add_header(self.header_name.clone(), &id, true).ok();
+                id
+            }
+        };
+        res.add_header(self.header_name.clone(), &id, true).ok();
+        let span = tracing::info_span!("request", request_id = %id);
+        depot.insert(REQUEST_ID_KEY, id);
+        ctrl.call_next(req, depot, res).instrument(span).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    #[handler]
+    async fn hello(req: &mut Request, depot: &mut Depot) -> String {
+        tracing::info!("in hello");
+        format!(
+            "{}|{}",
+            depot.request_id().unwrap_or_default(),
+            req.header::<String>("x-request-id").unwrap_or_default()
+        )
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_request_id() {
+        let service = Service::new(Router::new().hoop(RequestId::new()).get(hello));
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-request-id", "client-id", true)
+            .send(&service)
+            .await;
+        let id = res.headers().get("x-request-id").unwrap().to_str().unwrap().to_owned();
+        assert_ne!(id, "client-id");
+        assert_eq!(res.take_string().await.unwrap(), format!("{id}|{id}"));
+        assert!(logs_contain(&format!("request{{request_id={id}}}")));
+        assert!(logs_contain("in hello"));
+    }
+
+    #[tokio::test]
+    async fn test_propagate_request_id() {
+        let router = Router::new()
+            .hoop(
+                RequestId::new()
+                    .header_name(HeaderName::from_static("x-correlation-id"))
+                    .overwrite(false)
+                    .generator(UlidGenerator::new()),
+            )
+            .get(hello);
+        let service = Service::new(router);
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-correlation-id", "client-id", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get("x-correlation-id").unwrap(), "client-id");
+
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-correlation-id", "bad id", true)
+            .send(&service)
+            .await;
+        let id = res.headers().get("x-correlation-id").unwrap();
+        assert_ne!(id, "bad id");
+        assert_eq!(id.len(), 26);
+    }
+
+    #[tokio::test]
+    async fn test_uuid_request_id() {
+        let router = Router::new()
+            .hoop(RequestId::new().overwrite(false))
+            .get(hello);
+        let service = Service::new(router);
+        let mut res = TestClient::get("http://127.0.0.1:5801/").send(&service).await;
+        let id = res.headers().get("x-request-id").unwrap().to_str().unwrap().to_owned();
+        assert!(Uuid::parse_str(&id).is_ok_and(|id| id.get_version_num() == 4));
+        assert_eq!(res.take_string().await.unwrap(), format!("{id}|{id}"));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-request-id", "client-id", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get("x-request-id").unwrap(), "client-id");
+        assert_eq!(res.take_string().await.unwrap(), "client-id|client-id");
     }
 }
@@ -5,23 +5,27 @@ use salvo_core::http::StatusError;
 use salvo_core::http::{Body, Request, Response};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
-/// MaxSize
+/// MaxSize limits the size of request body.
+///
+/// Requests with `Content-Length` larger than the limit are rejected with `413 Payload Too Large` directly.
+/// The request's [`secure_max_size`](Request::secure_max_size) is also lowered to the limit, so body parsing
+/// methods like [`Request::parse_json`] return `ParseError::PayloadTooLarge` when a streaming body exceeds it.
+/// It is never raised, a limit larger than the current one does not lift the body parsing limit, use
+/// [`Request::set_secure_max_size`] for that.
 pub struct MaxSize(pub u64);
 #[async_trait]
 impl Handler for MaxSize {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        let size_hint = req.body().size_hint().upper();
-        if let Some(upper) = size_hint {
-            if upper > self.0 {
-                res.render(StatusError::payload_too_large());
-                ctrl.skip_rest();
-            } else {
-                ctrl.call_next(req, depot, res).await;
-            }
-        } else {
-            res.render(StatusError::bad_request().brief("Request body size is unknown."));
+        if req.body().size_hint().lower() > self.0 {
+            res.render(StatusError::payload_too_large());
             ctrl.skip_rest();
+            return;
         }
+        let max_size = usize::try_from(self.0).unwrap_or(usize::MAX);
+        if max_size < req.secure_max_size() {
+            req.set_secure_max_size(max_size);
+        }
+        ctrl.call_next(req, depot, res).await;
     }
 }
 /// Create a new `MaxSize`.
@@ -32,8 +36,14 @@ pub fn max_size(size: u64) -> MaxSize {
 
 #[cfg(test)]
 mod tests {
+    use http_body_util::StreamBody;
+    use salvo_core::http::body::ReqBody;
+    use salvo_core::http::ParseError;
+    use salvo_core::hyper::body::{Bytes, Frame};
     use salvo_core::prelude::*;
     use salvo_core::test::{ResponseExt, TestClient};
+    use salvo_core::BoxedError;
+    use tokio_stream::StreamExt;
 
     use super::*;
 
@@ -65,4 +75,48 @@ mod tests {
             .await;
         assert_eq!(res.status_code.unwrap(), StatusCode::PAYLOAD_TOO_LARGE);
     }
+
+    #[tokio::test]
+    async fn test_size_limiter_streaming() {
+        #[handler]
+        async fn echo(req: &mut Request) -> Result<String, ParseError> {
+            req.parse_json::<String>().await
+        }
+        let router = Router::new()
+            .hoop(MaxSize(32))
+            .push(Router::with_path("echo").post(echo));
+        let service = Service::new(router);
+
+        let chunks = ["\"abcdefghijklmnopqrstuvwxyz", "abcdefghijklmnopqrstuvwxyz\""];
+        let body = ReqBody::Boxed {
+            inner: Box::pin(StreamBody::new(
+                tokio_stream::iter(chunks).map(|c| Ok::<_, BoxedError>(Frame::data(Bytes::from_static(c.as_bytes())))),
+            )),
+            fusewire: None,
+        };
+        let res = TestClient::post("http://127.0.0.1:5801/echo")
+            .add_header("content-type", "application/json", true)
+            .body(body)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_size_limiter_never_raises_limit() {
+        #[handler]
+        async fn echo(req: &mut Request) -> Result<String, ParseError> {
+            req.parse_json::<String>().await
+        }
+        let router = Router::new()
+            .hoop(MaxSize(1024 * 1024))
+            .push(Router::with_path("echo").post(echo));
+        let service = Service::new(router).secure_max_size(8);
+
+        let res = TestClient::post("http://127.0.0.1:5801/echo")
+            .json(&"abcdefghijklmnopqrstuvwxyz")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
 }
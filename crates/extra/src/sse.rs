@@ -63,7 +63,7 @@ use pin_project::pin_project;
 use salvo_core::http::header::{HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
 use tokio::time::{self, Sleep};
 
-use salvo_core::http::Response;
+use salvo_core::http::{Request, Response};
 
 /// Server-sent event data type
 #[derive(Clone, Debug)]
@@ -95,20 +95,27 @@ pub struct SseEvent {
 
 impl SseEvent {
     /// Sets Server-sent event data.
+    ///
+    /// Multi-line data is sent as one `data:` field per line, `\r\n`, `\r` and `\n` are all line breaks, so
+    /// clients receive the lines joined with `\n`.
     #[inline]
     pub fn text<T: Into<String>>(mut self, data: T) -> SseEvent {
         self.data = Some(DataType::Text(data.into()));
         self
     }
 
-    /// Sets Server-sent event data.
+    /// Sets Server-sent event data serialized as json.
+    ///
+    /// Json is serialized in compact form and line breaks in strings are escaped, so data is always sent in one
+    /// line.
     #[inline]
     pub fn json<T: Serialize>(mut self, data: T) -> Result<SseEvent, serde_json::Error> {
         self.data = Some(DataType::Json(serde_json::to_string(&data)?));
         Ok(self)
     }
 
-    /// Sets Server-sent event comment.`
+    /// Sets Server-sent event comment, which is ignored by clients, multi-line comment is sent as one `:` line per
+    /// line.
     #[inline]
     pub fn comment<T: Into<String>>(mut self, comment: T) -> SseEvent {
         self.comment = Some(comment.into());
@@ -116,12 +123,28 @@ impl SseEvent {
     }
 
     /// Sets Server-sent event event.
+    ///
+    /// Line breaks in `event` are removed, since they would corrupt the event stream, use [`SseEvent::try_name`]
+    /// to reject such name instead.
     #[inline]
     pub fn name<T: Into<String>>(mut self, event: T) -> SseEvent {
-        self.name = Some(event.into());
+        let mut event = event.into();
+        event.retain(|c| !matches!(c, '\n' | '\r'));
+        self.name = Some(event);
         self
     }
 
+    /// Sets Server-sent event event, an error is returned if `event` contains line breaks.
+    #[inline]
+    pub fn try_name<T: Into<String>>(mut self, event: T) -> Result<SseEvent, SseError> {
+        let event = event.into();
+        if event.contains(['\n', '\r']) {
+            return Err(SseError);
+        }
+        self.name = Some(event);
+        Ok(self)
+    }
+
     /// Sets Server-sent event retry.
     #[inline]
     pub fn retry(mut self, duration: Duration) -> SseEvent {
@@ -129,20 +152,39 @@ impl SseEvent {
         self
     }
 
-    /// Sets Server-sent event id.
+    /// Sets Server-sent event id, browsers send the id of the last received event in `Last-Event-ID` header when
+    /// they reconnect, see [`stream_resumable`].
+    ///
+    /// Line breaks and null characters in `id` are removed, since they would corrupt the event stream, use
+    /// [`SseEvent::try_id`] to reject such id instead.
     #[inline]
     pub fn id<T: Into<String>>(mut self, id: T) -> SseEvent {
-        self.id = Some(id.into());
+        let mut id = id.into();
+        id.retain(|c| !matches!(c, '\n' | '\r' | '\0'));
+        self.id = Some(id);
         self
     }
+
+    /// Sets Server-sent event id, an error is returned if `id` contains line breaks or null characters.
+    #[inline]
+    pub fn try_id<T: Into<String>>(mut self, id: T) -> Result<SseEvent, SseError> {
+        let id = id.into();
+        if id.contains(['\n', '\r', '\0']) {
+            return Err(SseError);
+        }
+        self.id = Some(id);
+        Ok(self)
+    }
 }
 
 impl Display for SseEvent {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         if let Some(comment) = &self.comment {
-            ":".fmt(f)?;
-            comment.fmt(f)?;
-            f.write_char('\n')?;
+            for line in lines(comment) {
+                ":".fmt(f)?;
+                line.fmt(f)?;
+                f.write_char('\n')?;
+            }
         }
 
         if let Some(name) = &self.name {
@@ -153,7 +195,7 @@ impl Display for SseEvent {
 
         match self.data {
             Some(DataType::Text(ref data)) => {
-                for line in data.split('\n') {
+                for line in lines(data) {
                     "data:".fmt(f)?;
                     line.fmt(f)?;
                     f.write_char('\n')?;
@@ -202,7 +244,30 @@ impl Display for SseEvent {
     }
 }
 
-/// SseKeepAlive
+/// Splits `text` on `\r\n`, `\r` and `\n`, which are all line breaks in event stream.
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(text);
+    std::iter::from_fn(move || {
+        let text = rest?;
+        match text.find(['\r', '\n']) {
+            Some(index) => {
+                let skip = if text[index..].starts_with("\r\n") { 2 } else { 1 };
+                rest = Some(&text[index + skip..]);
+                Some(&text[..index])
+            }
+            None => {
+                rest = None;
+                Some(text)
+            }
+        }
+    })
+}
+
+/// Event stream which sends a keep-alive comment whenever no event has been sent for `max_interval`, so that idle
+/// connections are not dropped by proxies and browsers.
+///
+/// The timer is reset by every event, and the stream ends when the inner stream ends, or when the client
+/// disconnects and the response body is dropped.
 #[allow(missing_debug_implementations)]
 #[pin_project]
 #[non_exhaustive]
@@ -240,6 +305,7 @@ where
     #[inline]
     pub fn max_interval(mut self, time: Duration) -> Self {
         self.max_interval = time;
+        self.alive_timer = time::sleep(time);
         self
     }
 
@@ -286,6 +352,39 @@ where
     res.stream(body_stream)
 }
 
+/// Send event stream which can be resumed by clients.
+///
+/// When a browser reconnects to an event stream, it sends the [`id`](SseEvent::id) of the last received event in
+/// `Last-Event-ID` header. `events` receives that id, or `None` for a new connection, so the application can
+/// replay the events which were missed.
+///
+/// # Example
+///
+/// ```
+/// use std::convert::Infallible;
+///
+/// use futures_util::{stream, StreamExt};
+/// use salvo_core::prelude::*;
+/// use salvo_extra::sse::{self, SseEvent};
+///
+/// #[handler]
+/// async fn events(req: &mut Request, res: &mut Response) {
+///     sse::stream_resumable(req, res, |last_id| {
+///         let start = last_id.and_then(|id| id.parse::<u64>().ok()).map_or(0, |id| id + 1);
+///         stream::iter(start..10).map(|id| Ok::<_, Infallible>(SseEvent::default().id(id.to_string()).text("tick")))
+///     });
+/// }
+/// ```
+#[inline]
+pub fn stream_resumable<F, S>(req: &Request, res: &mut Response, events: F)
+where
+    F: FnOnce(Option<&str>) -> S,
+    S: TryStream<Ok = SseEvent> + Send + 'static,
+    S::Error: StdError + Send + Sync + 'static,
+{
+    stream(res, events(req.last_event_id()))
+}
+
 impl<S> Stream for SseKeepAlive<S>
 where
     S: TryStream<Ok = SseEvent> + Send + 'static,
@@ -354,6 +453,33 @@ mod tests {
         assert!(text.contains("data:1"));
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_sse_keep_alive_interval() {
+        // Events are sent at 0ms, 300ms and 450ms, keep-alive is sent at 200ms, it is not sent at 400ms since the
+        // timer is reset by the event at 300ms.
+        let event_stream = futures_util::stream::unfold(0, |index| async move {
+            let delay = match index {
+                0 => 0,
+                1 => 300,
+                2 => 150,
+                _ => return None,
+            };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            Some((
+                Ok::<_, Infallible>(SseEvent::default().text(index.to_string())),
+                index + 1,
+            ))
+        });
+        let texts = SseKeepAlive::new(event_stream)
+            .comment("ping")
+            .max_interval(Duration::from_millis(200))
+            .map_ok(|event| event.to_string())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(texts, vec!["data:0\n\n", ":ping\n\n", "data:1\n\n", "data:2\n\n"]);
+    }
+
     #[tokio::test]
     async fn test_sse_json() {
         #[derive(Serialize, Debug)]
@@ -379,6 +505,33 @@ mod tests {
         assert!(text.contains(":comment"));
     }
 
+    #[test]
+    fn test_sse_multi_line() {
+        let event = SseEvent::default()
+            .name("update")
+            .comment("first\nsecond\r\nthird")
+            .text("line1\nline2\r\nline3\rline4\n");
+        // Clients join the data lines with `\n`, so the event is dispatched with data `line1\nline2\nline3\nline4\n`.
+        assert_eq!(
+            event.to_string(),
+            ":first\n:second\n:third\nevent:update\ndata:line1\ndata:line2\ndata:line3\ndata:line4\ndata:\n\n"
+        );
+
+        let event = SseEvent::default()
+            .json(serde_json::json!({ "text": "a\nb\rc" }))
+            .unwrap();
+        // Line breaks are escaped by JSON, so the data is sent as one line.
+        assert_eq!(event.to_string(), "data:{\"text\":\"a\\nb\\rc\"}\n\n");
+    }
+
+    #[test]
+    fn test_sse_name_with_line_break() {
+        let event = SseEvent::default().name("update\rdata:injected");
+        assert_eq!(event.to_string(), "event:updatedata:injected\n\n");
+        assert!(SseEvent::default().try_name("update\rdata:injected").is_err());
+        assert!(SseEvent::default().try_name("update").is_ok());
+    }
+
     #[tokio::test]
     async fn test_sse_name() {
         let event_stream = tokio_stream::iter(vec![Ok::<_, Infallible>(SseEvent::default().name("evt2"))]);
@@ -407,6 +560,49 @@ mod tests {
         assert!(text.contains("retry:1001"));
     }
 
+    #[tokio::test]
+    async fn test_sse_resumable() {
+        use salvo_core::test::TestClient;
+
+        #[handler]
+        async fn events(req: &mut Request, res: &mut Response) {
+            stream_resumable(req, res, |last_id| {
+                let start = last_id.and_then(|id| id.parse::<usize>().ok()).map_or(0, |id| id + 1);
+                tokio_stream::iter(
+                    (start..3).map(|id| Ok::<_, Infallible>(SseEvent::default().id(id.to_string()).text("tick"))),
+                )
+            });
+        }
+        let service = Service::new(Router::new().get(events));
+
+        let text = TestClient::get("http://127.0.0.1:5801/")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(text, "data:tick\nid:0\n\ndata:tick\nid:1\n\ndata:tick\nid:2\n\n");
+
+        // The client reconnects after it received event `0`.
+        let text = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("last-event-id", "0", true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(text, "data:tick\nid:1\n\ndata:tick\nid:2\n\n");
+    }
+
+    #[test]
+    fn test_sse_id_with_line_break() {
+        let event = SseEvent::default().id("1\ndata:\0injected");
+        assert_eq!(event.to_string(), "id:1data:injected\n\n");
+        assert!(SseEvent::default().try_id("1\ndata:injected").is_err());
+        assert!(SseEvent::default().try_id("1\0").is_err());
+        assert!(SseEvent::default().try_id("1").is_ok());
+    }
+
     #[tokio::test]
     async fn test_sse_id() {
         let event_stream = tokio_stream::iter(vec![Ok::<_, Infallible>(SseEvent::default().id("jobs"))]);
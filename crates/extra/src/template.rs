@@ -0,0 +1,360 @@
+//! Template engine integration.
+//!
+//! Templates are rendered as `text/html; charset=utf-8`. Render errors become `500 Internal Server Error`, and the
+//! error text is sent as detail of the [`StatusError`] only in debug builds, so that template internals are not
+//! leaked in production.
+//!
+//! Engines which render templates by name, like `tera`, implement [`TemplateEngine`] and are stored into depot by
+//! the [`Templates`] hoop. Compiled templates, like `askama`, implement [`RenderTemplate`] and are written by
+//! [`Html`]. `tera::Tera` and `askama::Template` are supported if `tera` and `askama` features are enabled.
+//!
+//! Read more: <https://salvo.rs>
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+use salvo_core::http::{Request, Response, StatusError};
+use salvo_core::writing::Text;
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Scribe};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Key for [`Templates`] in depot.
+pub const TEMPLATES_KEY: &str = "::salvo::template::templates";
+
+/// A template engine which renders templates by name with a serializable context.
+///
+/// It is implemented for `tera::Tera` if `tera` feature is enabled, and for closures.
+pub trait TemplateEngine: Send + Sync + 'static {
+    /// Error type of rendering.
+    type Error: Display;
+    /// Render template `name` with `context`.
+    fn render(&self, name: &str, context: &Value) -> Result<String, Self::Error>;
+}
+
+impl<F, E> TemplateEngine for F
+where
+    F: Fn(&str, &Value) -> Result<String, E> + Send + Sync + 'static,
+    E: Display,
+{
+    type Error = E;
+    fn render(&self, name: &str, context: &Value) -> Result<String, Self::Error> {
+        self(name, context)
+    }
+}
+
+#[cfg(feature = "tera")]
+impl TemplateEngine for tera::Tera {
+    type Error = tera::Error;
+    fn render(&self, name: &str, context: &Value) -> Result<String, Self::Error> {
+        tera::Tera::render(self, name, &tera::Context::from_value(context.clone())?)
+    }
+}
+
+trait DynTemplateEngine: Send + Sync + 'static {
+    fn render(&self, name: &str, context: &Value) -> Result<String, String>;
+}
+impl<E> DynTemplateEngine for E
+where
+    E: TemplateEngine,
+{
+    fn render(&self, name: &str, context: &Value) -> Result<String, String> {
+        TemplateEngine::render(self, name, context).map_err(|e| e.to_string())
+    }
+}
+
+/// A hoop which stores a [`TemplateEngine`] into depot, templates are rendered by
+/// [`TemplateDepotExt::render_template`].
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::template::{TemplateDepotExt, Templates};
+///
+/// #[handler]
+/// async fn hello(depot: &mut Depot) -> Result<Text<String>, StatusError> {
+///     depot.render_template("hello.html", &serde_json::json!({"name": "jobs"}))
+/// }
+///
+/// let engine = |_name: &str, context: &serde_json::Value| {
+///     Ok::<_, std::convert::Infallible>(format!("<p>Hello {}</p>", context["name"]))
+/// };
+/// let router = Router::new().hoop(Templates::new(engine)).get(hello);
+/// ```
+#[derive(Clone)]
+pub struct Templates {
+    engine: Arc<dyn DynTemplateEngine>,
+}
+
+impl fmt::Debug for Templates {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Templates").finish()
+    }
+}
+
+impl Templates {
+    /// Create new `Templates` hoop with the template engine.
+    #[inline]
+    pub fn new(engine: impl TemplateEngine) -> Self {
+        Self {
+            engine: Arc::new(engine),
+        }
+    }
+
+    /// Render template `name` with `context`.
+    pub fn render<C>(&self, name: &str, context: &C) -> Result<Text<String>, StatusError>
+    where
+        C: Serialize + ?Sized,
+    {
+        let context = serde_json::to_value(context).map_err(|e| render_error(name, e))?;
+        self.engine
+            .render(name, &context)
+            .map(Text::Html)
+            .map_err(|e| render_error(name, e))
+    }
+}
+
+#[async_trait]
+impl Handler for Templates {
+    async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        depot.insert(TEMPLATES_KEY, self.clone());
+        ctrl.call_next(req, depot, res).await;
+    }
+}
+
+/// Extension trait for rendering templates by [`Templates`] in depot.
+pub trait TemplateDepotExt {
+    /// Render template `name` with `context`, a `500 Internal Server Error` is returned if [`Templates`] is not
+    /// in depot or rendering failed.
+    fn render_template<C>(&self, name: &str, context: &C) -> Result<Text<String>, StatusError>
+    where
+        C: Serialize + ?Sized;
+}
+
+impl TemplateDepotExt for Depot {
+    fn render_template<C>(&self, name: &str, context: &C) -> Result<Text<String>, StatusError>
+    where
+        C: Serialize + ?Sized,
+    {
+        match self.get::<Templates>(TEMPLATES_KEY) {
+            Ok(templates) => templates.render(name, context),
+            Err(_) => Err(render_error(name, "`Templates` is not found in depot")),
+        }
+    }
+}
+
+/// A compiled template which renders itself.
+///
+/// It is implemented for all `askama::Template` types if `askama` feature is enabled.
+pub trait RenderTemplate {
+    /// Error type of rendering.
+    type Error: Display;
+    /// Render the template.
+    fn render_template(&self) -> Result<String, Self::Error>;
+}
+
+#[cfg(feature = "askama")]
+impl<T> RenderTemplate for T
+where
+    T: askama::Template,
+{
+    type Error = askama::Error;
+    fn render_template(&self) -> Result<String, Self::Error> {
+        askama::Template::render(self)
+    }
+}
+
+/// Write a [`RenderTemplate`] to response as `text/html; charset=utf-8` content.
+///
+/// # Example
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_extra::template::{Html, RenderTemplate};
+///
+/// struct Hello<'a> {
+///     name: &'a str,
+/// }
+/// impl RenderTemplate for Hello<'_> {
+///     type Error = std::fmt::Error;
+///     fn render_template(&self) -> Result<String, Self::Error> {
+///         Ok(format!("<p>Hello {}</p>", self.name))
+///     }
+/// }
+///
+/// #[handler]
+/// async fn hello(res: &mut Response) {
+///     res.render(Html(Hello { name: "jobs" }));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Html<T>(pub T);
+
+impl<T> Scribe for Html<T>
+where
+    T: RenderTemplate,
+{
+    fn render(self, res: &mut Response) {
+        match self.0.render_template() {
+            Ok(content) => res.render(Text::Html(content)),
+            Err(e) => {
+                let error = render_error(std::any::type_name::<T>(), &e);
+                res.render_failed(e.to_string());
+                res.render(error);
+            }
+        }
+    }
+}
+
+fn render_error(name: &str, e: impl Display) -> StatusError {
+    tracing::error!(error = %e, template = name, "render template failed");
+    let err = StatusError::internal_server_error().brief("render template failed.");
+    if cfg!(debug_assertions) {
+        err.detail(format!("template `{name}`: {e}"))
+    } else {
+        err
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::http::header::CONTENT_TYPE;
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+    use serde_json::json;
+
+    use super::*;
+
+    fn engine(name: &str, context: &Value) -> Result<String, String> {
+        match (name, context["name"].as_str()) {
+            ("hello.html", Some(value)) => Ok(format!("<p>Hello {value}</p>")),
+            ("hello.html", None) => Err("variable `name` not found".into()),
+            _ => Err(format!("template `{name}` not found")),
+        }
+    }
+
+    struct Hello {
+        name: Option<String>,
+    }
+    impl RenderTemplate for Hello {
+        type Error = &'static str;
+        fn render_template(&self) -> Result<String, Self::Error> {
+            self.name
+                .as_ref()
+                .map(|name| format!("<p>Hi {name}</p>"))
+                .ok_or("name is missing")
+        }
+    }
+
+    #[handler]
+    async fn by_name(req: &mut Request, depot: &mut Depot) -> Result<Text<String>, StatusError> {
+        let context = match req.query::<String>("name") {
+            Some(name) => json!({ "name": name }),
+            None => json!({}),
+        };
+        depot.render_template("hello.html", &context)
+    }
+    #[handler]
+    async fn compiled(req: &mut Request, res: &mut Response) {
+        res.render(Html(Hello {
+            name: req.query::<String>("name"),
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_templates() {
+        let router = Router::new()
+            .hoop(Templates::new(engine))
+            .push(Router::with_path("by_name").get(by_name))
+            .push(Router::with_path("compiled").get(compiled));
+        let service = Service::new(router);
+
+        for (path, expected) in [("by_name", "<p>Hello jobs</p>"), ("compiled", "<p>Hi jobs</p>")] {
+            let mut res = TestClient::get(format!("http://127.0.0.1:5801/{path}?name=jobs"))
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code, Some(StatusCode::OK));
+            assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/html; charset=utf-8");
+            assert_eq!(res.take_string().await.unwrap(), expected);
+        }
+
+        for path in ["by_name", "compiled"] {
+            let res = TestClient::get(format!("http://127.0.0.1:5801/{path}"))
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+        }
+    }
+
+    // Error text is sent as detail only in debug builds.
+    #[cfg(debug_assertions)]
+    #[tokio::test]
+    async fn test_templates_error_detail() {
+        let router = Router::new()
+            .hoop(Templates::new(engine))
+            .push(Router::with_path("by_name").get(by_name))
+            .push(Router::with_path("compiled").get(compiled));
+        let service = Service::new(router);
+
+        for (path, detail) in [
+            ("by_name", "template `hello.html`: variable `name` not found"),
+            ("compiled", "name is missing"),
+        ] {
+            let mut res = TestClient::get(format!("http://127.0.0.1:5801/{path}"))
+                .add_header("accept", "application/json", true)
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+            let error = res.take_json::<Value>().await.unwrap();
+            assert!(error["error"]["message"].as_str().unwrap().ends_with(detail));
+        }
+    }
+
+    #[cfg(feature = "tera")]
+    #[tokio::test]
+    async fn test_tera() {
+        let mut tera = tera::Tera::default();
+        tera.add_raw_template("hello.html", "<p>Hello {{ name }}</p>").unwrap();
+        let service = Service::new(Router::new().hoop(Templates::new(tera)).get(by_name));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/?name=%3Cjobs%3E")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "<p>Hello &lt;jobs&gt;</p>");
+
+        let res = TestClient::get("http://127.0.0.1:5801/").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    #[cfg(feature = "askama")]
+    #[tokio::test]
+    async fn test_askama() {
+        #[derive(askama::Template)]
+        #[template(source = "<p>Hello {{ name }}</p>", ext = "html")]
+        struct HelloTemplate {
+            name: String,
+        }
+        #[handler]
+        async fn hello(req: &mut Request, res: &mut Response) {
+            res.render(Html(HelloTemplate {
+                name: req.query::<String>("name").unwrap_or_default(),
+            }));
+        }
+        let service = Service::new(Router::new().get(hello));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/?name=%3Cjobs%3E")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "text/html; charset=utf-8");
+        assert_eq!(res.take_string().await.unwrap(), "<p>Hello &lt;jobs&gt;</p>");
+    }
+
+    #[tokio::test]
+    async fn test_templates_missing() {
+        let service = Service::new(Router::new().get(by_name));
+        let res = TestClient::get("http://127.0.0.1:5801/?name=jobs").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+}
@@ -3,28 +3,77 @@
 //! Read more: <https://salvo.rs>
 use std::time::Duration;
 
-use salvo_core::http::{Request, Response, StatusError};
+use salvo_core::http::{Request, ResBody, Response, StatusCode, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
-/// Timeout
+/// Timeout middleware, it responds an error if the rest of the handler chain is not done in time.
+///
+/// When the time limit is exceeded, the future of the rest of the handler chain is dropped, so the handler is
+/// cancelled at its next `.await` point. Status code and body written by the cancelled handlers are replaced with
+/// the timeout error, headers are restored to those before the rest of the handler chain is called.
+///
+/// Different routers can use different time limits, the shortest one takes effect if timeouts are nested. If a
+/// [`Deadline`](salvo_core::http::Deadline) is set in depot, for example by
+/// [`RequestDeadline`](crate::deadline::RequestDeadline), its remaining budget is used when it is shorter.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use salvo_core::prelude::*;
+/// use salvo_extra::timeout::Timeout;
+///
+/// #[handler]
+/// async fn report() -> &'static str {
+///     "report"
+/// }
+///
+/// let router = Router::new()
+///     .hoop(Timeout::new(Duration::from_secs(30)))
+///     .push(Router::with_path("report").hoop(Timeout::new(Duration::from_secs(5))).get(report));
+/// ```
 pub struct Timeout {
     value: Duration,
+    status_code: StatusCode,
 }
 impl Timeout {
-    /// Create a new `Timeout`.
+    /// Create a new `Timeout`, `503 Service Unavailable` is responded when time limit is exceeded.
     #[inline]
     pub fn new(value: Duration) -> Self {
-        Timeout { value }
+        Timeout {
+            value,
+            status_code: StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Sets the status code responded when time limit is exceeded, such as `504 Gateway Timeout`.
+    ///
+    /// `503 Service Unavailable` is used if `status_code` is not an error status code.
+    #[inline]
+    pub fn status_code(mut self, status_code: StatusCode) -> Self {
+        if status_code.is_client_error() || status_code.is_server_error() {
+            self.status_code = status_code;
+        }
+        self
     }
 }
 #[async_trait]
 impl Handler for Timeout {
     #[inline]
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
+        let headers = res.headers().clone();
+        let value = depot
+            .deadline()
+            .map_or(self.value, |deadline| deadline.remaining().min(self.value));
         tokio::select! {
             _ = ctrl.call_next(req, depot, res) => {},
-            _ = tokio::time::sleep(self.value) => {
-                res.render(StatusError::internal_server_error().brief("Server process the request timeout."));
+            _ = tokio::time::sleep(value) => {
+                res.status_code = None;
+                res.body = ResBody::None;
+                res.set_headers(headers);
+                let error = StatusError::from_code(self.status_code).unwrap_or_else(StatusError::service_unavailable);
+                res.render(error.brief("Server process the request timeout."));
                 ctrl.skip_rest();
             }
         }
@@ -33,11 +82,70 @@ impl Handler for Timeout {
 
 #[cfg(test)]
 mod tests {
+    use salvo_core::http::Deadline;
     use salvo_core::prelude::*;
     use salvo_core::test::{ResponseExt, TestClient};
 
     use super::*;
 
+    #[tokio::test]
+    async fn test_timeout_status() {
+        #[handler]
+        async fn slow(res: &mut Response) {
+            res.status_code(StatusCode::CREATED);
+            res.add_header("x-partial", "1", true).unwrap();
+            res.render("partial");
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+
+        let router = Router::new()
+            .hoop(Timeout::new(Duration::from_secs(5)))
+            .push(
+                Router::with_path("slow")
+                    .hoop(Timeout::new(Duration::from_millis(50)))
+                    .get(slow),
+            )
+            .push(
+                Router::with_path("gateway")
+                    .hoop(Timeout::new(Duration::from_millis(50)).status_code(StatusCode::GATEWAY_TIMEOUT))
+                    .get(slow),
+            );
+        let service = Service::new(router);
+
+        let now = std::time::Instant::now();
+        let mut res = TestClient::get("http://127.0.0.1:5801/slow").send(&service).await;
+        assert!(now.elapsed() < Duration::from_millis(200));
+        assert_eq!(res.status_code, Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(res.headers().get("x-partial").is_none());
+        let content = res.take_string().await.unwrap();
+        assert!(content.contains("timeout") && !content.contains("partial"));
+
+        let res = TestClient::get("http://127.0.0.1:5801/gateway").send(&service).await;
+        assert_eq!(res.status_code, Some(StatusCode::GATEWAY_TIMEOUT));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_deadline() {
+        #[handler]
+        async fn slow() -> &'static str {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            "hello"
+        }
+        #[handler]
+        async fn set_deadline(depot: &mut Depot) {
+            depot.set_deadline(Deadline::after(Duration::from_millis(50)));
+        }
+
+        let router = Router::new()
+            .hoop(set_deadline)
+            .hoop(Timeout::new(Duration::from_secs(5)))
+            .get(slow);
+        let now = std::time::Instant::now();
+        let res = TestClient::get("http://127.0.0.1:5801").send(router).await;
+        assert!(now.elapsed() < Duration::from_millis(500));
+        assert_eq!(res.status_code, Some(StatusCode::SERVICE_UNAVAILABLE));
+    }
+
     #[tokio::test]
     async fn test_timeout_handler() {
         #[handler]
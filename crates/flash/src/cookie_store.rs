@@ -1,10 +1,13 @@
 use salvo_core::http::cookie::time::Duration;
-use salvo_core::http::cookie::{Cookie, SameSite};
+use salvo_core::http::cookie::{Cookie, Key, SameSite};
 use salvo_core::{Depot, Request, Response};
 
 use super::{Flash, FlashHandler, FlashStore};
 
 /// CookieStore is a `FlashStore` implementation that stores the flash messages in a cookie.
+///
+/// The cookie is signed with HMAC, tampered cookies are ignored. A random key is generated by default, use
+/// [`CookieStore::key`] to share the key between server instances or restarts.
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct CookieStore {
@@ -18,6 +21,8 @@ pub struct CookieStore {
     pub path: String,
     /// The cookie name.
     pub name: String,
+    /// The key used to sign the cookie.
+    pub key: Key,
 }
 impl Default for CookieStore {
     fn default() -> Self {
@@ -34,6 +39,7 @@ impl CookieStore {
             http_only: true,
             path: "/".into(),
             name: "salvo.flash".into(),
+            key: Key::generate(),
         }
     }
 
@@ -67,6 +73,12 @@ impl CookieStore {
         self
     }
 
+    /// Sets the key used to sign the cookie.
+    pub fn key(mut self, key: Key) -> Self {
+        self.key = key;
+        self
+    }
+
     /// Into `FlashHandler`.
     pub fn into_handler(self) -> FlashHandler<CookieStore> {
         FlashHandler::new(self)
@@ -74,8 +86,12 @@ impl CookieStore {
 }
 impl FlashStore for CookieStore {
     async fn load_flash(&self, req: &mut Request, _depot: &mut Depot) -> Option<Flash> {
-        match req.cookie(&self.name) {
-            None => None,
+        req.cookie(&self.name)?;
+        match req.signed_cookie(&self.name, &self.key) {
+            None => {
+                tracing::warn!("flash cookie signature is invalid");
+                None
+            }
             Some(cookie) => match serde_json::from_str(cookie.value()) {
                 Ok(flash) => Some(flash),
                 Err(e) => {
@@ -86,13 +102,14 @@ impl FlashStore for CookieStore {
         }
     }
     async fn save_flash(&self, _req: &mut Request, _depot: &mut Depot, res: &mut Response, flash: Flash) {
-        res.add_cookie(
+        res.add_signed_cookie(
             Cookie::build((self.name.clone(), serde_json::to_string(&flash).unwrap_or_default()))
                 .max_age(self.max_age)
                 .path(self.path.clone())
                 .same_site(self.same_site)
                 .http_only(self.http_only)
                 .build(),
+            &self.key,
         );
     }
     async fn clear_flash(&self, _depot: &mut Depot, res: &mut Response) {
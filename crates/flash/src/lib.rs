@@ -99,6 +99,14 @@ pub struct FlashMessage {
     pub value: String,
 }
 impl FlashMessage {
+    /// Create a new `FlashMessage` with level.
+    #[inline]
+    pub fn new(level: FlashLevel, message: impl Into<String>) -> Self {
+        Self {
+            level,
+            value: message.into(),
+        }
+    }
     /// Create a new `FlashMessage` with `FlashLevel::Debug`.
     #[inline]
     pub fn debug(message: impl Into<String>) -> Self {
@@ -203,6 +211,10 @@ pub trait FlashDepotExt {
     fn outgoing_flash(&self) -> &Flash;
     /// Get mutable outgoing flash.
     fn outgoing_flash_mut(&mut self) -> &mut Flash;
+    /// Get messages of incoming flash, they were added in the previous request.
+    fn flash_messages(&self) -> &[FlashMessage];
+    /// Add a message to outgoing flash, it is available in the next request.
+    fn add_flash(&mut self, level: FlashLevel, message: impl Into<String>) -> &mut Self;
 }
 
 impl FlashDepotExt for Depot {
@@ -222,6 +234,19 @@ impl FlashDepotExt for Depot {
         self.get_mut::<Flash>(OUTGOING_FLASH_KEY)
             .expect("Flash should be initialized")
     }
+
+    #[inline]
+    fn flash_messages(&self) -> &[FlashMessage] {
+        self.get::<Flash>(INCOMING_FLASH_KEY)
+            .map(|flash| &flash.0[..])
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    fn add_flash(&mut self, level: FlashLevel, message: impl Into<String>) -> &mut Self {
+        self.outgoing_flash_mut().0.push(FlashMessage::new(level, message));
+        self
+    }
 }
 
 /// `FlashHandler` is a middleware for flash messages.
@@ -344,6 +369,56 @@ mod tests {
         assert!(respone.take_string().await.unwrap().is_empty());
     }
 
+    #[cfg(feature = "cookie-store")]
+    #[tokio::test]
+    async fn test_cookie_store_signed() {
+        #[handler]
+        async fn login(depot: &mut Depot, res: &mut Response) {
+            depot.add_flash(FlashLevel::Success, "Login successful");
+            res.render(Redirect::other("/home"));
+        }
+        #[handler]
+        async fn home(depot: &mut Depot) -> String {
+            depot
+                .flash_messages()
+                .iter()
+                .map(|message| format!("{}: {}", message.level, message.value))
+                .collect()
+        }
+        let router = Router::new()
+            .hoop(CookieStore::new().into_handler())
+            .push(Router::with_path("login").post(login))
+            .push(Router::with_path("home").get(home));
+        let service = Service::new(router);
+
+        let respone = TestClient::post("http://127.0.0.1:5800/login").send(&service).await;
+        let cookie = respone.headers().get(SET_COOKIE).unwrap().to_str().unwrap().to_owned();
+        assert!(
+            !cookie.starts_with("salvo.flash=%5B"),
+            "flash cookie is not signed: {cookie}"
+        );
+
+        let content = TestClient::get("http://127.0.0.1:5800/home")
+            .add_header(COOKIE, &cookie, true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "success: Login successful");
+
+        let (name, value) = cookie.split(';').next().unwrap().split_once('=').unwrap();
+        let tampered = format!("{name}={}", value.replace("Login", "Admin"));
+        let content = TestClient::get("http://127.0.0.1:5800/home")
+            .add_header(COOKIE, tampered, true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert!(content.is_empty());
+    }
+
     #[cfg(feature = "session-store")]
     #[tokio::test]
     async fn test_session_store() {
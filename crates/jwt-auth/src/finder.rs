@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use salvo_core::async_trait;
+use salvo_core::http::auth::parse_bearer_token;
 use salvo_core::http::header::{HeaderName, AUTHORIZATION, PROXY_AUTHORIZATION};
 use salvo_core::http::{Method, Request};
 
@@ -66,8 +67,8 @@ impl JwtTokenFinder for HeaderFinder {
         if self.cared_methods.contains(req.method()) {
             for header_name in &self.header_names {
                 if let Some(Ok(auth)) = req.headers().get(header_name).map(|auth| auth.to_str()) {
-                    if auth.starts_with("Bearer") {
-                        return auth.split_once(' ').map(|(_, token)| token.to_owned());
+                    if let Some(token) = parse_bearer_token(auth) {
+                        return Some(token.to_owned());
                     }
                 }
             }
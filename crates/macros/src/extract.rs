@@ -19,6 +19,7 @@ struct FieldInfo {
     rename: Option<String>,
     serde_rename: Option<String>,
     flatten: bool,
+    whole_body: bool,
 }
 impl TryFrom<&Field> for FieldInfo {
     type Error = Error;
@@ -30,12 +31,14 @@ impl TryFrom<&Field> for FieldInfo {
         let mut aliases = Vec::with_capacity(field.attrs.len());
         let mut rename = None;
         let mut flatten = None;
+        let mut whole_body = false;
         for attr in attrs {
             if attr.path().is_ident("salvo") {
                 if let Ok(Some(metas)) = attribute::find_nested_list(&attr, "extract") {
                     let info: ExtractFieldInfo = metas.parse_args()?;
                     sources.extend(info.sources);
                     aliases.extend(info.aliases);
+                    whole_body |= info.whole_body;
                     flatten = info.flatten;
                     if info.rename.is_some() {
                         rename = info.rename;
@@ -64,6 +67,14 @@ impl TryFrom<&Field> for FieldInfo {
                 return Err(Error::new_spanned(ident, "flatten field should not define aliases."));
             }
         }
+        if whole_body {
+            if flatten {
+                return Err(Error::new_spanned(ident, "whole body field should not be flatten."));
+            }
+            if !sources.is_empty() {
+                return Err(Error::new_spanned(ident, "whole body field should not define sources."));
+            }
+        }
 
         Ok(Self {
             ident,
@@ -73,6 +84,7 @@ impl TryFrom<&Field> for FieldInfo {
             rename,
             serde_rename,
             flatten,
+            whole_body,
         })
     }
 }
@@ -83,6 +95,7 @@ struct ExtractFieldInfo {
     aliases: Vec<String>,
     rename: Option<String>,
     flatten: Option<bool>,
+    whole_body: bool,
 }
 impl Parse for ExtractFieldInfo {
     fn parse(input: ParseStream) -> syn::Result<Self> {
@@ -108,6 +121,15 @@ impl Parse for ExtractFieldInfo {
                 "flatten" => {
                     extract.flatten = Some(true);
                 }
+                "param" | "query" | "header" => {
+                    extract.sources.push(SourceInfo {
+                        from: id,
+                        parser: "smart".to_owned(),
+                    });
+                }
+                "body" => {
+                    extract.whole_body = true;
+                }
                 _ => {
                     return Err(input.error("unexpected attribute"));
                 }
@@ -195,15 +217,11 @@ impl ExtractibleArgs {
                     let nested = metas.parse_args_with(Punctuated::<Meta, Comma>::parse_terminated)?;
                     for meta in nested {
                         match meta {
-                            Meta::List(meta) => {
-                                if meta.path.is_ident("default_source") {
-                                    default_sources.push(meta.parse_args()?);
-                                }
+                            Meta::List(meta) if meta.path.is_ident("default_source") => {
+                                default_sources.push(meta.parse_args()?);
                             }
-                            Meta::NameValue(meta) => {
-                                if meta.path.is_ident("rename_all") {
-                                    rename_all = Some(expr_lit_value(&meta.value)?.parse::<RenameRule>()?);
-                                }
+                            Meta::NameValue(meta) if meta.path.is_ident("rename_all") => {
+                                rename_all = Some(expr_lit_value(&meta.value)?.parse::<RenameRule>()?);
                             }
                             _ => {}
                         }
@@ -337,6 +355,11 @@ pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
                 field = field.serde_rename(#serde_rename);
             }
         });
+        let whole_body = field.whole_body.then(|| {
+            quote! {
+                field = field.whole_body(true);
+            }
+        });
         fields.push(quote! {
             let mut field = #salvo::extract::metadata::Field::new(#field_ident);
             #nested_metadata
@@ -344,6 +367,7 @@ pub(crate) fn generate(args: DeriveInput) -> Result<TokenStream, Error> {
             #(#aliases)*
             #rename
             #serde_rename
+            #whole_body
             metadata = metadata.add_field(field);
         });
     }
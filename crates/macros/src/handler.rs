@@ -1,5 +1,6 @@
-use proc_macro2::{Span, TokenStream};
-use quote::{quote, ToTokens};
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
 use syn::{Ident, ImplItem, Item, Pat, ReturnType, Signature, Type};
 
 use crate::shared::*;
@@ -93,8 +94,8 @@ fn handle_fn(salvo: &Ident, sig: &Signature) -> syn::Result<TokenStream> {
             }
             InputType::Unknown => {
                 return Err(syn::Error::new_spanned(
-                    &sig.inputs,
-                    "the inputs parameters must be Request, Depot, Response or FlowCtrl",
+                    input,
+                    "the inputs parameters must be Request, Depot, Response, FlowCtrl or types implement `Extractible`",
                 ))
             }
             InputType::NoReference(pat) => {
@@ -106,8 +107,15 @@ fn handle_fn(salvo: &Ident, sig: &Signature) -> syn::Result<TokenStream> {
                     let id = Ident::new(&idv, Span::call_site());
                     let idv = idv.trim_start_matches('_');
 
-                    extract_ts.push(quote!{
-                        let #id: #ty = match <#ty as #salvo::Extractible>::extract_with_arg(__macro_gen_req, #idv).await {
+                    // Spanned at the type, so that the error points to the parameter if it is not extractible.
+                    let span = pat.ty.span();
+                    let salvo = Ident::new(&salvo.to_string(), span);
+                    let spanned_ty = respan(ty.to_token_stream(), span);
+                    let extract = quote_spanned! {span=>
+                        <#spanned_ty as #salvo::Extractible>::extract_with_arg(__macro_gen_req, #idv).await
+                    };
+                    extract_ts.push(quote! {
+                        let #id: #ty = match #extract {
                             Ok(data) => data,
                             Err(e) => {
                                 e.write(__macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
@@ -149,21 +157,46 @@ fn handle_fn(salvo: &Ident, sig: &Signature) -> syn::Result<TokenStream> {
             }
         }
         ReturnType::Type(_, _) => {
+            // Return value is written by `Writer` if it is implemented, otherwise by `Responder`.
+            let write_ret = quote! {
+                #[allow(unused_imports)]
+                use #salvo::__private::{ViaResponder as _, ViaWriter as _};
+                (&&#salvo::__private::Wrap::of(&__macro_gen_ret))
+                    .__responder_kind()
+                    .respond(__macro_gen_ret, __macro_gen_req, __macro_gen_depot, __macro_gen_res)
+                    .await;
+            };
             if sig.asyncness.is_none() {
                 Ok(quote! {
                     async fn handle(&self, __macro_gen_req: &mut #salvo::Request, __macro_gen_depot: &mut #salvo::Depot, __macro_gen_res: &mut #salvo::Response, __macro_gen_ctrl: &mut #salvo::FlowCtrl) {
                         #(#extract_ts)*
-                        #salvo::Writer::write(Self::#name(#(#call_args),*), __macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
+                        let __macro_gen_ret = Self::#name(#(#call_args),*);
+                        #write_ret
                     }
                 })
             } else {
                 Ok(quote! {
                     async fn handle(&self, __macro_gen_req: &mut #salvo::Request, __macro_gen_depot: &mut #salvo::Depot, __macro_gen_res: &mut #salvo::Response, __macro_gen_ctrl: &mut #salvo::FlowCtrl) {
                         #(#extract_ts)*
-                        #salvo::Writer::write(Self::#name(#(#call_args),*).await, __macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
+                        let __macro_gen_ret = Self::#name(#(#call_args),*).await;
+                        #write_ret
                     }
                 })
             }
         }
     }
 }
+
+/// Sets span of all tokens, types rebuilt from string lose their original spans.
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                token = TokenTree::Group(Group::new(group.delimiter(), respan(group.stream(), span)));
+            }
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
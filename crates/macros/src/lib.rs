@@ -32,6 +32,10 @@ pub fn handler(_args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 /// Generate code for extractible type.
+///
+/// Field sources can be set by `#[salvo(extract(source(from = "query")))]`, or by the shorthands
+/// `#[salvo(extract(param))]`, `#[salvo(extract(query))]` and `#[salvo(extract(header))]`. Field with
+/// `#[salvo(extract(body))]` is deserialized from the whole body.
 #[proc_macro_derive(Extractible, attributes(salvo))]
 pub fn derive_extractible(input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(input as DeriveInput);
@@ -119,7 +123,13 @@ mod tests {
                         __macro_gen_res: &mut salvo::Response,
                         __macro_gen_ctrl: &mut salvo::FlowCtrl
                     ) {
-                        salvo::Writer::write(Self::hello(__macro_gen_req, __macro_gen_depot, __macro_gen_res, __macro_gen_ctrl).await, __macro_gen_req, __macro_gen_depot, __macro_gen_res).await;
+                        let __macro_gen_ret = Self::hello(__macro_gen_req, __macro_gen_depot, __macro_gen_res, __macro_gen_ctrl).await;
+                        #[allow(unused_imports)]
+                        use salvo::__private::{ViaResponder as _, ViaWriter as _};
+                        (&&salvo::__private::Wrap::of(&__macro_gen_ret))
+                            .__responder_kind()
+                            .respond(__macro_gen_ret, __macro_gen_req, __macro_gen_depot, __macro_gen_res)
+                            .await;
                     }
                 }
             }
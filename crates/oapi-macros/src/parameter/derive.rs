@@ -514,6 +514,9 @@ impl ToTokens for Parameter<'_> {
             tokens.extend(quote! { .parameter_in(#parameter_in) });
         } else if let Some(parameter_in) = &self.container_attributes.default_parameter_in {
             tokens.extend(parameter_in.to_token_stream());
+        } else {
+            // Same as the default source of extractor.
+            tokens.extend(quote! { .parameter_in(#oapi::oapi::parameter::ParameterIn::Query) });
         }
 
         if let Some(style) = param_features.pop_style_feature() {
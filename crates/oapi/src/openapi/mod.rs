@@ -1002,6 +1002,80 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_openapi_router_parameters() {
+        use salvo_core::routing::Resource;
+        use salvo_core::test::{ResponseExt, TestClient};
+        use serde::{Deserialize, Serialize};
+
+        use crate::{endpoint, ToParameters};
+
+        #[derive(Deserialize, ToParameters)]
+        struct Pager {
+            /// Page number.
+            page: u32,
+            size: Option<u32>,
+        }
+        #[derive(Serialize, Deserialize, ToSchema)]
+        struct User {
+            name: String,
+        }
+        #[endpoint]
+        async fn list_users(pager: Pager) -> Json<Vec<User>> {
+            let _ = (pager.page, pager.size);
+            Json(vec![])
+        }
+        #[endpoint]
+        async fn update_user(id: PathParam<u64>, user: JsonBody<User>) -> Json<User> {
+            let _ = id.into_inner();
+            Json(user.into_inner())
+        }
+
+        let router = Router::new()
+            .push(Router::with_path("users").resource(Resource::new().index(list_users).update(update_user)));
+        let doc = OpenApi::new("user api", "0.1.0").merge_router(&router);
+        let router = router.unshift(doc.into_router("/api-doc/openapi.json"));
+
+        let doc = TestClient::get("http://127.0.0.1:5801/api-doc/openapi.json")
+            .send(router)
+            .await
+            .take_json::<Value>()
+            .await
+            .unwrap();
+        assert_eq!(doc["openapi"], "3.1.0");
+        assert_eq!(
+            doc["paths"]["/users"]["get"]["parameters"],
+            json!([
+                {
+                    "name": "page",
+                    "in": "query",
+                    "description": "Page number.",
+                    "required": true,
+                    "schema": {"type": "integer", "format": "int32", "minimum": 0.0}
+                },
+                {
+                    "name": "size",
+                    "in": "query",
+                    "required": false,
+                    "schema": {"type": "integer", "format": "int32", "minimum": 0.0, "nullable": true}
+                }
+            ])
+        );
+        for method in ["put", "patch"] {
+            let operation = &doc["paths"]["/users/{id}"][method];
+            assert_eq!(operation["parameters"][0]["name"], "id");
+            assert_eq!(operation["parameters"][0]["in"], "path");
+            assert_eq!(operation["parameters"][0]["required"], true);
+            assert!(
+                operation["requestBody"]["content"]["application/json"]["schema"]["$ref"]
+                    .as_str()
+                    .unwrap()
+                    .ends_with("User")
+            );
+        }
+        assert!(doc["paths"]["/users/{id}"].get("delete").is_none());
+    }
+
     #[test]
     fn test_build_openapi() {
         let _doc = OpenApi::new("pet api", "0.1.0")
@@ -1140,6 +1214,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_openapi_schema_nested_with_examples() {
+        #[derive(Serialize, Deserialize, ToSchema)]
+        struct Tag {
+            #[salvo(schema(example = "cute"))]
+            name: String,
+        }
+        #[derive(Serialize, Deserialize, ToSchema)]
+        struct Pet {
+            #[salvo(schema(example = 7))]
+            id: u64,
+            nickname: Option<String>,
+            tags: Vec<Tag>,
+            owner_tags: Option<Vec<Tag>>,
+        }
+        #[salvo_oapi::endpoint]
+        async fn create_pet(pet: JsonBody<Pet>) -> Json<Pet> {
+            Json(pet.into_inner())
+        }
+
+        let router = Router::with_path("pets").post(create_pet);
+        let doc = OpenApi::new("pet api", "0.1.0").merge_router(&router);
+        let doc = Value::from_str(&doc.to_json().unwrap()).unwrap();
+        let tag_ref = "#/components/schemas/salvo_oapi.openapi.tests.test_openapi_schema_nested_with_examples.Tag";
+        assert_eq!(
+            doc["components"]["schemas"]["salvo_oapi.openapi.tests.test_openapi_schema_nested_with_examples.Pet"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "id": {"type": "integer", "format": "int64", "minimum": 0.0, "example": 7},
+                    "nickname": {"type": "string", "nullable": true},
+                    "tags": {"type": "array", "items": {"$ref": tag_ref}},
+                    "owner_tags": {"type": "array", "items": {"$ref": tag_ref}, "nullable": true}
+                },
+                "required": ["id", "tags"]
+            })
+        );
+        assert_eq!(
+            doc["components"]["schemas"]["salvo_oapi.openapi.tests.test_openapi_schema_nested_with_examples.Tag"]
+                ["properties"]["name"],
+            json!({"type": "string", "example": "cute"})
+        );
+    }
+
     #[test]
     fn test_openapi_schema_work_with_generics() {
         #[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
@@ -12,11 +12,14 @@ pub use config::Config;
 pub use oauth::Config as OauthConfig;
 use rust_embed::RustEmbed;
 use salvo_core::http::uri::{Parts as UriParts, Uri};
-use salvo_core::http::{header, HeaderValue, ResBody, StatusError};
+use salvo_core::http::{header, CacheControl, HeaderValue, ResBody, StatusError};
 use salvo_core::writing::Redirect;
 use salvo_core::{async_trait, Depot, Error, FlowCtrl, Handler, Request, Response, Router};
 use serde::Serialize;
 
+/// Version of the bundled Swagger UI, the `folder` of [`SwaggerUiDist`] must be updated with it.
+const SWAGGER_UI_VERSION: &str = "5.13.0";
+
 #[derive(RustEmbed)]
 #[folder = "src/swagger_ui/v5.13.0"]
 struct SwaggerUiDist;
@@ -29,7 +32,7 @@ const INDEX_TMPL: &str = r#"
     <title>{{title}}</title>
     {{keywords}}
     {{description}}
-    <link rel="stylesheet" type="text/css" href="./swagger-ui.css" />
+    <link rel="stylesheet" type="text/css" href="./swagger-ui.css?v={{version}}" />
     <style>
     html {
         box-sizing: border-box;
@@ -50,8 +53,8 @@ const INDEX_TMPL: &str = r#"
 
   <body>
     <div id="swagger-ui"></div>
-    <script src="./swagger-ui-bundle.js" charset="UTF-8"></script>
-    <script src="./swagger-ui-standalone-preset.js" charset="UTF-8"></script>
+    <script src="./swagger-ui-bundle.js?v={{version}}" charset="UTF-8"></script>
+    <script src="./swagger-ui-standalone-preset.js?v={{version}}" charset="UTF-8"></script>
     <script>
     window.onload = function() {
         let config = {
@@ -254,10 +257,19 @@ impl Handler for SwaggerUi {
             .as_ref()
             .map(|s| format!("<meta name=\"description\" content=\"{}\">", s))
             .unwrap_or_default();
+        // Paths which are not assets are routes of the page, e.g. deep links, so index page is served for them.
+        let is_asset = path.rsplit('/').next().unwrap_or_default().contains('.');
+        let path = if is_asset { path } else { "" };
         match serve(path, &self.title, &keywords, &description, &self.config) {
             Ok(Some(file)) => {
                 res.headers_mut()
                     .insert(header::CONTENT_TYPE, HeaderValue::from_str(&file.content_type).expect("content type parse failed"));
+                if is_asset && !path.ends_with(".html") {
+                    // Urls of bundled assets in index page contain the version, so they can be cached forever.
+                    res.cache_control(CacheControl::new().public().max_age(31_536_000).immutable());
+                } else {
+                    res.cache_control(CacheControl::new().no_cache());
+                }
                 res.body(ResBody::Once(file.bytes.to_vec().into()));
             }
             Ok(None) => {
@@ -401,7 +413,8 @@ pub fn serve<'a>(
             .replacen("{{config}}", &config_json, 1)
             .replacen("{{description}}", description, 1)
             .replacen("{{keywords}}", keywords, 1)
-            .replacen("{{title}}", title, 1);
+            .replacen("{{title}}", title, 1)
+            .replace("{{version}}", SWAGGER_UI_VERSION);
 
         if let Some(oauth) = &config.oauth {
             let oauth_json = serde_json::to_string(oauth)?;
@@ -418,3 +431,52 @@ pub fn serve<'a>(
 
     Ok(file)
 }
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_serve_swagger_ui() {
+        let router = Router::new().push(SwaggerUi::new("/api-doc/openapi.json").into_router("swagger-ui"));
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801/swagger-ui?a=1")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::FOUND));
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "http://127.0.0.1:5801/swagger-ui/?a=1"
+        );
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/swagger-ui/pets/list")
+            .send(&service)
+            .await;
+        assert_eq!(res.content_type().unwrap().subtype(), "html");
+        assert_eq!(res.headers().get(header::CACHE_CONTROL).unwrap(), "no-cache");
+        let index = res.take_string().await.unwrap();
+        assert!(index.contains("/api-doc/openapi.json"));
+        assert!(index.contains(&format!("./swagger-ui-bundle.js?v={SWAGGER_UI_VERSION}")));
+        assert!(!index.contains("{{version}}"));
+        let dist = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("src/swagger_ui/v{SWAGGER_UI_VERSION}"));
+        assert!(dist.is_dir(), "bundled swagger ui folder does not match `SWAGGER_UI_VERSION`");
+
+        let res = TestClient::get("http://127.0.0.1:5801/swagger-ui/swagger-ui.css")
+            .send(&service)
+            .await;
+        assert_eq!(res.content_type().unwrap().essence_str(), "text/css");
+        assert_eq!(
+            res.headers().get(header::CACHE_CONTROL).unwrap(),
+            "public, immutable, max-age=31536000"
+        );
+
+        let res = TestClient::get("http://127.0.0.1:5801/swagger-ui/missing.js")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::NOT_FOUND));
+    }
+}
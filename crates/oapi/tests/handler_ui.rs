@@ -0,0 +1,17 @@
+//! UI tests of `#[handler]` macro with extractors of this crate.
+
+#[test]
+fn test_handler_ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/handler-extract-args.rs");
+}
+
+/// Compiler diagnostics change between Rust releases, so the expected stderr only matches the toolchain it was
+/// generated with. Run it by `cargo test -p salvo-oapi --test handler_ui -- --ignored`, and regenerate the stderr
+/// with `TRYBUILD=overwrite` after upgrading the toolchain.
+#[test]
+#[ignore = "expected stderr depends on the compiler version"]
+fn test_handler_ui_errors() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/handler-bad-arg.rs");
+}
@@ -0,0 +1,13 @@
+use salvo_core::prelude::*;
+use salvo_oapi::extract::PathParam;
+
+struct Unextractable;
+
+#[handler]
+async fn show(id: PathParam<i64>, _bad: Unextractable) -> String {
+    format!("{}", id.into_inner())
+}
+
+fn main() {
+    let _router = Router::with_path("users/<id>").get(show);
+}
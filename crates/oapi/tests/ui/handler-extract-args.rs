@@ -0,0 +1,21 @@
+use salvo_core::prelude::*;
+use salvo_oapi::extract::{PathParam, QueryParam};
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct User {
+    id: i64,
+    page: u32,
+}
+
+#[handler]
+async fn show(id: PathParam<i64>, page: QueryParam<u32, true>) -> Json<User> {
+    Json(User {
+        id: id.into_inner(),
+        page: page.into_inner(),
+    })
+}
+
+fn main() {
+    let _router = Router::with_path("users/<id>").get(show);
+}
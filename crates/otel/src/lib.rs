@@ -9,4 +9,4 @@ mod metrics;
 mod tracing;
 
 pub use metrics::Metrics;
-pub use tracing::Tracing;
+pub use tracing::{OtelDepotExt, Tracing, OTEL_CONTEXT_KEY};
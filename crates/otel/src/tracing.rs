@@ -1,12 +1,35 @@
 use headers03::{HeaderMap, HeaderName, HeaderValue};
-use opentelemetry::trace::{FutureExt, Span, SpanKind, TraceContextExt, Tracer};
+use opentelemetry::trace::{FutureExt, Span, SpanKind, SpanRef, TraceContextExt, Tracer};
 use opentelemetry::{global, Context, KeyValue};
 use opentelemetry_http::HeaderExtractor;
 use opentelemetry_semantic_conventions::{resource, trace};
 use salvo_core::http::headers::{self, HeaderMapExt};
+use salvo_core::http::ResBody;
 use salvo_core::prelude::*;
 
+/// Key for saving the OpenTelemetry context of current request in [`Depot`].
+pub const OTEL_CONTEXT_KEY: &str = "::salvo::otel::context";
+
+/// Extension for Depot.
+pub trait OtelDepotExt {
+    /// Get the span of current request started by [`Tracing`], handlers can add custom attributes and events to it.
+    fn otel_span(&self) -> Option<SpanRef<'_>>;
+}
+
+impl OtelDepotExt for Depot {
+    #[inline]
+    fn otel_span(&self) -> Option<SpanRef<'_>> {
+        self.get::<Context>(OTEL_CONTEXT_KEY).ok().map(|cx| cx.span())
+    }
+}
+
 /// Middleware for tracing with OpenTelemetry.
+///
+/// A server span is started for every request, the parent context is extracted from the `traceparent` and
+/// `tracestate` headers by the global text map propagator. The span is named by the matched route, such as
+/// `GET /users/<id>`, and it is ended after the rest of the chain is done.
+///
+/// The context is saved in [`Depot`], use [`OtelDepotExt::otel_span`] to get the span in handlers.
 pub struct Tracing<T> {
     tracer: T,
 }
@@ -25,7 +48,13 @@ where
     T::Span: Send + Sync + 'static,
 {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        let remote_addr = req.remote_addr().to_string();
+        let client_ip = req
+            .remote_addr()
+            .clone()
+            .into_std()
+            .map(|addr| addr.ip().to_string())
+            .unwrap_or_else(|| req.remote_addr().to_string());
+        let route = req.matched_route().map(ToOwned::to_owned);
 
         //TODO: Will remove after opentelemetry_http updated
         let mut headers = HeaderMap::with_capacity(req.headers().len());
@@ -46,26 +75,39 @@ where
         attributes.push(KeyValue::new(resource::TELEMETRY_SDK_LANGUAGE, "rust"));
         attributes.push(KeyValue::new(trace::HTTP_REQUEST_METHOD, req.method().to_string()));
         attributes.push(KeyValue::new(trace::URL_FULL, req.uri().to_string()));
-        attributes.push(KeyValue::new(trace::CLIENT_ADDRESS, remote_addr));
+        attributes.push(KeyValue::new(trace::CLIENT_ADDRESS, client_ip));
+        if let Some(route) = &route {
+            attributes.push(KeyValue::new(trace::HTTP_ROUTE, route.clone()));
+        }
         attributes.push(KeyValue::new(
             trace::NETWORK_PROTOCOL_VERSION,
             format!("{:?}", req.version()),
         ));
         let mut span = self
             .tracer
-            .span_builder(format!("{} {}", req.method(), req.uri()))
+            .span_builder(format!(
+                "{} {}",
+                req.method(),
+                route.as_deref().unwrap_or(&req.uri().to_string())
+            ))
             .with_kind(SpanKind::Server)
             .with_attributes(attributes)
             .start_with_context(&self.tracer, &parent_cx);
 
         span.add_event("request.started".to_string(), vec![]);
+        let cx = Context::current_with_span(span);
+        depot.insert(OTEL_CONTEXT_KEY, cx.clone());
 
         async move {
             ctrl.call_next(req, depot, res).await;
             let cx = Context::current();
             let span = cx.span();
 
-            let status = res.status_code.unwrap_or(StatusCode::NOT_FOUND);
+            let status = res.status_code.unwrap_or(match &res.body {
+                ResBody::None => StatusCode::NOT_FOUND,
+                ResBody::Error(e) => e.code,
+                _ => StatusCode::OK,
+            });
             let event = if status.is_client_error() || status.is_server_error() {
                 "request.failure"
             } else {
@@ -76,8 +118,57 @@ where
             if let Some(content_length) = res.headers().typed_get::<headers::ContentLength>() {
                 span.set_attribute(KeyValue::new(trace::HTTP_RESPONSE_BODY_SIZE, content_length.0 as i64));
             }
+            span.end();
         }
-        .with_context(Context::current_with_span(span))
+        .with_context(cx)
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::Value;
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporterBuilder;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[handler]
+    async fn hello(depot: &mut Depot) -> &'static str {
+        let span = depot.otel_span().expect("span should be saved in depot");
+        span.set_attribute(KeyValue::new("custom", "value"));
+        "hello"
+    }
+
+    #[tokio::test]
+    async fn test_otel_span_attributes() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let provider = TracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+        let router = Router::new()
+            .hoop(Tracing::new(provider.tracer("salvo-test")))
+            .push(Router::with_path("users/<id>").get(hello));
+        let mut req = TestClient::get("http://127.0.0.1:5801/users/12").build();
+        *req.remote_addr_mut() = "10.0.0.1:3000".parse::<std::net::SocketAddr>().unwrap().into();
+        let content = Service::new(router).handle(req).await.take_string().await.unwrap();
+        assert_eq!(content, "hello");
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        let span = &spans[0];
+        assert_eq!(span.name, "GET /users/<id>");
+        assert_eq!(span.span_kind, SpanKind::Server);
+        let attribute = |key: &str| {
+            span.attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == key)
+                .map(|kv| kv.value.clone())
+        };
+        assert_eq!(attribute(trace::HTTP_ROUTE), Some(Value::from("/users/<id>")));
+        assert_eq!(attribute(trace::HTTP_RESPONSE_STATUS_CODE), Some(Value::I64(200)));
+        assert_eq!(attribute(trace::CLIENT_ADDRESS), Some(Value::from("10.0.0.1")));
+        assert_eq!(attribute("custom"), Some(Value::from("value")));
+    }
+}
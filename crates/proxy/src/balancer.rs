@@ -0,0 +1,449 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use salvo_core::http::{ReqBody, StatusCode};
+use salvo_core::Error;
+use tokio::task::JoinHandle;
+
+use crate::{Client, Releaser, Upstreams};
+
+/// Strategy used by [`LoadBalancer`] to elect an upstream.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BalancingStrategy {
+    /// Elect healthy upstreams in turn.
+    #[default]
+    RoundRobin,
+    /// Elect a healthy upstream randomly.
+    Random,
+    /// Elect the healthy upstream with the fewest inflight requests.
+    ///
+    /// A request is inflight until its response body is finished or dropped.
+    LeastConnections,
+}
+impl BalancingStrategy {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Random,
+            2 => Self::LeastConnections,
+            _ => Self::RoundRobin,
+        }
+    }
+    fn as_u8(self) -> u8 {
+        match self {
+            Self::RoundRobin => 0,
+            Self::Random => 1,
+            Self::LeastConnections => 2,
+        }
+    }
+}
+
+/// Health check options used by [`LoadBalancer::spawn_health_check`].
+#[derive(Clone, Debug)]
+pub struct HealthCheck {
+    path: String,
+    interval: Duration,
+    timeout: Duration,
+    unhealthy_threshold: u32,
+    healthy_threshold: u32,
+}
+impl Default for HealthCheck {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl HealthCheck {
+    /// Create a new `HealthCheck` which requests `/health` every 10 seconds.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            path: "/health".into(),
+            interval: Duration::from_secs(10),
+            timeout: Duration::from_secs(5),
+            unhealthy_threshold: 3,
+            healthy_threshold: 2,
+        }
+    }
+
+    /// Sets the path requested on each upstream, the default is `/health`.
+    #[inline]
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Sets the interval between checks, the default is 10 seconds.
+    #[inline]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the timeout of each check request, the default is 5 seconds.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the number of consecutive failures after which an upstream is marked as down, the default is `3`.
+    #[inline]
+    pub fn unhealthy_threshold(mut self, threshold: u32) -> Self {
+        self.unhealthy_threshold = threshold.max(1);
+        self
+    }
+
+    /// Sets the number of consecutive successes after which a down upstream is enabled again, the default is `2`.
+    #[inline]
+    pub fn healthy_threshold(mut self, threshold: u32) -> Self {
+        self.healthy_threshold = threshold.max(1);
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Upstream {
+    url: String,
+    inflight: AtomicUsize,
+    healthy: AtomicBool,
+    failures: AtomicU32,
+    successes: AtomicU32,
+}
+
+impl Upstream {
+    fn release(&self) {
+        let _ = self
+            .inflight
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    upstreams: Vec<Upstream>,
+    /// Indexes of healthy upstreams, it is replaced by health check task without blocking elections.
+    active: ArcSwap<Vec<usize>>,
+    strategy: AtomicU8,
+    counter: AtomicUsize,
+}
+
+impl Inner {
+    fn refresh_active(&self) {
+        let active = self
+            .upstreams
+            .iter()
+            .enumerate()
+            .filter(|(_, upstream)| upstream.healthy.load(Ordering::Acquire))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        self.active.store(Arc::new(active));
+    }
+}
+
+/// [`Upstreams`] which balances requests between upstreams with a [`BalancingStrategy`].
+///
+/// Upstreams can be checked periodically by [`LoadBalancer::spawn_health_check`], unhealthy upstreams are not
+/// elected until they are healthy again.
+///
+/// # Example
+///
+/// ```no_run
+/// use salvo_core::prelude::*;
+/// use salvo_proxy::{BalancingStrategy, HealthCheck, HyperClient, LoadBalancer, Proxy};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let balancer = LoadBalancer::new(["http://10.0.0.1:8080", "http://10.0.0.2:8080"])
+///         .strategy(BalancingStrategy::LeastConnections);
+///     balancer.spawn_health_check(HyperClient::default(), HealthCheck::new().path("/ping"));
+///     let router = Router::with_path("<**rest>").goal(Proxy::new(balancer, HyperClient::default()));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct LoadBalancer {
+    inner: Arc<Inner>,
+}
+
+impl LoadBalancer {
+    /// Create a new `LoadBalancer` with upstream urls, all upstreams are healthy at first.
+    pub fn new<I>(urls: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let upstreams = urls
+            .into_iter()
+            .map(|url| Upstream {
+                url: url.into(),
+                inflight: AtomicUsize::new(0),
+                healthy: AtomicBool::new(true),
+                failures: AtomicU32::new(0),
+                successes: AtomicU32::new(0),
+            })
+            .collect::<Vec<_>>();
+        let active = (0..upstreams.len()).collect::<Vec<_>>();
+        Self {
+            inner: Arc::new(Inner {
+                upstreams,
+                active: ArcSwap::from_pointee(active),
+                strategy: AtomicU8::new(BalancingStrategy::default().as_u8()),
+                counter: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Sets the [`BalancingStrategy`], the default is [`BalancingStrategy::RoundRobin`].
+    ///
+    /// The strategy is changed in place, so it is shared by all clones, health states and running health check
+    /// tasks are kept.
+    #[inline]
+    pub fn strategy(self, strategy: BalancingStrategy) -> Self {
+        self.set_strategy(strategy);
+        self
+    }
+
+    /// Changes the [`BalancingStrategy`] of this balancer and all its clones.
+    #[inline]
+    pub fn set_strategy(&self, strategy: BalancingStrategy) {
+        self.inner.strategy.store(strategy.as_u8(), Ordering::Relaxed);
+    }
+
+    /// Returns the current [`BalancingStrategy`].
+    #[inline]
+    pub fn current_strategy(&self) -> BalancingStrategy {
+        BalancingStrategy::from_u8(self.inner.strategy.load(Ordering::Relaxed))
+    }
+
+    /// Returns urls of upstreams which are healthy now.
+    pub fn healthy_upstreams(&self) -> Vec<&str> {
+        self.inner
+            .active
+            .load()
+            .iter()
+            .map(|&index| self.inner.upstreams[index].url.as_str())
+            .collect()
+    }
+
+    /// Returns the number of inflight requests of `upstream`.
+    pub fn inflight(&self, upstream: &str) -> Option<usize> {
+        self.inner
+            .upstreams
+            .iter()
+            .find(|u| u.url == upstream)
+            .map(|u| u.inflight.load(Ordering::Relaxed))
+    }
+
+    /// Spawn a background task which requests health check path of all upstreams periodically.
+    ///
+    /// An upstream is marked as down after [`HealthCheck::unhealthy_threshold`] consecutive failures, and it is
+    /// enabled again after [`HealthCheck::healthy_threshold`] consecutive successes. A request is successful if
+    /// upstream responds with a `2xx` status code in [`HealthCheck::timeout`].
+    ///
+    /// The task stops when all clones of this `LoadBalancer` are dropped. It must be called in tokio runtime.
+    pub fn spawn_health_check<C>(&self, client: C, check: HealthCheck) -> JoinHandle<()>
+    where
+        C: Client,
+    {
+        let inner = Arc::downgrade(&self.inner);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(check.interval);
+            loop {
+                interval.tick().await;
+                let Some(inner) = Weak::upgrade(&inner) else {
+                    break;
+                };
+                check_upstreams(&inner, &client, &check).await;
+            }
+        })
+    }
+}
+
+async fn check_upstreams<C: Client>(inner: &Inner, client: &C, check: &HealthCheck) {
+    let mut changed = false;
+    for upstream in &inner.upstreams {
+        let url = format!(
+            "{}/{}",
+            upstream.url.trim_end_matches('/'),
+            check.path.trim_start_matches('/')
+        );
+        let success = match hyper::Request::get(&url).body(ReqBody::None) {
+            Ok(req) => matches!(
+                tokio::time::timeout(check.timeout, client.execute(req, None)).await,
+                Ok(Ok(res)) if StatusCode::is_success(&res.status())
+            ),
+            Err(e) => {
+                tracing::error!(error = ?e, url, "build health check request failed");
+                false
+            }
+        };
+        let healthy = upstream.healthy.load(Ordering::Acquire);
+        if success {
+            upstream.failures.store(0, Ordering::Relaxed);
+            let successes = upstream.successes.fetch_add(1, Ordering::Relaxed) + 1;
+            if !healthy && successes >= check.healthy_threshold {
+                tracing::info!(upstream = upstream.url, "upstream is healthy");
+                upstream.healthy.store(true, Ordering::Release);
+                changed = true;
+            }
+        } else {
+            upstream.successes.store(0, Ordering::Relaxed);
+            let failures = upstream.failures.fetch_add(1, Ordering::Relaxed) + 1;
+            if healthy && failures >= check.unhealthy_threshold {
+                tracing::warn!(upstream = upstream.url, "upstream is down");
+                upstream.healthy.store(false, Ordering::Release);
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        inner.refresh_active();
+    }
+}
+
+impl Upstreams for LoadBalancer {
+    type Error = Error;
+
+    async fn elect(&self) -> Result<&str, Self::Error> {
+        let active = self.inner.active.load();
+        if active.is_empty() {
+            return Err(Error::other("no healthy upstream"));
+        }
+        let index = match self.current_strategy() {
+            BalancingStrategy::RoundRobin => active[self.inner.counter.fetch_add(1, Ordering::Relaxed) % active.len()],
+            BalancingStrategy::Random => active[fastrand::usize(..active.len())],
+            BalancingStrategy::LeastConnections => active
+                .iter()
+                .copied()
+                .min_by_key(|&index| self.inner.upstreams[index].inflight.load(Ordering::Relaxed))
+                .unwrap_or(active[0]),
+        };
+        let upstream = &self.inner.upstreams[index];
+        upstream.inflight.fetch_add(1, Ordering::Relaxed);
+        Ok(&upstream.url)
+    }
+
+    fn release(&self, upstream: &str) {
+        if let Some(upstream) = self.inner.upstreams.iter().find(|u| u.url == upstream) {
+            upstream.release();
+        }
+    }
+
+    fn releaser(&self, upstream: &str) -> Option<Releaser> {
+        let index = self.inner.upstreams.iter().position(|u| u.url == upstream)?;
+        let inner = self.inner.clone();
+        Some(Box::new(move || inner.upstreams[index].release()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::Mutex;
+
+    use hyper::upgrade::OnUpgrade;
+    use salvo_core::http::ResBody;
+    use salvo_core::test::{ResponseExt, TestClient};
+    use salvo_core::{Router, Service};
+
+    use super::*;
+    use crate::{HyperRequest, HyperResponse, Proxy};
+
+    /// Client which responds `503` for hosts in `down` list.
+    #[derive(Clone, Default)]
+    struct MockClient {
+        down: Arc<Mutex<Vec<String>>>,
+    }
+    impl Client for MockClient {
+        type Error = Infallible;
+
+        async fn execute(&self, req: HyperRequest, _upgraded: Option<OnUpgrade>) -> Result<HyperResponse, Infallible> {
+            let host = req.uri().host().unwrap_or_default().to_owned();
+            let status = if self.down.lock().unwrap().contains(&host) {
+                StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                StatusCode::OK
+            };
+            Ok(hyper::Response::builder().status(status).body(ResBody::None).unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_strategies() {
+        let balancer = LoadBalancer::new(["http://a", "http://b", "http://c"]);
+        let mut elected = Vec::new();
+        for _ in 0..6 {
+            elected.push(balancer.elect().await.unwrap().to_owned());
+        }
+        assert_eq!(
+            elected,
+            ["http://a", "http://b", "http://c", "http://a", "http://b", "http://c"]
+        );
+
+        let balancer = LoadBalancer::new(["http://a", "http://b"]).strategy(BalancingStrategy::LeastConnections);
+        assert_eq!(balancer.elect().await.unwrap(), "http://a");
+        assert_eq!(balancer.elect().await.unwrap(), "http://b");
+        assert_eq!(balancer.elect().await.unwrap(), "http://a");
+        balancer.release("http://b");
+        assert_eq!(balancer.inflight("http://a"), Some(2));
+        assert_eq!(balancer.inflight("http://b"), Some(0));
+        assert_eq!(balancer.elect().await.unwrap(), "http://b");
+
+        let balancer = LoadBalancer::new(["http://a", "http://b"]).strategy(BalancingStrategy::Random);
+        assert!(["http://a", "http://b"].contains(&balancer.elect().await.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_strategy_keeps_health() {
+        let balancer = LoadBalancer::new(["http://a", "http://b"]);
+        let client = MockClient::default();
+        let check = HealthCheck::new().unhealthy_threshold(1);
+        client.down.lock().unwrap().push("a".into());
+        check_upstreams(&balancer.inner, &client, &check).await;
+
+        let cloned = balancer.clone().strategy(BalancingStrategy::LeastConnections);
+        assert_eq!(balancer.current_strategy(), BalancingStrategy::LeastConnections);
+        assert!(Arc::ptr_eq(&balancer.inner, &cloned.inner));
+        assert_eq!(cloned.healthy_upstreams(), ["http://b"]);
+    }
+
+    #[tokio::test]
+    async fn test_release_on_body_drop() {
+        let balancer = LoadBalancer::new(["http://a"]).strategy(BalancingStrategy::LeastConnections);
+        let router = Router::with_path("<**rest>").goal(Proxy::new(balancer.clone(), MockClient::default()));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/").send(&service).await;
+        assert_eq!(balancer.inflight("http://a"), Some(1));
+        res.take_bytes(None).await.unwrap();
+        assert_eq!(balancer.inflight("http://a"), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_health_check() {
+        let balancer = LoadBalancer::new(["http://a", "http://b"]);
+        let client = MockClient::default();
+        let check = HealthCheck::new().unhealthy_threshold(2).healthy_threshold(2);
+
+        client.down.lock().unwrap().push("a".into());
+        check_upstreams(&balancer.inner, &client, &check).await;
+        assert_eq!(balancer.healthy_upstreams(), ["http://a", "http://b"]);
+        check_upstreams(&balancer.inner, &client, &check).await;
+        assert_eq!(balancer.healthy_upstreams(), ["http://b"]);
+        assert_eq!(balancer.elect().await.unwrap(), "http://b");
+        assert_eq!(balancer.elect().await.unwrap(), "http://b");
+
+        client.down.lock().unwrap().clear();
+        check_upstreams(&balancer.inner, &client, &check).await;
+        assert_eq!(balancer.healthy_upstreams(), ["http://b"]);
+        check_upstreams(&balancer.inner, &client, &check).await;
+        assert_eq!(balancer.healthy_upstreams(), ["http://a", "http://b"]);
+
+        client.down.lock().unwrap().extend(["a".into(), "b".into()]);
+        check_upstreams(&balancer.inner, &client, &check).await;
+        check_upstreams(&balancer.inner, &client, &check).await;
+        assert!(balancer.elect().await.is_err());
+    }
+}
@@ -8,17 +8,27 @@
 use std::convert::Infallible;
 use std::error::Error as StdError;
 use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
+use hyper::body::Bytes;
 use hyper::upgrade::OnUpgrade;
 use percent_encoding::{utf8_percent_encode, CONTROLS};
-use salvo_core::http::header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, HOST, UPGRADE};
+use salvo_core::http::body::{Body, Frame, SizeHint};
+use salvo_core::http::header::{
+    HeaderMap, HeaderName, HeaderValue, CONNECTION, HOST, PROXY_AUTHENTICATE, PROXY_AUTHORIZATION, TE, TRAILER,
+    TRANSFER_ENCODING, UPGRADE, VIA,
+};
 use salvo_core::http::uri::Uri;
-use salvo_core::http::{ReqBody, ResBody, StatusCode};
+use salvo_core::http::{ReqBody, ResBody, StatusCode, Version, DEADLINE_HEADER, DEADLINE_HEADER_KEY};
 use salvo_core::{async_trait, BoxedError, Depot, Error, FlowCtrl, Handler, Request, Response};
 
 #[macro_use]
 mod cfg;
 
+mod balancer;
+pub use balancer::*;
+
 cfg_feature! {
     #![feature = "hyper-client"]
     mod hyper_client;
@@ -60,6 +70,57 @@ pub trait Upstreams: Send + Sync + 'static {
     type Error: StdError + Send + Sync + 'static;
     /// Elect a upstream to process current request.
     fn elect(&self) -> impl Future<Output = Result<&str, Self::Error>> + Send;
+    /// Called when the proxied request to `upstream` elected by [`Upstreams::elect`] is finished.
+    ///
+    /// It is called after the response head is received or the request is failed, response body may be still
+    /// streaming. If [`Upstreams::releaser`] returns a [`Releaser`], it is called instead of this function when a
+    /// response is received. This can be used to track inflight requests, the default implementation does nothing.
+    #[inline]
+    fn release(&self, _upstream: &str) {}
+    /// Returns a [`Releaser`] of `upstream` elected by [`Upstreams::elect`], it is called when the proxied
+    /// response body is finished or dropped, so requests whose response body is still streaming are tracked.
+    ///
+    /// The default implementation returns `None`, and [`Upstreams::release`] is called when the response head is
+    /// received.
+    #[inline]
+    fn releaser(&self, _upstream: &str) -> Option<Releaser> {
+        None
+    }
+}
+
+/// Function which releases an elected upstream, see [`Upstreams::releaser`].
+pub type Releaser = Box<dyn FnOnce() + Send + Sync + 'static>;
+
+/// Response body which calls its [`Releaser`] when dropped.
+struct ReleaseBody {
+    inner: ResBody,
+    releaser: Option<Releaser>,
+}
+impl Body for ReleaseBody {
+    type Data = Bytes;
+    type Error = BoxedError;
+
+    #[inline]
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, BoxedError>>> {
+        Pin::new(&mut self.get_mut().inner).poll_frame(cx).map_err(Into::into)
+    }
+
+    #[inline]
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+impl Drop for ReleaseBody {
+    fn drop(&mut self) {
+        if let Some(releaser) = self.releaser.take() {
+            releaser();
+        }
+    }
 }
 impl Upstreams for &'static str {
     type Error = Infallible;
@@ -103,6 +164,44 @@ where
 /// Url part getter. You can use this to get the proxied url path or query.
 pub type UrlPartGetter = Box<dyn Fn(&Request, &Depot) -> Option<String> + Send + Sync + 'static>;
 
+/// Url path rewriter. It receives the path got by url path getter and returns the path sent to upstream.
+pub type UrlPathRewriter = Box<dyn Fn(&str) -> String + Send + Sync + 'static>;
+
+/// Headers which are meaningful only for a single transport-level connection and must not be forwarded by proxies.
+const HOP_BY_HOP_HEADERS: [HeaderName; 7] = [
+    CONNECTION,
+    HeaderName::from_static("keep-alive"),
+    HeaderName::from_static("proxy-connection"),
+    TE,
+    TRAILER,
+    TRANSFER_ENCODING,
+    UPGRADE,
+];
+
+/// Returns `true` if `name` is a hop-by-hop header or it is listed in `Connection` header.
+fn is_hop_by_hop(name: &HeaderName, headers: &HeaderMap) -> bool {
+    HOP_BY_HOP_HEADERS.contains(name)
+        || name == PROXY_AUTHENTICATE
+        || name == PROXY_AUTHORIZATION
+        || headers.get_all(CONNECTION).iter().any(|value| {
+            value
+                .to_str()
+                .unwrap_or_default()
+                .split(',')
+                .any(|token| token.trim().eq_ignore_ascii_case(name.as_str()))
+        })
+}
+
+fn via_protocol(version: Version) -> &'static str {
+    match version {
+        Version::HTTP_09 => "0.9",
+        Version::HTTP_10 => "1.0",
+        Version::HTTP_2 => "2",
+        Version::HTTP_3 => "3",
+        _ => "1.1",
+    }
+}
+
 /// Default url path getter. This getter will get the url path from request wildcard param, like `<**rest>`, `<*+rest>`.
 pub fn default_url_path_getter(req: &Request, _depot: &Depot) -> Option<String> {
     let param = req.params().iter().find(|(key, _)| key.starts_with('*'));
@@ -118,6 +217,14 @@ pub fn default_url_query_getter(req: &Request, _depot: &Depot) -> Option<String>
 }
 
 /// Handler that can proxy request to other server.
+///
+/// Hop-by-hop headers such as `Connection`, `Transfer-Encoding` and `Upgrade` are not forwarded, except `Upgrade`
+/// of WebSocket requests. `X-Forwarded-For`, `X-Forwarded-Proto`, `X-Forwarded-Host` and `Via` headers are added
+/// to proxied request, and response body from upstream is streamed back to client.
+///
+/// If a [`Deadline`] is set in depot, it is forwarded to upstream in the header saved in depot with
+/// [`DEADLINE_HEADER_KEY`], `x-request-deadline` is used if it is absent, so the upstream gets the remaining budget. `504 Gateway Timeout` is responded if the deadline has passed before the upstream
+/// responds.
 #[non_exhaustive]
 pub struct Proxy<U, C>
 where
@@ -132,6 +239,8 @@ where
     pub url_path_getter: UrlPartGetter,
     /// Url query getter.
     pub url_query_getter: UrlPartGetter,
+    /// Url path rewriter.
+    pub url_path_rewriter: Option<UrlPathRewriter>,
 }
 
 impl<U, C> Proxy<U, C>
@@ -147,6 +256,7 @@ where
             client,
             url_path_getter: Box::new(default_url_path_getter),
             url_query_getter: Box::new(default_url_query_getter),
+            url_path_rewriter: None,
         }
     }
 
@@ -170,6 +280,27 @@ where
         self
     }
 
+    /// Set url path rewriter, it is applied to the path got by url path getter before it is sent to upstream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_core::prelude::*;
+    /// use salvo_proxy::{HyperClient, Proxy};
+    ///
+    /// let proxy = Proxy::new("http://127.0.0.1:8080", HyperClient::default())
+    ///     .path_rewrite(|path| format!("api/v2/{}", path.trim_start_matches('/')));
+    /// let router = Router::with_path("api/<**rest>").goal(proxy);
+    /// ```
+    #[inline]
+    pub fn path_rewrite<F>(mut self, rewriter: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.url_path_rewriter = Some(Box::new(rewriter));
+        self
+    }
+
     /// Get upstreams list.
     #[inline]
     pub fn upstreams(&self) -> &U {
@@ -192,14 +323,17 @@ where
         &mut self.client
     }
 
-    async fn build_proxied_request(&self, req: &mut Request, depot: &Depot) -> Result<HyperRequest, Error> {
-        let upstream = self.upstreams.elect().await.map_err(Error::other)?;
+    fn build_proxied_request(&self, upstream: &str, req: &mut Request, depot: &Depot) -> Result<HyperRequest, Error> {
         if upstream.is_empty() {
             tracing::error!("upstreams is empty");
             return Err(Error::other("upstreams is empty"));
         }
 
-        let path = encode_url_path(&(self.url_path_getter)(req, depot).unwrap_or_default());
+        let mut path = (self.url_path_getter)(req, depot).unwrap_or_default();
+        if let Some(rewriter) = &self.url_path_rewriter {
+            path = rewriter(&path);
+        }
+        let path = encode_url_path(&path);
         let query = (self.url_query_getter)(req, depot);
         let rest = if let Some(query) = query {
             if query.starts_with('?') {
@@ -221,32 +355,68 @@ where
         };
         let forward_url: Uri = TryFrom::try_from(forward_url).map_err(Error::other)?;
         let mut build = hyper::Request::builder().method(req.method()).uri(&forward_url);
+        let upgrade_type = get_upgrade_type(req.headers()).map(|s| s.to_owned());
+        let deadline = depot.deadline();
+        let deadline_header = depot
+            .get::<HeaderName>(DEADLINE_HEADER_KEY)
+            .ok()
+            .cloned()
+            .unwrap_or_else(|| HeaderName::from_static(DEADLINE_HEADER));
         for (key, value) in req.headers() {
-            if key != HOST {
+            if deadline.is_some() && *key == deadline_header {
+                continue;
+            }
+            if key != HOST && !is_hop_by_hop(key, req.headers()) && !key.as_str().starts_with("x-forwarded-") {
                 build = build.header(key, value);
             }
         }
+        if let Some(deadline) = deadline {
+            build = build.header(deadline_header, deadline.unix_millis());
+        }
+        if let Some(upgrade_type) = upgrade_type.and_then(|t| HeaderValue::from_str(&t).ok()) {
+            build = build
+                .header(CONNECTION, HeaderValue::from_static("upgrade"))
+                .header(UPGRADE, upgrade_type);
+        }
         if let Some(host) = forward_url.host().and_then(|host| HeaderValue::from_str(host).ok()) {
             build = build.header(HeaderName::from_static("host"), host);
         }
-        // let x_forwarded_for_header_name = "x-forwarded-for";
-        // // Add forwarding information in the headers
-        // match request.headers_mut().entry(x_forwarded_for_header_name) {
-        //     Ok(header_entry) => {
-        //         match header_entry {
-        //             hyper::header::Entry::Vacant(entry) => {
-        //                 let addr = format!("{}", client_ip);
-        //                 entry.insert(addr.parse().unwrap());
-        //             },
-        //             hyper::header::Entry::Occupied(mut entry) => {
-        //                 let addr = format!("{}, {}", entry.get().to_str().unwrap(), client_ip);
-        //                 entry.insert(addr.parse().unwrap());
-        //             }
-        //         }
-        //     }
-        //     // shouldn't happen...
-        //     Err(_) => panic!("Invalid header name: {}", x_forwarded_for_header_name),
-        // }
+
+        // Add forwarding information in the headers.
+        let client_ip = req
+            .remote_addr()
+            .as_ipv4()
+            .map(|addr| addr.ip().to_string())
+            .or_else(|| req.remote_addr().as_ipv6().map(|addr| addr.ip().to_string()));
+        let forwarded_for = req
+            .headers()
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(|value| value.trim().to_owned())
+            .chain(client_ip)
+            .filter(|value| !value.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Ok(value) = HeaderValue::from_str(&forwarded_for) {
+            if !forwarded_for.is_empty() {
+                build = build.header("x-forwarded-for", value);
+            }
+        }
+        if let Ok(value) = HeaderValue::from_str(req.scheme().as_str()) {
+            build = build.header("x-forwarded-proto", value);
+        }
+        let original_host = req.headers().get(HOST).cloned().or_else(|| {
+            req.uri()
+                .authority()
+                .and_then(|a| HeaderValue::from_str(a.as_str()).ok())
+        });
+        if let Some(host) = original_host {
+            build = build.header("x-forwarded-host", host);
+        }
+        if let Ok(via) = HeaderValue::from_str(&format!("{} salvo", via_protocol(req.version()))) {
+            build = build.header(VIA, via);
+        }
         build.body(req.take_body()).map_err(Error::other)
     }
 }
@@ -259,13 +429,37 @@ where
     C: Client,
 {
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, _ctrl: &mut FlowCtrl) {
-        match self.build_proxied_request(req, depot).await {
+        let upstream = match self.upstreams.elect().await {
+            Ok(upstream) => upstream,
+            Err(e) => {
+                let e: BoxedError = e.into();
+                tracing::error!(error = ?e, "elect upstream failed");
+                res.status_code(StatusCode::BAD_GATEWAY);
+                return;
+            }
+        };
+        let deadline = depot.deadline().copied();
+        if deadline.is_some_and(|deadline| deadline.is_expired()) {
+            res.status_code(StatusCode::GATEWAY_TIMEOUT);
+            self.upstreams.release(upstream);
+            return;
+        }
+        match self.build_proxied_request(upstream, req, depot) {
             Ok(proxied_request) => {
-                match self
-                    .client
-                    .execute(proxied_request, req.extensions_mut().remove())
-                    .await
-                {
+                let execute = self.client.execute(proxied_request, req.extensions_mut().remove());
+                let result = match deadline {
+                    Some(deadline) => match tokio::time::timeout(deadline.remaining(), execute).await {
+                        Ok(result) => result,
+                        Err(_) => {
+                            tracing::error!(uri = ?req.uri(), "request deadline exceeded");
+                            res.status_code(StatusCode::GATEWAY_TIMEOUT);
+                            self.upstreams.release(upstream);
+                            return;
+                        }
+                    },
+                    None => execute.await,
+                };
+                match result {
                     Ok(response) => {
                         let (
                             salvo_core::http::response::Parts {
@@ -278,11 +472,19 @@ where
                             body,
                         ) = response.into_parts();
                         res.status_code(status);
-                        for (name, value) in headers {
-                            if let Some(name) = name {
-                                res.headers.insert(name, value);
+                        let upgraded = status == StatusCode::SWITCHING_PROTOCOLS;
+                        for (name, value) in headers.iter() {
+                            if upgraded || !is_hop_by_hop(name, &headers) {
+                                res.headers.append(name, value.clone());
                             }
                         }
+                        if let Some(releaser) = self.upstreams.releaser(upstream) {
+                            res.body(ResBody::boxed(ReleaseBody {
+                                inner: body,
+                                releaser: Some(releaser),
+                            }));
+                            return;
+                        }
                         res.body(body);
                     }
                     Err(e) => {
@@ -295,10 +497,10 @@ where
                 tracing::error!(error = ?e, "build proxied request failed");
             }
         }
+        self.upstreams.release(upstream);
     }
 }
 #[inline]
-#[allow(dead_code)]
 fn get_upgrade_type(headers: &HeaderMap) -> Option<&str> {
     if headers
         .get(&CONNECTION)
@@ -323,8 +525,133 @@ fn get_upgrade_type(headers: &HeaderMap) -> Option<&str> {
 // Unit tests for Proxy
 #[cfg(test)]
 mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
     use super::*;
 
+    /// Client which echoes headers and uri of proxied request in response headers.
+    struct EchoClient;
+    impl Client for EchoClient {
+        type Error = Infallible;
+
+        async fn execute(&self, req: HyperRequest, _upgraded: Option<OnUpgrade>) -> Result<HyperResponse, Infallible> {
+            let mut res = hyper::Response::builder()
+                .header("x-uri", req.uri().to_string())
+                .header(CONNECTION, "close, x-hop")
+                .header("x-hop", "1")
+                .header("set-cookie", "a=1")
+                .header("set-cookie", "b=2");
+            for (name, value) in req.headers() {
+                res = res.header(format!("x-echo-{name}"), value);
+            }
+            Ok(res.body(ResBody::Once("proxied".into())).unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forward_headers() {
+        let router = Router::with_path("api/<**rest>")
+            .goal(Proxy::new("http://upstream:8080", EchoClient).path_rewrite(|path| format!("v2/{path}")));
+        let mut res = TestClient::get("http://127.0.0.1:5801/api/users?page=2")
+            .add_header("x-forwarded-for", "10.0.0.1", true)
+            .add_header(CONNECTION, "keep-alive, x-secret", true)
+            .add_header("x-secret", "1", true)
+            .add_header("keep-alive", "timeout=5", true)
+            .add_header(TRANSFER_ENCODING, "chunked", true)
+            .send(router)
+            .await;
+        let headers = res.headers().clone();
+        assert_eq!(headers.get("x-uri").unwrap(), "http://upstream:8080/v2/users?page=2");
+        assert_eq!(headers.get("x-echo-host").unwrap(), "upstream");
+        // Remote address of test request is unknown, so no client ip is appended.
+        assert_eq!(headers.get("x-echo-x-forwarded-for").unwrap(), "10.0.0.1");
+        assert_eq!(headers.get("x-echo-x-forwarded-proto").unwrap(), "http");
+        assert_eq!(headers.get("x-echo-x-forwarded-host").unwrap(), "127.0.0.1:5801");
+        assert_eq!(headers.get("x-echo-via").unwrap(), "1.1 salvo");
+        for name in ["connection", "keep-alive", "transfer-encoding", "x-secret", "upgrade"] {
+            assert!(
+                !headers.contains_key(format!("x-echo-{name}").as_str()),
+                "{name} is forwarded"
+            );
+        }
+        assert!(!headers.contains_key(CONNECTION));
+        assert!(!headers.contains_key("x-hop"));
+        assert_eq!(headers.get_all("set-cookie").iter().count(), 2);
+        assert_eq!(res.take_string().await.unwrap(), "proxied");
+    }
+
+    #[tokio::test]
+    async fn test_forward_deadline() {
+        use std::time::Duration;
+
+        use salvo_core::http::Deadline;
+
+        struct SlowClient;
+        impl Client for SlowClient {
+            type Error = Infallible;
+
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Infallible> {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(hyper::Response::new(ResBody::None))
+            }
+        }
+
+        struct SetDeadline(Deadline);
+        #[async_trait]
+        impl Handler for SetDeadline {
+            async fn handle(&self, _req: &mut Request, depot: &mut Depot, _res: &mut Response, _ctrl: &mut FlowCtrl) {
+                depot.set_deadline(self.0);
+            }
+        }
+
+        let deadline = Deadline::after(Duration::from_secs(30));
+        let router = Router::new()
+            .hoop(SetDeadline(deadline))
+            .goal(Proxy::new("http://upstream:8080", EchoClient));
+        let res = TestClient::get("http://127.0.0.1:5801")
+            .add_header(DEADLINE_HEADER, "1", true)
+            .send(router)
+            .await;
+        assert_eq!(
+            res.headers().get("x-echo-x-request-deadline").unwrap(),
+            &deadline.unix_millis().to_string()
+        );
+
+        struct SetDeadlineHeader(&'static str);
+        #[async_trait]
+        impl Handler for SetDeadlineHeader {
+            async fn handle(&self, _req: &mut Request, depot: &mut Depot, _res: &mut Response, _ctrl: &mut FlowCtrl) {
+                depot.insert(DEADLINE_HEADER_KEY, HeaderName::from_static(self.0));
+            }
+        }
+        let router = Router::new()
+            .hoop(SetDeadline(deadline))
+            .hoop(SetDeadlineHeader("x-deadline"))
+            .goal(Proxy::new("http://upstream:8080", EchoClient));
+        let res = TestClient::get("http://127.0.0.1:5801")
+            .add_header("x-deadline", "1", true)
+            .send(router)
+            .await;
+        assert_eq!(
+            res.headers().get("x-echo-x-deadline").unwrap(),
+            &deadline.unix_millis().to_string()
+        );
+        assert!(res.headers().get("x-echo-x-request-deadline").is_none());
+
+        let router = Router::new()
+            .hoop(SetDeadline(Deadline::after(Duration::from_millis(50))))
+            .goal(Proxy::new("http://upstream:8080", SlowClient));
+        let now = std::time::Instant::now();
+        let res = TestClient::get("http://127.0.0.1:5801").send(router).await;
+        assert!(now.elapsed() < Duration::from_secs(5));
+        assert_eq!(res.status_code, Some(StatusCode::GATEWAY_TIMEOUT));
+    }
+
     #[test]
     fn test_encode_url_path() {
         let path = "/test/path";
@@ -84,10 +84,40 @@ cfg_feature! {
     pub use salvo_extra::websocket;
 }
 cfg_feature! {
-    #![feature ="request_id"]
+    #![feature ="request-id"]
     #[doc(no_inline)]
     pub use salvo_extra::request_id;
 }
+cfg_feature! {
+    #![feature ="range"]
+    #[doc(no_inline)]
+    pub use salvo_extra::range;
+}
+cfg_feature! {
+    #![feature ="locale"]
+    #[doc(no_inline)]
+    pub use salvo_extra::locale;
+}
+cfg_feature! {
+    #![feature ="health"]
+    #[doc(no_inline)]
+    pub use salvo_extra::health;
+}
+cfg_feature! {
+    #![feature ="deadline"]
+    #[doc(no_inline)]
+    pub use salvo_extra::deadline;
+}
+cfg_feature! {
+    #![feature ="template"]
+    #[doc(no_inline)]
+    pub use salvo_extra::template;
+}
+cfg_feature! {
+    #![feature ="forwarded-header"]
+    #[doc(no_inline)]
+    pub use salvo_extra::forwarded_header;
+}
 cfg_feature! {
     #![feature ="cache"]
     #[doc(no_inline)]
@@ -160,7 +190,7 @@ pub mod prelude {
     }
     cfg_feature! {
         #![feature ="compression"]
-        pub use salvo_compression::{Compression, CompressionAlgo, CompressionLevel};
+        pub use salvo_compression::{Compression, CompressionAlgo, CompressionLevel, RequestDecompress};
     }
     cfg_feature! {
         #![feature ="csrf"]
@@ -214,6 +244,10 @@ pub mod prelude {
         #![feature ="request-id"]
         pub use salvo_extra::request_id::RequestId;
     }
+    cfg_feature! {
+        #![feature ="forwarded-header"]
+        pub use salvo_extra::forwarded_header::ForwardedHeader;
+    }
     cfg_feature! {
         #![feature ="serve-static"]
         pub use salvo_serve_static::{StaticFile, StaticDir};
@@ -17,7 +17,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use time::{macros::format_description, OffsetDateTime};
 
-use super::{decode_url_path_safely, encode_url_path, format_url_path_safely, join_path, redirect_to_dir_url};
+use super::{
+    decode_url_path_safely, encode_url_path, format_url_path_safely, is_hashed_file_name, join_path,
+    redirect_to_dir_url, set_immutable_cache_control,
+};
 
 /// CompressionAlgo
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Hash)]
@@ -120,6 +123,12 @@ where
 }
 
 /// Handler that serves a directory.
+///
+/// Request path is normalized before joined with roots, so `..` can not escape from roots. Files and directories
+/// whose name starts with `.` are hidden unless [`include_dot_files`](Self::include_dot_files) is set.
+///
+/// Files are served by [`NamedFile`], so `Last-Modified`, `ETag`, conditional requests and range requests are
+/// supported.
 #[non_exhaustive]
 pub struct StaticDir {
     /// Static roots.
@@ -131,7 +140,7 @@ pub struct StaticDir {
     ///
     /// The default is 1M.
     pub chunk_size: Option<u64>,
-    /// List dot files.
+    /// Serve and list dot files, files in dot directories are also served if it is true.
     pub include_dot_files: bool,
     #[allow(clippy::type_complexity)]
     exclude_filters: Vec<Box<dyn Fn(&str) -> bool + Send + Sync>>,
@@ -142,10 +151,13 @@ pub struct StaticDir {
     /// The key is the compression algorithm, and the value is the file extension.
     /// If the compression file exists, it will serve the compressed file instead of the original file.
     pub compressed_variations: HashMap<CompressionAlgo, Vec<String>>,
-    /// Default file names list.
+    /// Default file names list, used when a directory is requested. The default is `index.html`.
     pub defaults: Vec<String>,
     /// Fallback file name. This is used when the requested file is not found.
     pub fallback: Option<String>,
+    /// Sets `Cache-Control: public, max-age=31536000, immutable` for files whose name contains a content hash,
+    /// such as `app.3f2a1b9c.js`. The default is `true`.
+    pub immutable_hashed_files: bool,
 }
 impl StaticDir {
     /// Create new `StaticDir`.
@@ -164,8 +176,9 @@ impl StaticDir {
             exclude_filters: vec![],
             auto_list: false,
             compressed_variations,
-            defaults: vec![],
+            defaults: vec!["index.html".to_owned()],
             fallback: None,
+            immutable_hashed_files: true,
         }
     }
 
@@ -219,6 +232,13 @@ impl StaticDir {
         self
     }
 
+    /// Sets immutable_hashed_files and returns a new `StaticDirOptions`.
+    #[inline]
+    pub fn immutable_hashed_files(mut self, immutable_hashed_files: bool) -> Self {
+        self.immutable_hashed_files = immutable_hashed_files;
+        self
+    }
+
     /// During the file chunk read, the maximum read size at one time will affect the
     /// access experience and the demand for server memory.
     ///
@@ -297,11 +317,7 @@ impl Handler for StaticDir {
         let rel_path = format_url_path_safely(&rel_path);
         let mut files: HashMap<String, Metadata> = HashMap::new();
         let mut dirs: HashMap<String, Metadata> = HashMap::new();
-        let is_dot_file = Path::new(&rel_path)
-            .file_name()
-            .and_then(|s| s.to_str())
-            .map(|s| s.starts_with('.'))
-            .unwrap_or(false);
+        let is_dot_file = rel_path.split('/').any(|part| part.starts_with('.'));
         let mut abs_path = None;
         if self.include_dot_files || !is_dot_file {
             for root in &self.roots {
@@ -363,6 +379,12 @@ impl Handler for StaticDir {
         };
 
         if abs_path.is_file() {
+            let is_hashed = self.immutable_hashed_files
+                && abs_path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map(is_hashed_file_name)
+                    .unwrap_or(false);
             let ext = abs_path.extension().and_then(|s| s.to_str()).map(|s| s.to_lowercase());
             let is_compressed_ext = ext.as_deref().map(|ext| self.is_compressed_ext(ext)).unwrap_or(false);
             let mut content_encoding = None;
@@ -419,6 +441,9 @@ impl Handler for StaticDir {
             if let Ok(named_file) = builder.build().await {
                 let headers = req.headers();
                 named_file.send(headers, res).await;
+                if is_hashed {
+                    set_immutable_cache_control(res);
+                }
             } else {
                 res.render(StatusError::internal_server_error().brief("Read file failed."));
             }
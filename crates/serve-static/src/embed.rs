@@ -1,14 +1,20 @@
 use std::borrow::Cow;
 use std::marker::PhantomData;
+use std::time::{Duration, UNIX_EPOCH};
 
 use rust_embed::{EmbeddedFile, Metadata, RustEmbed};
-use salvo_core::http::header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use salvo_core::http::header::CONTENT_TYPE;
+use salvo_core::http::headers::{ContentLength, ETag, HeaderMapExt, LastModified};
 use salvo_core::http::{HeaderValue, Mime, Request, Response, StatusCode};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler, IntoVecString};
 
 use super::{decode_url_path_safely, format_url_path_safely, join_path, redirect_to_dir_url};
 
 /// Handler that serves embed file.
+///
+/// Files are embedded at compile time by [`RustEmbed`], so no filesystem access is needed at runtime. Responses
+/// have `Content-Type`, `Content-Length`, `ETag` from the hash computed at compile time and `Last-Modified` if it is
+/// available, conditional requests are responded with `304 Not Modified`.
 #[non_exhaustive]
 #[derive(Default)]
 pub struct StaticEmbed<T> {
@@ -51,25 +57,30 @@ fn render_embedded_data(
             .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
     );
 
-    let hash = hex::encode(metadata.sha256_hash());
-    // if etag is matched, return 304
-    if req
-        .headers()
-        .get(IF_NONE_MATCH)
-        .map(|etag| etag.to_str().unwrap_or("000000").eq(&hash))
-        .unwrap_or(false)
-    {
-        res.status_code(StatusCode::NOT_MODIFIED);
-        return;
+    let etag = format!("\"{}\"", hex::encode(metadata.sha256_hash()));
+    let etag = match etag.parse::<ETag>() {
+        Ok(etag) => Some(etag),
+        Err(e) => {
+            tracing::error!(error = ?e, etag = %etag, "Failed to parse etag hash");
+            None
+        }
+    };
+    let last_modified = metadata
+        .last_modified()
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+    if let Some(etag) = &etag {
+        res.headers_mut().typed_insert(etag.clone());
     }
-
-    // otherwise, return 200 with etag hash
-    if let Ok(hash) = hash.parse() {
-        res.headers_mut().insert(ETAG, hash);
-    } else {
-        tracing::error!("Failed to parse etag hash: {}", hash);
+    if let Some(last_modified) = last_modified {
+        res.headers_mut().typed_insert(LastModified::from(last_modified));
+    }
+    // if preconditions are not passed, return 304 or 412 without body.
+    if let Some(status_code) = req.is_fresh(etag.as_ref(), last_modified).status_code() {
+        res.status_code(status_code);
+        return;
     }
 
+    res.headers_mut().typed_insert(ContentLength(data.len() as u64));
     match data {
         Cow::Borrowed(data) => {
             res.write_body(data).ok();
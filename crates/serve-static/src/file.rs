@@ -4,15 +4,34 @@ use salvo_core::fs::{NamedFile, NamedFileBuilder};
 use salvo_core::http::{Request, Response, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Writer};
 
+use super::{is_hashed_file_name, set_immutable_cache_control};
+
 /// `StaticFile` is a handler that serves a single file.
+///
+/// File is served by [`NamedFile`], so `Last-Modified`, `ETag`, conditional requests and range requests are
+/// supported.
 #[derive(Clone)]
-pub struct StaticFile(NamedFileBuilder);
+pub struct StaticFile {
+    builder: NamedFileBuilder,
+    is_hashed: bool,
+    immutable_hashed_files: bool,
+}
 
 impl StaticFile {
     /// Create a new `StaticFile`.
     #[inline]
     pub fn new(path: impl Into<PathBuf>) -> Self {
-        StaticFile(NamedFile::builder(path))
+        let path = path.into();
+        let is_hashed = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(is_hashed_file_name)
+            .unwrap_or(false);
+        StaticFile {
+            builder: NamedFile::builder(path),
+            is_hashed,
+            immutable_hashed_files: true,
+        }
     }
 
     /// During the file chunk read, the maximum read size at one time will affect the
@@ -22,8 +41,17 @@ impl StaticFile {
     ///
     /// The default is 1M.
     #[inline]
-    pub fn chunk_size(self, size: u64) -> Self {
-        Self(self.0.buffer_size(size))
+    pub fn chunk_size(mut self, size: u64) -> Self {
+        self.builder = self.builder.buffer_size(size);
+        self
+    }
+
+    /// Sets `Cache-Control: public, max-age=31536000, immutable` if file name contains a content hash,
+    /// such as `app.3f2a1b9c.js`. The default is `true`.
+    #[inline]
+    pub fn immutable_hashed_files(mut self, immutable_hashed_files: bool) -> Self {
+        self.immutable_hashed_files = immutable_hashed_files;
+        self
     }
 }
 
@@ -31,8 +59,13 @@ impl StaticFile {
 impl Handler for StaticFile {
     #[inline]
     async fn handle(&self, req: &mut Request, depot: &mut Depot, res: &mut Response, ctrl: &mut FlowCtrl) {
-        match self.0.clone().build().await {
-            Ok(file) => file.write(req, depot, res).await,
+        match self.builder.clone().build().await {
+            Ok(file) => {
+                file.write(req, depot, res).await;
+                if self.is_hashed && self.immutable_hashed_files {
+                    set_immutable_cache_control(res);
+                }
+            }
             Err(_) => {
                 res.render(StatusError::not_found());
             }
@@ -9,7 +9,9 @@ pub mod dir;
 mod file;
 
 use percent_encoding::{utf8_percent_encode, CONTROLS};
+use salvo_core::http::header::{HeaderValue, CACHE_CONTROL};
 use salvo_core::http::uri::{Parts as UriParts, Uri};
+use salvo_core::http::StatusCode;
 use salvo_core::writing::Redirect;
 use salvo_core::Response;
 
@@ -70,6 +72,38 @@ pub(crate) fn format_url_path_safely(path: &str) -> String {
     used_parts.join("/") + final_slash
 }
 
+/// Returns true if file name contains a content hash, such as `app.3f2a1b9c.js` or `index-BQ2d9f3a.js`.
+///
+/// The hash should be the last segment of the file stem, separated by `.` or `-`, at least 8 alphanumeric
+/// characters and contains both letters and digits.
+pub(crate) fn is_hashed_file_name(name: &str) -> bool {
+    let Some((stem, _)) = name.rsplit_once('.') else {
+        return false;
+    };
+    let Some((_, hash)) = stem.rsplit_once(['.', '-']) else {
+        return false;
+    };
+    hash.len() >= 8
+        && hash.chars().all(|c| c.is_ascii_alphanumeric())
+        && hash.chars().any(|c| c.is_ascii_digit())
+        && hash.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+/// Sets `Cache-Control: public, max-age=31536000, immutable` for successful responses of hashed files, if
+/// `Cache-Control` is not set yet.
+pub(crate) fn set_immutable_cache_control(res: &mut Response) {
+    let succeeded = matches!(
+        res.status_code,
+        None | Some(StatusCode::OK | StatusCode::PARTIAL_CONTENT | StatusCode::NOT_MODIFIED)
+    );
+    if succeeded && !res.headers().contains_key(CACHE_CONTROL) {
+        res.headers_mut().insert(
+            CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=31536000, immutable"),
+        );
+    }
+}
+
 pub(crate) fn redirect_to_dir_url(req_uri: &Uri, res: &mut Response) {
     let UriParts {
         scheme,
@@ -181,6 +215,62 @@ mod tests {
         assert_eq!(response.status_code.unwrap(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn test_serve_static_caching() {
+        use salvo_core::http::header::{CACHE_CONTROL, CONTENT_RANGE, ETAG, IF_NONE_MATCH, RANGE};
+
+        let router = Router::new()
+            .push(Router::with_path("hashed.js").get(StaticFile::new("test/static/app.3f2a1b9c.js")))
+            .push(Router::with_path("<*path>").get(StaticDir::new("test/static")));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/").send(&service).await;
+        assert!(res.take_string().await.unwrap().contains("Index page"));
+
+        let res = TestClient::get("http://127.0.0.1:5801/.hidden/secret.txt")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::NOT_FOUND);
+
+        let res = TestClient::get("http://127.0.0.1:5801/test1.txt").send(&service).await;
+        assert!(!res.headers().contains_key(CACHE_CONTROL));
+        let etag = res.headers().get(ETAG).unwrap().to_str().unwrap().to_owned();
+        assert!(etag.starts_with('"'));
+        let res = TestClient::get("http://127.0.0.1:5801/test1.txt")
+            .add_header(IF_NONE_MATCH, &etag, true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::NOT_MODIFIED);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/test1.txt")
+            .add_header(RANGE, "bytes=1-2", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.headers().get(CONTENT_RANGE).unwrap(), "bytes 1-2/5");
+        assert_eq!(res.take_string().await.unwrap(), "op");
+
+        for url in [
+            "http://127.0.0.1:5801/app.3f2a1b9c.js",
+            "http://127.0.0.1:5801/hashed.js",
+        ] {
+            let res = TestClient::get(url).send(&service).await;
+            assert_eq!(
+                res.headers().get(CACHE_CONTROL).unwrap(),
+                "public, max-age=31536000, immutable"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_hashed_file_name() {
+        assert!(is_hashed_file_name("app.3f2a1b9c.js"));
+        assert!(is_hashed_file_name("index-BQ2d9f3a.js"));
+        assert!(!is_hashed_file_name("my-component.js"));
+        assert!(!is_hashed_file_name("jquery-3.6.0.min.js"));
+        assert!(!is_hashed_file_name("3f2a1b9c"));
+    }
+
     #[cfg(feature = "embed")]
     #[tokio::test]
     async fn test_serve_embed_files() {
@@ -242,5 +332,21 @@ mod tests {
             .send(&service)
             .await;
         assert_eq!(response.status_code.unwrap(), StatusCode::NOT_FOUND);
+
+        let response = TestClient::get("http://127.0.0.1:5801/dir/test1.txt")
+            .send(&service)
+            .await;
+        assert_eq!(response.headers().get("content-length").unwrap(), "5");
+        assert_eq!(response.headers().get("content-type").unwrap(), "text/plain");
+        let etag = response.headers().get("etag").unwrap().to_str().unwrap().to_owned();
+        assert!(etag.starts_with('"') && etag.ends_with('"'));
+        for if_none_match in [etag.clone(), format!("W/{etag}"), format!("\"other\", {etag}")] {
+            let response = TestClient::get("http://127.0.0.1:5801/test1.txt")
+                .add_header("if-none-match", if_none_match, true)
+                .send(&service)
+                .await;
+            assert_eq!(response.status_code.unwrap(), StatusCode::NOT_MODIFIED);
+            assert_eq!(response.headers().get("etag").unwrap(), &etag);
+        }
     }
 }
@@ -0,0 +1 @@
+console.log('app');
\ No newline at end of file
@@ -1,34 +1,51 @@
-use std::fmt::Write;
-
-use salvo::flash::{CookieStore, FlashDepotExt};
+use salvo::flash::{CookieStore, FlashDepotExt, FlashLevel};
+use salvo::http::cookie::Key;
 use salvo::prelude::*;
 
 #[handler]
-pub async fn set_flash(depot: &mut Depot, res: &mut Response) {
-    let flash = depot.outgoing_flash_mut();
-    flash.info("Hey there!").debug("How is it going?");
-    res.render(Redirect::other("/get"));
+pub async fn login_page() -> Text<&'static str> {
+    Text::Html(
+        r#"<form action="/login" method="post">
+    <input type="text" name="username" placeholder="username" />
+    <button type="submit">Login</button>
+</form>"#,
+    )
 }
 
 #[handler]
-pub async fn get_flash(depot: &mut Depot, _res: &mut Response) -> String {
-    let mut body = String::new();
-    if let Some(flash) = depot.incoming_flash() {
-        for message in flash.iter() {
-            writeln!(body, "{} - {}", message.value, message.level).unwrap();
+pub async fn login(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    match req.form::<String>("username").await {
+        Some(username) if !username.is_empty() => {
+            depot.add_flash(FlashLevel::Success, "Login successful");
+            res.render(Redirect::other("/home"));
+        }
+        _ => {
+            depot.add_flash(FlashLevel::Error, "Username is required");
+            res.render(Redirect::other("/login"));
         }
     }
-    body
+}
+
+#[handler]
+pub async fn home(depot: &mut Depot) -> Text<String> {
+    let messages = depot
+        .flash_messages()
+        .iter()
+        .map(|message| format!(r#"<p class="{}">{}</p>"#, message.level, message.value))
+        .collect::<String>();
+    Text::Html(format!("{messages}<h1>Home</h1>"))
 }
 
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt().init();
 
+    // Use a fixed key in production, so that flash cookies can be verified by all server instances.
+    let key = Key::generate();
     let router = Router::new()
-        .hoop(CookieStore::new().into_handler())
-        .push(Router::with_path("get").get(get_flash))
-        .push(Router::with_path("set").get(set_flash));
+        .hoop(CookieStore::new().key(key).into_handler())
+        .push(Router::with_path("login").get(login_page).post(login))
+        .push(Router::with_path("home").get(home));
     let acceptor = TcpListener::new("0.0.0.0:5800").bind().await;
     Server::new(acceptor).serve(router).await;
 }
@@ -0,0 +1,52 @@
+use salvo::http::errors::ProblemDetail;
+use salvo::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, Debug)]
+struct NewUser {
+    name: String,
+    email: String,
+}
+
+impl NewUser {
+    fn validate(&self) -> Result<(), ProblemDetail> {
+        let mut errors = vec![];
+        if self.name.trim().is_empty() {
+            errors.push(json!({"field": "name", "reason": "must not be empty"}));
+        }
+        if !self.email.contains('@') {
+            errors.push(json!({"field": "email", "reason": "must be a valid email address"}));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ProblemDetail::new("https://example.com/probs/validation-error")
+                .title("Your request parameters didn't validate.")
+                .status(StatusCode::UNPROCESSABLE_ENTITY)
+                .detail(format!("{} field(s) failed validation.", errors.len()))
+                .instance("/users")
+                .extension("errors", errors))
+        }
+    }
+}
+
+#[handler]
+async fn create_user(req: &mut Request) -> Result<&'static str, ProblemDetail> {
+    let user: NewUser = req
+        .parse_json()
+        .await
+        .map_err(|e| ProblemDetail::with_status(StatusCode::BAD_REQUEST).detail(format!("invalid json body: {e}")))?;
+    user.validate()?;
+    Ok("User created")
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt().init();
+
+    // Try it: curl -X POST -d '{"name": "", "email": "jobs"}' http://0.0.0.0:5800/users
+    let router = Router::with_path("users").post(create_user);
+    let acceptor = TcpListener::new("0.0.0.0:5800").bind().await;
+    Server::new(acceptor).serve(router).await;
+}